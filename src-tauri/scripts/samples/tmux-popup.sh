@@ -14,6 +14,7 @@
 #   OVIM_FILE       - temp file path to edit
 #   OVIM_EDITOR     - configured editor executable
 #   OVIM_SOCKET     - RPC socket path (for live sync)
+#   OVIM_WINDOW_TITLE - title to give the editor window (lets ovim raise it)
 #   OVIM_TERMINAL   - selected terminal type
 #   OVIM_WIDTH      - popup width in pixels
 #   OVIM_HEIGHT     - popup height in pixels
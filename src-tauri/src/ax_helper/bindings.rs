@@ -58,8 +58,13 @@ pub const CLICKABLE_ROLES: &[&str] = &[
 /// Default depth limit for traversal
 pub const DEFAULT_MAX_DEPTH: usize = 10;
 pub const DEFAULT_MAX_ELEMENTS: usize = 500;
+/// Default settle delay before querying (matches click_mode.ax_stabilization_delay_ms)
+pub const DEFAULT_DELAY_MS: u64 = 10;
 
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use super::types::{RoleOverrides, TitleAttributes};
 
 /// Runtime-configurable limits
 pub static MAX_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_DEPTH);
@@ -77,3 +82,25 @@ pub fn get_max_depth() -> usize {
 pub fn get_max_elements() -> usize {
     MAX_ELEMENTS.load(Ordering::Relaxed)
 }
+
+/// Title attributes per role for the queried app
+static TITLE_ATTRIBUTES: OnceLock<TitleAttributes> = OnceLock::new();
+
+pub fn set_title_attributes(attributes: TitleAttributes) {
+    let _ = TITLE_ATTRIBUTES.set(attributes);
+}
+
+pub fn get_title_attributes() -> &'static TitleAttributes {
+    TITLE_ATTRIBUTES.get_or_init(TitleAttributes::default)
+}
+
+/// Clickable role changes for the queried app
+static ROLE_OVERRIDES: OnceLock<RoleOverrides> = OnceLock::new();
+
+pub fn set_role_overrides(overrides: RoleOverrides) {
+    let _ = ROLE_OVERRIDES.set(overrides);
+}
+
+pub fn get_role_overrides() -> &'static RoleOverrides {
+    ROLE_OVERRIDES.get_or_init(RoleOverrides::default)
+}
@@ -1,6 +1,7 @@
 //! RAII wrapper for Core Foundation types
 
 use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
 use core_foundation::string::CFString;
 
 use super::bindings::{K_AX_VALUE_CG_POINT_TYPE, K_AX_VALUE_CG_SIZE_TYPE, AXUIElementCopyAttributeValue, AXValueGetValue};
@@ -40,6 +41,18 @@ impl CFHandle {
         Some(result)
     }
 
+    pub fn get_bool_attribute(&self, attr_name: &str) -> Option<bool> {
+        let handle = self.get_attribute(attr_name)?;
+
+        let type_id = unsafe { core_foundation::base::CFGetTypeID(handle.0) };
+        if type_id != CFBoolean::type_id() {
+            return None;
+        }
+
+        let value = unsafe { CFBoolean::wrap_under_get_rule(handle.0 as _) };
+        Some(value.into())
+    }
+
     pub fn extract_point(&self) -> Option<(f64, f64)> {
         let mut point = core_graphics::geometry::CGPoint::new(0.0, 0.0);
         let extracted = unsafe {
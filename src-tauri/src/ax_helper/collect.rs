@@ -3,9 +3,9 @@
 use core_foundation::base::{CFRetain, CFTypeRef, TCFType};
 use core_foundation::string::CFString;
 
-use super::bindings::{AXUIElementCopyAttributeValue, get_max_depth, get_max_elements};
+use super::bindings::{AXUIElementCopyAttributeValue, get_max_depth, get_max_elements, get_role_overrides};
 use super::cf_handle::CFHandle;
-use super::element::{has_press_action, is_clickable_role, is_visible};
+use super::element::{classify_role, element_title, has_press_action, is_visible, truncate_title};
 use super::types::{RawElement, WindowBounds};
 
 /// Inner element collection function
@@ -27,46 +27,7 @@ pub fn collect_elements_inner(
         return;
     }
 
-    // Skip these roles as clickable but still traverse their children
-    let skip_as_clickable = role == "AXMenu"
-        || role == "AXMenuBar"
-        || role == "AXBusyIndicator"
-        || role == "AXProgressIndicator"
-        || role == "AXValueIndicator"
-        || role == "AXScrollBar"
-        || role == "AXOutline"
-        || role == "AXScrollArea"
-        || role == "AXSplitGroup"
-        || role == "AXGroup";
-
-    // If we're inside a row, skip cell contents (text, images, cells)
-    // These are redundant - clicking the row is sufficient
-    let skip_row_children =
-        inside_row && matches!(role.as_str(), "AXCell" | "AXStaticText" | "AXImage");
-
-    // Only check AXActions for roles that commonly have them to avoid crashes
-    // Note: AXStaticText, AXImage, AXHeading are only clickable if they have AXPress action
-    let check_actions = matches!(
-        role.as_str(),
-        "AXButton"
-            | "AXLink"
-            | "AXMenuItem"
-            | "AXMenuButton"
-            | "AXCheckBox"
-            | "AXRadioButton"
-            | "AXPopUpButton"
-            | "AXDisclosureTriangle"
-            | "AXToolbarButton"
-            | "AXStaticText"
-            | "AXImage"
-            | "AXHeading"
-            | "AXCell"
-            | "AXRow"
-    );
-
-    let is_clickable = !skip_as_clickable
-        && !skip_row_children
-        && (is_clickable_role(&role) || (check_actions && has_press_action(element)));
+    let (is_clickable, _) = classify_role(&role, inside_row, get_role_overrides(), || has_press_action(element));
 
     // Track if this element is a row (for children)
     let is_row = role == "AXRow";
@@ -88,15 +49,9 @@ pub fn collect_elements_inner(
             if in_bounds {
                 // For rows, try to get a meaningful title from children
                 let title = if is_row {
-                    get_row_title(element).unwrap_or_default()
+                    get_row_title(element).map(|t| truncate_title(&t)).unwrap_or_default()
                 } else {
-                    element
-                        .get_string_attribute("AXTitle")
-                        .or_else(|| element.get_string_attribute("AXDescription"))
-                        .or_else(|| element.get_string_attribute("AXValue"))
-                        .or_else(|| element.get_string_attribute("AXLabel"))
-                        .or_else(|| element.get_string_attribute("AXHelp"))
-                        .unwrap_or_default()
+                    element_title(element, &role)
                 };
 
                 elements.push(RawElement {
@@ -0,0 +1,129 @@
+//! Full accessibility tree dump for debugging
+//!
+//! Unlike the normal query, every element is reported, including the ones the
+//! clickable filter skips, together with the reason for the decision.
+
+use core_foundation::base::{CFRetain, CFTypeRef, TCFType};
+use core_foundation::string::CFString;
+use serde::Serialize;
+
+use super::bindings::{
+    AXUIElementCopyAttributeValue, AXUIElementCreateApplication, get_max_depth, get_max_elements,
+    get_role_overrides,
+};
+use super::cf_handle::CFHandle;
+use super::element::{classify_role, element_title, has_press_action, is_visible};
+use super::get_window_bounds;
+use super::types::WindowBounds;
+
+/// One element of the dumped tree
+#[derive(Debug, Serialize)]
+pub struct DumpedElement {
+    pub depth: usize,
+    pub role: String,
+    pub title: String,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    /// Whether the normal query would return this element
+    pub clickable: bool,
+    /// Why the element was accepted or skipped
+    pub reason: String,
+}
+
+/// Dump output for one app
+#[derive(Debug, Serialize)]
+pub struct TreeDump {
+    pub pid: i32,
+    pub elements: Vec<DumpedElement>,
+}
+
+/// Walk the focused window (or the app element) and record every element
+pub fn dump_tree(pid: i32) -> Result<TreeDump, String> {
+    let app_element = unsafe {
+        let ptr = AXUIElementCreateApplication(pid);
+        if ptr.is_null() {
+            return Err("Could not create AX element for app".to_string());
+        }
+        CFHandle(ptr)
+    };
+
+    let (root, window_bounds) = match app_element.get_attribute("AXFocusedWindow") {
+        Some(window) => {
+            let bounds = get_window_bounds(&window);
+            (window, bounds)
+        }
+        None => {
+            unsafe { CFRetain(app_element.0) };
+            (CFHandle(app_element.0), None)
+        }
+    };
+
+    let mut elements = Vec::new();
+    dump_element(&root, &mut elements, 0, window_bounds, false);
+    Ok(TreeDump { pid, elements })
+}
+
+fn dump_element(
+    element: &CFHandle,
+    elements: &mut Vec<DumpedElement>,
+    depth: usize,
+    window_bounds: Option<WindowBounds>,
+    inside_row: bool,
+) {
+    if depth > get_max_depth() || elements.len() >= get_max_elements() {
+        return;
+    }
+
+    let role = element.get_string_attribute("AXRole").unwrap_or_default();
+    let title = element_title(element, &role);
+    let pos = element.get_attribute("AXPosition").and_then(|p| p.extract_point());
+    let size = element.get_attribute("AXSize").and_then(|s| s.extract_size());
+
+    let (mut clickable, mut reason) = classify_role(&role, inside_row, get_role_overrides(), || has_press_action(element));
+    if clickable && !is_visible(element) {
+        (clickable, reason) = (false, "not visible");
+    }
+    if let (true, Some(bounds), Some(pos), Some(size)) = (clickable, window_bounds, pos, size) {
+        if !bounds.contains(pos.0, pos.1, size.0, size.1) {
+            (clickable, reason) = (false, "outside window");
+        }
+    }
+
+    elements.push(DumpedElement {
+        depth,
+        role: role.clone(),
+        title,
+        x: pos.map(|p| p.0),
+        y: pos.map(|p| p.1),
+        width: size.map(|s| s.0),
+        height: size.map(|s| s.1),
+        clickable,
+        reason: reason.to_string(),
+    });
+
+    let children_attr = CFString::new("AXChildren");
+    let mut children_value: CFTypeRef = std::ptr::null();
+    let result = unsafe {
+        AXUIElementCopyAttributeValue(element.0, children_attr.as_CFTypeRef(), &mut children_value)
+    };
+    if result != 0 || children_value.is_null() {
+        return;
+    }
+
+    let _children_handle = CFHandle(children_value);
+    let count = unsafe { core_foundation::array::CFArrayGetCount(children_value as _) };
+
+    for i in 0..count.clamp(0, 100) {
+        let child_ptr =
+            unsafe { core_foundation::array::CFArrayGetValueAtIndex(children_value as _, i) };
+        if child_ptr.is_null() {
+            continue;
+        }
+
+        unsafe { CFRetain(child_ptr) };
+        let child = CFHandle(child_ptr);
+        dump_element(&child, elements, depth + 1, window_bounds, inside_row || role == "AXRow");
+    }
+}
@@ -3,13 +3,106 @@
 use core_foundation::string::CFString;
 
 use core_foundation::base::TCFType;
-use super::bindings::CLICKABLE_ROLES;
+use super::bindings::{get_title_attributes, CLICKABLE_ROLES};
 use super::cf_handle::CFHandle;
+use super::types::RoleOverrides;
 
 pub fn is_clickable_role(role: &str) -> bool {
     CLICKABLE_ROLES.iter().any(|r| *r == role)
 }
 
+/// Roles never treated as clickable themselves (their children are still traversed)
+const CONTAINER_ROLES: &[&str] = &[
+    "AXMenu",
+    "AXMenuBar",
+    "AXBusyIndicator",
+    "AXProgressIndicator",
+    "AXValueIndicator",
+    "AXScrollBar",
+    "AXOutline",
+    "AXScrollArea",
+    "AXSplitGroup",
+    "AXGroup",
+];
+
+/// Roles that are only clickable when they expose an AXPress/AXShowMenu action.
+/// Only these are checked, to avoid crashes from querying AXActions on other roles.
+const ACTION_CHECKED_ROLES: &[&str] = &[
+    "AXButton",
+    "AXLink",
+    "AXMenuItem",
+    "AXMenuButton",
+    "AXCheckBox",
+    "AXRadioButton",
+    "AXPopUpButton",
+    "AXDisclosureTriangle",
+    "AXToolbarButton",
+    "AXStaticText",
+    "AXImage",
+    "AXHeading",
+    "AXCell",
+    "AXRow",
+];
+
+/// Decide whether an element with `role` is clickable, with the reason.
+/// `has_press` is only called when the decision depends on the element's actions.
+pub fn classify_role(
+    role: &str,
+    inside_row: bool,
+    overrides: &RoleOverrides,
+    has_press: impl FnOnce() -> bool,
+) -> (bool, &'static str) {
+    if role.is_empty() || role == "AXUnknown" {
+        return (false, "unknown role");
+    }
+    if overrides.removed.iter().any(|r| r == role) {
+        return (false, "removed for app");
+    }
+    if overrides.added.iter().any(|r| r == role) {
+        return (true, "added for app");
+    }
+    if CONTAINER_ROLES.contains(&role) {
+        return (false, "container role");
+    }
+    // Cell contents inside a row are redundant - clicking the row is sufficient
+    if inside_row && matches!(role, "AXCell" | "AXStaticText" | "AXImage") {
+        return (false, "inside row");
+    }
+    if is_clickable_role(role) {
+        return (true, "clickable role");
+    }
+    if !ACTION_CHECKED_ROLES.contains(&role) {
+        return (false, "role not clickable");
+    }
+    if has_press() {
+        (true, "press action")
+    } else {
+        (false, "no press action")
+    }
+}
+
+/// Maximum characters kept from an element title
+pub const MAX_TITLE_CHARS: usize = 80;
+
+/// Attributes tried in order for an element's title, for roles the settings don't list
+const TITLE_ATTRIBUTES: &[&str] = &["AXTitle", "AXDescription", "AXValue", "AXLabel", "AXHelp"];
+
+/// Cap a title at `MAX_TITLE_CHARS` characters
+pub fn truncate_title(title: &str) -> String {
+    title.trim().chars().take(MAX_TITLE_CHARS).collect()
+}
+
+/// Title of an element from the first non-empty attribute for its role
+pub fn element_title(element: &CFHandle, role: &str) -> String {
+    get_title_attributes()
+        .for_role(role, TITLE_ATTRIBUTES)
+        .into_iter()
+        .filter_map(|attr| element.get_string_attribute(attr))
+        .find(|title| !title.trim().is_empty())
+        .map(|title| truncate_title(&title))
+        .unwrap_or_default()
+}
+
 pub fn has_press_action(element: &CFHandle) -> bool {
     let actions_handle = match element.get_attribute("AXActions") {
         Some(h) => h,
@@ -65,3 +158,47 @@ pub fn is_visible(element: &CFHandle) -> bool {
 
     w > 0.0 && h > 0.0 && x >= -10000.0 && y >= -10000.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::TitleAttributes;
+
+    #[test]
+    fn test_classify_role_reasons() {
+        let none = RoleOverrides::default();
+        assert_eq!(classify_role("AXGroup", false, &none, || true), (false, "container role"));
+        assert_eq!(classify_role("AXStaticText", true, &none, || true), (false, "inside row"));
+        assert_eq!(classify_role("AXStaticText", false, &none, || true), (true, "press action"));
+        assert_eq!(classify_role("AXImage", false, &none, || false), (false, "no press action"));
+        assert_eq!(classify_role("AXWindow", false, &none, || true), (false, "role not clickable"));
+    }
+
+    #[test]
+    fn test_classify_role_with_app_overrides() {
+        let overrides = RoleOverrides::from_args(Some("AXGroup, AXWindow"), Some("AXRow"));
+        assert_eq!(classify_role("AXGroup", false, &overrides, || false), (true, "added for app"));
+        assert_eq!(classify_role("AXRow", false, &overrides, || true), (false, "removed for app"));
+        assert_eq!(classify_role("AXButton", false, &overrides, || false), (true, "clickable role"));
+    }
+
+    #[test]
+    fn test_title_attributes_per_role() {
+        let attributes = TitleAttributes::from_arg(Some(r#"{"AXTextField":["AXTitle","AXLabel"]}"#));
+        assert_eq!(attributes.for_role("AXTextField", TITLE_ATTRIBUTES), vec!["AXTitle", "AXLabel"]);
+        assert_eq!(attributes.for_role("AXButton", TITLE_ATTRIBUTES), TITLE_ATTRIBUTES);
+
+        let attributes = TitleAttributes::from_arg(Some(r#"{"*":["AXTitle"]}"#));
+        assert_eq!(attributes.for_role("AXButton", TITLE_ATTRIBUTES), vec!["AXTitle"]);
+
+        let attributes = TitleAttributes::from_arg(Some("not json"));
+        assert_eq!(attributes.for_role("AXTextField", TITLE_ATTRIBUTES), TITLE_ATTRIBUTES);
+    }
+
+    #[test]
+    fn test_truncate_title() {
+        assert_eq!(truncate_title("  Save  "), "Save");
+        let long = "é".repeat(MAX_TITLE_CHARS + 10);
+        assert_eq!(truncate_title(&long).chars().count(), MAX_TITLE_CHARS);
+    }
+}
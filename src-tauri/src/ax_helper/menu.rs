@@ -1,6 +1,8 @@
 //! Menu detection and collection logic
 
-use core_foundation::base::{CFRetain, CFTypeRef, TCFType};
+use std::time::{Duration, Instant};
+
+use core_foundation::base::{CFEqual, CFRetain, CFTypeRef, TCFType};
 use core_foundation::string::CFString;
 
 use super::bindings::{
@@ -9,6 +11,11 @@ use super::bindings::{
 use super::cf_handle::CFHandle;
 use super::types::RawElement;
 
+/// Time allowed for searching one window for menus. The search comes up empty in the
+/// common no-menu case, so it must not hold up the main window traversal. Each window
+/// gets its own budget, so a large first window can't hide a menu in a later one.
+const MENU_SCAN_BUDGET_PER_WINDOW: Duration = Duration::from_millis(10);
+
 /// Check if the focused element is a menu item and collect menu items
 /// This handles popup/context menus that appear outside the normal window hierarchy
 pub fn collect_menu_elements(elements: &mut Vec<RawElement>, pid: i32) -> bool {
@@ -97,6 +104,7 @@ pub fn collect_menu_elements(elements: &mut Vec<RawElement>, pid: i32) -> bool {
 
     // Look for any visible popup menus in the app's children
     // Context menus are often added as direct children of the app element
+    let mut windows: Vec<CFHandle> = Vec::new();
     if let Some(children) = app_element.get_attribute("AXChildren") {
         let children_ptr = children.0;
         let count = unsafe { core_foundation::array::CFArrayGetCount(children_ptr as _) };
@@ -122,19 +130,16 @@ pub fn collect_menu_elements(elements: &mut Vec<RawElement>, pid: i32) -> bool {
                 }
             }
 
-            // Check if this is a window - look for menus inside any window
-            // Context menus can appear in various window types
+            // Windows are searched below, once the cheap checks found nothing
             if child_role == "AXWindow" {
-                if find_menus_in_element(&child, elements) {
-                    return true;
-                }
+                windows.push(child);
             }
         }
     }
 
     // Also check AXWindows array directly (more reliable way to get all windows)
-    if let Some(windows) = app_element.get_attribute("AXWindows") {
-        let windows_ptr = windows.0;
+    if let Some(app_windows) = app_element.get_attribute("AXWindows") {
+        let windows_ptr = app_windows.0;
         let count = unsafe { core_foundation::array::CFArrayGetCount(windows_ptr as _) };
 
         for i in 0..count.min(10) {
@@ -145,26 +150,41 @@ pub fn collect_menu_elements(elements: &mut Vec<RawElement>, pid: i32) -> bool {
                 continue;
             }
 
+            // Usually the same windows as in AXChildren - don't search them twice
+            if windows.iter().any(|w| unsafe { CFEqual(w.0, window_ptr) } != 0) {
+                continue;
+            }
+
             unsafe { CFRetain(window_ptr) };
-            let window = CFHandle(window_ptr);
+            windows.push(CFHandle(window_ptr));
+        }
+    }
 
-            if find_menus_in_element(&window, elements) {
-                return true;
-            }
+    // Context menus can appear in various window types. Searching them is the
+    // expensive part, so each window's search stops once its time budget is spent.
+    for window in &windows {
+        let deadline = Instant::now() + MENU_SCAN_BUDGET_PER_WINDOW;
+        if find_menus_in_element(window, elements, deadline) {
+            return true;
         }
     }
 
     false
 }
 
-/// Recursively search for AXMenu elements within an element (limited depth)
-fn find_menus_in_element(element: &CFHandle, elements: &mut Vec<RawElement>) -> bool {
-    find_menus_recursive(element, elements, 0)
+/// Recursively search for AXMenu elements within an element (limited depth and time)
+fn find_menus_in_element(element: &CFHandle, elements: &mut Vec<RawElement>, deadline: Instant) -> bool {
+    find_menus_recursive(element, elements, 0, deadline)
 }
 
-fn find_menus_recursive(element: &CFHandle, elements: &mut Vec<RawElement>, depth: usize) -> bool {
-    // Limit recursion depth to avoid performance issues
-    if depth > 3 {
+fn find_menus_recursive(
+    element: &CFHandle,
+    elements: &mut Vec<RawElement>,
+    depth: usize,
+    deadline: Instant,
+) -> bool {
+    // Limit recursion depth and time to avoid performance issues
+    if depth > 3 || Instant::now() >= deadline {
         return false;
     }
 
@@ -209,7 +229,7 @@ fn find_menus_recursive(element: &CFHandle, elements: &mut Vec<RawElement>, dept
             role.as_str(),
             "AXStaticText" | "AXImage" | "AXTextField" | "AXTextArea"
         ) {
-            if find_menus_recursive(&child, elements, depth + 1) {
+            if find_menus_recursive(&child, elements, depth + 1, deadline) {
                 return true;
             }
         }
@@ -219,7 +239,7 @@ fn find_menus_recursive(element: &CFHandle, elements: &mut Vec<RawElement>, dept
 }
 
 /// Collect menu items from a menu element
-fn collect_menu_items(menu: &CFHandle, elements: &mut Vec<RawElement>) {
+pub fn collect_menu_items(menu: &CFHandle, elements: &mut Vec<RawElement>) {
     let children_attr = CFString::new("AXChildren");
     let mut children_value: CFTypeRef = std::ptr::null();
 
@@ -0,0 +1,94 @@
+//! Menu bar collection
+//!
+//! Collects the app's menu bar titles (File, Edit, ...), or the items of the
+//! menu bar menu that is currently open. Used by the menu bar shortcut, which
+//! activates again after a title is clicked to hint the opened menu.
+
+use core_foundation::base::CFRetain;
+
+use super::bindings::AXUIElementCreateApplication;
+use super::cf_handle::CFHandle;
+use super::element::is_visible;
+use super::menu::collect_menu_items;
+use super::types::{HelperOutput, RawElement};
+
+/// Collect the menu bar of the app with `pid`
+pub fn collect_menu_bar(pid: i32) -> Result<HelperOutput, String> {
+    let app_element = unsafe {
+        let ptr = AXUIElementCreateApplication(pid);
+        if ptr.is_null() {
+            return Err("Could not create AX element for app".to_string());
+        }
+        CFHandle(ptr)
+    };
+
+    let menu_bar = app_element
+        .get_attribute("AXMenuBar")
+        .ok_or("App has no menu bar")?;
+
+    let mut elements = Vec::new();
+    for item in children(&menu_bar, 50) {
+        let role = item.get_string_attribute("AXRole").unwrap_or_default();
+        if role != "AXMenuBarItem" {
+            continue;
+        }
+
+        // An open menu takes priority: hint its items instead of the titles
+        if item.get_bool_attribute("AXSelected") == Some(true) {
+            let mut items = Vec::new();
+            for menu in children(&item, 5) {
+                if menu.get_string_attribute("AXRole").as_deref() == Some("AXMenu") {
+                    collect_menu_items(&menu, &mut items);
+                }
+            }
+            if !items.is_empty() {
+                return Ok(HelperOutput {
+                    elements: items,
+                    is_modal: true,
+                });
+            }
+        }
+
+        if !is_visible(&item) {
+            continue;
+        }
+        let (Some(pos), Some(size)) = (
+            item.get_attribute("AXPosition").and_then(|p| p.extract_point()),
+            item.get_attribute("AXSize").and_then(|s| s.extract_size()),
+        ) else {
+            continue;
+        };
+        elements.push(RawElement {
+            x: pos.0,
+            y: pos.1,
+            width: size.0,
+            height: size.1,
+            role,
+            title: item.get_string_attribute("AXTitle").unwrap_or_default(),
+        });
+    }
+
+    Ok(HelperOutput {
+        elements,
+        is_modal: false,
+    })
+}
+
+/// The first `limit` children of `element`
+fn children(element: &CFHandle, limit: isize) -> Vec<CFHandle> {
+    let Some(children) = element.get_attribute("AXChildren") else {
+        return Vec::new();
+    };
+    let count = unsafe { core_foundation::array::CFArrayGetCount(children.0 as _) };
+    (0..count.clamp(0, limit))
+        .filter_map(|i| {
+            let child_ptr =
+                unsafe { core_foundation::array::CFArrayGetValueAtIndex(children.0 as _, i) };
+            if child_ptr.is_null() {
+                return None;
+            }
+            unsafe { CFRetain(child_ptr) };
+            Some(CFHandle(child_ptr))
+        })
+        .collect()
+}
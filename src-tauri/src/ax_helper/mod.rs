@@ -13,9 +13,12 @@
 mod bindings;
 mod cf_handle;
 mod collect;
+mod dump;
 mod element;
 mod menu;
+mod menu_bar;
 mod types;
+mod window_controls;
 
 use core_foundation::base::CFRetain;
 use std::env;
@@ -121,7 +124,11 @@ fn query_elements_inner(pid: i32) -> Result<HelperOutput, String> {
 
     // First, check if there's an open menu (popup menu, context menu, etc.)
     // These take priority as they're the most likely target when visible
-    if collect_menu_elements(&mut elements, pid) {
+    let menu_start = std::time::Instant::now();
+    let has_menu = collect_menu_elements(&mut elements, pid);
+    // stdout carries the JSON result; the app logs stderr lines
+    eprintln!("[TIMING] menu detection: {}ms (menu found: {})", menu_start.elapsed().as_millis(), has_menu);
+    if has_menu {
         return Ok(HelperOutput {
             elements: deduplicate_elements(elements),
             is_modal: true,
@@ -273,9 +280,22 @@ fn query_elements(pid: i32) -> Result<HelperOutput, String> {
 }
 
 pub fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Usage: ovim-ax-helper --dump <pid> [delay_ms] [max_depth] [max_elements] [added_roles] [removed_roles] [title_attributes]
+    // Reports every element with its clickable decision instead of only clickable ones
+    let dump = args.get(1).is_some_and(|a| a == "--dump");
+    // Usage: ovim-ax-helper --window-controls <pid> [...same arguments]
+    // Reports only the focused window's title bar buttons, toolbars and tabs
+    let window_controls = args.get(1).is_some_and(|a| a == "--window-controls");
+    // Usage: ovim-ax-helper --menu-bar <pid> [...same arguments]
+    // Reports the menu bar titles, or the items of the open menu bar menu
+    let menu_bar = args.get(1).is_some_and(|a| a == "--menu-bar");
+    if dump || window_controls || menu_bar {
+        args.remove(1);
+    }
 
-    // Usage: ovim-ax-helper <pid> [delay_ms] [max_depth] [max_elements]
+    // Usage: ovim-ax-helper <pid> [delay_ms] [max_depth] [max_elements] [added_roles] [removed_roles] [title_attributes]
     // Or: ovim-ax-helper (uses frontmost app with defaults)
     let pid = if args.len() > 1 {
         args[1].parse::<i32>().ok()
@@ -291,11 +311,11 @@ pub fn main() {
         }
     };
 
-    // Get delay from command line arg, default to 10ms
+    // Get settle delay from command line arg (click_mode.ax_stabilization_delay_ms)
     let delay_ms: u64 = args
         .get(2)
         .and_then(|s| s.parse().ok())
-        .unwrap_or(10);
+        .unwrap_or(bindings::DEFAULT_DELAY_MS);
 
     // Get max_depth from command line arg
     let max_depth: usize = args
@@ -312,12 +332,49 @@ pub fn main() {
     // Set the limits
     bindings::set_limits(max_depth, max_elements);
 
+    // Per-app clickable role changes (comma-separated)
+    bindings::set_role_overrides(types::RoleOverrides::from_args(
+        args.get(5).map(String::as_str),
+        args.get(6).map(String::as_str),
+    ));
+
+    // Title attributes per role (JSON object of role to attribute list)
+    bindings::set_title_attributes(types::TitleAttributes::from_arg(
+        args.get(7).map(String::as_str),
+    ));
+
     // Configurable delay - increase if hints are missing on slower systems
     if delay_ms > 0 {
         std::thread::sleep(std::time::Duration::from_millis(delay_ms));
     }
 
-    match query_elements(pid) {
+    if dump {
+        match dump::dump_tree(pid) {
+            Ok(output) => {
+                let json = serde_json::to_string_pretty(&output)
+                    .unwrap_or_else(|_| r#"{"elements":[]}"#.to_string());
+                println!("{}", json);
+            }
+            Err(e) => {
+                eprintln!("{{\"error\": \"{}\"}}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let output = if window_controls {
+        window_controls::collect_window_controls(pid).map(|mut output| {
+            output.elements = deduplicate_elements(output.elements);
+            output
+        })
+    } else if menu_bar {
+        menu_bar::collect_menu_bar(pid)
+    } else {
+        query_elements(pid)
+    };
+
+    match output {
         Ok(output) => {
             let json = serde_json::to_string(&output)
                 .unwrap_or_else(|_| r#"{"elements":[],"is_modal":false}"#.to_string());
@@ -1,5 +1,7 @@
 //! Data structures for accessibility helper
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Window bounds for filtering elements
@@ -42,3 +44,57 @@ pub struct HelperOutput {
     /// True if elements were collected from a sheet/dialog (modal UI)
     pub is_modal: bool,
 }
+
+/// Per-app changes to which roles count as clickable
+#[derive(Debug, Clone, Default)]
+pub struct RoleOverrides {
+    /// Roles treated as clickable in addition to the defaults
+    pub added: Vec<String>,
+    /// Default roles never treated as clickable
+    pub removed: Vec<String>,
+}
+
+impl RoleOverrides {
+    /// Parse comma-separated role lists from the command line
+    pub fn from_args(added: Option<&str>, removed: Option<&str>) -> Self {
+        let parse = |list: Option<&str>| -> Vec<String> {
+            list.unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        Self {
+            added: parse(added),
+            removed: parse(removed),
+        }
+    }
+}
+
+/// Accessibility attributes tried for element titles, per role
+#[derive(Debug, Clone, Default)]
+pub struct TitleAttributes {
+    /// Attribute lists keyed by role, "*" for every other role
+    by_role: HashMap<String, Vec<String>>,
+}
+
+impl TitleAttributes {
+    /// Parse the JSON object argument, e.g. `{"AXTextField":["AXTitle","AXLabel"]}`.
+    /// A missing or invalid argument leaves every role on the default chain.
+    pub fn from_arg(arg: Option<&str>) -> Self {
+        let by_role = arg
+            .filter(|a| !a.is_empty())
+            .and_then(|a| serde_json::from_str(a).ok())
+            .unwrap_or_default();
+        Self { by_role }
+    }
+
+    /// Attributes for `role` in priority order: its own list, the "*" list, or `default`
+    pub fn for_role<'a>(&'a self, role: &str, default: &[&'a str]) -> Vec<&'a str> {
+        match self.by_role.get(role).or_else(|| self.by_role.get("*")) {
+            Some(attributes) => attributes.iter().map(String::as_str).collect(),
+            None => default.to_vec(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Window control collection
+//!
+//! Collects only the focused window's chrome: the close/minimize/zoom buttons
+//! exposed as window attributes, plus the clickable elements inside its
+//! toolbars and tab groups. Used by the window controls shortcut.
+
+use core_foundation::base::CFRetain;
+
+use super::bindings::AXUIElementCreateApplication;
+use super::cf_handle::CFHandle;
+use super::collect::collect_elements_inner;
+use super::element::{element_title, is_visible};
+use super::types::{HelperOutput, RawElement};
+use super::get_window_bounds;
+
+/// Window attributes pointing directly at title bar buttons, with fallback titles
+const WINDOW_BUTTON_ATTRIBUTES: &[(&str, &str)] = &[
+    ("AXCloseButton", "Close"),
+    ("AXMinimizeButton", "Minimize"),
+    ("AXZoomButton", "Zoom"),
+    ("AXFullScreenButton", "Full Screen"),
+];
+
+/// Window children whose clickable descendants count as window controls
+const CONTROL_CONTAINER_ROLES: &[&str] = &["AXToolbar", "AXTabGroup"];
+
+/// Collect the focused window's controls for `pid`
+pub fn collect_window_controls(pid: i32) -> Result<HelperOutput, String> {
+    let app_element = unsafe {
+        let ptr = AXUIElementCreateApplication(pid);
+        if ptr.is_null() {
+            return Err("Could not create AX element for app".to_string());
+        }
+        CFHandle(ptr)
+    };
+
+    let window = app_element
+        .get_attribute("AXFocusedWindow")
+        .ok_or("No focused window")?;
+    let window_bounds = get_window_bounds(&window);
+
+    let mut elements = Vec::new();
+
+    for (attribute, fallback_title) in WINDOW_BUTTON_ATTRIBUTES {
+        let Some(button) = window.get_attribute(attribute) else {
+            continue;
+        };
+        if !is_visible(&button) {
+            continue;
+        }
+        let (Some(pos), Some(size)) = (
+            button.get_attribute("AXPosition").and_then(|p| p.extract_point()),
+            button.get_attribute("AXSize").and_then(|s| s.extract_size()),
+        ) else {
+            continue;
+        };
+        let role = button.get_string_attribute("AXRole").unwrap_or_default();
+        let title = element_title(&button, &role);
+        elements.push(RawElement {
+            x: pos.0,
+            y: pos.1,
+            width: size.0,
+            height: size.1,
+            role,
+            title: if title.is_empty() { fallback_title.to_string() } else { title },
+        });
+    }
+
+    if let Some(children) = window.get_attribute("AXChildren") {
+        let count = unsafe { core_foundation::array::CFArrayGetCount(children.0 as _) };
+        for i in 0..count.clamp(0, 100) {
+            let child_ptr =
+                unsafe { core_foundation::array::CFArrayGetValueAtIndex(children.0 as _, i) };
+            if child_ptr.is_null() {
+                continue;
+            }
+            unsafe { CFRetain(child_ptr) };
+            let child = CFHandle(child_ptr);
+            let role = child.get_string_attribute("AXRole").unwrap_or_default();
+            if CONTROL_CONTAINER_ROLES.contains(&role.as_str()) {
+                collect_elements_inner(&child, &mut elements, 0, window_bounds, false);
+            }
+        }
+    }
+
+    Ok(HelperOutput {
+        elements,
+        is_modal: false,
+    })
+}
@@ -3,6 +3,9 @@ use std::path::PathBuf;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
 
+#[path = "socket_path.rs"]
+mod socket_path;
+
 /// IPC command from CLI to main app
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum IpcCommand {
@@ -14,6 +17,8 @@ pub enum IpcCommand {
     Visual,
     EditPopup,
     ClickMode,
+    FocusNextField,
+    FocusPreviousField,
     LauncherHandled {
         session_id: String,
         editor_pid: Option<u32>,
@@ -23,6 +28,13 @@ pub enum IpcCommand {
     },
 }
 
+/// A command with the configured token
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IpcRequest {
+    pub command: IpcCommand,
+    pub token: Option<String>,
+}
+
 /// IPC response from main app to CLI
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum IpcResponse {
@@ -31,24 +43,50 @@ pub enum IpcResponse {
     Error(String),
 }
 
-fn socket_path() -> PathBuf {
-    let runtime_dir = dirs::runtime_dir()
-        .or_else(dirs::cache_dir)
-        .unwrap_or_else(|| PathBuf::from("/tmp"));
-    runtime_dir.join("ovim.sock")
+/// The part of the app's settings file the CLI needs
+#[derive(Default, serde::Deserialize)]
+struct CliSettings {
+    #[serde(default)]
+    ipc_socket_path: Option<PathBuf>,
+    #[serde(default)]
+    ipc_token: Option<String>,
+}
+
+impl CliSettings {
+    fn load() -> Self {
+        dirs::config_dir()
+            .map(|p| p.join("ovim").join("settings.yaml"))
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|contents| serde_yml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// `OVIM_IPC_TOKEN`, or the `ipc_token` setting
+fn ipc_token(settings: &CliSettings) -> Option<String> {
+    env::var("OVIM_IPC_TOKEN")
+        .ok()
+        .or_else(|| settings.ipc_token.clone())
+        .filter(|t| !t.is_empty())
 }
 
 async fn send_command(cmd: IpcCommand) -> Result<IpcResponse, String> {
-    let path = socket_path();
+    let settings = CliSettings::load();
+    // Same validation and fallback as the app, so both pick the same socket
+    let path = socket_path::ipc_socket_path(settings.ipc_socket_path.as_deref());
 
-    let stream = UnixStream::connect(&path)
-        .await
-        .map_err(|e| format!("Failed to connect to ovim (is it running?): {}", e))?;
+    let stream = UnixStream::connect(&path).await.map_err(|e| {
+        format!("Failed to connect to ovim at {:?} (is it running?): {}", path, e)
+    })?;
 
     let (reader, mut writer) = stream.into_split();
     let mut reader = BufReader::new(reader);
 
-    let cmd_str = serde_json::to_string(&cmd).map_err(|e| e.to_string())?;
+    let request = IpcRequest {
+        command: cmd,
+        token: ipc_token(&settings),
+    };
+    let cmd_str = serde_json::to_string(&request).map_err(|e| e.to_string())?;
     writer
         .write_all(cmd_str.as_bytes())
         .await
@@ -79,6 +117,8 @@ fn print_usage() {
     eprintln!("  set <mode>        Set mode to insert/normal/visual");
     eprintln!("  edit, e           Activate Edit Popup (edit text field in nvim)");
     eprintln!("  click, c          Activate Click Mode (keyboard-driven clicking)");
+    eprintln!("  next-field        Focus the next text field in the focused window");
+    eprintln!("  prev-field        Focus the previous text field in the focused window");
     eprintln!();
     eprintln!("Launcher script commands:");
     eprintln!("  launcher-handled --session <id> [--pid <pid>]");
@@ -120,6 +160,8 @@ async fn main() {
         "visual" | "v" => IpcCommand::Visual,
         "edit" | "e" => IpcCommand::EditPopup,
         "click" | "c" => IpcCommand::ClickMode,
+        "next-field" => IpcCommand::FocusNextField,
+        "prev-field" => IpcCommand::FocusPreviousField,
         "set" => {
             if args.len() < 3 {
                 eprintln!("Error: 'set' requires a mode argument (insert/normal/visual)");
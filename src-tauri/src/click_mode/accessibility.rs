@@ -3,11 +3,15 @@
 //! Uses macOS Accessibility API to discover clickable UI elements
 //! in the frontmost application.
 
-use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use super::element::ClickableElementInternal;
-use super::hints::generate_hints;
+use super::overlap::stack_hints;
+use std::collections::HashMap;
+
+use crate::config::click_mode::{ClickableRoleOverrides, HintOrder};
 
 /// Cache for clickable elements to speed up repeated activations
 struct ElementCache {
@@ -31,6 +35,17 @@ struct BrowserJsCache {
     timestamp: Instant,
 }
 
+/// Element set frozen by `pin_elements`
+struct PinnedElements {
+    /// Elements in hint order, so regenerated hints match the pinned ones
+    elements: Vec<RawElementData>,
+    /// PID of the app these elements belong to
+    pid: i32,
+}
+
+/// Pinned elements bypass the caches and re-querying until unpinned
+static PINNED_ELEMENTS: Mutex<Option<PinnedElements>> = Mutex::new(None);
+
 /// Global element cache with short TTL
 static ELEMENT_CACHE: OnceLock<Mutex<Option<ElementCache>>> = OnceLock::new();
 
@@ -74,6 +89,99 @@ pub fn update_timing_settings(cache_ttl_ms: u32, ax_delay_ms: u32, max_depth: u3
     }
 }
 
+/// Which elements get the shortest hints (updated from user settings)
+static HINT_ORDER: Mutex<HintOrder> = Mutex::new(HintOrder::DiscoveryOrder);
+
+/// Update hint ordering from user configuration
+pub fn set_hint_order(order: HintOrder) {
+    if let Ok(mut current) = HINT_ORDER.lock() {
+        *current = order;
+    }
+}
+
+/// Whether browsers are scanned past the visible viewport (updated from user settings)
+static FULL_PAGE_SCAN: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable the browser full-page scan
+pub fn set_full_page_scan(enabled: bool) {
+    FULL_PAGE_SCAN.store(enabled, Ordering::Relaxed);
+    invalidate_cache();
+}
+
+/// Helper argument with the title attributes per role, as JSON (updated from user settings)
+static TITLE_ATTRIBUTES_ARG: Mutex<String> = Mutex::new(String::new());
+
+/// Update which attributes the helper reads element titles from, per role
+pub fn set_title_attributes(by_role: &HashMap<String, Vec<String>>) {
+    if let Ok(mut arg) = TITLE_ATTRIBUTES_ARG.lock() {
+        *arg = serde_json::to_string(by_role).unwrap_or_default();
+    }
+    invalidate_cache();
+}
+
+/// Helper argument for `TITLE_ATTRIBUTES_ARG`
+fn title_attributes_arg() -> String {
+    TITLE_ATTRIBUTES_ARG.lock().map(|arg| arg.clone()).unwrap_or_default()
+}
+
+/// Clickable role overrides keyed by bundle ID (updated from user settings)
+static APP_CLICKABLE_ROLES: Mutex<Option<HashMap<String, ClickableRoleOverrides>>> = Mutex::new(None);
+
+/// Update per-app clickable role overrides from user configuration
+pub fn set_app_clickable_roles(overrides: HashMap<String, ClickableRoleOverrides>) {
+    if let Ok(mut current) = APP_CLICKABLE_ROLES.lock() {
+        *current = Some(overrides);
+    }
+    invalidate_cache();
+}
+
+/// Helper arguments (comma-separated added and removed roles) for the app with `pid`
+fn role_override_args(pid: i32) -> (String, String) {
+    let Some(bundle_id) = get_app_bundle_id_for_pid(pid) else {
+        return (String::new(), String::new());
+    };
+    APP_CLICKABLE_ROLES
+        .lock()
+        .ok()
+        .and_then(|map| {
+            map.as_ref()?
+                .get(&bundle_id)
+                .map(|o| (o.add.join(","), o.remove.join(",")))
+        })
+        .unwrap_or_default()
+}
+
+/// Sort elements by screen position so the first hints go to top-left elements
+fn sort_by_position(elements: &mut [RawElementData]) {
+    elements.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+}
+
+/// Sort elements by distance from `point` to their center, closest first
+fn sort_by_distance(elements: &mut [RawElementData], point: (f64, f64)) {
+    let distance = |e: &RawElementData| {
+        let dx = e.x + e.width / 2.0 - point.0;
+        let dy = e.y + e.height / 2.0 - point.1;
+        dx * dx + dy * dy
+    };
+    elements.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+}
+
+/// Reorder elements according to the configured hint order
+fn apply_hint_order(elements: &mut [RawElementData]) {
+    order_elements(elements, HINT_ORDER.lock().map(|o| *o).unwrap_or_default());
+}
+
+fn order_elements(elements: &mut [RawElementData], order: HintOrder) {
+    match order {
+        HintOrder::DiscoveryOrder => {}
+        HintOrder::VisualOrder => sort_by_position(elements),
+        HintOrder::DistanceFromCursor => match super::mouse::cursor_position() {
+            Some(point) => sort_by_distance(elements, point),
+            None => log::warn!("Could not read cursor position, keeping discovery order"),
+        },
+    }
+}
+
 fn get_cache() -> &'static Mutex<Option<ElementCache>> {
     ELEMENT_CACHE.get_or_init(|| Mutex::new(None))
 }
@@ -82,18 +190,28 @@ fn get_browser_js_cache() -> &'static Mutex<Option<BrowserJsCache>> {
     BROWSER_JS_CACHE.get_or_init(|| Mutex::new(None))
 }
 
-/// Check if we have valid cached elements for the given PID
-fn get_cached_elements(pid: i32) -> Option<(Vec<RawElementData>, bool)> {
-    let cache_ttl = get_timing_settings()
+/// Configured cache TTL (`click_mode.cache_ttl_ms`)
+fn cache_ttl_ms() -> u128 {
+    get_timing_settings()
         .lock()
         .map(|s| s.cache_ttl_ms)
-        .unwrap_or(500);
+        .unwrap_or(500)
+}
+
+/// Whether a cache entry for `cached_pid` populated at `timestamp` can serve `pid`
+fn is_cache_fresh(cached_pid: i32, timestamp: Instant, pid: i32, ttl_ms: u128) -> bool {
+    cached_pid == pid && timestamp.elapsed().as_millis() < ttl_ms
+}
+
+/// Check if we have valid cached elements for the given PID
+fn get_cached_elements(pid: i32) -> Option<(Vec<RawElementData>, bool)> {
+    let cache_ttl = cache_ttl_ms();
 
     let cache = get_cache().lock().ok()?;
     let cached = cache.as_ref()?;
 
     // Check if cache is for the right PID and not expired
-    if cached.pid == pid && cached.timestamp.elapsed().as_millis() < cache_ttl {
+    if is_cache_fresh(cached.pid, cached.timestamp, pid, cache_ttl) {
         log::info!("Using cached elements (age: {}ms)", cached.timestamp.elapsed().as_millis());
         Some((cached.elements.clone(), cached.is_modal))
     } else {
@@ -115,15 +233,12 @@ fn cache_elements(pid: i32, elements: Vec<RawElementData>, is_modal: bool) {
 
 /// Check if we have valid cached browser JS elements for the given PID
 fn get_cached_browser_js_elements(pid: i32) -> Option<Vec<RawElementData>> {
-    let cache_ttl = get_timing_settings()
-        .lock()
-        .map(|s| s.cache_ttl_ms)
-        .unwrap_or(500);
+    let cache_ttl = cache_ttl_ms();
 
     let cache = get_browser_js_cache().lock().ok()?;
     let cached = cache.as_ref()?;
 
-    if cached.pid == pid && cached.timestamp.elapsed().as_millis() < cache_ttl {
+    if is_cache_fresh(cached.pid, cached.timestamp, pid, cache_ttl) {
         log::info!("Using cached browser JS elements (age: {}ms)", cached.timestamp.elapsed().as_millis());
         Some(cached.elements.clone())
     } else {
@@ -142,6 +257,18 @@ fn cache_browser_js_elements(pid: i32, elements: Vec<RawElementData>) {
     }
 }
 
+/// The last browser JS elements for `pid`, however old. A failed JS query uses
+/// these, so one transient failure doesn't drop the web content from the hints.
+fn last_browser_js_elements(pid: i32) -> Vec<RawElementData> {
+    let Ok(cache) = get_browser_js_cache().lock() else {
+        return Vec::new();
+    };
+    match cache.as_ref() {
+        Some(cached) if cached.pid == pid => cached.elements.clone(),
+        _ => Vec::new(),
+    }
+}
+
 /// Invalidate the cache (call when app focus changes)
 pub fn invalidate_cache() {
     if let Ok(mut cache) = get_cache().lock() {
@@ -153,6 +280,61 @@ pub fn invalidate_cache() {
     log::debug!("Element caches invalidated");
 }
 
+/// Freeze the given element set and hints for the frontmost app until unpinned.
+/// Unlike the caches, pinned elements survive `invalidate_cache` and the TTL.
+pub fn pin_elements<'a>(
+    elements: impl IntoIterator<Item = &'a ClickableElementInternal>,
+) -> Result<(), String> {
+    let pid = get_frontmost_app_pid().ok_or("Could not get frontmost app")?;
+    let elements = elements
+        .into_iter()
+        .map(|e| RawElementData {
+            x: e.element.x,
+            y: e.element.y,
+            width: e.element.width,
+            height: e.element.height,
+            role: e.element.role.clone(),
+            title: e.element.title.clone(),
+        })
+        .collect::<Vec<_>>();
+    log::info!("Pinned {} elements for PID {}", elements.len(), pid);
+    *PINNED_ELEMENTS.lock().map_err(|e| format!("Lock error: {}", e))? =
+        Some(PinnedElements { elements, pid });
+    Ok(())
+}
+
+/// Drop the pinned element set (call when app focus changes).
+/// Returns true if elements were pinned.
+pub fn unpin_elements() -> bool {
+    let was_pinned = PINNED_ELEMENTS
+        .lock()
+        .map(|mut pinned| pinned.take().is_some())
+        .unwrap_or(false);
+    if was_pinned {
+        log::info!("Unpinned click mode elements");
+    }
+    was_pinned
+}
+
+/// Check if an element set is pinned
+pub fn is_pinned() -> bool {
+    PINNED_ELEMENTS.lock().map(|p| p.is_some()).unwrap_or(false)
+}
+
+/// Pinned elements for `pid`; a pin for another app is dropped
+fn get_pinned_elements(pid: i32) -> Option<Vec<RawElementData>> {
+    let mut pinned = PINNED_ELEMENTS.lock().ok()?;
+    match pinned.as_ref() {
+        Some(p) if p.pid == pid => Some(p.elements.clone()),
+        Some(_) => {
+            log::info!("Frontmost app changed, unpinning click mode elements");
+            *pinned = None;
+            None
+        }
+        None => None,
+    }
+}
+
 /// Prefetch elements in background for faster click mode activation
 /// Call this when window focus changes to warm the cache
 pub fn prefetch_elements() {
@@ -217,6 +399,209 @@ pub fn get_frontmost_app_bundle_id() -> Option<String> {
     }
 }
 
+/// Convert an NSString to a Rust String
+unsafe fn nsstring_to_string(ns_string: *mut objc::runtime::Object) -> Option<String> {
+    use objc::{msg_send, sel, sel_impl};
+
+    if ns_string.is_null() {
+        return None;
+    }
+    let utf8: *const std::os::raw::c_char = msg_send![ns_string, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+    std::ffi::CStr::from_ptr(utf8).to_str().ok().map(|s| s.to_string())
+}
+
+/// Get the localized name of the frontmost application (e.g. "Safari")
+fn get_frontmost_app_name() -> Option<String> {
+    unsafe {
+        use objc::{class, msg_send, sel, sel_impl};
+
+        let workspace: *mut objc::runtime::Object =
+            msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return None;
+        }
+
+        let app: *mut objc::runtime::Object = msg_send![workspace, frontmostApplication];
+        if app.is_null() {
+            return None;
+        }
+
+        let name: *mut objc::runtime::Object = msg_send![app, localizedName];
+        nsstring_to_string(name)
+    }
+}
+
+/// Find a running application (NSRunningApplication) by bundle ID
+unsafe fn get_running_app(bundle_id: &str) -> Option<*mut objc::runtime::Object> {
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let c_bundle_id = std::ffi::CString::new(bundle_id).ok()?;
+    let ns_bundle_id: *mut objc::runtime::Object =
+        msg_send![class!(NSString), stringWithUTF8String: c_bundle_id.as_ptr()];
+    if ns_bundle_id.is_null() {
+        return None;
+    }
+
+    let apps: *mut objc::runtime::Object = msg_send![
+        class!(NSRunningApplication),
+        runningApplicationsWithBundleIdentifier: ns_bundle_id
+    ];
+    if apps.is_null() {
+        return None;
+    }
+
+    let app: *mut objc::runtime::Object = msg_send![apps, firstObject];
+    (!app.is_null()).then_some(app)
+}
+
+/// Get the localized name of a running application by bundle ID
+pub fn get_running_app_name(bundle_id: &str) -> Option<String> {
+    unsafe {
+        use objc::{msg_send, sel, sel_impl};
+
+        let app = get_running_app(bundle_id)?;
+        let name: *mut objc::runtime::Object = msg_send![app, localizedName];
+        nsstring_to_string(name)
+    }
+}
+
+/// Get the bundle identifier of a running application by PID
+fn get_app_bundle_id_for_pid(pid: i32) -> Option<String> {
+    unsafe {
+        use objc::{class, msg_send, sel, sel_impl};
+
+        let app: *mut objc::runtime::Object = msg_send![
+            class!(NSRunningApplication),
+            runningApplicationWithProcessIdentifier: pid
+        ];
+        if app.is_null() {
+            return None;
+        }
+
+        let bundle_id: *mut objc::runtime::Object = msg_send![app, bundleIdentifier];
+        nsstring_to_string(bundle_id)
+    }
+}
+
+/// Get the PID of a running application by bundle ID
+fn get_running_app_pid(bundle_id: &str) -> Option<i32> {
+    unsafe {
+        use objc::{msg_send, sel, sel_impl};
+
+        let app = get_running_app(bundle_id)?;
+        let pid: i32 = msg_send![app, processIdentifier];
+        Some(pid)
+    }
+}
+
+/// The frontmost app, kept current by the focus observer so the keyboard
+/// callback doesn't ask NSWorkspace on every key press
+#[derive(Debug, Clone, Default)]
+struct FrontmostApp {
+    bundle_id: Option<String>,
+    pid: Option<i32>,
+}
+
+impl FrontmostApp {
+    fn query() -> Self {
+        Self {
+            bundle_id: get_frontmost_app_bundle_id(),
+            pid: get_frontmost_app_pid(),
+        }
+    }
+}
+
+/// `None` until the first focus change or read
+static FRONTMOST_APP: Mutex<Option<FrontmostApp>> = Mutex::new(None);
+
+/// Re-read the frontmost app (call on focus change)
+pub fn refresh_frontmost_app_cache() {
+    let app = FrontmostApp::query();
+    if let Ok(mut cached) = FRONTMOST_APP.lock() {
+        *cached = Some(app);
+    }
+}
+
+/// The cached frontmost app, queried live when the cache is empty
+fn cached_frontmost_app() -> FrontmostApp {
+    let Ok(mut cached) = FRONTMOST_APP.lock() else {
+        return FrontmostApp::query();
+    };
+    match cached.as_ref() {
+        Some(app) if app.bundle_id.is_some() => app.clone(),
+        _ => cached.insert(FrontmostApp::query()).clone(),
+    }
+}
+
+/// Bundle identifier of the frontmost app, as of the last focus change
+pub fn cached_frontmost_app_bundle_id() -> Option<String> {
+    cached_frontmost_app().bundle_id
+}
+
+/// PID of the frontmost app, as of the last focus change
+pub fn cached_frontmost_app_pid() -> Option<i32> {
+    cached_frontmost_app().pid
+}
+
+/// Reference point for `FOCUS_CHANGED_MS`
+static FOCUS_CLOCK_BASE: OnceLock<Instant> = OnceLock::new();
+
+/// When the focus last moved (an app was activated or the mouse was clicked), as
+/// milliseconds since `FOCUS_CLOCK_BASE` plus one; 0 means never. Atomic because
+/// the event tap reads it on every key.
+static FOCUS_CHANGED_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Note a focus change (call on app activation and mouse clicks)
+pub fn record_focus_change() {
+    let base = *FOCUS_CLOCK_BASE.get_or_init(Instant::now);
+    let ms = base.elapsed().as_millis() as u64;
+    FOCUS_CHANGED_MS.store(ms + 1, Ordering::Relaxed);
+}
+
+/// Whether the focus changed less than `grace` ago. Accessibility focus can still
+/// report the previous element then.
+pub fn is_focus_settling(grace: Duration) -> bool {
+    let changed_at = match FOCUS_CHANGED_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => FOCUS_CLOCK_BASE.get().map(|base| *base + Duration::from_millis(ms - 1)),
+    };
+    is_within(changed_at, grace, Instant::now())
+}
+
+fn is_within(changed_at: Option<Instant>, grace: Duration, now: Instant) -> bool {
+    changed_at.is_some_and(|at| now.saturating_duration_since(at) < grace)
+}
+
+/// Bundle identifier and localized name of the most recently focused app other than ovim itself
+static LAST_EXTERNAL_APP: Mutex<Option<(String, Option<String>)>> = Mutex::new(None);
+
+/// Remember the frontmost app unless it is ovim (call on focus change)
+pub fn record_frontmost_app() {
+    if get_frontmost_app_pid() == Some(std::process::id() as i32) {
+        return;
+    }
+    if let Some(bundle_id) = get_frontmost_app_bundle_id() {
+        let name = get_frontmost_app_name();
+        if let Ok(mut last) = LAST_EXTERNAL_APP.lock() {
+            *last = Some((bundle_id, name));
+        }
+    }
+}
+
+/// Get the frontmost app (bundle ID, localized name),
+/// or the last app used before ovim's own windows took focus
+pub fn get_last_external_app() -> Option<(String, Option<String>)> {
+    if get_frontmost_app_pid() != Some(std::process::id() as i32) {
+        if let Some(bundle_id) = get_frontmost_app_bundle_id() {
+            return Some((bundle_id, get_frontmost_app_name()));
+        }
+    }
+    LAST_EXTERNAL_APP.lock().ok()?.clone()
+}
+
 /// Raw element data from subprocess (matches ax_helper output)
 #[derive(Debug, Clone, serde::Deserialize)]
 struct RawElementData {
@@ -342,6 +727,15 @@ fn query_elements_subprocess(pid: i32) -> Result<(Vec<RawElementData>, bool), St
         .unwrap_or((10, 30, 500));
 
     log::info!("[TIMING] helper_path lookup: {}ms", start.elapsed().as_millis());
+    log::debug!(
+        "Running helper for PID {}: delay={}ms, max_depth={}, max_elements={}",
+        pid,
+        delay_ms,
+        max_depth,
+        max_elements
+    );
+
+    let (added_roles, removed_roles) = role_override_args(pid);
 
     // Run the helper subprocess - single attempt for speed, retry only on failure
     let subprocess_start = Instant::now();
@@ -350,6 +744,9 @@ fn query_elements_subprocess(pid: i32) -> Result<(Vec<RawElementData>, bool), St
         .arg(delay_ms.to_string())
         .arg(max_depth.to_string())
         .arg(max_elements.to_string())
+        .arg(added_roles)
+        .arg(removed_roles)
+        .arg(title_attributes_arg())
         .output();
 
     log::info!("[TIMING] subprocess execution: {}ms", subprocess_start.elapsed().as_millis());
@@ -368,6 +765,11 @@ fn query_elements_subprocess(pid: i32) -> Result<(Vec<RawElementData>, bool), St
         return Err(format!("Helper failed: {}", stderr.trim()));
     }
 
+    // The helper reports its own timings (e.g. menu detection) on stderr
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        log::info!("[helper] {}", line);
+    }
+
     let parse_start = Instant::now();
     let stdout = String::from_utf8_lossy(&output.stdout);
     let helper_output: HelperOutput = serde_json::from_str(&stdout)
@@ -387,6 +789,57 @@ fn query_elements_subprocess(pid: i32) -> Result<(Vec<RawElementData>, bool), St
     Ok((helper_output.elements, is_modal))
 }
 
+/// Dump the whole accessibility tree of the frontmost app (or the last app used
+/// before ovim took focus) as pretty JSON, including elements the clickable filter skips
+pub fn dump_ax_tree() -> Result<String, String> {
+    let (bundle_id, _) = get_last_external_app().ok_or("Could not determine target app")?;
+    let pid = get_running_app_pid(&bundle_id)
+        .ok_or_else(|| format!("{} is not running", bundle_id))?;
+    let helper_path = get_helper_binary_path()
+        .ok_or("Helper binary not found. Please reinstall ovim.")?;
+
+    let (max_depth, max_elements) = get_timing_settings()
+        .lock()
+        .map(|s| (s.max_depth, s.max_elements))
+        .unwrap_or((10, 500));
+
+    let (added_roles, removed_roles) = role_override_args(pid);
+
+    log::info!("Dumping AX tree for {} (PID {})", bundle_id, pid);
+    let output = std::process::Command::new(&helper_path)
+        .arg("--dump")
+        .arg(pid.to_string())
+        .arg("0")
+        .arg(max_depth.to_string())
+        // Skipped elements are reported too, so allow more than the clickable limit
+        .arg((max_elements * 10).to_string())
+        .arg(added_roles)
+        .arg(removed_roles)
+        .arg(title_attributes_arg())
+        .output()
+        .map_err(|e| format!("Failed to run helper: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Helper failed: {}", stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// How long activation waits for the browser JS query in the AX+JS fallback
+const BROWSER_JS_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Chromium's error when "Allow JavaScript from Apple Events" is off
+fn is_js_disabled_error(error: &str) -> bool {
+    error.contains("JavaScript through AppleScript is turned off")
+}
+
+/// Receive from `rx`, giving up at `deadline`
+fn recv_before<T>(rx: &mpsc::Receiver<T>, deadline: Instant) -> Option<T> {
+    rx.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok()
+}
+
 /// Query all clickable elements using a subprocess
 /// This prevents crashes from Objective-C exceptions in the accessibility API
 pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String> {
@@ -395,12 +848,92 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
     let pid = get_frontmost_app_pid().ok_or("Could not get frontmost app")?;
     let bundle_id = get_frontmost_app_bundle_id();
 
+    // Pinned elements keep their hints; skip querying and hint ordering entirely
+    if let Some(pinned) = get_pinned_elements(pid) {
+        log::info!("[TIMING] Using {} pinned elements ({}ms)", pinned.len(), start.elapsed().as_millis());
+        return Ok(to_internal_elements(pinned));
+    }
+
     log::info!("Querying clickable elements for PID {}", pid);
 
+    let order = HINT_ORDER.lock().map(|o| *o).unwrap_or_default();
+    let (elements, timing) =
+        build_clickable_elements(|| query_raw_elements(pid, bundle_id.as_deref(), start), order)?;
+
+    log::info!("[TIMING] Total get_clickable_elements took {}ms ({})", start.elapsed().as_millis(), timing);
+
+    Ok(elements)
+}
+
+/// Time spent in each step of building the clickable element list
+#[derive(Debug, Default, Clone, Copy)]
+struct ActivationTiming {
+    /// Element query (subprocess, JS injection or cache)
+    query: Duration,
+    /// Sorting into hint order
+    ordering: Duration,
+    /// Hint label generation
+    hints: Duration,
+    /// Conversion to `ClickableElementInternal`
+    conversion: Duration,
+}
+
+impl ActivationTiming {
+    /// The pure-Rust steps after the query
+    fn processing(&self) -> Duration {
+        self.ordering + self.hints + self.conversion
+    }
+}
+
+impl std::fmt::Display for ActivationTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "query={:.2?} order={:.2?} hints={:.2?} convert={:.2?} processing={:.2?}",
+            self.query,
+            self.ordering,
+            self.hints,
+            self.conversion,
+            self.processing()
+        )
+    }
+}
+
+/// Query elements from `source`, order them and assign hints, timing each step
+fn build_clickable_elements(
+    source: impl FnOnce() -> Result<Vec<RawElementData>, String>,
+    order: HintOrder,
+) -> Result<(Vec<ClickableElementInternal>, ActivationTiming), String> {
+    let mut timing = ActivationTiming::default();
+
+    let step = Instant::now();
+    let mut elements = source()?;
+    timing.query = step.elapsed();
+    log::info!("Total clickable elements: {}", elements.len());
+
+    let step = Instant::now();
+    order_elements(&mut elements, order);
+    timing.ordering = step.elapsed();
+
+    let step = Instant::now();
+    let hints = element_hints(&elements);
+    timing.hints = step.elapsed();
+
+    let step = Instant::now();
+    let elements = wrap_elements(elements, hints);
+    timing.conversion = step.elapsed();
+
+    Ok((elements, timing))
+}
+
+/// Get raw elements for `pid` from the caches, browser JS injection or the AX subprocess
+fn query_raw_elements(
+    pid: i32,
+    bundle_id: Option<&str>,
+    start: Instant,
+) -> Result<Vec<RawElementData>, String> {
     // Detect browser type early so we can parallelize
-    let browser_type = bundle_id
-        .as_ref()
-        .and_then(|id| super::browser_clickables::detect_browser_type(id));
+    let browser_type = bundle_id.and_then(super::browser_clickables::detect_browser_type);
 
     // Check caches
     let cached_ax = get_cached_elements(pid);
@@ -408,7 +941,7 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
 
     // Browser-fast path: for Chromium browsers, skip AX subprocess entirely
     // and rely only on JS injection for web content. Much faster (~30-60ms vs ~130-200ms).
-    let all_elements: Vec<RawElementData> = if let Some(bt) = browser_type {
+    let elements: Vec<RawElementData> = if let Some(bt) = browser_type {
         if bt.needs_js_injection() {
             // Chromium browser fast path
             if let (Some((cached_ax_els, _)), Some(cached_js_els)) = (&cached_ax, &cached_js) {
@@ -427,7 +960,11 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
                 // JS cache miss - run fast JS-only query
                 log::info!("[TIMING] Browser fast path: JS-only query (no AX subprocess)");
                 let js_start = Instant::now();
-                let js_result = super::browser_clickables::get_browser_clickables_fast(bt);
+                let js_result = if FULL_PAGE_SCAN.load(Ordering::Relaxed) {
+                    super::browser_clickables::get_browser_clickables_full_page(bt)
+                } else {
+                    super::browser_clickables::get_browser_clickables_fast(bt)
+                };
                 log::info!("[TIMING] Fast JS query took {}ms", js_start.elapsed().as_millis());
 
                 match js_result {
@@ -446,25 +983,57 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
                         cache_browser_js_elements(pid, raw_els.clone());
                         raw_els
                     }
+                    Err(e) if is_js_disabled_error(&e) => {
+                        // Retrying JS would fail the same way; AX only, no extra thread
+                        log::warn!("Browser JS is disabled ({}), using AX elements only", e);
+                        match cached_ax {
+                            Some((cached_els, _)) => cached_els,
+                            None => query_elements_subprocess(pid)?.0,
+                        }
+                    }
+                    Err(e) if cached_ax.is_some() => {
+                        // The AX elements are cached, so there is nothing to run in parallel
+                        let previous_web = last_browser_js_elements(pid);
+                        log::warn!(
+                            "Fast JS path failed ({}), using cached AX elements and {} previous web elements",
+                            e,
+                            previous_web.len()
+                        );
+                        let mut els = cached_ax.map(|(cached_els, _)| cached_els).unwrap_or_default();
+                        els.extend(previous_web);
+                        els
+                    }
                     Err(e) => {
                         // Fast path failed, fall back to parallel AX+JS approach
                         log::warn!("Fast JS path failed ({}), falling back to AX+JS parallel", e);
-                        let bt_clone = bt;
-                        let js_handle = std::thread::spawn(move || {
-                            super::browser_clickables::get_browser_clickables(bt_clone)
+                        let deadline = Instant::now() + BROWSER_JS_TIMEOUT;
+                        let (js_tx, js_rx) = mpsc::channel();
+                        std::thread::spawn(move || {
+                            let _ = js_tx.send(super::browser_clickables::get_browser_clickables(bt));
                         });
 
                         let (ax_elements, _is_modal) = query_elements_subprocess(pid)?;
-                        let js_result = js_handle.join().ok().and_then(|r| r.ok());
+                        // A hung osascript must not stall activation: past the deadline,
+                        // continue with AX results and let the thread finish on its own
+                        let js_result = match recv_before(&js_rx, deadline) {
+                            Some(result) => result.ok(),
+                            None => {
+                                log::warn!("Browser JS query timed out after {:?}, using AX elements only", BROWSER_JS_TIMEOUT);
+                                None
+                            }
+                        };
 
                         let mut els = ax_elements;
-                        if let Some(web_els) = js_result {
-                            for wc in web_els {
-                                els.push(RawElementData {
-                                    x: wc.x, y: wc.y, width: wc.width, height: wc.height,
-                                    role: wc.tag, title: wc.text,
-                                });
+                        match js_result {
+                            Some(web_els) => {
+                                for wc in web_els {
+                                    els.push(RawElementData {
+                                        x: wc.x, y: wc.y, width: wc.width, height: wc.height,
+                                        role: wc.tag, title: wc.text,
+                                    });
+                                }
                             }
+                            None => els.extend(last_browser_js_elements(pid)),
                         }
                         els
                     }
@@ -495,11 +1064,74 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
         }
     };
 
-    log::info!("Total clickable elements: {}", all_elements.len());
+    Ok(elements)
+}
+
+/// Query only the frontmost window's controls: title bar buttons, toolbars and tabs
+pub fn get_window_control_elements() -> Result<Vec<ClickableElementInternal>, String> {
+    query_helper_mode("--window-controls", "window controls")
+}
+
+/// Query the frontmost app's menu bar titles, or the items of its open menu bar menu
+pub fn get_menu_bar_elements() -> Result<Vec<ClickableElementInternal>, String> {
+    query_helper_mode("--menu-bar", "menu bar elements")
+}
+
+/// Run the helper in a focused collection `mode` (no settle delay, not cached)
+fn query_helper_mode(mode: &str, label: &str) -> Result<Vec<ClickableElementInternal>, String> {
+    let pid = get_frontmost_app_pid().ok_or("Could not get frontmost app")?;
+    let helper_path = get_helper_binary_path()
+        .ok_or("Helper binary not found. Please reinstall ovim.")?;
+
+    let (max_depth, max_elements) = get_timing_settings()
+        .lock()
+        .map(|s| (s.max_depth, s.max_elements))
+        .unwrap_or((10, 500));
+
+    let (added_roles, removed_roles) = role_override_args(pid);
+
+    log::info!("Querying {} for PID {}", label, pid);
+    let output = std::process::Command::new(&helper_path)
+        .arg(mode)
+        .arg(pid.to_string())
+        .arg("0")
+        .arg(max_depth.to_string())
+        .arg(max_elements.to_string())
+        .arg(added_roles)
+        .arg(removed_roles)
+        .arg(title_attributes_arg())
+        .output()
+        .map_err(|e| format!("Failed to run helper: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Helper failed: {}", stderr.trim()));
+    }
 
-    // Generate hints
-    let hints = generate_hints(all_elements.len(), super::hints::DEFAULT_HINT_CHARS);
+    let helper_output: HelperOutput = serde_json::from_str(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|e| format!("Failed to parse helper output: {}", e))?;
+    log::info!("Found {} {}", helper_output.elements.len(), label);
 
+    let mut elements = helper_output.elements;
+    apply_hint_order(&mut elements);
+    Ok(to_internal_elements(elements))
+}
+
+/// Assign hints and wrap raw elements for the click mode manager
+fn to_internal_elements(all_elements: Vec<RawElementData>) -> Vec<ClickableElementInternal> {
+    let hints = element_hints(&all_elements);
+    wrap_elements(all_elements, hints)
+}
+
+/// Hints for elements in order; elements hidden under an earlier one at the
+/// same position don't get their own, they share its hint
+fn element_hints(elements: &[RawElementData]) -> Vec<String> {
+    let bounds: Vec<_> = elements.iter().map(|e| (e.x, e.y, e.width, e.height)).collect();
+    stack_hints(&bounds)
+}
+
+/// Wrap raw elements with their generated `hints`
+fn wrap_elements(all_elements: Vec<RawElementData>, hints: Vec<String>) -> Vec<ClickableElementInternal> {
     // Log hint length for debugging prefix conflicts
     if let Some(first_hint) = hints.first() {
         log::info!("Hint length: {} chars (first hint: '{}', count: {})",
@@ -508,7 +1140,7 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
 
     // Convert to internal elements
     // Note: No AXElementHandle - clicks will use position-based mouse simulation
-    let elements: Vec<ClickableElementInternal> = all_elements
+    all_elements
         .into_iter()
         .enumerate()
         .map(|(i, elem)| {
@@ -524,11 +1156,7 @@ pub fn get_clickable_elements() -> Result<Vec<ClickableElementInternal>, String>
                 None, // No AX handle in subprocess mode
             )
         })
-        .collect();
-
-    log::info!("[TIMING] Total get_clickable_elements took {}ms", start.elapsed().as_millis());
-
-    Ok(elements)
+        .collect()
 }
 
 // Re-export mouse click functions for backwards compatibility
@@ -536,3 +1164,156 @@ pub use super::mouse::click_at as perform_click_at_position;
 pub use super::mouse::right_click_at as perform_right_click_at_position;
 pub use super::mouse::double_click_at as perform_double_click_at_position;
 pub use super::mouse::cmd_click_at as perform_cmd_click_at_position;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(x: f64, y: f64, title: &str) -> RawElementData {
+        RawElementData {
+            x,
+            y,
+            width: 10.0,
+            height: 10.0,
+            role: "AXButton".to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    /// A grid of `count` elements in reverse reading order, so sorting has work to do
+    fn synthetic_elements(count: usize) -> Vec<RawElementData> {
+        (0..count)
+            .rev()
+            .map(|i| raw((i % 40) as f64 * 30.0, (i / 40) as f64 * 20.0, &format!("Element {}", i)))
+            .collect()
+    }
+
+    /// Element-processing time for `count` stubbed elements: the fastest of a few runs,
+    /// so a busy machine doesn't fail the guard
+    fn best_processing_timing(count: usize) -> ActivationTiming {
+        (0..5)
+            .map(|_| {
+                let source = || Ok(synthetic_elements(count));
+                let (elements, timing) = build_clickable_elements(source, HintOrder::VisualOrder).unwrap();
+                assert_eq!(elements.len(), count);
+                timing
+            })
+            .min_by_key(ActivationTiming::processing)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_build_clickable_elements_orders_and_assigns_hints() {
+        let source = || Ok(vec![raw(50.0, 50.0, "second"), raw(10.0, 10.0, "first")]);
+        let (elements, _) = build_clickable_elements(source, HintOrder::VisualOrder).unwrap();
+        let titles: Vec<&str> = elements.iter().map(|e| e.element.title.as_str()).collect();
+        assert_eq!(titles, vec!["first", "second"]);
+        assert_ne!(elements[0].element.hint, elements[1].element.hint);
+
+        let failing = || Err("no frontmost app".to_string());
+        assert!(build_clickable_elements(failing, HintOrder::VisualOrder).is_err());
+    }
+
+    /// Budget for ordering, hint generation and conversion of `BUDGET_ELEMENT_COUNT`
+    /// elements. Generous enough for debug builds; a regression to quadratic work blows it.
+    const PROCESSING_BUDGET: Duration = Duration::from_millis(50);
+    const BUDGET_ELEMENT_COUNT: usize = 2000;
+
+    /// Latency breakdown for a range of element counts, checked against `PROCESSING_BUDGET`.
+    /// Timing depends on the machine, so this is not part of the normal test run. Run with
+    /// `cargo test --release bench_element_processing -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_element_processing() {
+        for count in [50, 200, 500, 1000, 2000, 5000] {
+            println!("{:>5} elements: {}", count, best_processing_timing(count));
+        }
+
+        let timing = best_processing_timing(BUDGET_ELEMENT_COUNT);
+        assert!(
+            timing.processing() < PROCESSING_BUDGET,
+            "processing {} elements took {}",
+            BUDGET_ELEMENT_COUNT,
+            timing
+        );
+    }
+
+    #[test]
+    fn test_recv_before_gives_up_at_deadline() {
+        let (tx, rx) = mpsc::channel::<u32>();
+        let slow = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            let _ = tx.send(1);
+        });
+        assert_eq!(recv_before(&rx, Instant::now() + Duration::from_millis(20)), None);
+        assert_eq!(recv_before(&rx, Instant::now() + Duration::from_secs(5)), Some(1));
+        slow.join().unwrap();
+    }
+
+    #[test]
+    fn test_is_js_disabled_error() {
+        assert!(is_js_disabled_error(
+            "AppleScript failed: Executing JavaScript through AppleScript is turned off."
+        ));
+        assert!(!is_js_disabled_error("AppleScript failed: Google Chrome got an error"));
+    }
+
+    #[test]
+    fn test_sort_by_position_orders_rows_then_columns() {
+        let mut elements = vec![
+            raw(50.0, 100.0, "bottom-right"),
+            raw(200.0, 10.0, "top-right"),
+            raw(10.0, 100.0, "bottom-left"),
+            raw(20.0, 10.0, "top-left"),
+        ];
+        sort_by_position(&mut elements);
+        let titles: Vec<&str> = elements.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["top-left", "top-right", "bottom-left", "bottom-right"]);
+    }
+
+    #[test]
+    fn test_sort_by_distance_puts_closest_first() {
+        let mut elements = vec![
+            raw(500.0, 500.0, "far"),
+            raw(95.0, 95.0, "near"),
+            raw(200.0, 100.0, "middle"),
+        ];
+        sort_by_distance(&mut elements, (100.0, 100.0));
+        let titles: Vec<&str> = elements.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["near", "middle", "far"]);
+    }
+
+    #[test]
+    fn test_expired_cache_is_not_used() {
+        let populated = Instant::now() - std::time::Duration::from_millis(600);
+        assert!(!is_cache_fresh(42, populated, 42, 500), "TTL exceeded, re-query");
+        assert!(is_cache_fresh(42, populated, 42, 1000), "longer TTL keeps the entry");
+        assert!(!is_cache_fresh(42, Instant::now(), 7, 500), "other app, re-query");
+    }
+
+    #[test]
+    fn test_pinned_elements_are_dropped_for_another_app() {
+        *PINNED_ELEMENTS.lock().unwrap() = Some(PinnedElements {
+            elements: vec![raw(1.0, 2.0, "pinned")],
+            pid: 42,
+        });
+        invalidate_cache();
+
+        let pinned = get_pinned_elements(42).expect("pin survives cache invalidation");
+        assert_eq!(pinned[0].title, "pinned");
+
+        assert!(get_pinned_elements(7).is_none());
+        assert!(!is_pinned());
+        assert!(get_pinned_elements(42).is_none());
+    }
+
+    #[test]
+    fn test_focus_grace_period() {
+        let now = Instant::now();
+        let grace = Duration::from_millis(100);
+        assert!(!is_within(None, grace, now));
+        assert!(is_within(Some(now - Duration::from_millis(40)), grace, now));
+        assert!(!is_within(Some(now - Duration::from_millis(100)), grace, now));
+        assert!(!is_within(Some(now), Duration::ZERO, now));
+    }
+}
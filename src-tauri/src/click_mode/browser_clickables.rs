@@ -3,9 +3,20 @@
 //! Chromium-based browsers (Brave, Chrome, Arc, Edge) don't expose web content
 //! well through the accessibility API. This module uses AppleScript + JavaScript
 //! to query clickable elements directly from the DOM.
+//!
+//! Same-origin iframes are included, offset by the iframe's position. Cross-origin
+//! iframes can't be read from the page's JavaScript and are skipped.
+//!
+//! The optional full-page scan scrolls through the whole page, merging the
+//! clickables found in each viewport, and scrolls back before showing hints.
 
-use std::process::Command;
-use std::time::Instant;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use super::accessibility::get_frontmost_app_bundle_id;
+use crate::nvim_edit::minify_js;
+use crate::osascript;
 
 /// Browser types we support for JavaScript injection
 #[derive(Debug, Clone, Copy)]
@@ -63,17 +74,29 @@ pub struct WebClickable {
     pub text: String,
 }
 
+/// Clickable collection script, recursing into same-origin iframes.
+/// `{{SELECTOR}}` is replaced with the CSS selector for clickable elements.
+const GET_CLICKABLES_JS_TEMPLATE: &str = include_str!("js/get_clickables.js");
+
+/// Basic selector for clickable elements
+const CLICKABLE_SELECTOR: &str =
+    "a[href],button,input,textarea,select,[role=button],[role=link],[onclick],[tabindex]";
+
+/// Expanded selector matching Vimium-style coverage for the fast path
+const CLICKABLE_SELECTOR_EXPANDED: &str = "a[href],button,input,textarea,select,[role=button],[role=link],[role=tab],[role=checkbox],[role=menuitem],[role=menuitemcheckbox],[role=menuitemradio],[role=radio],[role=textbox],[onclick],[tabindex],[contenteditable],label,summary,details";
+
+fn build_clickables_js(selector: &str) -> String {
+    minify_js(&GET_CLICKABLES_JS_TEMPLATE.replace("{{SELECTOR}}", selector))
+}
+
 /// Combined JavaScript that gets both viewport info and clickable elements in one call
 /// Returns JSON: {"vh": viewportHeight, "els": [...clickables]}
-const GET_ALL_JS: &str = r#"(function(){var r=[];var seen=new Set();var s="a[href],button,input,textarea,select,[role=button],[role=link],[onclick],[tabindex]";var els=document.querySelectorAll(s);for(var i=0;i<els.length&&r.length<200;i++){var el=els[i];var rect=el.getBoundingClientRect();if(rect.width<=0||rect.height<=0)continue;if(rect.top>window.innerHeight||rect.bottom<0)continue;if(rect.left>window.innerWidth||rect.right<0)continue;var k=Math.round(rect.left)+","+Math.round(rect.top);if(seen.has(k))continue;seen.add(k);var t=el.textContent||el.value||el.placeholder||"";t=t.trim().substring(0,50);r.push({x:rect.left,y:rect.top,width:rect.width,height:rect.height,tag:el.tagName.toLowerCase(),text:t});}return JSON.stringify({vh:window.innerHeight,els:r});})()
-"#;
+static GET_ALL_JS: LazyLock<String> = LazyLock::new(|| build_clickables_js(CLICKABLE_SELECTOR));
 
-/// JavaScript that returns screen coordinates alongside elements, eliminating the need
-/// for System Events window position queries. Returns JSON with screen position info.
-/// Expanded selector matches Vimium-style coverage.
-/// Expanded selector matching Vimium-style coverage for the fast path.
+/// Same as `GET_ALL_JS` with the expanded selector.
 /// Returns viewport height + elements. Window position comes from AppleScript bounds.
-const GET_ALL_JS_EXPANDED: &str = r#"(function(){var r=[];var seen=new Set();var s="a[href],button,input,textarea,select,[role=button],[role=link],[role=tab],[role=checkbox],[role=menuitem],[role=menuitemcheckbox],[role=menuitemradio],[role=radio],[role=textbox],[onclick],[tabindex],[contenteditable],label,summary,details";var els=document.querySelectorAll(s);for(var i=0;i<els.length&&r.length<200;i++){var el=els[i];var rect=el.getBoundingClientRect();if(rect.width<=0||rect.height<=0)continue;if(rect.top>window.innerHeight||rect.bottom<0)continue;if(rect.left>window.innerWidth||rect.right<0)continue;var k=Math.round(rect.left)+","+Math.round(rect.top);if(seen.has(k))continue;seen.add(k);var t=el.textContent||el.value||el.placeholder||"";t=t.trim().substring(0,50);r.push({x:rect.left,y:rect.top,width:rect.width,height:rect.height,tag:el.tagName.toLowerCase(),text:t});}return JSON.stringify({vh:window.innerHeight,els:r});})()"#;
+static GET_ALL_JS_EXPANDED: LazyLock<String> =
+    LazyLock::new(|| build_clickables_js(CLICKABLE_SELECTOR_EXPANDED));
 
 /// Combined result from browser query (used with System Events window position)
 #[derive(Debug, serde::Deserialize)]
@@ -87,9 +110,71 @@ struct BrowserQueryResult {
 #[derive(Debug, serde::Deserialize)]
 pub struct BrowserQueryResultFull {
     pub vh: f64,
+    /// Vertical scroll offset of the page
+    #[serde(default)]
+    pub sy: f64,
+    /// Total scrollable height of the page
+    #[serde(default)]
+    pub sh: f64,
     pub els: Vec<WebClickable>,
 }
 
+/// Parsed fast query: JS result plus where the web content sits on screen
+struct FastQuery {
+    win_x: f64,
+    /// Screen y of the top of the web content (below the browser toolbar)
+    content_top: f64,
+    result: BrowserQueryResultFull,
+}
+
+/// Most viewports visited by the full-page scan
+const MAX_PAGE_SCANS: usize = 10;
+
+/// Visible web content area after a full-page scan, in screen coordinates.
+/// Clicks outside it scroll the page first.
+#[derive(Debug, Clone)]
+pub struct PageView {
+    browser_type: BrowserType,
+    /// Browser that was frontmost during the scan
+    bundle_id: Option<String>,
+    top: f64,
+    bottom: f64,
+}
+
+static PAGE_VIEW: Mutex<Option<PageView>> = Mutex::new(None);
+
+/// Merges clickables from successive viewports into page coordinates
+#[derive(Debug, Default)]
+struct PageMerge {
+    clickables: Vec<WebClickable>,
+    /// Signatures in page coordinates
+    seen: HashSet<(String, i64, i64, String)>,
+    /// Signatures in viewport coordinates, to skip fixed headers seen in every viewport
+    seen_in_viewport: HashSet<(String, i64, i64, String)>,
+}
+
+impl PageMerge {
+    /// Add one viewport's clickables (viewport coordinates) found at `scroll_y`
+    fn add(&mut self, clickables: Vec<WebClickable>, scroll_y: f64) {
+        for mut c in clickables {
+            let viewport_key = signature(&c, c.y);
+            c.y += scroll_y;
+            let page_key = signature(&c, c.y);
+            if self.seen_in_viewport.contains(&viewport_key) || self.seen.contains(&page_key) {
+                continue;
+            }
+            self.seen_in_viewport.insert(viewport_key);
+            self.seen.insert(page_key);
+            self.clickables.push(c);
+        }
+    }
+}
+
+/// Stable identity of a clickable at a given y
+fn signature(c: &WebClickable, y: f64) -> (String, i64, i64, String) {
+    (c.tag.clone(), c.x.round() as i64, y.round() as i64, c.text.clone())
+}
+
 /// Build a combined AppleScript that gets window info AND runs JS in one osascript call
 fn build_combined_chrome_script(app_name: &str, js: &str) -> String {
     format!(
@@ -145,6 +230,10 @@ return winInfo & "|" & jsResult"#,
     )
 }
 
+/// Time allowed for one fast-path script. A hung browser or permission prompt
+/// fails the query instead of stalling click mode, which falls back to AX.
+const FAST_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Build a fast AppleScript for Chromium browsers that gets window bounds + JS in one call,
 /// without going through System Events (which adds latency).
 fn build_fast_chrome_script(app_name: &str, js: &str) -> String {
@@ -168,79 +257,13 @@ return winInfo & "|" & jsResult"#,
     )
 }
 
-/// Execute AppleScript in-process using NSAppleScript (avoids ~240ms osascript subprocess overhead)
-fn execute_applescript_inline(source: &str) -> Result<String, String> {
-    unsafe {
-        use objc::{class, msg_send, sel, sel_impl};
-
-        let ns_string_class = class!(NSString);
-        let c_source = std::ffi::CString::new(source).map_err(|e| format!("Invalid script: {}", e))?;
-        let source_nsstring: *mut objc::runtime::Object = msg_send![
-            ns_string_class,
-            stringWithUTF8String: c_source.as_ptr()
-        ];
-
-        if source_nsstring.is_null() {
-            return Err("Failed to create NSString".to_string());
-        }
-
-        let script_class = class!(NSAppleScript);
-        let script: *mut objc::runtime::Object = msg_send![script_class, alloc];
-        let script: *mut objc::runtime::Object = msg_send![script, initWithSource: source_nsstring];
-
-        if script.is_null() {
-            return Err("Failed to create NSAppleScript".to_string());
-        }
-
-        let mut error: *mut objc::runtime::Object = std::ptr::null_mut();
-        let result: *mut objc::runtime::Object = msg_send![script, executeAndReturnError: &mut error as *mut _];
-
-        if result.is_null() || !error.is_null() {
-            let err_msg = if !error.is_null() {
-                let desc: *mut objc::runtime::Object = msg_send![error, description];
-                if !desc.is_null() {
-                    let utf8: *const std::os::raw::c_char = msg_send![desc, UTF8String];
-                    if !utf8.is_null() {
-                        std::ffi::CStr::from_ptr(utf8).to_string_lossy().to_string()
-                    } else {
-                        "Unknown error".to_string()
-                    }
-                } else {
-                    "Unknown error".to_string()
-                }
-            } else {
-                "Script returned nil".to_string()
-            };
-            let _: () = msg_send![script, release];
-            return Err(format!("AppleScript error: {}", err_msg));
-        }
-
-        let string_value: *mut objc::runtime::Object = msg_send![result, stringValue];
-        let output = if !string_value.is_null() {
-            let utf8: *const std::os::raw::c_char = msg_send![string_value, UTF8String];
-            if !utf8.is_null() {
-                std::ffi::CStr::from_ptr(utf8).to_string_lossy().trim().to_string()
-            } else {
-                String::new()
-            }
-        } else {
-            String::new()
-        };
-
-        let _: () = msg_send![script, release];
-        Ok(output)
-    }
-}
-
-/// Query clickable elements using the fast path (no System Events, no osascript subprocess).
-/// Gets window bounds from the browser's own AppleScript and elements from JS.
-pub fn get_browser_clickables_fast(browser_type: BrowserType) -> Result<Vec<WebClickable>, String> {
-    log::info!("Querying web clickables (fast path) from {:?}", browser_type);
-
-    let script = build_fast_chrome_script(browser_type.app_name(), GET_ALL_JS_EXPANDED);
+/// Run JS through the fast path and parse window bounds plus the clickables result.
+/// Returns None when the page returned nothing.
+fn run_fast_query(browser_type: BrowserType, js: &str) -> Result<Option<FastQuery>, String> {
+    let script = build_fast_chrome_script(browser_type.app_name(), js);
 
     let start = Instant::now();
-    let stdout = execute_applescript_inline(&script)?;
+    let stdout = osascript::run_in_process_timeout(&script, FAST_QUERY_TIMEOUT)?;
     log::info!("[TIMING] In-process AppleScript took {}ms", start.elapsed().as_millis());
 
     log::debug!("Fast AppleScript output: {}", &stdout[..stdout.len().min(200)]);
@@ -264,8 +287,7 @@ pub fn get_browser_clickables_fast(browser_type: BrowserType) -> Result<Vec<WebC
 
     let js_result = parts[1];
     if js_result.is_empty() || js_result == "null" || js_result == "missing value" {
-        log::info!("No clickables returned from browser (fast path)");
-        return Ok(Vec::new());
+        return Ok(None);
     }
 
     let result: BrowserQueryResultFull = serde_json::from_str(js_result)
@@ -278,13 +300,31 @@ pub fn get_browser_clickables_fast(browser_type: BrowserType) -> Result<Vec<WebC
         win_x, win_y, win_height, result.vh, chrome_height, result.els.len()
     );
 
+    Ok(Some(FastQuery {
+        win_x,
+        content_top: win_y + chrome_height,
+        result,
+    }))
+}
+
+/// Query clickable elements using the fast path (no System Events, no osascript subprocess).
+/// Gets window bounds from the browser's own AppleScript and elements from JS.
+pub fn get_browser_clickables_fast(browser_type: BrowserType) -> Result<Vec<WebClickable>, String> {
+    log::info!("Querying web clickables (fast path) from {:?}", browser_type);
+    *PAGE_VIEW.lock().unwrap() = None;
+
+    let Some(query) = run_fast_query(browser_type, &GET_ALL_JS_EXPANDED)? else {
+        log::info!("No clickables returned from browser (fast path)");
+        return Ok(Vec::new());
+    };
+
     // Convert viewport-relative coordinates to screen coordinates
     // Same logic as the original get_browser_clickables
-    let screen_clickables: Vec<WebClickable> = result.els
+    let screen_clickables: Vec<WebClickable> = query.result.els
         .into_iter()
         .map(|mut c| {
-            c.x += win_x;
-            c.y += win_y + chrome_height;
+            c.x += query.win_x;
+            c.y += query.content_top;
             c
         })
         .collect();
@@ -292,6 +332,118 @@ pub fn get_browser_clickables_fast(browser_type: BrowserType) -> Result<Vec<WebC
     Ok(screen_clickables)
 }
 
+/// Query clickables from the whole page by scrolling through it one viewport at a time.
+/// The page is scrolled back afterwards; elements below or above the visible area get
+/// off-screen positions and are scrolled into view by `reveal_point` before clicking.
+pub fn get_browser_clickables_full_page(browser_type: BrowserType) -> Result<Vec<WebClickable>, String> {
+    log::info!("Querying web clickables (full page) from {:?}", browser_type);
+    *PAGE_VIEW.lock().unwrap() = None;
+
+    let Some(first) = run_fast_query(browser_type, &GET_ALL_JS_EXPANDED)? else {
+        log::info!("No clickables returned from browser (full page)");
+        return Ok(Vec::new());
+    };
+    let origin_y = first.result.sy;
+    let (win_x, content_top, viewport_height) = (first.win_x, first.content_top, first.result.vh);
+
+    let mut merge = PageMerge::default();
+    let (mut scroll_y, mut page_height) = (first.result.sy, first.result.sh);
+    merge.add(first.result.els, scroll_y);
+
+    for _ in 1..MAX_PAGE_SCANS {
+        if scroll_y + viewport_height >= page_height {
+            break;
+        }
+        let js = format!("window.scrollTo(window.scrollX,{});{}", scroll_y + viewport_height, *GET_ALL_JS_EXPANDED);
+        let Some(page) = run_fast_query(browser_type, &js)? else {
+            break;
+        };
+        // The page refused to scroll further
+        if page.result.sy <= scroll_y {
+            break;
+        }
+        scroll_y = page.result.sy;
+        page_height = page.result.sh;
+        merge.add(page.result.els, scroll_y);
+    }
+
+    let restore = format!("window.scrollTo(window.scrollX,{});null", origin_y);
+    let script = build_fast_chrome_script(browser_type.app_name(), &restore);
+    if let Err(e) = osascript::run_in_process_timeout(&script, FAST_QUERY_TIMEOUT) {
+        log::warn!("Failed to restore scroll position after full-page scan: {}", e);
+    }
+
+    *PAGE_VIEW.lock().unwrap() = Some(PageView {
+        browser_type,
+        bundle_id: get_frontmost_app_bundle_id(),
+        top: content_top,
+        bottom: content_top + viewport_height,
+    });
+
+    log::info!("Full-page scan found {} clickables", merge.clickables.len());
+
+    // Page coordinates to screen coordinates at the restored scroll position
+    Ok(merge
+        .clickables
+        .into_iter()
+        .map(|mut c| {
+            c.x += win_x;
+            c.y += content_top - origin_y;
+            c
+        })
+        .collect())
+}
+
+/// Viewport of the current click mode session's full-page scan, if any
+pub fn page_view() -> Option<PageView> {
+    PAGE_VIEW.lock().unwrap().clone()
+}
+
+/// Forget the full-page scan (call when click mode is deactivated)
+pub fn clear_page_view() {
+    *PAGE_VIEW.lock().unwrap() = None;
+}
+
+/// Scroll the page so an off-screen point from the full-page scan in `view` is
+/// visible. Returns the point's new screen position (unchanged if no scroll was
+/// needed, or another app is frontmost now).
+pub fn reveal_point(view: Option<&PageView>, x: f64, y: f64) -> (f64, f64) {
+    let Some(view) = view else {
+        return (x, y);
+    };
+    if y >= view.top && y <= view.bottom {
+        return (x, y);
+    }
+    if get_frontmost_app_bundle_id() != view.bundle_id {
+        log::warn!("Frontmost app changed since the full-page scan, not scrolling");
+        return (x, y);
+    }
+
+    // Center the point in the viewport; the page may scroll less near its ends
+    let delta = y - (view.top + view.bottom) / 2.0;
+    let js = format!(
+        "(function(){{var b=window.scrollY;window.scrollBy(0,{});return String(window.scrollY-b);}})()",
+        delta
+    );
+    let script = build_fast_chrome_script(view.browser_type.app_name(), &js);
+    let scrolled = osascript::run_in_process_timeout(&script, FAST_QUERY_TIMEOUT)
+        .ok()
+        .and_then(|out| out.split_once('|').and_then(|(_, d)| d.parse::<f64>().ok()));
+
+    match scrolled {
+        Some(scrolled) => {
+            clear_page_view();
+            // Give the page a moment to repaint before clicking
+            std::thread::sleep(Duration::from_millis(50));
+            (x, y - scrolled)
+        }
+        None => {
+            log::warn!("Failed to scroll element into view");
+            (x, y)
+        }
+    }
+}
+
 /// Query clickable elements from the browser's web content
 /// Uses a single combined AppleScript call for speed
 pub fn get_browser_clickables(browser_type: BrowserType) -> Result<Vec<WebClickable>, String> {
@@ -299,16 +451,13 @@ pub fn get_browser_clickables(browser_type: BrowserType) -> Result<Vec<WebClicka
 
     // Build combined script that gets window info AND clickables in one call
     let script = match browser_type {
-        BrowserType::Safari => build_combined_safari_script(GET_ALL_JS),
+        BrowserType::Safari => build_combined_safari_script(&GET_ALL_JS),
         BrowserType::Chrome | BrowserType::Brave | BrowserType::Arc => {
-            build_combined_chrome_script(browser_type.app_name(), GET_ALL_JS)
+            build_combined_chrome_script(browser_type.app_name(), &GET_ALL_JS)
         }
     };
 
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT)
         .map_err(|e| format!("Failed to execute AppleScript: {}", e))?;
 
     if !output.status.success() {
@@ -385,6 +534,44 @@ mod tests {
         assert!(detect_browser_type("com.example.unknown").is_none());
     }
 
+    #[test]
+    fn test_clickables_js_uses_selector() {
+        assert!(GET_ALL_JS.contains(CLICKABLE_SELECTOR));
+        assert!(GET_ALL_JS_EXPANDED.contains(CLICKABLE_SELECTOR_EXPANDED));
+        assert!(!GET_ALL_JS.contains("{{SELECTOR}}"));
+        assert!(!GET_ALL_JS.contains("//"));
+    }
+
+    fn clickable(y: f64, text: &str) -> WebClickable {
+        WebClickable {
+            x: 10.0,
+            y,
+            width: 50.0,
+            height: 20.0,
+            tag: "a".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_page_merge_dedupes_across_viewports() {
+        let mut merge = PageMerge::default();
+        merge.add(vec![clickable(100.0, "Home"), clickable(700.0, "More")], 0.0);
+        // "More" is seen again after scrolling 500px; "Next" is new
+        merge.add(vec![clickable(200.0, "More"), clickable(600.0, "Next")], 500.0);
+
+        let ys: Vec<f64> = merge.clickables.iter().map(|c| c.y).collect();
+        assert_eq!(ys, vec![100.0, 700.0, 1100.0]);
+    }
+
+    #[test]
+    fn test_page_merge_skips_fixed_elements() {
+        let mut merge = PageMerge::default();
+        merge.add(vec![clickable(0.0, "Menu")], 0.0);
+        merge.add(vec![clickable(0.0, "Menu")], 800.0);
+        assert_eq!(merge.clickables.len(), 1);
+    }
+
     #[test]
     fn test_needs_js_injection() {
         assert!(!BrowserType::Safari.needs_js_injection());
@@ -9,10 +9,14 @@
 /// Excludes r, c, d, n which are reserved for action switching (right-click, cmd-click, double-click, normal click)
 pub const DEFAULT_HINT_CHARS: &str = "asfghjklqwetyuiopzxvbm";
 
+/// Longest hint built only from hint characters before falling back to digits
+pub const MAX_HINT_LENGTH: usize = 3;
+
 /// Generate hint labels for a given number of elements
 ///
-/// All hints have the same length to avoid prefix conflicts.
-/// Uses shortest possible length that can generate enough unique hints.
+/// All hints have the same length, so no hint is a prefix of another.
+/// Uses shortest possible length (up to `MAX_HINT_LENGTH`) that can generate enough unique hints.
+/// If the characters can't cover `count` within that length, a fixed-width digit suffix is appended.
 ///
 /// # Arguments
 /// * `count` - Number of hints needed
@@ -25,56 +29,68 @@ pub fn generate_hints(count: usize, chars: &str) -> Vec<String> {
         return Vec::new();
     }
 
-    let chars: Vec<char> = chars.chars().collect();
-    if chars.is_empty() {
-        return (0..count).map(|i| i.to_string()).collect();
+    // Duplicate characters would produce identical hints
+    let mut unique: Vec<char> = Vec::new();
+    for c in chars.chars().map(|c| c.to_ascii_uppercase()) {
+        if !c.is_whitespace() && !unique.contains(&c) {
+            unique.push(c);
+        }
     }
+    let chars = unique;
 
-    let base = chars.len();
+    if chars.is_empty() {
+        let width = digit_width(count);
+        return (0..count).map(|i| format!("{:0width$}", i, width = width)).collect();
+    }
 
     // Calculate minimum hint length needed
     // With N chars, we can have N^1 single-char, N^2 two-char, etc.
-    let hint_length = if count <= base {
-        1
-    } else if count <= base * base {
-        2
-    } else {
-        3 // Should be enough for most cases (26^3 = 17576)
-    };
+    let mut hint_length = 1;
+    let mut capacity = chars.len();
+    while capacity < count && hint_length < MAX_HINT_LENGTH {
+        hint_length += 1;
+        capacity = capacity.saturating_mul(chars.len());
+    }
 
-    let mut hints = Vec::with_capacity(count);
+    if capacity >= count {
+        return (0..count).map(|i| encode_hint(i, &chars, hint_length)).collect();
+    }
 
-    match hint_length {
-        1 => {
-            for c in chars.iter().take(count) {
-                hints.push(c.to_string().to_uppercase());
-            }
-        }
-        2 => {
-            'outer: for c1 in &chars {
-                for c2 in &chars {
-                    if hints.len() >= count {
-                        break 'outer;
-                    }
-                    hints.push(format!("{}{}", c1, c2).to_uppercase());
-                }
-            }
-        }
-        _ => {
-            'outer: for c1 in &chars {
-                for c2 in &chars {
-                    for c3 in &chars {
-                        if hints.len() >= count {
-                            break 'outer;
-                        }
-                        hints.push(format!("{}{}{}", c1, c2, c3).to_uppercase());
-                    }
-                }
-            }
-        }
+    // Alphabet exhausted: share each letter hint between a fixed-width range of digits
+    let width = digit_width(count.div_ceil(capacity));
+    let per_hint = 10usize.pow(width as u32);
+    (0..count)
+        .map(|i| {
+            format!(
+                "{}{:0width$}",
+                encode_hint(i / per_hint, &chars, hint_length),
+                i % per_hint,
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// Encode an index as a fixed-length hint, most significant character first
+fn encode_hint(mut index: usize, chars: &[char], length: usize) -> String {
+    let base = chars.len();
+    let mut hint = vec![chars[0]; length];
+    for slot in hint.iter_mut().rev() {
+        *slot = chars[index % base];
+        index /= base;
     }
+    hint.into_iter().collect()
+}
 
-    hints
+/// Number of decimal digits needed to number `count` items from zero
+fn digit_width(count: usize) -> usize {
+    let mut width = 1;
+    let mut capacity = 10usize;
+    while capacity < count {
+        width += 1;
+        capacity = capacity.saturating_mul(10);
+    }
+    width
 }
 
 /// Check if a hint matches the current input buffer
@@ -160,6 +176,53 @@ mod tests {
         }
     }
 
+    fn assert_prefix_free(hints: &[String]) {
+        for (i, h1) in hints.iter().enumerate() {
+            for (j, h2) in hints.iter().enumerate() {
+                if i != j {
+                    assert!(!h2.starts_with(h1.as_str()), "{} is prefix of {}", h1, h2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefix_free_at_high_counts() {
+        for count in [1, 22, 23, 200, 484, 485, 1000] {
+            let hints = generate_hints(count, DEFAULT_HINT_CHARS);
+            assert_eq!(hints.len(), count);
+            assert_prefix_free(&hints);
+        }
+    }
+
+    #[test]
+    fn test_three_char_hints() {
+        let hints = generate_hints(5, "ab");
+        assert_eq!(hints, vec!["AAA", "AAB", "ABA", "ABB", "BAA"]);
+    }
+
+    #[test]
+    fn test_digit_fallback_when_alphabet_runs_out() {
+        // "ab" covers 8 three-char hints, so 20 elements need digit suffixes
+        let hints = generate_hints(20, "ab");
+        assert_eq!(hints.len(), 20);
+        assert_eq!(hints[0], "AAA0");
+        assert_eq!(hints[19], "AAB9");
+        assert!(hints.iter().all(|h| h.len() == 4));
+        assert_prefix_free(&hints);
+    }
+
+    #[test]
+    fn test_duplicate_and_empty_chars() {
+        let hints = generate_hints(4, "aAb");
+        assert_eq!(hints, vec!["AA", "AB", "BA", "BB"]);
+
+        let hints = generate_hints(12, "");
+        assert_eq!(hints[0], "00");
+        assert_eq!(hints[11], "11");
+        assert_prefix_free(&hints);
+    }
+
     #[test]
     fn test_generate_empty() {
         let hints = generate_hints(0, "abc");
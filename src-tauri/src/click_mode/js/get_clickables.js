@@ -0,0 +1,72 @@
+// Collect visible clickable elements in the page and its same-origin iframes
+// Returns JSON: {vh: viewportHeight, sy: scrollY, sh: scrollHeight, els: [{x, y, width, height, tag, text}]}
+// Coordinates are relative to the top-level viewport.
+(function () {
+  var selector = "{{SELECTOR}}";
+  var results = [];
+  var seen = new Set();
+
+  function collect(doc, offsetX, offsetY) {
+    var view = doc.defaultView || window;
+    var els = doc.querySelectorAll(selector);
+    for (var i = 0; i < els.length && results.length < 200; i++) {
+      var el = els[i];
+      var rect = el.getBoundingClientRect();
+      if (rect.width <= 0 || rect.height <= 0) continue;
+      // Must be visible inside its own frame
+      if (rect.top > view.innerHeight || rect.bottom < 0) continue;
+      if (rect.left > view.innerWidth || rect.right < 0) continue;
+
+      var x = rect.left + offsetX;
+      var y = rect.top + offsetY;
+      // And inside the top-level viewport
+      if (y > window.innerHeight || y + rect.height < 0) continue;
+      if (x > window.innerWidth || x + rect.width < 0) continue;
+
+      var key = Math.round(x) + "," + Math.round(y);
+      if (seen.has(key)) continue;
+      seen.add(key);
+
+      var text = el.textContent || el.value || el.placeholder || "";
+      results.push({
+        x: x,
+        y: y,
+        width: rect.width,
+        height: rect.height,
+        tag: el.tagName.toLowerCase(),
+        text: text.trim().substring(0, 50),
+      });
+    }
+
+    // Recurse into iframes. Only same-origin frames are readable: cross-origin
+    // frames return a null contentDocument or throw, and are skipped.
+    var frames = doc.querySelectorAll("iframe,frame");
+    for (var j = 0; j < frames.length && results.length < 200; j++) {
+      var frame = frames[j];
+      var frameDoc = null;
+      try {
+        frameDoc = frame.contentDocument;
+      } catch (e) {
+        frameDoc = null;
+      }
+      if (!frameDoc) continue;
+
+      // Frame content starts inside the frame's border
+      var frameRect = frame.getBoundingClientRect();
+      collect(
+        frameDoc,
+        offsetX + frameRect.left + frame.clientLeft,
+        offsetY + frameRect.top + frame.clientTop
+      );
+    }
+  }
+
+  collect(document, 0, 0);
+  var root = document.documentElement;
+  return JSON.stringify({
+    vh: window.innerHeight,
+    sy: window.scrollY,
+    sh: Math.max(root.scrollHeight, document.body ? document.body.scrollHeight : 0),
+    els: results,
+  });
+})();
@@ -9,7 +9,12 @@ pub mod element;
 pub mod hints;
 pub mod mouse;
 pub mod native_hints;
+pub mod overlap;
+pub mod search;
+pub mod undo;
 
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 pub use element::{ClickableElement, ClickableElementInternal};
@@ -28,6 +33,10 @@ pub enum ClickAction {
     CmdClick,
     /// Double-click
     DoubleClick,
+    /// Copy the element's text to the clipboard instead of clicking
+    Copy,
+    /// Collect the texts of several elements, copied together on Enter
+    BatchCopy,
 }
 
 impl ClickAction {
@@ -38,6 +47,8 @@ impl ClickAction {
             ClickAction::RightClick => "right",
             ClickAction::CmdClick => "cmd",
             ClickAction::DoubleClick => "double",
+            ClickAction::Copy => "copy",
+            ClickAction::BatchCopy => "batch",
         }
     }
 }
@@ -124,6 +135,44 @@ pub enum HintInputResult {
     NoMatch,
 }
 
+/// Outcome of ranking the current search query
+#[derive(Debug, Clone)]
+pub enum SearchMatch {
+    /// No element matches the query
+    None,
+    /// A single element has the highest rank
+    Best(ClickableElement),
+    /// Several elements share the highest rank
+    Tied(Vec<ClickableElement>),
+}
+
+/// How to deliver a click to an element
+#[derive(Debug)]
+pub enum ClickTarget {
+    /// Synthetic mouse click at a screen position
+    Position(f64, f64),
+    /// AXPress on the element's own handle (direct mode only)
+    Press(crate::nvim_edit::accessibility::AXElementHandle),
+}
+
+/// Whether an activation is querying elements (between `set_activating` and the query result)
+static ACTIVATING: AtomicBool = AtomicBool::new(false);
+
+/// Set when the activation is cancelled while its query is running
+static ACTIVATION_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Cancel an activation whose element query is still running.
+/// Doesn't take the manager lock, which the activation thread holds during the query.
+/// Returns false if no activation is in progress.
+pub fn cancel_activation() -> bool {
+    if !ACTIVATING.load(Ordering::SeqCst) {
+        return false;
+    }
+    ACTIVATION_CANCELLED.store(true, Ordering::SeqCst);
+    log::info!("Click mode: activation cancelled");
+    true
+}
+
 /// Manager for click mode state and elements
 pub struct ClickModeManager {
     /// Current state
@@ -132,6 +181,10 @@ pub struct ClickModeManager {
     elements: Vec<ClickableElementInternal>,
     /// Current click action type
     click_action: ClickAction,
+    /// Elements hidden under a shown element at the same position (sharing its hint)
+    stacked: Vec<ClickableElementInternal>,
+    /// Element texts collected in batch copy, in selection order
+    batch_copy: Vec<String>,
 }
 
 impl ClickModeManager {
@@ -140,6 +193,8 @@ impl ClickModeManager {
             state: ClickModeState::Inactive,
             elements: Vec::new(),
             click_action: ClickAction::Click,
+            stacked: Vec::new(),
+            batch_copy: Vec::new(),
         }
     }
 
@@ -157,6 +212,8 @@ impl ClickModeManager {
     /// This ensures keys are captured while elements are being queried
     pub fn set_activating(&mut self) {
         log::info!("Click mode: set to activating state");
+        ACTIVATION_CANCELLED.store(false, Ordering::SeqCst);
+        ACTIVATING.store(true, Ordering::SeqCst);
         self.click_action = ClickAction::Click; // Reset to default
         self.state = ClickModeState::ShowingHints {
             input_buffer: String::new(),
@@ -171,9 +228,37 @@ impl ClickModeManager {
     /// Returns the elements for display in the overlay
     pub fn activate(&mut self) -> Result<Vec<ClickableElement>, String> {
         log::info!("Activating click mode");
+        self.activate_with(accessibility::get_clickable_elements)
+    }
+
+    /// Activate click mode with only the frontmost window's controls
+    /// (title bar buttons, toolbars and tabs)
+    pub fn activate_window_controls(&mut self) -> Result<Vec<ClickableElement>, String> {
+        log::info!("Activating click mode for window controls");
+        self.activate_with(accessibility::get_window_control_elements)
+    }
+
+    /// Activate click mode with the frontmost app's menu bar titles,
+    /// or the items of its open menu bar menu
+    pub fn activate_menu_bar(&mut self) -> Result<Vec<ClickableElement>, String> {
+        log::info!("Activating click mode for the menu bar");
+        self.activate_with(accessibility::get_menu_bar_elements)
+    }
 
+    fn activate_with(
+        &mut self,
+        query: fn() -> Result<Vec<ClickableElementInternal>, String>,
+    ) -> Result<Vec<ClickableElement>, String> {
         // Query clickable elements from the frontmost app
-        let internal_elements = accessibility::get_clickable_elements()?;
+        let result = query();
+
+        // The query can't be interrupted, so a cancelled activation discards its result
+        ACTIVATING.store(false, Ordering::SeqCst);
+        if ACTIVATION_CANCELLED.swap(false, Ordering::SeqCst) {
+            self.deactivate();
+            return Err("Activation was cancelled".to_string());
+        }
+        let internal_elements = result?;
 
         if internal_elements.is_empty() {
             log::warn!("No clickable elements found");
@@ -182,6 +267,7 @@ impl ClickModeManager {
         }
 
         log::info!("Found {} clickable elements", internal_elements.len());
+        let (internal_elements, stacked) = split_stacked(internal_elements);
 
         // Convert to serializable elements for frontend
         let elements: Vec<ClickableElement> = internal_elements
@@ -193,6 +279,7 @@ impl ClickModeManager {
 
         // Store internal elements and update state
         self.elements = internal_elements;
+        self.stacked = stacked;
         self.state = ClickModeState::ShowingHints {
             input_buffer: String::new(),
             element_count,
@@ -206,9 +293,13 @@ impl ClickModeManager {
     /// Deactivate click mode
     pub fn deactivate(&mut self) {
         log::info!("Deactivating click mode");
+        ACTIVATING.store(false, Ordering::SeqCst);
         self.state = ClickModeState::Inactive;
         self.elements.clear();
         self.click_action = ClickAction::Click;
+        self.stacked.clear();
+        self.batch_copy.clear();
+        browser_clickables::clear_page_view();
     }
 
     /// Handle a character input in hint mode
@@ -290,12 +381,45 @@ impl ClickModeManager {
         HintInputResult::NoMatch
     }
 
-    /// Get the center position of an element by ID
-    pub fn get_element_position(&self, element_id: usize) -> Option<(f64, f64)> {
-        self.elements
+    /// Show the next element of each stack of overlapping elements in place of
+    /// the current one. Returns the elements to show, or None if nothing overlaps.
+    pub fn cycle_overlapping(&mut self) -> Option<Vec<ClickableElement>> {
+        if self.stacked.is_empty() {
+            return None;
+        }
+
+        for shown in self.elements.iter_mut() {
+            let hint = &shown.element.hint;
+            if let Some(i) = self.stacked.iter().position(|e| &e.element.hint == hint) {
+                let next = self.stacked.remove(i);
+                self.stacked.push(std::mem::replace(shown, next));
+            }
+        }
+        Some(self.get_all_elements())
+    }
+
+    /// Resolve how to click an element. Stacked elements are pressed through their
+    /// AX handle when available; otherwise the click point is nudged off center.
+    pub fn click_target(&self, element_id: usize, action: ClickAction) -> Option<ClickTarget> {
+        let element = self.elements.iter().find(|e| e.element.id == element_id)?;
+        let hidden: Vec<usize> = self
+            .stacked
             .iter()
-            .find(|e| e.element.id == element_id)
-            .map(|e| e.center())
+            .filter(|e| e.element.hint == element.element.hint)
+            .map(|e| e.element.id)
+            .collect();
+        if hidden.is_empty() {
+            let (x, y) = element.center();
+            return Some(ClickTarget::Position(x, y));
+        }
+
+        if let (ClickAction::Click, Some(handle)) = (action, element.ax_element.clone()) {
+            return Some(ClickTarget::Press(handle));
+        }
+        // Ids follow discovery order, so this is the element's place in its stack
+        let index = hidden.iter().filter(|id| **id < element_id).count();
+        let (x, y) = overlap::nudged_center(&element.element, index);
+        Some(ClickTarget::Position(x, y))
     }
 
     /// Get a clone of the AXElementHandle for an element by ID (if available)
@@ -316,7 +440,56 @@ impl ClickModeManager {
 
         // Use position-based click (works for both subprocess and direct modes)
         let (x, y) = element.center();
-        accessibility::perform_click_at_position(x, y)
+        let page_view = browser_clickables::page_view();
+        let (x, y) = browser_clickables::reveal_point(page_view.as_ref(), x, y);
+        accessibility::perform_click_at_position(x, y)?;
+        undo::record_click();
+        Ok(())
+    }
+
+    /// Copy an element's text to the clipboard and return it.
+    pub fn copy_element(&self, element_id: usize) -> Result<String, String> {
+        let text = self.element_text(element_id)?;
+        crate::nvim_edit::clipboard::set_clipboard_content(&text).map_err(|e| e.to_string())?;
+        Ok(text)
+    }
+
+    /// Add an element's text to the batch copy. Returns the number of texts collected.
+    pub fn add_to_batch_copy(&mut self, element_id: usize) -> Result<usize, String> {
+        let text = self.element_text(element_id)?;
+        self.batch_copy.push(text);
+        Ok(self.batch_copy.len())
+    }
+
+    /// Copy the collected batch texts to the clipboard, one per line.
+    /// Returns the number of texts copied.
+    pub fn finish_batch_copy(&mut self) -> Result<usize, String> {
+        if self.batch_copy.is_empty() {
+            return Err("No element texts collected".to_string());
+        }
+        crate::nvim_edit::clipboard::set_clipboard_content(&self.batch_copy.join("\n"))
+            .map_err(|e| e.to_string())?;
+        Ok(std::mem::take(&mut self.batch_copy).len())
+    }
+
+    /// Text of an element for copying.
+    /// The live AX value or title is preferred over the title captured on activation.
+    fn element_text(&self, element_id: usize) -> Result<String, String> {
+        let element = self
+            .elements
+            .iter()
+            .find(|e| e.element.id == element_id)
+            .ok_or_else(|| format!("Element {} not found", element_id))?;
+
+        let text = element
+            .ax_element
+            .as_ref()
+            .and_then(crate::nvim_edit::accessibility::get_element_text)
+            .unwrap_or_else(|| element.element.title.clone());
+        if text.trim().is_empty() {
+            return Err(format!("Element {} has no text to copy", element_id));
+        }
+        Ok(text)
     }
 
     /// Perform right-click on element by ID
@@ -344,18 +517,61 @@ impl ClickModeManager {
         };
     }
 
+    /// Leave search mode and return to hint selection with an empty buffer
+    pub fn exit_search_mode(&mut self) {
+        if !self.state.is_searching() {
+            return;
+        }
+        self.state = ClickModeState::ShowingHints {
+            input_buffer: String::new(),
+            element_count: self.elements.len(),
+            click_action: self.click_action,
+            wrong_second_key: false,
+        };
+    }
+
+    /// Switch to hint selection for the current elements, assigning fresh hints
+    /// in their current order. Returns the elements to show.
+    pub fn enter_hint_mode(&mut self) -> Vec<ClickableElement> {
+        if !self.is_active() {
+            return Vec::new();
+        }
+        let labels = hints::generate_hints(self.elements.len(), hints::DEFAULT_HINT_CHARS);
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        for (element, hint) in self.elements.iter_mut().zip(labels) {
+            let old = std::mem::replace(&mut element.element.hint, hint.clone());
+            renamed.insert(old, hint);
+        }
+        // Hidden elements keep sharing the hint of the element they are under
+        for element in &mut self.stacked {
+            if let Some(hint) = renamed.get(&element.element.hint) {
+                element.element.hint = hint.clone();
+            }
+        }
+        self.state = ClickModeState::ShowingHints {
+            input_buffer: String::new(),
+            element_count: self.elements.len(),
+            click_action: self.click_action,
+            wrong_second_key: false,
+        };
+        self.get_all_elements()
+    }
+
+    /// Switch between hint and search mode, keeping the discovered elements
+    pub fn toggle_search_mode(&mut self) {
+        if self.state.is_searching() {
+            self.enter_hint_mode();
+        } else {
+            self.enter_search_mode();
+        }
+    }
+
     /// Handle search input
     pub fn handle_search_input(&mut self, query: &str) -> Vec<ClickableElement> {
-        let query_lower = query.to_lowercase();
-
         let matching: Vec<ClickableElement> = self
-            .elements
-            .iter()
-            .filter(|e| {
-                e.element.title.to_lowercase().contains(&query_lower)
-                    || e.element.role.to_lowercase().contains(&query_lower)
-            })
-            .map(|e| e.to_serializable())
+            .ranked_search_matches(query)
+            .into_iter()
+            .map(|(_, e)| e.to_serializable())
             .collect();
 
         self.state = ClickModeState::Searching {
@@ -367,6 +583,78 @@ impl ClickModeManager {
         matching
     }
 
+    /// Elements matching a search query, best match first
+    fn ranked_search_matches(&self, query: &str) -> Vec<(u32, &ClickableElementInternal)> {
+        let mut matches: Vec<(u32, &ClickableElementInternal)> = self
+            .elements
+            .iter()
+            .filter_map(|e| {
+                search::match_score(&e.element.title, &e.element.role, query).map(|score| (score, e))
+            })
+            .collect();
+        search::sort_by_score(&mut matches);
+        matches
+    }
+
+    /// Find the top-ranked element for the current search query
+    pub fn best_search_match(&self) -> SearchMatch {
+        let ClickModeState::Searching { query, .. } = &self.state else {
+            return SearchMatch::None;
+        };
+
+        let matches = self.ranked_search_matches(query);
+        let Some(&(top_score, _)) = matches.first() else {
+            return SearchMatch::None;
+        };
+
+        let mut best: Vec<ClickableElement> = matches
+            .into_iter()
+            .take_while(|(score, _)| *score == top_score)
+            .map(|(_, e)| e.to_serializable())
+            .collect();
+
+        if best.len() == 1 {
+            SearchMatch::Best(best.remove(0))
+        } else {
+            SearchMatch::Tied(best)
+        }
+    }
+
+    /// Keep only the given elements and return to hint selection over them
+    pub fn narrow_to_elements(&mut self, element_ids: &[usize]) {
+        self.elements.retain(|e| element_ids.contains(&e.element.id));
+        let elements = &self.elements;
+        self.stacked
+            .retain(|hidden| elements.iter().any(|e| e.element.hint == hidden.element.hint));
+        self.state = ClickModeState::ShowingHints {
+            input_buffer: String::new(),
+            element_count: self.elements.len(),
+            click_action: self.click_action,
+            wrong_second_key: false,
+        };
+    }
+
+    /// Pin the shown elements so later activations reuse them, or unpin them.
+    /// Returns whether elements are pinned afterwards.
+    pub fn toggle_pin(&self) -> Result<bool, String> {
+        if accessibility::unpin_elements() {
+            return Ok(false);
+        }
+        if self.elements.is_empty() {
+            return Err("No elements to pin".to_string());
+        }
+        accessibility::pin_elements(self.elements.iter().chain(&self.stacked))?;
+        Ok(true)
+    }
+
+    /// Clear the typed hint characters so another hint can be selected
+    pub fn clear_hint_input(&mut self) {
+        if let ClickModeState::ShowingHints { input_buffer, wrong_second_key, .. } = &mut self.state {
+            input_buffer.clear();
+            *wrong_second_key = false;
+        }
+    }
+
     /// Clear input buffer (backspace)
     pub fn clear_last_input(&mut self) {
         match &mut self.state {
@@ -395,17 +683,11 @@ impl ClickModeManager {
                         .collect()
                 }
             }
-            ClickModeState::Searching { query, .. } => {
-                let query_lower = query.to_lowercase();
-                self.elements
-                    .iter()
-                    .filter(|e| {
-                        e.element.title.to_lowercase().contains(&query_lower)
-                            || e.element.role.to_lowercase().contains(&query_lower)
-                    })
-                    .map(|e| e.to_serializable())
-                    .collect()
-            }
+            ClickModeState::Searching { query, .. } => self
+                .ranked_search_matches(query)
+                .into_iter()
+                .map(|(_, e)| e.to_serializable())
+                .collect(),
         }
     }
 
@@ -455,6 +737,17 @@ impl ClickModeManager {
     }
 }
 
+/// Split off elements that share a hint with an earlier element: they are hidden
+/// under it (see `overlap::stack_hints`) and only shown by cycling
+fn split_stacked(
+    elements: Vec<ClickableElementInternal>,
+) -> (Vec<ClickableElementInternal>, Vec<ClickableElementInternal>) {
+    let mut seen: HashSet<String> = HashSet::new();
+    elements
+        .into_iter()
+        .partition(|e| seen.insert(e.element.hint.clone()))
+}
+
 impl Default for ClickModeManager {
     fn default() -> Self {
         Self::new()
@@ -464,6 +757,38 @@ impl Default for ClickModeManager {
 /// Thread-safe wrapper for ClickModeManager
 pub type SharedClickModeManager = Arc<Mutex<ClickModeManager>>;
 
+#[cfg(test)]
+impl ClickModeManager {
+    /// A manager showing hints `H0`, `H1`, ... for elements with the given titles
+    pub(crate) fn showing_hints(titles: &[&str]) -> Self {
+        let mut mgr = ClickModeManager::new();
+        mgr.elements = titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                ClickableElementInternal::new(
+                    i,
+                    format!("H{}", i),
+                    0.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                    "AXButton".to_string(),
+                    title.to_string(),
+                    None,
+                )
+            })
+            .collect();
+        mgr.state = ClickModeState::ShowingHints {
+            input_buffer: String::new(),
+            element_count: titles.len(),
+            click_action: ClickAction::Click,
+            wrong_second_key: false,
+        };
+        mgr
+    }
+}
+
 /// Create a new shared click mode manager
 pub fn create_manager() -> SharedClickModeManager {
     Arc::new(Mutex::new(ClickModeManager::new()))
@@ -564,3 +889,140 @@ where
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn searching_manager(titles: &[&str], query: &str) -> ClickModeManager {
+        let mut mgr = ClickModeManager::showing_hints(titles);
+        mgr.enter_search_mode();
+        mgr.handle_search_input(query);
+        mgr
+    }
+
+    #[test]
+    fn test_batch_copy_collects_texts_in_order() {
+        let mut mgr = searching_manager(&["Price", "", "Total"], "");
+        assert_eq!(mgr.add_to_batch_copy(2), Ok(1));
+        assert!(mgr.add_to_batch_copy(1).is_err());
+        assert_eq!(mgr.add_to_batch_copy(0), Ok(2));
+        assert_eq!(mgr.batch_copy, vec!["Total", "Price"]);
+
+        mgr.deactivate();
+        assert!(mgr.batch_copy.is_empty());
+        assert!(mgr.finish_batch_copy().is_err());
+    }
+
+    #[test]
+    fn test_best_search_match_prefers_higher_rank() {
+        let mgr = searching_manager(&["Autosave", "Save As", "Save"], "save");
+        match mgr.best_search_match() {
+            SearchMatch::Best(element) => assert_eq!(element.title, "Save"),
+            other => panic!("expected best match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_best_search_match_reports_ties_and_misses() {
+        let mgr = searching_manager(&["Save As", "Save All", "Open"], "save");
+        match mgr.best_search_match() {
+            SearchMatch::Tied(elements) => assert_eq!(elements.len(), 2),
+            other => panic!("expected tie, got {:?}", other),
+        }
+
+        let mgr = searching_manager(&["Open"], "save");
+        assert!(matches!(mgr.best_search_match(), SearchMatch::None));
+    }
+
+    #[test]
+    fn test_cancelled_activation_discards_elements() {
+        fn query() -> Result<Vec<ClickableElementInternal>, String> {
+            Ok(searching_manager(&["Save"], "").elements)
+        }
+
+        let mut mgr = ClickModeManager::new();
+        assert!(!cancel_activation());
+
+        mgr.set_activating();
+        assert!(cancel_activation());
+        assert!(mgr.activate_with(query).is_err());
+        assert!(!mgr.is_active());
+
+        // A later activation isn't affected by the earlier cancel
+        mgr.set_activating();
+        assert_eq!(mgr.activate_with(query).map(|e| e.len()), Ok(1));
+        assert!(!cancel_activation());
+    }
+
+    #[test]
+    fn test_toggle_search_mode_keeps_elements() {
+        let mut mgr = searching_manager(&["Save As", "Save All", "Open"], "save");
+        mgr.toggle_search_mode();
+        assert!(mgr.state().is_showing_hints());
+        assert_eq!(mgr.get_current_input(), "");
+        assert_eq!(mgr.get_filtered_elements().len(), 3);
+
+        mgr.toggle_search_mode();
+        assert!(mgr.state().is_searching());
+        assert_eq!(mgr.get_all_elements().len(), 3);
+    }
+
+    #[test]
+    fn test_enter_hint_mode_regenerates_hints() {
+        let mut mgr = searching_manager(&["Save As", "Save All", "Open"], "save");
+        mgr.narrow_to_elements(&[1, 2]);
+        let hints: Vec<String> = mgr.enter_hint_mode().into_iter().map(|e| e.hint).collect();
+        assert_eq!(hints, hints::generate_hints(2, hints::DEFAULT_HINT_CHARS));
+    }
+
+    #[test]
+    fn test_narrow_to_elements_returns_to_hints() {
+        let mut mgr = searching_manager(&["Save As", "Save All", "Open"], "save");
+        mgr.narrow_to_elements(&[0, 1]);
+        assert!(mgr.state().is_showing_hints());
+        assert_eq!(mgr.get_all_elements().len(), 2);
+    }
+
+    #[test]
+    fn test_cycle_overlapping_swaps_stacked_elements() {
+        let elements = ["H0", "H1", "H0"]
+            .iter()
+            .enumerate()
+            .map(|(i, hint)| {
+                ClickableElementInternal::new(
+                    i,
+                    hint.to_string(),
+                    0.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                    "AXButton".to_string(),
+                    String::new(),
+                    None,
+                )
+            })
+            .collect();
+        let mut mgr = ClickModeManager::new();
+        (mgr.elements, mgr.stacked) = split_stacked(elements);
+        let ids = |elements: Vec<ClickableElement>| elements.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(mgr.get_all_elements()), vec![0, 1]);
+
+        assert_eq!(mgr.cycle_overlapping().map(ids), Some(vec![2, 1]));
+        assert!(matches!(
+            mgr.click_target(2, ClickAction::Click),
+            Some(ClickTarget::Position(x, y)) if x == 7.0 && y == 7.0
+        ));
+        assert!(matches!(
+            mgr.click_target(1, ClickAction::Click),
+            Some(ClickTarget::Position(x, y)) if x == 5.0 && y == 5.0
+        ));
+        assert_eq!(mgr.cycle_overlapping().map(ids), Some(vec![0, 1]));
+
+        // Hidden elements follow the element they are under when hints change
+        mgr.narrow_to_elements(&[0]);
+        let hint = mgr.enter_hint_mode()[0].hint.clone();
+        assert_eq!(mgr.stacked.len(), 1);
+        assert_eq!(mgr.stacked[0].element.hint, hint);
+    }
+}
@@ -6,6 +6,8 @@ use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGEventTyp
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 use core_graphics::geometry::CGPoint;
 
+use crate::keyboard::mark_injected;
+
 /// Perform a left-click at a specific position
 pub fn click_at(x: f64, y: f64) -> Result<(), String> {
     log::info!("Performing mouse click at position ({}, {})", x, y);
@@ -106,6 +108,14 @@ pub fn cmd_click_at(x: f64, y: f64) -> Result<(), String> {
     Ok(())
 }
 
+/// Current mouse cursor position in screen coordinates (top-left origin)
+pub fn cursor_position() -> Option<(f64, f64)> {
+    let source = create_event_source().ok()?;
+    let event = CGEvent::new(source).ok()?;
+    let point = event.location();
+    Some((point.x, point.y))
+}
+
 // Helper functions
 
 fn create_event_source() -> Result<CGEventSource, String> {
@@ -124,8 +134,10 @@ fn create_mouse_event(
         _ => CGMouseButton::Left,
     };
 
-    CGEvent::new_mouse_event(source.clone(), event_type, point, button)
-        .map_err(|_| format!("Could not create {:?} event", event_type))
+    let event = CGEvent::new_mouse_event(source.clone(), event_type, point, button)
+        .map_err(|_| format!("Could not create {:?} event", event_type))?;
+    mark_injected(&event);
+    Ok(event)
 }
 
 fn post_mouse_event(
@@ -136,6 +148,7 @@ fn post_mouse_event(
 ) -> Result<(), String> {
     let event = CGEvent::new_mouse_event(source.clone(), event_type, point, button)
         .map_err(|_| format!("Could not create {:?} event", event_type))?;
+    mark_injected(&event);
     event.post(CGEventTapLocation::HID);
     Ok(())
 }
@@ -12,6 +12,7 @@ use objc::{class, msg_send, sel, sel_impl};
 use std::sync::Mutex;
 
 use super::element::ClickableElement;
+use crate::config::click_mode::ClickModeSettings;
 
 // ============================================================================
 // Types
@@ -33,6 +34,8 @@ struct WindowPool {
     windows: Vec<PooledWindow>,
     /// How many windows from the pool are currently in use (visible)
     active_count: usize,
+    /// Whether the active hints include element labels
+    show_labels: bool,
 }
 
 /// Global window pool
@@ -41,6 +44,9 @@ static WINDOW_POOL: Mutex<Option<WindowPool>> = Mutex::new(None);
 /// Max pool size - pre-create this many windows
 const POOL_SIZE: usize = 200;
 
+/// Max characters of an element title shown next to a hint
+const MAX_LABEL_CHARS: usize = 20;
+
 /// Style settings for hint windows
 #[derive(Clone)]
 pub struct HintStyle {
@@ -48,6 +54,8 @@ pub struct HintStyle {
     pub bg_color: (f64, f64, f64),
     pub text_color: (f64, f64, f64),
     pub opacity: f64,
+    /// Show the element title next to the hint characters
+    pub show_labels: bool,
 }
 
 impl Default for HintStyle {
@@ -57,10 +65,34 @@ impl Default for HintStyle {
             bg_color: (1.0, 0.8, 0.0),
             text_color: (0.0, 0.0, 0.0),
             opacity: 0.95,
+            show_labels: false,
+        }
+    }
+}
+
+impl HintStyle {
+    /// Style toggles taken from the user's click mode settings
+    pub fn from_settings(settings: &ClickModeSettings) -> Self {
+        Self {
+            show_labels: settings.show_element_labels,
+            ..Self::default()
         }
     }
 }
 
+/// Style of the hints currently on screen, for re-showing a subset of them
+pub fn current_style() -> HintStyle {
+    let show_labels = WINDOW_POOL
+        .lock()
+        .ok()
+        .and_then(|pool| pool.as_ref().map(|p| p.show_labels))
+        .unwrap_or(false);
+    HintStyle {
+        show_labels,
+        ..HintStyle::default()
+    }
+}
+
 // ============================================================================
 // Pool Initialization
 // ============================================================================
@@ -84,6 +116,7 @@ pub fn init_pool() {
             *pool = Some(WindowPool {
                 windows: pool_windows,
                 active_count: 0,
+                show_labels: false,
             });
         }
 
@@ -205,8 +238,9 @@ unsafe fn create_pooled_window(style: &HintStyle) -> Option<PooledWindow> {
 // ============================================================================
 
 /// Show native hint windows for the given elements using the pre-created pool
-pub fn show_hints(elements: &[ClickableElement], _style: &HintStyle) {
+pub fn show_hints(elements: &[ClickableElement], style: &HintStyle) {
     let start = std::time::Instant::now();
+    let show_labels = style.show_labels;
 
     let elements = elements.to_vec();
     let element_count = elements.len();
@@ -258,7 +292,8 @@ pub fn show_hints(elements: &[ClickableElement], _style: &HintStyle) {
                         continue;
                     }
 
-                    let width = (element.hint.len() as f64 * char_width).max(20.0) + 8.0;
+                    let text = hint_text(&element.hint, &element.title, show_labels);
+                    let width = (text.chars().count() as f64 * char_width).max(20.0) + 8.0;
                     let cocoa_y = screen_height - element.y - hint_height;
 
                     if i < 3 {
@@ -274,7 +309,7 @@ pub fn show_hints(elements: &[ClickableElement], _style: &HintStyle) {
 
                     unsafe {
                         // Update text
-                        let nsstring = create_nsstring(&element.hint);
+                        let nsstring = create_nsstring(&text);
                         let _: () = msg_send![tf, setStringValue: nsstring];
 
                         // Resize text field
@@ -297,6 +332,7 @@ pub fn show_hints(elements: &[ClickableElement], _style: &HintStyle) {
                 }
 
                 pool.active_count = count;
+                pool.show_labels = show_labels;
 
                 log::info!(
                     "Showed {} hint windows from pool (screen_height={})",
@@ -363,12 +399,15 @@ pub fn filter_hints(input: &str, elements: &[ClickableElement]) {
 pub fn filter_hints_with_input(input: &str, elements: &[ClickableElement]) {
     let input_upper = input.to_uppercase();
     let input_len = input_upper.len();
-    let hints: Vec<String> = elements.iter().map(|e| e.hint.clone()).collect();
+    let hints: Vec<(String, String)> = elements
+        .iter()
+        .map(|e| (e.hint.clone(), e.title.clone()))
+        .collect();
 
     Queue::main().exec_async(move || {
         if let Ok(pool) = WINDOW_POOL.lock() {
             if let Some(ref pool) = *pool {
-                for (i, hint) in hints.iter().enumerate() {
+                for (i, (hint, title)) in hints.iter().enumerate() {
                     if i < pool.windows.len() && i < pool.active_count {
                         let w = pool.windows[i].window.0;
                         let tf = pool.windows[i].text_field.0;
@@ -381,7 +420,8 @@ pub fn filter_hints_with_input(input: &str, elements: &[ClickableElement]) {
                             set_window_visibility(w, true);
                             if input_len > 0 && hint.len() > input_len && !tf.is_null() {
                                 unsafe {
-                                    let nsstring = create_nsstring(&hint[input_len..]);
+                                    let text = hint_text(&hint[input_len..], title, pool.show_labels);
+                                    let nsstring = create_nsstring(&text);
                                     let _: () = msg_send![tf, setStringValue: nsstring];
                                 }
                             }
@@ -395,6 +435,29 @@ pub fn filter_hints_with_input(input: &str, elements: &[ClickableElement]) {
     });
 }
 
+/// Show only the hints whose element ids are in `visible_ids` (search mode)
+pub fn filter_hints_by_ids(visible_ids: &[usize], elements: &[ClickableElement]) {
+    let visibility: Vec<bool> = elements
+        .iter()
+        .map(|e| visible_ids.contains(&e.id))
+        .collect();
+
+    Queue::main().exec_async(move || {
+        if let Ok(pool) = WINDOW_POOL.lock() {
+            if let Some(ref pool) = *pool {
+                for (i, visible) in visibility.iter().enumerate() {
+                    if i < pool.windows.len() && i < pool.active_count {
+                        let w = pool.windows[i].window.0;
+                        if !w.is_null() {
+                            set_window_visibility(w, *visible);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
 /// Trigger shake animation on all visible hint windows
 pub fn shake_hints() {
     Queue::main().exec_async(|| {
@@ -422,6 +485,30 @@ pub fn shake_hints() {
 // Helpers
 // ============================================================================
 
+/// Text shown in a hint window: the hint characters, optionally followed by the element title
+fn hint_text(hint: &str, title: &str, show_labels: bool) -> String {
+    if !show_labels {
+        return hint.to_string();
+    }
+    match truncate_label(title) {
+        Some(label) => format!("{} {}", hint, label),
+        None => hint.to_string(),
+    }
+}
+
+/// Collapse whitespace in an element title and truncate it; None for empty titles
+fn truncate_label(title: &str) -> Option<String> {
+    let label = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if label.is_empty() {
+        return None;
+    }
+    if label.chars().count() <= MAX_LABEL_CHARS {
+        return Some(label);
+    }
+    let truncated: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+    Some(format!("{}\u{2026}", truncated.trim_end()))
+}
+
 fn get_primary_screen_height() -> Option<f64> {
     unsafe {
         let screens: *mut objc::runtime::Object = msg_send![class!(NSScreen), screens];
@@ -490,3 +577,29 @@ unsafe fn create_nsstring(s: &str) -> *mut objc::runtime::Object {
     let len = s.len();
     msg_send![nsstring, initWithBytes: bytes length: len encoding: 4u64]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_text_without_labels() {
+        assert_eq!(hint_text("AS", "Save", false), "AS");
+    }
+
+    #[test]
+    fn test_hint_text_skips_empty_titles() {
+        assert_eq!(hint_text("AS", "", true), "AS");
+        assert_eq!(hint_text("AS", "  \n ", true), "AS");
+        assert_eq!(hint_text("AS", "Save", true), "AS Save");
+    }
+
+    #[test]
+    fn test_truncate_label() {
+        assert_eq!(truncate_label("Open\n  Recent"), Some("Open Recent".to_string()));
+        let long = "A very long button title that keeps going";
+        let label = truncate_label(long).unwrap();
+        assert!(label.chars().count() <= MAX_LABEL_CHARS);
+        assert!(label.ends_with('\u{2026}'));
+    }
+}
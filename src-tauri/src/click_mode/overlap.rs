@@ -0,0 +1,121 @@
+//! Handling of elements stacked at the same position
+//!
+//! Some apps stack several clickable elements on nearly the same coordinates,
+//! so their hints overlap and a position click always hits the same one.
+//! Only the first element at each position gets its own hint; the ones hidden
+//! under it share that hint, and click mode cycles which of them it targets.
+
+use std::collections::HashMap;
+
+use super::element::ClickableElement;
+use super::hints::{generate_hints, DEFAULT_HINT_CHARS};
+
+/// Centers closer than this (in points) count as the same position
+const OVERLAP_GRID: f64 = 4.0;
+
+/// Distance between nudged click points for stacked elements
+const OVERLAP_NUDGE: f64 = 2.0;
+
+/// Element bounds as (x, y, width, height)
+pub type Bounds = (f64, f64, f64, f64);
+
+/// Grid cell of an element's center
+fn position_key((x, y, width, height): Bounds) -> (i64, i64) {
+    let cx = x + width / 2.0;
+    let cy = y + height / 2.0;
+    ((cx / OVERLAP_GRID).round() as i64, (cy / OVERLAP_GRID).round() as i64)
+}
+
+/// For each element, the index of the first element at its position
+/// (its own index when nothing earlier covers it)
+pub fn stack_tops(bounds: &[Bounds]) -> Vec<usize> {
+    let mut first_at: HashMap<(i64, i64), usize> = HashMap::new();
+    bounds
+        .iter()
+        .enumerate()
+        .map(|(i, b)| *first_at.entry(position_key(*b)).or_insert(i))
+        .collect()
+}
+
+/// Hints for elements with `bounds`. Labels are generated only for elements
+/// on top of their position; elements hidden under them reuse their hint.
+pub fn stack_hints(bounds: &[Bounds]) -> Vec<String> {
+    let tops = stack_tops(bounds);
+    let top_count = tops.iter().enumerate().filter(|(i, top)| i == *top).count();
+    let mut labels = generate_hints(top_count, DEFAULT_HINT_CHARS).into_iter();
+
+    let mut hints: Vec<String> = Vec::with_capacity(bounds.len());
+    for (i, top) in tops.into_iter().enumerate() {
+        let hint = if i == top {
+            labels.next().unwrap_or_default()
+        } else {
+            hints[top].clone()
+        };
+        hints.push(hint);
+    }
+    hints
+}
+
+/// Click point for a stacked element, moved slightly off center for members
+/// after the first so repeated position clicks can reach different elements.
+/// The point always stays inside the element.
+pub fn nudged_center(element: &ClickableElement, index: usize) -> (f64, f64) {
+    let nudge = index as f64 * OVERLAP_NUDGE;
+    let max_x = (element.width / 2.0 - 1.0).max(0.0);
+    let max_y = (element.height / 2.0 - 1.0).max(0.0);
+    (
+        element.x + element.width / 2.0 + nudge.min(max_x),
+        element.y + element.height / 2.0 + nudge.min(max_y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(id: usize, x: f64, y: f64) -> ClickableElement {
+        ClickableElement {
+            id,
+            hint: String::new(),
+            x,
+            y,
+            width: 20.0,
+            height: 10.0,
+            role: "AXButton".to_string(),
+            title: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_stack_tops() {
+        let bounds = [
+            (0.0, 0.0, 20.0, 10.0),
+            (100.0, 0.0, 20.0, 10.0),
+            (1.0, 0.5, 20.0, 10.0),
+        ];
+        assert_eq!(stack_tops(&bounds), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_hidden_elements_share_hints() {
+        let bounds = [
+            (0.0, 0.0, 20.0, 10.0),
+            (100.0, 0.0, 20.0, 10.0),
+            (0.0, 0.0, 20.0, 10.0),
+        ];
+        let labels = generate_hints(2, DEFAULT_HINT_CHARS);
+        assert_eq!(
+            stack_hints(&bounds),
+            vec![labels[0].clone(), labels[1].clone(), labels[0].clone()]
+        );
+        assert!(stack_hints(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_nudged_center_stays_inside_element() {
+        let e = element(0, 0.0, 0.0);
+        assert_eq!(nudged_center(&e, 0), (10.0, 5.0));
+        assert_eq!(nudged_center(&e, 1), (12.0, 7.0));
+        assert_eq!(nudged_center(&e, 10), (19.0, 9.0));
+    }
+}
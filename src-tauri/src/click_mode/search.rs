@@ -0,0 +1,78 @@
+//! Search ranking for click mode
+//!
+//! Scores elements against a search query so the best match can be
+//! clicked directly with Enter.
+
+/// Title equals the query
+const SCORE_EXACT: u32 = 100;
+/// Title starts with the query
+const SCORE_PREFIX: u32 = 80;
+/// A word inside the title starts with the query
+const SCORE_WORD_PREFIX: u32 = 60;
+/// Title contains the query anywhere
+const SCORE_SUBSTRING: u32 = 40;
+/// Only the role contains the query
+const SCORE_ROLE: u32 = 10;
+
+/// Score an element's title and role against a search query.
+/// Returns None when the element does not match. An empty query matches everything equally.
+pub fn match_score(title: &str, role: &str, query: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let title = title.trim().to_lowercase();
+    if title == query {
+        return Some(SCORE_EXACT);
+    }
+    if title.starts_with(&query) {
+        return Some(SCORE_PREFIX);
+    }
+    if title
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(&query))
+    {
+        return Some(SCORE_WORD_PREFIX);
+    }
+    if title.contains(&query) {
+        return Some(SCORE_SUBSTRING);
+    }
+    if role.to_lowercase().contains(&query) {
+        return Some(SCORE_ROLE);
+    }
+    None
+}
+
+/// Sort `(score, item)` pairs best first, keeping discovery order for equal scores
+pub fn sort_by_score<T>(matches: &mut [(u32, T)]) {
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_tiers() {
+        assert_eq!(match_score("Save", "AXButton", "save"), Some(SCORE_EXACT));
+        assert_eq!(match_score("Save As", "AXButton", "save"), Some(SCORE_PREFIX));
+        assert_eq!(match_score("Quick Save", "AXButton", "save"), Some(SCORE_WORD_PREFIX));
+        assert_eq!(match_score("Autosave", "AXButton", "save"), Some(SCORE_SUBSTRING));
+        assert_eq!(match_score("OK", "AXButton", "button"), Some(SCORE_ROLE));
+        assert_eq!(match_score("OK", "AXButton", "save"), None);
+    }
+
+    #[test]
+    fn test_empty_query_matches_all_equally() {
+        assert_eq!(match_score("Save", "AXButton", ""), Some(0));
+        assert_eq!(match_score("", "AXLink", "  "), Some(0));
+    }
+
+    #[test]
+    fn test_sort_keeps_order_for_ties() {
+        let mut matches = vec![(40, "a"), (80, "b"), (40, "c"), (80, "d")];
+        sort_by_score(&mut matches);
+        assert_eq!(matches, vec![(80, "b"), (80, "d"), (40, "a"), (40, "c")]);
+    }
+}
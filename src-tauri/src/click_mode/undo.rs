@@ -0,0 +1,55 @@
+//! Best-effort undo for click mode clicks
+//!
+//! Arbitrary UI actions can't be reverted, so undo only injects Cmd+Z into the
+//! frontmost app shortly after a click mode click. Whether that reverts the
+//! click depends entirely on the app.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::keyboard::{KeyInjector, SystemInjector};
+
+/// How long after a click the undo binding stays available
+pub const CLICK_UNDO_WINDOW: Duration = Duration::from_secs(5);
+
+/// Time of the last click performed by click mode
+static LAST_CLICK: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Remember that click mode just clicked an element
+pub fn record_click() {
+    *LAST_CLICK.lock().unwrap() = Some(Instant::now());
+}
+
+/// Consume the last click if it happened within the undo window
+pub fn take_recent_click() -> bool {
+    let mut last = LAST_CLICK.lock().unwrap();
+    let recent = is_recent(*last, Instant::now());
+    if recent {
+        *last = None;
+    }
+    recent
+}
+
+fn is_recent(last: Option<Instant>, now: Instant) -> bool {
+    last.is_some_and(|at| now.saturating_duration_since(at) <= CLICK_UNDO_WINDOW)
+}
+
+/// Inject Cmd+Z into the frontmost app
+pub fn undo_last_click() -> Result<(), String> {
+    log::info!("Click mode: injecting Cmd+Z to undo last click");
+    SystemInjector.undo()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_is_recent_within_window() {
+        let now = Instant::now();
+        assert!(is_recent(Some(now), now));
+        assert!(is_recent(Some(now), now + CLICK_UNDO_WINDOW));
+        assert!(!is_recent(Some(now), now + CLICK_UNDO_WINDOW + Duration::from_millis(1)));
+        assert!(!is_recent(None, now));
+    }
+}
@@ -85,6 +85,17 @@ pub async fn get_click_mode_state(state: State<'_, AppState>) -> Result<ClickMod
     Ok(manager.state().clone())
 }
 
+/// Pin the shown elements so repeated activations reuse them, or unpin them.
+/// Returns whether elements are pinned afterwards.
+#[tauri::command]
+pub async fn toggle_click_mode_pin(state: State<'_, AppState>) -> Result<bool, String> {
+    let manager = state
+        .click_mode_manager
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    manager.toggle_pin()
+}
+
 /// Click an element by its ID
 #[tauri::command]
 pub async fn click_mode_click_element(
@@ -123,6 +134,26 @@ pub async fn click_mode_right_click_element(
     deactivate_click_mode(app, state).await
 }
 
+/// Copy an element's text to the clipboard by its ID instead of clicking it
+#[tauri::command]
+pub async fn click_mode_copy_element(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    element_id: usize,
+) -> Result<String, String> {
+    let text = {
+        let manager = state
+            .click_mode_manager
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        manager.copy_element(element_id)?
+    };
+
+    // Deactivate after copying
+    deactivate_click_mode(app, state).await?;
+    Ok(text)
+}
+
 /// Handle hint input from the frontend
 #[tauri::command]
 pub async fn click_mode_input_hint(
@@ -196,3 +227,21 @@ pub async fn get_click_mode_elements(
         .map_err(|e| format!("Lock error: {}", e))?;
     Ok(manager.get_filtered_elements())
 }
+
+/// Dump the accessibility tree of the last used app to a JSON file for debugging.
+/// Writes to `path`, or to ovim-ax-tree.json in the Downloads folder, and returns the file path.
+#[tauri::command]
+pub async fn dump_ax_tree(path: Option<String>) -> Result<String, String> {
+    let json = crate::click_mode::accessibility::dump_ax_tree()?;
+
+    let path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => dirs::download_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("ovim-ax-tree.json"),
+    };
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log::info!("Wrote AX tree dump to {}", path.display());
+    Ok(path.display().to_string())
+}
@@ -114,12 +114,11 @@ pub fn toggle_indicator_visible(
     app: tauri::AppHandle,
     state: tauri::State<crate::AppState>,
 ) -> Result<bool, String> {
-    let mut settings = state.settings.lock().unwrap();
-    settings.indicator_visible = !settings.indicator_visible;
-    let visible = settings.indicator_visible;
-    settings.save()?;
-    let new_settings = settings.clone();
-    drop(settings);
+    let new_settings = crate::config::Settings::update(&state.settings, |settings| {
+        settings.indicator_visible = !settings.indicator_visible;
+        Ok(())
+    })?;
+    let visible = new_settings.indicator_visible;
 
     let _ = app.emit("settings-changed", new_settings);
     Ok(visible)
@@ -20,6 +20,7 @@ pub struct RecordedModifiers {
     pub control: bool,
     pub option: bool,
     pub command: bool,
+    pub function: bool,
 }
 
 #[tauri::command]
@@ -3,6 +3,7 @@
 mod click_mode;
 mod indicator;
 mod keys;
+mod nvim_edit;
 mod permissions;
 mod settings;
 mod updater;
@@ -13,6 +14,7 @@ mod widgets;
 pub use click_mode::*;
 pub use indicator::*;
 pub use keys::*;
+pub use nvim_edit::*;
 pub use permissions::*;
 pub use settings::*;
 pub use updater::*;
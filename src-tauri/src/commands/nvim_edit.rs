@@ -0,0 +1,27 @@
+//! Tauri commands for Edit Popup sessions
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::nvim_edit::{self, EditError, EditSessionInfo, PopupGeometryPreview};
+use crate::AppState;
+
+/// List active edit sessions
+#[tauri::command]
+pub fn list_edit_sessions(state: State<AppState>) -> Vec<EditSessionInfo> {
+    state.edit_session_manager.list_sessions()
+}
+
+/// Force-end a stuck edit session without restoring its text
+#[tauri::command]
+pub fn kill_edit_session(state: State<AppState>, id: String) -> Result<(), EditError> {
+    let session_id = Uuid::parse_str(&id).map_err(|_| EditError::UnknownSession(id))?;
+    state.edit_session_manager.kill_session(&session_id)
+}
+
+/// Where the Edit Popup would open for the focused field, without spawning an editor
+#[tauri::command]
+pub fn preview_popup_geometry(state: State<AppState>) -> Result<PopupGeometryPreview, EditError> {
+    let settings = state.settings.lock().unwrap().nvim_edit.clone();
+    nvim_edit::preview_popup_geometry(&settings)
+}
@@ -2,13 +2,19 @@
 
 use tauri::State;
 
-use crate::keyboard::{check_accessibility_permission, request_accessibility_permission};
+use crate::keyboard::{
+    check_accessibility_permission, check_input_monitoring_permission,
+    request_accessibility_permission,
+};
 use crate::AppState;
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PermissionStatus {
     pub accessibility: bool,
+    pub input_monitoring: bool,
     pub capture_running: bool,
+    /// The event tap is running and receiving key events
+    pub tap_live: bool,
 }
 
 #[tauri::command]
@@ -25,7 +31,9 @@ pub fn request_permission() -> bool {
 pub fn get_permission_status(state: State<AppState>) -> PermissionStatus {
     PermissionStatus {
         accessibility: check_accessibility_permission(),
+        input_monitoring: check_input_monitoring_permission(),
         capture_running: state.keyboard_capture.is_running(),
+        tap_live: state.keyboard_capture.is_live(),
     }
 }
 
@@ -1,10 +1,12 @@
 //! Settings-related Tauri commands
 
+use std::collections::HashMap;
 use std::process::Command;
 
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::config::Settings;
+use crate::config::{validate_filetype, CapsLockRemap, EditorCapabilities, EditorType, Settings};
 use crate::nvim_edit::terminals::ensure_launcher_script;
 use crate::AppState;
 
@@ -20,41 +22,102 @@ pub fn set_settings(
     state: State<AppState>,
     new_settings: Settings,
 ) -> Result<(), String> {
-    // Update click mode settings
-    crate::click_mode::accessibility::update_timing_settings(
-        new_settings.click_mode.cache_ttl_ms,
-        new_settings.click_mode.ax_stabilization_delay_ms,
-        new_settings.click_mode.max_depth,
-        new_settings.click_mode.max_elements,
-    );
+    apply_click_mode_settings(&new_settings);
+    apply_caps_lock_remap(&new_settings);
 
-    let mut settings = state.settings.lock().unwrap();
-    *settings = new_settings.clone();
-    settings.save()?;
+    let new_settings = Settings::update(&state.settings, |settings| {
+        settings.replace_from_ui(new_settings);
+        Ok(())
+    })?;
 
     let _ = app.emit("settings-changed", new_settings);
     Ok(())
 }
 
+/// Get a single setting by key, e.g. `nvim_edit.live_sync_enabled`
 #[tauri::command]
-pub fn open_settings_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("settings") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-    }
+pub fn get_setting(state: State<AppState>, key: String) -> Result<serde_json::Value, String> {
+    let settings = state.settings.lock().unwrap();
+    settings.get_value(&key)
+}
+
+/// Change a single setting by key and persist it.
+/// Unlike `set_settings` this can't overwrite fields changed concurrently elsewhere.
+#[tauri::command]
+pub fn set_setting(
+    app: AppHandle,
+    state: State<AppState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let new_settings = Settings::update(&state.settings, |settings| settings.set_value(&key, value))?;
+
+    apply_click_mode_settings(&new_settings);
+    apply_caps_lock_remap(&new_settings);
+    log::info!("Setting {} changed", key);
+    let _ = app.emit("settings-changed", new_settings);
     Ok(())
 }
 
+/// Push click mode settings to the accessibility scanner
+fn apply_click_mode_settings(settings: &Settings) {
+    crate::click_mode::accessibility::update_timing_settings(
+        settings.click_mode.cache_ttl_ms,
+        settings.click_mode.ax_stabilization_delay_ms,
+        settings.click_mode.max_depth,
+        settings.click_mode.max_elements,
+    );
+    crate::click_mode::accessibility::set_hint_order(settings.click_mode.hint_order);
+    crate::click_mode::accessibility::set_full_page_scan(settings.click_mode.full_page_scan);
+    crate::click_mode::accessibility::set_title_attributes(&settings.click_mode.title_attributes);
+    crate::click_mode::accessibility::set_app_clickable_roles(settings.click_mode.app_clickable_roles.clone());
+}
+
+/// Remap Caps Lock at the HID level only while a remap is selected
+fn apply_caps_lock_remap(settings: &Settings) {
+    crate::keyboard::hid_remap::set_caps_lock_remapped(settings.caps_lock_remap != CapsLockRemap::None);
+}
+
 #[tauri::command]
-pub fn pick_app(app: AppHandle) -> Result<Option<String>, String> {
-    use std::process::Command;
+pub fn open_settings_window(app: AppHandle) -> Result<(), String> {
+    crate::window::show_settings_window(&app)
+}
 
+/// An app chosen by the user, with its human-readable name when known
+#[derive(Debug, Clone, Serialize)]
+pub struct PickedApp {
+    pub bundle_id: String,
+    pub display_name: Option<String>,
+}
+
+/// Store the display name for a bundle ID and notify the UI
+fn remember_app_display_name(app: &AppHandle, state: &AppState, picked: &PickedApp) {
+    let Some(name) = picked.display_name.clone() else {
+        return;
+    };
+    if state.settings.lock().unwrap().app_display_names.get(&picked.bundle_id) == Some(&name) {
+        return;
+    }
+    match Settings::update(&state.settings, |settings| {
+        settings.set_app_display_name(&picked.bundle_id, Some(name));
+        Ok(())
+    }) {
+        Ok(new_settings) => {
+            let _ = app.emit("settings-changed", new_settings);
+        }
+        Err(e) => log::warn!("Failed to save app display name: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn pick_app(app: AppHandle, state: State<AppState>) -> Result<Option<PickedApp>, String> {
     let script = r#"
         set appPath to choose file of type {"app"} with prompt "Select an application" default location "/Applications"
         set appPath to POSIX path of appPath
         return appPath
     "#;
 
+    // Not `osascript::run`: the chooser waits for the user, so it has no timeout
     let output = Command::new("osascript")
         .arg("-e")
         .arg(script)
@@ -87,7 +150,53 @@ pub fn pick_app(app: AppHandle) -> Result<Option<String>, String> {
         return Err("Could not determine bundle identifier".to_string());
     }
 
-    Ok(Some(bundle_id))
+    // Prefer the running app's localized name, fall back to the bundle's display name
+    let display_name = crate::click_mode::accessibility::get_running_app_name(&bundle_id)
+        .or_else(|| {
+            let output = Command::new("mdls")
+                .args(["-name", "kMDItemDisplayName", "-raw", &app_path])
+                .output()
+                .ok()?;
+            let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let name = name.strip_suffix(".app").unwrap_or(&name).to_string();
+            (!name.is_empty() && name != "(null)").then_some(name)
+        });
+
+    let picked = PickedApp {
+        bundle_id,
+        display_name,
+    };
+    remember_app_display_name(&app, &state, &picked);
+
+    Ok(Some(picked))
+}
+
+/// Add the app the user was last in to a named bundle ID list (see `APP_LIST_NAMES`)
+/// Returns the app that was added (or was already present)
+#[tauri::command]
+pub fn add_frontmost_app_to_list(
+    app: AppHandle,
+    state: State<AppState>,
+    list_name: String,
+) -> Result<PickedApp, String> {
+    let (bundle_id, display_name) = crate::click_mode::accessibility::get_last_external_app()
+        .ok_or("Could not determine the frontmost app")?;
+
+    let mut added = false;
+    let new_settings = Settings::update(&state.settings, |settings| {
+        added = settings.add_app_to_list(&list_name, &bundle_id)?;
+        settings.set_app_display_name(&bundle_id, display_name.clone());
+        Ok(())
+    })?;
+    if added {
+        log::info!("Added {} to {}", bundle_id, list_name);
+    }
+    let _ = app.emit("settings-changed", new_settings);
+
+    Ok(PickedApp {
+        bundle_id,
+        display_name,
+    })
 }
 
 /// Open the launcher script in the user's configured terminal and editor
@@ -116,9 +225,10 @@ pub fn open_launcher_script(state: State<AppState>) -> Result<(), String> {
     match crate::nvim_edit::terminals::spawn_terminal(
         &nvim_edit_clone,
         &script_path,
+        "ovim-launcher-script",
         None,  // No popup geometry - open fullscreen
         None,  // No RPC socket needed
-        false, // Script file is not empty
+        crate::config::EditorStart::End,
         None,  // No filetype override
     ) {
         Ok(spawn_info) => {
@@ -138,21 +248,71 @@ pub fn open_launcher_script(state: State<AppState>) -> Result<(), String> {
     }
 }
 
+/// Forget the filetype of a domain/app. Returns the updated mappings, which are
+/// also sent to the settings window as `filetype-mappings-changed`.
 #[tauri::command]
-pub fn remove_domain_filetype(
+pub fn remove_filetype_mapping(
+    app: AppHandle,
     state: State<AppState>,
     domain: String,
-) -> Result<(), String> {
+) -> HashMap<String, String> {
     let mut settings = state.settings.lock().unwrap();
+    // Saves the filetype files; settings.save() doesn't include them
     settings.nvim_edit.remove_filetype_for_domain(&domain);
-    // Note: settings.save() doesn't save domain_filetypes (they're in separate file)
-    // remove_filetype_for_domain already saves to domain-filetypes.yaml
-    Ok(())
+    let mappings = settings.nvim_edit.get_all_domain_filetypes();
+    let _ = app.emit("filetype-mappings-changed", &mappings);
+    mappings
+}
+
+/// Set the filetype for a domain/app, replacing a learned one. Returns the updated
+/// mappings, which are also sent to the settings window as `filetype-mappings-changed`.
+#[tauri::command]
+pub fn set_filetype_mapping(
+    app: AppHandle,
+    state: State<AppState>,
+    domain: String,
+    filetype: String,
+) -> Result<HashMap<String, String>, String> {
+    let domain = domain.trim();
+    if domain.is_empty() {
+        return Err("Domain is empty".to_string());
+    }
+    let filetype = filetype.trim();
+    validate_filetype(filetype)?;
+
+    let mut settings = state.settings.lock().unwrap();
+    // Saved to domain-filetype-overrides.yaml and kept by clear_learned_data
+    settings
+        .nvim_edit
+        .set_explicit_filetype_for_domain(domain.to_string(), filetype.to_string());
+    let mappings = settings.nvim_edit.get_all_domain_filetypes();
+    let _ = app.emit("filetype-mappings-changed", &mappings);
+    Ok(mappings)
 }
 
 /// Get all domain filetypes (stored in separate file from main settings)
 #[tauri::command]
-pub fn get_domain_filetypes(state: State<AppState>) -> std::collections::HashMap<String, String> {
+pub fn get_filetype_mappings(state: State<AppState>) -> HashMap<String, String> {
     let settings = state.settings.lock().unwrap();
-    settings.nvim_edit.get_all_domain_filetypes().clone()
+    settings.nvim_edit.get_all_domain_filetypes()
+}
+
+/// Forget learned filetypes and app display names (see `Settings::clear_learned_data`)
+#[tauri::command]
+pub fn clear_learned_data(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let new_settings = Settings::update(&state.settings, |settings| {
+        settings.clear_learned_data();
+        settings.nvim_edit.save_domain_filetypes_file();
+        Ok(())
+    })?;
+    log::info!("Cleared learned data");
+    let _ = app.emit("filetype-mappings-changed", new_settings.nvim_edit.get_all_domain_filetypes());
+    let _ = app.emit("settings-changed", new_settings);
+    Ok(())
+}
+
+/// List the supported editors and what each of them can do
+#[tauri::command]
+pub fn get_supported_editors() -> Vec<EditorCapabilities> {
+    EditorType::ALL.into_iter().map(EditorCapabilities::from).collect()
 }
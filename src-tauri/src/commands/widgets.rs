@@ -1,5 +1,6 @@
 //! Widget info Tauri commands
 
+use crate::nvim_edit::accessibility::{get_focused_text_selection, TextSelection};
 use crate::widgets::{battery, capslock, selection, shell};
 
 #[tauri::command]
@@ -7,6 +8,12 @@ pub fn get_selection_info() -> selection::SelectionInfo {
     selection::get_selection_info()
 }
 
+/// Full value, selected text and selection range of the focused field
+#[tauri::command]
+pub fn get_selection_content() -> Option<TextSelection> {
+    get_focused_text_selection()
+}
+
 #[tauri::command]
 pub fn get_battery_info() -> Option<battery::BatteryInfo> {
     battery::get_battery_info()
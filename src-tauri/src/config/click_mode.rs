@@ -2,6 +2,8 @@
 //!
 //! Configuration for the keyboard-driven element clicking feature.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use super::VimKeyModifiers;
@@ -16,9 +18,33 @@ pub enum DoubleTapModifier {
     Option,
     Control,
     Shift,
+    Function,
     Escape,
 }
 
+/// Order in which elements receive hints (earlier elements get shorter hints)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HintOrder {
+    /// Order the elements were found in the accessibility tree
+    #[default]
+    DiscoveryOrder,
+    /// Top-to-bottom, left-to-right
+    VisualOrder,
+    /// Closest to the mouse cursor first
+    DistanceFromCursor,
+}
+
+/// Per-app changes to which accessibility roles count as clickable
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClickableRoleOverrides {
+    /// Roles to treat as clickable in addition to the defaults (e.g. "AXGroup")
+    pub add: Vec<String>,
+    /// Default roles to stop treating as clickable
+    pub remove: Vec<String>,
+}
+
 /// Settings for Click Mode feature
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -29,6 +55,14 @@ pub struct ClickModeSettings {
     pub shortcut_key: String,
     /// Shortcut modifiers (default: Cmd+Shift)
     pub shortcut_modifiers: VimKeyModifiers,
+    /// Shortcut key that shows hints only for window controls (empty = disabled)
+    pub window_controls_shortcut_key: String,
+    /// Modifiers for the window controls shortcut
+    pub window_controls_shortcut_modifiers: VimKeyModifiers,
+    /// Keyboard shortcut that shows hints for the menu bar titles (empty = disabled)
+    pub menu_bar_shortcut_key: String,
+    /// Modifiers for the menu bar shortcut
+    pub menu_bar_shortcut_modifiers: VimKeyModifiers,
     /// Double-tap modifier to activate click mode (alternative to keyboard shortcut)
     #[serde(default)]
     pub double_tap_modifier: DoubleTapModifier,
@@ -36,6 +70,22 @@ pub struct ClickModeSettings {
     pub hint_chars: String,
     /// Show search bar when click mode is activated
     pub show_search_bar: bool,
+    /// Key that switches from hint mode to search mode (e.g., "slash")
+    pub search_key: String,
+    /// Key that returns from search mode to hint mode (e.g., "tab")
+    pub exit_search_key: String,
+    /// Key that switches between hint and search mode in both directions (empty = disabled)
+    pub toggle_search_key: String,
+    /// Key that cycles between elements stacked at the same position (e.g., "tab")
+    pub cycle_overlap_key: String,
+    /// Key that pins the shown elements so repeated activations skip re-querying (e.g., "equal")
+    pub pin_key: String,
+    /// Key that switches to batch copy: element texts are collected and copied on Enter (e.g., "comma")
+    pub batch_copy_key: String,
+    /// Modifiers that turn h/j/k/l into scroll keys while hints are shown
+    pub scroll_modifiers: VimKeyModifiers,
+    /// Modifiers held while typing a hint to copy the element's text instead of clicking
+    pub copy_modifiers: VimKeyModifiers,
     /// Opacity of hint labels (0.0-1.0)
     pub hint_opacity: f32,
     /// Hint label font size
@@ -44,6 +94,41 @@ pub struct ClickModeSettings {
     pub hint_bg_color: String,
     /// Hint label text color (hex)
     pub hint_text_color: String,
+    /// Show a truncated element title next to each hint
+    pub show_element_labels: bool,
+    /// Which elements get the shortest hints
+    pub hint_order: HintOrder,
+    /// Allow undoing a click mode click with Cmd+Z (best-effort)
+    pub click_undo_enabled: bool,
+    /// Key that injects Cmd+Z shortly after a click mode click (e.g., "z")
+    pub click_undo_key: String,
+    /// Modifiers for the undo key (default: Ctrl+Option)
+    pub click_undo_modifiers: VimKeyModifiers,
+    /// Deactivate click mode on a real left click
+    pub deactivate_on_left_click: bool,
+    /// Deactivate click mode on a real right click
+    pub deactivate_on_right_click: bool,
+    /// Deactivate click mode on a real scroll
+    pub deactivate_on_scroll: bool,
+    /// Accessibility attributes tried in order for element titles, keyed by role
+    /// ("*" for all other roles). Unlisted roles use AXTitle, AXDescription,
+    /// AXValue, AXLabel, AXHelp.
+    pub title_attributes: HashMap<String, Vec<String>>,
+    /// Browsers only: scroll through the whole page to collect hints (slower, moves the page)
+    pub full_page_scan: bool,
+    /// Bundle identifiers of apps where click mode is allowed (empty = all apps)
+    #[serde(default)]
+    pub enabled_apps: Vec<String>,
+    /// Bundle identifiers of apps where click mode is never activated.
+    /// Takes precedence over enabled_apps.
+    #[serde(default)]
+    pub disabled_apps: Vec<String>,
+    /// Bundle identifiers of apps where click mode activates whenever the app becomes frontmost
+    #[serde(default)]
+    pub auto_activate_apps: Vec<String>,
+    /// Clickable role overrides keyed by bundle identifier
+    #[serde(default)]
+    pub app_clickable_roles: HashMap<String, ClickableRoleOverrides>,
 
     // Advanced timing settings
     /// Delay before querying accessibility elements (ms).
@@ -81,6 +166,14 @@ fn default_max_elements() -> u32 {
     500
 }
 
+/// Text inputs skip AXValue: it's the typed contents, not a label
+fn default_title_attributes() -> HashMap<String, Vec<String>> {
+    ["AXTextField", "AXTextArea", "AXComboBox", "AXSearchField"]
+        .into_iter()
+        .map(|role| (role.to_string(), vec!["AXTitle".to_string(), "AXLabel".to_string()]))
+        .collect()
+}
+
 impl Default for ClickModeSettings {
     fn default() -> Self {
         Self {
@@ -91,14 +184,59 @@ impl Default for ClickModeSettings {
                 control: false,
                 option: false,
                 command: false,
+                function: false,
             },
+            window_controls_shortcut_key: "".to_string(), // Disabled by default
+            window_controls_shortcut_modifiers: VimKeyModifiers::default(),
+            menu_bar_shortcut_key: "".to_string(), // Disabled by default
+            menu_bar_shortcut_modifiers: VimKeyModifiers::default(),
             double_tap_modifier: DoubleTapModifier::Option, // Opt+Opt by default
             hint_chars: "asfghjklqwetyuiopzxvbm".to_string(), // excludes r, c, d, n (action keys)
             show_search_bar: true,
+            search_key: "slash".to_string(),
+            exit_search_key: "tab".to_string(),
+            toggle_search_key: String::new(),
+            cycle_overlap_key: "tab".to_string(),
+            pin_key: "equal".to_string(),
+            batch_copy_key: "comma".to_string(),
+            scroll_modifiers: VimKeyModifiers {
+                shift: false,
+                control: true,
+                option: false,
+                command: false,
+                function: false,
+            },
+            copy_modifiers: VimKeyModifiers {
+                shift: false,
+                control: false,
+                option: true,
+                command: false,
+                function: false,
+            },
             hint_opacity: 0.95,
             hint_font_size: 12,
             hint_bg_color: "#FFCC00".to_string(), // Yellow background like Vimium
             hint_text_color: "#000000".to_string(), // Black text
+            show_element_labels: false,
+            hint_order: HintOrder::default(),
+            click_undo_enabled: false,
+            click_undo_key: "z".to_string(),
+            click_undo_modifiers: VimKeyModifiers {
+                shift: false,
+                control: true,
+                option: true,
+                command: false,
+                function: false,
+            },
+            deactivate_on_left_click: true,
+            deactivate_on_right_click: true,
+            deactivate_on_scroll: true,
+            full_page_scan: false,
+            title_attributes: default_title_attributes(),
+            enabled_apps: vec![],
+            disabled_apps: vec![],
+            auto_activate_apps: vec![],
+            app_clickable_roles: HashMap::new(),
             ax_stabilization_delay_ms: default_ax_delay(),
             cache_ttl_ms: default_cache_ttl(),
             max_depth: default_max_depth(),
@@ -108,6 +246,31 @@ impl Default for ClickModeSettings {
 }
 
 impl ClickModeSettings {
+    /// Check if a real mouse click should deactivate click mode
+    pub fn deactivates_on_click(&self, is_left_click: bool, is_right_click: bool) -> bool {
+        (is_left_click && self.deactivate_on_left_click)
+            || (is_right_click && self.deactivate_on_right_click)
+    }
+
+    /// Check if click mode may activate in the given app.
+    /// disabled_apps wins over enabled_apps; an empty enabled_apps allows every app.
+    pub fn is_enabled_for_app(&self, bundle_id: Option<&str>) -> bool {
+        let Some(bundle_id) = bundle_id else {
+            return self.enabled_apps.is_empty();
+        };
+        if self.disabled_apps.iter().any(|id| id == bundle_id) {
+            return false;
+        }
+        self.enabled_apps.is_empty() || self.enabled_apps.iter().any(|id| id == bundle_id)
+    }
+
+    /// Check if click mode should activate on its own when this app becomes frontmost
+    pub fn auto_activates_for_app(&self, bundle_id: Option<&str>) -> bool {
+        self.enabled
+            && bundle_id.is_some_and(|id| self.auto_activate_apps.iter().any(|app| app == id))
+            && self.is_enabled_for_app(bundle_id)
+    }
+
     /// Check if the shortcut matches the given key and modifiers
     pub fn matches_shortcut(
         &self,
@@ -130,3 +293,65 @@ impl ClickModeSettings {
         key_matches && mods_match
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_lists_allow_all_apps() {
+        let settings = ClickModeSettings::default();
+        assert!(settings.is_enabled_for_app(Some("com.apple.Safari")));
+        assert!(settings.is_enabled_for_app(None));
+    }
+
+    #[test]
+    fn test_enabled_apps_restricts_activation() {
+        let settings = ClickModeSettings {
+            enabled_apps: vec!["com.apple.Safari".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.is_enabled_for_app(Some("com.apple.Safari")));
+        assert!(!settings.is_enabled_for_app(Some("com.apple.finder")));
+        assert!(!settings.is_enabled_for_app(None));
+    }
+
+    #[test]
+    fn test_disabled_apps_win_over_enabled_apps() {
+        let settings = ClickModeSettings {
+            enabled_apps: vec!["com.apple.Safari".to_string()],
+            disabled_apps: vec!["com.apple.Safari".to_string()],
+            ..Default::default()
+        };
+        assert!(!settings.is_enabled_for_app(Some("com.apple.Safari")));
+    }
+
+    #[test]
+    fn test_auto_activate_apps() {
+        let settings = ClickModeSettings {
+            auto_activate_apps: vec!["com.apple.Safari".to_string(), "com.apple.finder".to_string()],
+            disabled_apps: vec!["com.apple.finder".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.auto_activates_for_app(Some("com.apple.Safari")));
+        assert!(!settings.auto_activates_for_app(Some("com.apple.finder")));
+        assert!(!settings.auto_activates_for_app(Some("com.apple.mail")));
+        assert!(!settings.auto_activates_for_app(None));
+
+        let disabled = ClickModeSettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!disabled.auto_activates_for_app(Some("com.apple.Safari")));
+    }
+
+    #[test]
+    fn test_deactivation_triggers() {
+        let settings = ClickModeSettings {
+            deactivate_on_right_click: false,
+            ..Default::default()
+        };
+        assert!(settings.deactivates_on_click(true, false));
+        assert!(!settings.deactivates_on_click(false, true));
+    }
+}
@@ -1,8 +1,13 @@
 pub mod click_mode;
 mod colors;
 mod nvim_edit;
-mod scroll_mode;
+pub mod scroll_mode;
 mod settings;
 
-pub use nvim_edit::NvimEditSettings;
-pub use settings::{Settings, VimKeyModifiers};
+pub use nvim_edit::{
+    validate_filetype, CancelBehavior, EditorCapabilities, EditorStart, EditorType,
+    NvimEditSettings, TrailingNewline,
+};
+pub use settings::{
+    CapsLockRemap, KeyBinding, KeyRepeatMode, KeyRepeatSettings, Settings, VimKeyModifiers,
+};
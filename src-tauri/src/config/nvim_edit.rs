@@ -19,6 +19,14 @@ pub enum EditorType {
 }
 
 impl EditorType {
+    /// Every editor type, in the order the settings UI lists them
+    pub const ALL: [EditorType; 4] = [
+        EditorType::Neovim,
+        EditorType::Vim,
+        EditorType::Helix,
+        EditorType::Custom,
+    ];
+
     pub fn from_string(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "neovim" | "nvim" => EditorType::Neovim,
@@ -57,6 +65,44 @@ impl EditorType {
         }
     }
 
+    /// Human-readable editor name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            EditorType::Neovim => "Neovim",
+            EditorType::Vim => "Vim",
+            EditorType::Helix => "Helix",
+            EditorType::Custom => "Custom",
+        }
+    }
+
+    /// Whether the editor needs an RPC socket (nvim --listen)
+    pub fn needs_socket(&self) -> bool {
+        matches!(self, EditorType::Neovim)
+    }
+
+    /// Whether the editor can be started at a given line from the command line
+    pub fn supports_start_position(&self) -> bool {
+        !matches!(self, EditorType::Custom)
+    }
+
+    /// Get the arguments to start at a 0-based line and column.
+    /// Helix only takes a line (`+N`); editors without support start at the end.
+    pub fn cursor_position_args(&self, line: usize, column: usize) -> Vec<String> {
+        match self {
+            EditorType::Neovim | EditorType::Vim => {
+                vec![format!("+call cursor({},{})", line + 1, column + 1)]
+            }
+            EditorType::Helix => vec![format!("+{}", line + 1)],
+            EditorType::Custom => self.cursor_end_args().iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// Whether edits sync into the text field while typing.
+    /// Other editors only restore the text when they exit.
+    pub fn supports_live_sync(&self) -> bool {
+        self.needs_socket()
+    }
+
     /// Get the arguments to position cursor at end of file and start in insert mode
     /// Used when the text field is empty
     pub fn cursor_end_args_insert(&self) -> Vec<&'static str> {
@@ -68,6 +114,110 @@ impl EditorType {
     }
 }
 
+/// Where the editor puts the cursor when it opens the file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditorStart {
+    /// At the end of the text
+    End,
+    /// In insert mode, for an empty text field
+    Insert,
+    /// At the text field's cursor (0-based line and column)
+    At { line: usize, column: usize },
+}
+
+/// Capabilities of an editor type, as shown in the settings UI
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EditorCapabilities {
+    /// Settings value for the editor (e.g., "neovim")
+    pub name: EditorType,
+    /// Human-readable editor name
+    pub label: &'static str,
+    /// Edits sync into the text field while typing (otherwise restored on exit)
+    pub supports_live_sync: bool,
+    /// The editor is started with an RPC socket
+    pub needs_socket: bool,
+    /// The editor can open at the text field's cursor line
+    pub supports_start_position: bool,
+    /// Arguments passed before the file path by default
+    pub default_args: Vec<&'static str>,
+}
+
+impl From<EditorType> for EditorCapabilities {
+    fn from(editor: EditorType) -> Self {
+        Self {
+            label: editor.display_name(),
+            supports_live_sync: editor.supports_live_sync(),
+            needs_socket: editor.needs_socket(),
+            supports_start_position: editor.supports_start_position(),
+            default_args: editor.cursor_end_args(),
+            name: editor,
+        }
+    }
+}
+
+/// How trailing newlines in the edited file are handled before restoring the text
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingNewline {
+    /// Remove the trailing "\n" nvim adds with 'fixeol': "a\n\n" -> "a\n".
+    /// Kept when the captured text already ended with a newline, since nvim added nothing.
+    #[default]
+    StripOne,
+    /// Remove trailing blank lines and the final line break, keeping any spaces
+    /// on the last line that has content: "a  \n \n\n" -> "a  "
+    StripAll,
+    /// Restore the file contents exactly as saved: "a\n" -> "a\n"
+    Preserve,
+}
+
+impl TrailingNewline {
+    /// Apply this mode to the text read from the edited file.
+    /// `original` is the text the file was created with.
+    pub fn apply<'a>(self, text: &'a str, original: &str) -> &'a str {
+        match self {
+            TrailingNewline::StripOne if original.ends_with('\n') => text,
+            TrailingNewline::StripOne => text.strip_suffix('\n').unwrap_or(text),
+            TrailingNewline::StripAll => {
+                let content_end = text.trim_end().len();
+                let rest = &text[content_end..];
+                let line_end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+                &text[..content_end + line_end]
+            }
+            TrailingNewline::Preserve => text,
+        }
+    }
+}
+
+/// Check that `filetype` looks like a Vim filetype name: letters, digits, `_` and `-`,
+/// with `.` joining several filetypes (e.g., "c.doxygen")
+pub fn validate_filetype(filetype: &str) -> Result<(), String> {
+    if filetype.is_empty() {
+        return Err("Filetype is empty".to_string());
+    }
+    if filetype.len() > 64 {
+        return Err("Filetype is longer than 64 characters".to_string());
+    }
+    let valid_part = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    if !filetype.split('.').all(valid_part) {
+        return Err(format!("\"{}\" is not a valid filetype", filetype));
+    }
+    Ok(())
+}
+
+/// What happens to live-synced changes when the editor quits without saving (`:q!`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelBehavior {
+    /// Leave whatever live sync already put in the text field
+    #[default]
+    KeepLiveSynced,
+    /// Put the originally captured text back, undoing live-synced changes
+    RevertToOriginal,
+}
+
 /// Settings for Edit Popup feature
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -96,9 +246,24 @@ pub struct NvimEditSettings {
     pub popup_width: u32,
     /// Popup window height in pixels
     pub popup_height: u32,
+    /// Popup width as a percentage of the screen's visible width (0 = use popup_width).
+    /// Takes precedence over popup_width when set
+    #[serde(default)]
+    pub popup_width_pct: u32,
+    /// Popup height as a percentage of the screen's visible height (0 = use popup_height).
+    /// Takes precedence over popup_height when set
+    #[serde(default)]
+    pub popup_height_pct: u32,
+    /// Pixels to keep between the popup and the edges of the visible screen
+    #[serde(default = "default_popup_margin")]
+    pub popup_margin: u32,
     /// Enable live sync (BETA) - sync text field as you type in editor
     #[serde(default)]
     pub live_sync_enabled: bool,
+    /// Directory for the nvim RPC socket (empty = ~/Library/Caches/ovim)
+    /// Paths over the Unix socket limit fall back to ~/.ovim/sockets
+    #[serde(default)]
+    pub socket_dir: String,
     /// Use custom launcher script instead of built-in terminal spawning
     #[serde(default)]
     pub use_custom_script: bool,
@@ -107,16 +272,71 @@ pub struct NvimEditSettings {
     /// When false (default), uses JavaScript for browsers and accessibility API for native apps
     #[serde(default)]
     pub clipboard_mode: bool,
+    /// Edit rich text (contenteditable) fields in browsers as Markdown and restore them as HTML
+    /// Lossy: only bold, italic, links and flat lists survive the round-trip
+    #[serde(default)]
+    pub rich_text_mode: bool,
+    /// Strip ANSI escape sequences and other control characters (except tab and
+    /// newline) from captured text, e.g. text copied from a terminal
+    #[serde(default)]
+    pub sanitize_control_chars: bool,
+    /// Briefly re-focus the original app after spawning the editor so apps that
+    /// clear their selection on blur keep it, then bring the editor forward
+    #[serde(default)]
+    pub reactivate_original_immediately: bool,
     /// Double-tap modifier to activate edit mode (alternative to keyboard shortcut)
     #[serde(default)]
     pub double_tap_modifier: DoubleTapModifier,
     /// Pre-warm a hidden terminal at startup for faster edit popup (Alacritty only)
     #[serde(default)]
     pub prewarm_terminal: bool,
-    /// Saved filetypes per domain (browser hostname) or app bundle ID
+    /// Trailing newline handling when restoring the edited text
+    #[serde(default)]
+    pub trailing_newline: TrailingNewline,
+    /// What happens to live-synced changes when the editor quits without saving
+    #[serde(default)]
+    pub cancel_behavior: CancelBehavior,
+    /// Shell command run on the edited file before it is restored, with `{file}`
+    /// replaced by the file path (e.g., "prettier --write {file}")
+    #[serde(default)]
+    pub format_command: Option<String>,
+    /// Open the editor at the text field's cursor when it is known
+    #[serde(default = "default_open_at_cursor")]
+    pub open_at_cursor: bool,
+    /// Texts longer than this many bytes are pasted back in chunks of this size,
+    /// since some apps silently truncate large pastes (0 = always paste at once)
+    #[serde(default = "default_clipboard_chunk_threshold")]
+    pub clipboard_chunk_threshold: usize,
+    /// Briefly draw the detected element frame and popup geometry on screen
+    #[serde(default)]
+    pub edit_popup_debug: bool,
+    /// Save the filetype set in nvim for the domain/app when the editor exits
+    #[serde(default = "default_learn_filetype")]
+    pub learn_filetype: bool,
+    /// Learned filetypes per domain (browser hostname) or app bundle ID
     /// Stored in separate domain-filetypes.yaml file, not in main settings
     #[serde(skip)]
     pub domain_filetypes: HashMap<String, String>,
+    /// Filetypes the user set per domain/app; these win over learned ones
+    /// Stored in separate domain-filetype-overrides.yaml file, not in main settings
+    #[serde(skip)]
+    pub explicit_domain_filetypes: HashMap<String, String>,
+}
+
+fn default_popup_margin() -> u32 {
+    8
+}
+
+fn default_clipboard_chunk_threshold() -> usize {
+    10_000
+}
+
+fn default_open_at_cursor() -> bool {
+    true
+}
+
+fn default_learn_filetype() -> bool {
+    true
 }
 
 impl Default for NvimEditSettings {
@@ -129,6 +349,7 @@ impl Default for NvimEditSettings {
                 control: false,
                 option: false,
                 command: false,
+                function: false,
             },
             terminal: "alacritty".to_string(),
             terminal_path: "".to_string(), // Empty means auto-detect
@@ -137,12 +358,27 @@ impl Default for NvimEditSettings {
             popup_mode: true,
             popup_width: 0, // 0 = match text field width
             popup_height: 300,
+            popup_width_pct: 0, // 0 = use popup_width
+            popup_height_pct: 0, // 0 = use popup_height
+            popup_margin: default_popup_margin(),
             live_sync_enabled: true, // BETA feature, enabled by default
+            socket_dir: "".to_string(), // Empty means the cache directory
             use_custom_script: false,
             clipboard_mode: false, // Use smart detection by default
+            rich_text_mode: false,
+            sanitize_control_chars: false,
+            reactivate_original_immediately: false,
             double_tap_modifier: DoubleTapModifier::Command, // Cmd+Cmd by default
             prewarm_terminal: false,
+            trailing_newline: TrailingNewline::default(),
+            cancel_behavior: CancelBehavior::default(),
+            format_command: None,
+            open_at_cursor: default_open_at_cursor(),
+            clipboard_chunk_threshold: default_clipboard_chunk_threshold(),
+            edit_popup_debug: false,
+            learn_filetype: default_learn_filetype(),
             domain_filetypes: HashMap::new(),
+            explicit_domain_filetypes: HashMap::new(),
         }
     }
 }
@@ -207,12 +443,25 @@ impl NvimEditSettings {
     }
 
     /// Get the editor arguments for cursor positioning
-    /// If text is empty, also start in insert mode
-    pub fn editor_args(&self, text_is_empty: bool) -> Vec<&'static str> {
-        if text_is_empty {
-            self.editor.cursor_end_args_insert()
-        } else {
-            self.editor.cursor_end_args()
+    pub fn editor_args(&self, start: EditorStart) -> Vec<String> {
+        match start {
+            EditorStart::End => self.editor.cursor_end_args().iter().map(|arg| arg.to_string()).collect(),
+            EditorStart::Insert => {
+                self.editor.cursor_end_args_insert().iter().map(|arg| arg.to_string()).collect()
+            }
+            EditorStart::At { line, column } => self.editor.cursor_position_args(line, column),
+        }
+    }
+
+    /// Where the editor should start: insert mode for empty text, the text field's
+    /// cursor when known and `open_at_cursor` is set, otherwise the end of the text
+    pub fn editor_start(&self, text_is_empty: bool, cursor: Option<(usize, usize)>) -> EditorStart {
+        match cursor {
+            _ if text_is_empty => EditorStart::Insert,
+            Some((line, column)) if self.open_at_cursor && self.editor.supports_start_position() => {
+                EditorStart::At { line, column }
+            }
+            _ => EditorStart::End,
         }
     }
 
@@ -229,52 +478,191 @@ impl NvimEditSettings {
         }
     }
 
-    /// Get the saved filetype for a domain/app
+    /// Get the saved filetype for a domain/app, preferring one the user set
     pub fn get_filetype_for_domain(&self, domain: &str) -> Option<&str> {
-        self.domain_filetypes.get(domain).map(|s| s.as_str())
+        self.explicit_domain_filetypes
+            .get(domain)
+            .or_else(|| self.domain_filetypes.get(domain))
+            .map(|s| s.as_str())
     }
 
-    /// Set the filetype for a domain/app and save to separate file for visibility
+    /// Learn the filetype for a domain/app and save to separate file for visibility
     pub fn set_filetype_for_domain(&mut self, domain: String, filetype: String) {
         self.domain_filetypes.insert(domain, filetype);
         // Also write to separate file for visibility
         self.save_domain_filetypes_file();
     }
 
-    /// Remove the filetype for a domain/app
+    /// Set the filetype for a domain/app on the user's behalf, replacing a learned one
+    pub fn set_explicit_filetype_for_domain(&mut self, domain: String, filetype: String) {
+        self.domain_filetypes.remove(&domain);
+        self.explicit_domain_filetypes.insert(domain, filetype);
+        self.save_domain_filetypes_file();
+    }
+
+    /// Remove the filetype for a domain/app, learned or explicit
     pub fn remove_filetype_for_domain(&mut self, domain: &str) {
         self.domain_filetypes.remove(domain);
+        self.explicit_domain_filetypes.remove(domain);
         self.save_domain_filetypes_file();
     }
 
-    /// Get path to the domain filetypes file
+    /// Move the domain filetypes out of `other`. They aren't serialized, so settings
+    /// deserialized from the UI or a single-key change don't have them.
+    pub fn take_domain_filetypes(&mut self, other: &mut NvimEditSettings) {
+        self.domain_filetypes = std::mem::take(&mut other.domain_filetypes);
+        self.explicit_domain_filetypes = std::mem::take(&mut other.explicit_domain_filetypes);
+    }
+
+    /// Get path to the learned domain filetypes file
     fn domain_filetypes_path() -> Option<std::path::PathBuf> {
         dirs::config_dir().map(|p| p.join("ovim").join("domain-filetypes.yaml"))
     }
 
-    /// Load domain filetypes from the separate file
+    /// Get path to the explicit domain filetypes file
+    fn explicit_domain_filetypes_path() -> Option<std::path::PathBuf> {
+        dirs::config_dir().map(|p| p.join("ovim").join("domain-filetype-overrides.yaml"))
+    }
+
+    /// Load domain filetypes from the separate files
     pub fn load_domain_filetypes(&mut self) {
-        if let Some(path) = Self::domain_filetypes_path() {
-            if let Ok(contents) = std::fs::read_to_string(&path) {
-                if let Ok(filetypes) = serde_yml::from_str(&contents) {
-                    self.domain_filetypes = filetypes;
-                    log::info!("Loaded {} domain filetypes", self.domain_filetypes.len());
-                }
-            }
+        if let Some(filetypes) = Self::domain_filetypes_path().and_then(|path| load_filetypes_file(&path)) {
+            self.domain_filetypes = filetypes;
+            log::info!("Loaded {} domain filetypes", self.domain_filetypes.len());
+        }
+        if let Some(filetypes) = Self::explicit_domain_filetypes_path().and_then(|path| load_filetypes_file(&path)) {
+            self.explicit_domain_filetypes = filetypes;
+            log::info!("Loaded {} explicit domain filetypes", self.explicit_domain_filetypes.len());
         }
     }
 
-    /// Save domain filetypes to the separate file
-    fn save_domain_filetypes_file(&self) {
+    /// Save domain filetypes to the separate files
+    pub fn save_domain_filetypes_file(&self) {
         if let Some(path) = Self::domain_filetypes_path() {
-            if let Ok(contents) = serde_yml::to_string(&self.domain_filetypes) {
-                let _ = std::fs::write(&path, contents);
-            }
+            save_filetypes_file(&path, &self.domain_filetypes);
+        }
+        if let Some(path) = Self::explicit_domain_filetypes_path() {
+            save_filetypes_file(&path, &self.explicit_domain_filetypes);
         }
     }
 
-    /// Get all domain filetypes
-    pub fn get_all_domain_filetypes(&self) -> &HashMap<String, String> {
-        &self.domain_filetypes
+    /// Get all domain filetypes, explicit ones replacing learned ones
+    pub fn get_all_domain_filetypes(&self) -> HashMap<String, String> {
+        let mut filetypes = self.domain_filetypes.clone();
+        filetypes.extend(self.explicit_domain_filetypes.clone());
+        filetypes
+    }
+}
+
+fn load_filetypes_file(path: &std::path::Path) -> Option<HashMap<String, String>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_yml::from_str(&contents).ok()
+}
+
+fn save_filetypes_file(path: &std::path::Path, filetypes: &HashMap<String, String>) {
+    if let Ok(contents) = serde_yml::to_string(filetypes) {
+        let _ = std::fs::write(path, contents);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_filetype_wins_over_learned() {
+        let mut settings = NvimEditSettings::default();
+        settings.domain_filetypes.insert("github.com".to_string(), "markdown".to_string());
+        settings.domain_filetypes.insert("gitlab.com".to_string(), "text".to_string());
+        settings.explicit_domain_filetypes.insert("github.com".to_string(), "gitcommit".to_string());
+
+        assert_eq!(settings.get_filetype_for_domain("github.com"), Some("gitcommit"));
+        assert_eq!(settings.get_filetype_for_domain("gitlab.com"), Some("text"));
+        let all = settings.get_all_domain_filetypes();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["github.com"], "gitcommit");
+    }
+
+    #[test]
+    fn test_trailing_newline_strip_one() {
+        assert_eq!(TrailingNewline::StripOne.apply("a\n", "a"), "a");
+        assert_eq!(TrailingNewline::StripOne.apply("a\n\n", "a"), "a\n");
+        assert_eq!(TrailingNewline::StripOne.apply("a", "a"), "a");
+    }
+
+    #[test]
+    fn test_trailing_newline_strip_one_keeps_original_newline() {
+        // nvim reads "a\n" as one line with 'eol' set and writes it back unchanged
+        assert_eq!(TrailingNewline::StripOne.apply("a\n", "a\n"), "a\n");
+        assert_eq!(TrailingNewline::StripOne.apply("a\nb\n", "a\n"), "a\nb\n");
+        assert_eq!(TrailingNewline::StripOne.apply("a\n\n", "a\n\n"), "a\n\n");
+        // Without one, the newline nvim added is removed
+        assert_eq!(TrailingNewline::StripOne.apply("a\nb\n", "a"), "a\nb");
+    }
+
+    #[test]
+    fn test_trailing_newline_strip_all() {
+        assert_eq!(TrailingNewline::StripAll.apply("a\n\n\n", "a"), "a");
+        assert_eq!(TrailingNewline::StripAll.apply("a  \n \n\n", "a"), "a  ");
+        assert_eq!(TrailingNewline::StripAll.apply("a\r\n\r\n", "a"), "a");
+        assert_eq!(TrailingNewline::StripAll.apply("a\n\nb\n", "a"), "a\n\nb");
+        assert_eq!(TrailingNewline::StripAll.apply("\n\n", "a"), "");
+    }
+
+    #[test]
+    fn test_trailing_newline_preserve() {
+        assert_eq!(TrailingNewline::Preserve.apply("a\n\n", "a"), "a\n\n");
+    }
+
+    #[test]
+    fn test_validate_filetype() {
+        for filetype in ["markdown", "c", "c.doxygen", "git-commit", "yaml_ansible", "x86asm"] {
+            assert!(validate_filetype(filetype).is_ok(), "{}", filetype);
+        }
+        for filetype in ["", "mark down", "1c", ".md", "md.", "set ft=md", "ft|!rm", &"a".repeat(65)] {
+            assert!(validate_filetype(filetype).is_err(), "{}", filetype);
+        }
+    }
+
+    #[test]
+    fn test_only_neovim_supports_live_sync() {
+        let live: Vec<EditorType> = EditorType::ALL
+            .into_iter()
+            .filter(EditorType::supports_live_sync)
+            .collect();
+        assert_eq!(live, vec![EditorType::Neovim]);
+
+        let helix = EditorCapabilities::from(EditorType::Helix);
+        assert!(!helix.needs_socket);
+        assert!(helix.default_args.is_empty());
+        assert_eq!(EditorCapabilities::from(EditorType::Vim).default_args, vec!["+normal G$"]);
+    }
+
+    #[test]
+    fn test_editor_start_uses_cursor_when_supported() {
+        let mut settings = NvimEditSettings::default();
+        assert_eq!(settings.editor_start(true, Some((2, 4))), EditorStart::Insert);
+        let start = settings.editor_start(false, Some((2, 4)));
+        assert_eq!(settings.editor_args(start), vec!["+call cursor(3,5)"]);
+
+        settings.editor = EditorType::Helix;
+        assert_eq!(settings.editor_args(start), vec!["+3"]);
+
+        settings.editor = EditorType::Custom;
+        assert_eq!(settings.editor_start(false, Some((2, 4))), EditorStart::End);
+
+        settings.editor = EditorType::Vim;
+        settings.open_at_cursor = false;
+        assert_eq!(settings.editor_start(false, Some((2, 4))), EditorStart::End);
+        assert_eq!(settings.editor_args(EditorStart::End), vec!["+normal G$"]);
+    }
+
+    #[test]
+    fn test_cancel_behavior_defaults_to_keeping_live_sync() {
+        assert_eq!(NvimEditSettings::default().cancel_behavior, CancelBehavior::KeepLiveSynced);
+        assert_eq!(
+            serde_json::from_str::<CancelBehavior>("\"revert_to_original\"").unwrap(),
+            CancelBehavior::RevertToOriginal
+        );
     }
 }
@@ -1,5 +1,17 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+/// Modifier that has to be held for scroll mode keys to scroll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollHoldModifier {
+    Command,
+    Option,
+    Control,
+    Function,
+}
+
 /// Settings for Scroll Mode feature (Vimium-style navigation)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -8,17 +20,32 @@ pub struct ScrollModeSettings {
     pub enabled: bool,
     /// Scroll amount in pixels for j/k keys
     pub scroll_step: u32,
+    /// Holding j/k scrolls smoothly until the key is released
+    pub continuous_scroll: bool,
+    /// When set, scroll keys only scroll while this modifier is held (e.g. Option+j);
+    /// without it they reach the app as usual
+    pub scroll_hold_modifier: Option<ScrollHoldModifier>,
     /// Enable list navigation mode (hjkl sends arrow keys instead of scroll)
     /// Useful for Finder, System Settings, and other list-based apps
     pub list_navigation: bool,
+    /// Treat lists as 2D grids (icon views, Launchpad): H/L extend the selection by column
+    /// like J/K do by row, instead of going back/forward. Grid arrow-key handling varies
+    /// by app, so this is best-effort.
+    pub list_navigation_grid: bool,
     /// Bundle identifiers of apps where scroll mode is enabled
     pub enabled_apps: Vec<String>,
     /// Bundle identifiers of apps where list navigation is enabled (hjkl = arrow keys)
     /// When empty, uses enabled_apps as fallback
     pub list_navigation_apps: Vec<String>,
-    /// Bundle identifiers of apps that disable scroll mode when they have visible windows
-    /// (e.g., overlay apps like Keyboard Maestro palettes)
+    /// Bundle identifiers of apps whose focused overlay windows (e.g., Keyboard Maestro
+    /// palettes) in front of the frontmost app disable scroll mode
     pub overlay_blocklist: Vec<String>,
+    /// Window titles (bundle ID -> titles, case-insensitive substrings) of blocklisted
+    /// apps that don't pause scroll mode, e.g. scrollable popovers and find results
+    pub overlay_title_allowlist: HashMap<String, Vec<String>>,
+    /// After an app switch or mouse click, scroll and list keys pass through for this
+    /// many milliseconds while the focused element settles. 0 disables the grace period.
+    pub focus_grace_ms: u64,
     /// Shortcut groups that are disabled (e.g., "hjkl", "gg", "G", "du", "slash", "HL", "rR")
     pub disabled_shortcuts: Vec<String>,
 }
@@ -28,7 +55,10 @@ impl Default for ScrollModeSettings {
         Self {
             enabled: false,
             scroll_step: 100,
+            continuous_scroll: false,
+            scroll_hold_modifier: None,
             list_navigation: false,
+            list_navigation_grid: false,
             enabled_apps: vec![
                 "com.apple.Safari".to_string(),
                 "com.google.Chrome".to_string(),
@@ -41,6 +71,7 @@ impl Default for ScrollModeSettings {
                 "com.apple.finder".to_string(),
                 "com.apple.systempreferences".to_string(),
                 "com.apple.SystemPreferences".to_string(),
+                "com.tonis.ovim".to_string(), // ovim's own settings window
             ],
             overlay_blocklist: vec![
                 "com.stairways.keyboardmaestro.engine".to_string(), // KM palettes
@@ -50,6 +81,8 @@ impl Default for ScrollModeSettings {
                 "com.1password.1password".to_string(),              // 1Password
                 "com.bitwarden.desktop".to_string(),                // Bitwarden
             ],
+            overlay_title_allowlist: HashMap::new(),
+            focus_grace_ms: 100,
             disabled_shortcuts: vec![],
         }
     }
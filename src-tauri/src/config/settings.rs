@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use super::click_mode::ClickModeSettings;
 use super::colors::ModeColors;
 use super::nvim_edit::NvimEditSettings;
 use super::scroll_mode::ScrollModeSettings;
+use crate::vim::VimMode;
 
 /// A row item in the indicator layout
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -53,6 +56,64 @@ pub struct VimKeyModifiers {
     pub control: bool,
     pub option: bool,
     pub command: bool,
+    /// Fn/Globe key
+    #[serde(default)]
+    pub function: bool,
+}
+
+/// A key combination: key name (as stored by `KeyCode::to_name`) plus exact modifiers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct KeyBinding {
+    pub key: String,
+    #[serde(default)]
+    pub modifiers: VimKeyModifiers,
+}
+
+/// What a Caps Lock press is remapped to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapsLockRemap {
+    /// Caps Lock behaves normally
+    #[default]
+    None,
+    /// Any press sends Escape
+    Escape,
+    /// Holding acts as Control for other keys
+    Control,
+    /// Tap sends Escape, hold acts as Control
+    EscapeOrControl,
+}
+
+/// Who repeats a held motion key
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyRepeatMode {
+    /// Rely on the OS key repeat
+    #[default]
+    System,
+    /// ovim repeats the motion at its own delay and rate while the key is held
+    Managed,
+}
+
+/// Repeat behavior for held motion keys in vim and scroll mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct KeyRepeatSettings {
+    pub mode: KeyRepeatMode,
+    /// Time a key must be held before repeating starts (ms)
+    pub initial_delay_ms: u32,
+    /// Time between repeats (ms)
+    pub interval_ms: u32,
+}
+
+impl Default for KeyRepeatSettings {
+    fn default() -> Self {
+        Self {
+            mode: KeyRepeatMode::System,
+            initial_delay_ms: 250,
+            interval_ms: 35,
+        }
+    }
 }
 
 /// Application settings
@@ -82,6 +143,9 @@ pub struct Settings {
     /// Whether the indicator window is visible
     #[serde(default = "default_true")]
     pub indicator_visible: bool,
+    /// Clicking the indicator opens settings (the window then captures clicks over it)
+    #[serde(default)]
+    pub indicator_click_opens_settings: bool,
     /// Show mode indicator in menu bar icon
     #[serde(default)]
     pub show_mode_in_menu_bar: bool,
@@ -93,6 +157,25 @@ pub struct Settings {
     pub indicator_font: String,
     /// Bundle identifiers of apps where vim mode is disabled
     pub ignored_apps: Vec<String>,
+    /// Key combinations that are never intercepted, regardless of mode
+    #[serde(default)]
+    pub passthrough_shortcuts: Vec<KeyBinding>,
+    /// Remap Caps Lock to Escape (tap) and/or Control (hold)
+    #[serde(default)]
+    pub caps_lock_remap: CapsLockRemap,
+    /// How held motion keys repeat
+    #[serde(default)]
+    pub key_repeat: KeyRepeatSettings,
+    /// Mode vim starts in when ovim launches
+    #[serde(default)]
+    pub initial_mode: VimMode,
+    /// Each app remembers the mode it was left in, restored when it is focused again
+    #[serde(default)]
+    pub remember_mode_per_app: bool,
+    /// Mode set when switching to an app without a remembered mode (every app switch
+    /// when `remember_mode_per_app` is off). Unset keeps the current mode.
+    #[serde(default)]
+    pub default_mode_on_new_app: Option<VimMode>,
     /// Launch at login
     pub launch_at_login: bool,
     /// Show in menu bar
@@ -122,6 +205,26 @@ pub struct Settings {
     /// User-defined shell script widgets
     #[serde(default)]
     pub shell_widgets: Vec<ShellWidgetConfig>,
+    /// Localized app names keyed by bundle ID (display only, matching always uses bundle IDs)
+    #[serde(default)]
+    pub app_display_names: HashMap<String, String>,
+    /// Unix socket the CLI connects to (`~/` is expanded). Unset means the cache directory.
+    /// Read at startup, so a change takes effect after restarting ovim.
+    #[serde(default)]
+    pub ipc_socket_path: Option<PathBuf>,
+    /// Shared secret the CLI must send with every command. Unset means no token is required.
+    #[serde(default)]
+    pub ipc_token: Option<String>,
+    /// Keys sent to the focused app when a `normal` IPC command switches modes, so an app
+    /// with its own insert mode (e.g., a vim emulator) leaves it too. Empty sends nothing.
+    #[serde(default)]
+    pub ipc_normal_keys: Vec<KeyBinding>,
+    /// Serve IPC commands over HTTP/WebSocket on 127.0.0.1 (requires `ipc_token`)
+    #[serde(default)]
+    pub ipc_bridge_enabled: bool,
+    /// Port of the HTTP/WebSocket bridge
+    #[serde(default = "default_ipc_bridge_port")]
+    pub ipc_bridge_port: u16,
 }
 
 fn default_none_widget() -> String {
@@ -140,6 +243,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_ipc_bridge_port() -> u16 {
+    47100
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -152,10 +259,17 @@ impl Default for Settings {
             indicator_offset_x: 0,
             indicator_offset_y: 0,
             indicator_visible: true,
+            indicator_click_opens_settings: false,
             show_mode_in_menu_bar: false,
             mode_colors: ModeColors::default(),
             indicator_font: default_font_family(),
             ignored_apps: vec![],
+            passthrough_shortcuts: vec![],
+            caps_lock_remap: CapsLockRemap::default(),
+            key_repeat: KeyRepeatSettings::default(),
+            initial_mode: VimMode::Insert,
+            remember_mode_per_app: false,
+            default_mode_on_new_app: None,
             launch_at_login: false,
             show_in_menu_bar: true,
             indicator_rows: vec![RowItem::ModeChar { size: 2 }],
@@ -167,11 +281,146 @@ impl Default for Settings {
             scroll_mode: ScrollModeSettings::default(),
             auto_update_enabled: true,
             shell_widgets: vec![],
+            app_display_names: HashMap::new(),
+            ipc_socket_path: None,
+            ipc_token: None,
+            ipc_normal_keys: vec![],
+            ipc_bridge_enabled: false,
+            ipc_bridge_port: default_ipc_bridge_port(),
         }
     }
 }
 
+/// Names of the bundle ID lists that can be edited generically (e.g. "add current app")
+pub const APP_LIST_NAMES: &[&str] = &[
+    "ignored_apps",
+    "electron_apps",
+    "scroll_mode.enabled_apps",
+    "scroll_mode.list_navigation_apps",
+    "scroll_mode.overlay_blocklist",
+    "click_mode.enabled_apps",
+    "click_mode.disabled_apps",
+    "click_mode.auto_activate_apps",
+];
+
 impl Settings {
+    /// Get a mutable reference to a bundle ID list by name (see `APP_LIST_NAMES`)
+    pub fn app_list_mut(&mut self, list_name: &str) -> Option<&mut Vec<String>> {
+        match list_name {
+            "ignored_apps" => Some(&mut self.ignored_apps),
+            "electron_apps" => Some(&mut self.electron_apps),
+            "scroll_mode.enabled_apps" => Some(&mut self.scroll_mode.enabled_apps),
+            "scroll_mode.list_navigation_apps" => Some(&mut self.scroll_mode.list_navigation_apps),
+            "scroll_mode.overlay_blocklist" => Some(&mut self.scroll_mode.overlay_blocklist),
+            "click_mode.enabled_apps" => Some(&mut self.click_mode.enabled_apps),
+            "click_mode.disabled_apps" => Some(&mut self.click_mode.disabled_apps),
+            "click_mode.auto_activate_apps" => Some(&mut self.click_mode.auto_activate_apps),
+            _ => None,
+        }
+    }
+
+    /// Add a bundle ID to the named list.
+    /// Returns Ok(false) if it was already present.
+    pub fn add_app_to_list(&mut self, list_name: &str, bundle_id: &str) -> Result<bool, String> {
+        let list = self.app_list_mut(list_name).ok_or_else(|| {
+            format!(
+                "Unknown app list: {} (expected one of: {})",
+                list_name,
+                APP_LIST_NAMES.join(", ")
+            )
+        })?;
+        if list.iter().any(|id| id == bundle_id) {
+            return Ok(false);
+        }
+        list.push(bundle_id.to_string());
+        Ok(true)
+    }
+
+    /// Remember the display name for a bundle ID
+    pub fn set_app_display_name(&mut self, bundle_id: &str, name: Option<String>) {
+        if let Some(name) = name.filter(|n| !n.is_empty()) {
+            self.app_display_names.insert(bundle_id.to_string(), name);
+        }
+    }
+
+    /// Keep display names from `previous` that are missing here.
+    /// Names are recorded by the backend, so a settings update from the UI may not include them yet.
+    pub fn merge_app_display_names(&mut self, previous: &Settings) {
+        for (bundle_id, name) in &previous.app_display_names {
+            self.app_display_names
+                .entry(bundle_id.clone())
+                .or_insert_with(|| name.clone());
+        }
+    }
+
+    /// Forget what ovim learned while running, keeping everything the user configured.
+    ///
+    /// Learned: `nvim_edit.domain_filetypes` (filetypes saved when nvim exits) and
+    /// `app_display_names` (names recorded when apps are added to a list).
+    /// Configured: everything else, including shortcuts, app lists and the filetypes set
+    /// with `set_filetype_mapping` (`nvim_edit.explicit_domain_filetypes`).
+    /// Popup positions are computed per session and never stored, so there are none to clear.
+    ///
+    /// Only changes memory; the caller saves settings.yaml and domain-filetypes.yaml.
+    pub fn clear_learned_data(&mut self) {
+        self.nvim_edit.domain_filetypes.clear();
+        self.app_display_names.clear();
+    }
+
+    /// Take a full settings object from the UI, keeping state only the backend knows:
+    /// learned domain filetypes (never serialized) and recorded app display names
+    pub fn replace_from_ui(&mut self, mut incoming: Settings) {
+        incoming.merge_app_display_names(self);
+        incoming.nvim_edit.take_domain_filetypes(&mut self.nvim_edit);
+        *self = incoming;
+    }
+
+    /// Apply `change` to the shared settings and save them, holding the lock throughout.
+    /// Every writer goes through here so changes made on other threads aren't lost.
+    /// Returns the updated settings for the `settings-changed` event.
+    pub fn update(
+        shared: &Mutex<Settings>,
+        change: impl FnOnce(&mut Settings) -> Result<(), String>,
+    ) -> Result<Settings, String> {
+        Self::update_with(shared, change, Settings::save)
+    }
+
+    fn update_with(
+        shared: &Mutex<Settings>,
+        change: impl FnOnce(&mut Settings) -> Result<(), String>,
+        persist: impl FnOnce(&Settings) -> Result<(), String>,
+    ) -> Result<Settings, String> {
+        let mut settings = shared.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut settings)?;
+        persist(&settings)?;
+        Ok(settings.clone())
+    }
+
+    /// Read one setting by key (see `setting_pointer` for the key syntax)
+    pub fn get_value(&self, key: &str) -> Result<serde_json::Value, String> {
+        let root = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        root.pointer(&setting_pointer(key))
+            .cloned()
+            .ok_or_else(|| format!("Unknown setting: {}", key))
+    }
+
+    /// Change one setting by key, leaving every other field as it is.
+    /// The value must have the field's type, e.g. `true` for a checkbox setting.
+    pub fn set_value(&mut self, key: &str, value: serde_json::Value) -> Result<(), String> {
+        let mut root = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let slot = root
+            .pointer_mut(&setting_pointer(key))
+            .ok_or_else(|| format!("Unknown setting: {}", key))?;
+        *slot = value;
+
+        let mut updated: Settings =
+            serde_json::from_value(root).map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+        // Not serialized: domain filetypes live in their own file
+        updated.nvim_edit.take_domain_filetypes(&mut self.nvim_edit);
+        *self = updated;
+        Ok(())
+    }
+
     /// Get the path to the YAML settings file
     pub fn file_path() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join("ovim").join("settings.yaml"))
@@ -336,3 +585,171 @@ impl Settings {
         std::fs::write(&path, contents).map_err(|e| format!("Failed to write settings: {}", e))
     }
 }
+
+/// Convert a setting key to a JSON pointer.
+/// Keys are dotted paths (`nvim_edit.live_sync_enabled`, `indicator_rows.0`), or JSON
+/// pointers starting with `/` for map keys that contain dots (`/app_display_names/com.apple.Safari`).
+fn setting_pointer(key: &str) -> String {
+    if key.starts_with('/') {
+        return key.to_string();
+    }
+    key.split('.')
+        .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_app_list_name_resolves() {
+        let mut settings = Settings::default();
+        for name in APP_LIST_NAMES {
+            assert!(settings.app_list_mut(name).is_some(), "{} should resolve", name);
+        }
+    }
+
+    #[test]
+    fn test_add_app_to_list_deduplicates() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.add_app_to_list("ignored_apps", "com.example.app"), Ok(true));
+        assert_eq!(settings.add_app_to_list("ignored_apps", "com.example.app"), Ok(false));
+        assert_eq!(settings.ignored_apps, vec!["com.example.app".to_string()]);
+    }
+
+    #[test]
+    fn test_add_app_to_unknown_list_fails() {
+        let mut settings = Settings::default();
+        assert!(settings.add_app_to_list("not_a_list", "com.example.app").is_err());
+    }
+
+    #[test]
+    fn test_merge_app_display_names_keeps_known_names() {
+        let mut previous = Settings::default();
+        previous.set_app_display_name("com.apple.Safari", Some("Safari".to_string()));
+        previous.set_app_display_name("com.example.app", Some("Old".to_string()));
+
+        let mut updated = Settings::default();
+        updated.set_app_display_name("com.example.app", Some("New".to_string()));
+        updated.set_app_display_name("com.example.empty", Some(String::new()));
+        updated.merge_app_display_names(&previous);
+
+        assert_eq!(updated.app_display_names.get("com.apple.Safari").map(String::as_str), Some("Safari"));
+        assert_eq!(updated.app_display_names.get("com.example.app").map(String::as_str), Some("New"));
+        assert!(!updated.app_display_names.contains_key("com.example.empty"));
+    }
+
+    #[test]
+    fn test_clear_learned_data_keeps_configuration() {
+        let mut settings = Settings::default();
+        settings.ignored_apps.push("com.example.app".to_string());
+        settings.nvim_edit.shortcut_key = "e".to_string();
+        settings.set_app_display_name("com.example.app", Some("Example".to_string()));
+        settings
+            .nvim_edit
+            .domain_filetypes
+            .insert("github.com".to_string(), "markdown".to_string());
+        settings
+            .nvim_edit
+            .explicit_domain_filetypes
+            .insert("gitlab.com".to_string(), "markdown".to_string());
+
+        settings.clear_learned_data();
+
+        assert!(settings.app_display_names.is_empty());
+        assert!(settings.nvim_edit.domain_filetypes.is_empty());
+        assert_eq!(settings.nvim_edit.get_filetype_for_domain("gitlab.com"), Some("markdown"));
+        assert_eq!(settings.ignored_apps, vec!["com.example.app".to_string()]);
+        assert_eq!(settings.nvim_edit.shortcut_key, "e");
+    }
+
+    #[test]
+    fn test_get_value_by_key() {
+        let settings = Settings::default();
+        assert_eq!(settings.get_value("indicator_visible"), Ok(serde_json::json!(true)));
+        assert_eq!(
+            settings.get_value("indicator_rows.0"),
+            Ok(serde_json::json!({ "type": "ModeChar", "size": 2 }))
+        );
+        assert!(settings.get_value("nvim_edit.no_such_field").is_err());
+    }
+
+    #[test]
+    fn test_set_value_changes_only_that_field() {
+        let mut settings = Settings::default();
+        settings.ignored_apps.push("com.example.app".to_string());
+        settings
+            .nvim_edit
+            .domain_filetypes
+            .insert("github.com".to_string(), "markdown".to_string());
+
+        settings.set_value("scroll_mode.enabled", serde_json::json!(true)).unwrap();
+        assert!(settings.scroll_mode.enabled);
+        assert_eq!(settings.ignored_apps, vec!["com.example.app".to_string()]);
+        assert_eq!(settings.nvim_edit.domain_filetypes.len(), 1);
+
+        settings
+            .set_value("/app_display_names/com.apple.Safari", serde_json::json!("Safari"))
+            .unwrap_err();
+        settings.set_app_display_name("com.apple.Safari", Some("Safari".to_string()));
+        settings
+            .set_value("/app_display_names/com.apple.Safari", serde_json::json!("Safari 2"))
+            .unwrap();
+        assert_eq!(settings.app_display_names["com.apple.Safari"], "Safari 2");
+    }
+
+    #[test]
+    fn test_set_value_rejects_wrong_type_and_unknown_key() {
+        let mut settings = Settings::default();
+        assert!(settings.set_value("indicator_visible", serde_json::json!("yes")).is_err());
+        assert!(settings.set_value("not_a_setting", serde_json::json!(true)).is_err());
+        assert!(settings.indicator_visible);
+    }
+
+    #[test]
+    fn test_concurrent_filetype_save_and_settings_set() {
+        let shared = std::sync::Arc::new(Mutex::new(Settings::default()));
+        let no_save = |_: &Settings| Ok(());
+
+        // The UI's copy never carries learned filetypes
+        let from_ui = Settings {
+            indicator_opacity: 0.5,
+            ..Settings::default()
+        };
+
+        let learner = {
+            let shared = std::sync::Arc::clone(&shared);
+            std::thread::spawn(move || {
+                for i in 0..50 {
+                    Settings::update_with(
+                        &shared,
+                        |s| {
+                            s.nvim_edit.domain_filetypes.insert(format!("site{}.com", i), "markdown".to_string());
+                            Ok(())
+                        },
+                        no_save,
+                    )
+                    .unwrap();
+                }
+            })
+        };
+        for _ in 0..50 {
+            let from_ui = from_ui.clone();
+            Settings::update_with(
+                &shared,
+                |s| {
+                    s.replace_from_ui(from_ui);
+                    Ok(())
+                },
+                no_save,
+            )
+            .unwrap();
+        }
+        learner.join().unwrap();
+
+        let settings = shared.lock().unwrap();
+        assert_eq!(settings.indicator_opacity, 0.5);
+        assert_eq!(settings.nvim_edit.domain_filetypes.len(), 50);
+    }
+}
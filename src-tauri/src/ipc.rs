@@ -1,15 +1,8 @@
-use std::path::PathBuf;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{UnixListener, UnixStream};
 
-/// Get the socket path for IPC
-pub fn socket_path() -> PathBuf {
-    let runtime_dir = dirs::runtime_dir()
-        .or_else(dirs::cache_dir)
-        .unwrap_or_else(|| PathBuf::from("/tmp"));
-    runtime_dir.join("ovim.sock")
-}
-
 /// IPC command from CLI to main app
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum IpcCommand {
@@ -29,6 +22,10 @@ pub enum IpcCommand {
     EditPopup,
     /// Activate Click Mode
     ClickMode,
+    /// Focus the next text field in the focused window
+    FocusNextField,
+    /// Focus the previous text field in the focused window
+    FocusPreviousField,
     /// Launcher script signals it handled spawning
     LauncherHandled {
         session_id: String,
@@ -38,6 +35,52 @@ pub enum IpcCommand {
     LauncherFallthrough { session_id: String },
 }
 
+/// A command with the client's token, as sent over the socket
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IpcRequest {
+    pub command: IpcCommand,
+    /// Must match the `ipc_token` setting when one is configured
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl IpcRequest {
+    /// Parse a request line. A bare command (sent by CLIs without token support)
+    /// is accepted as a request without a token.
+    fn parse(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line)
+            .or_else(|_| {
+                serde_json::from_str(line).map(|command| Self {
+                    command,
+                    token: None,
+                })
+            })
+            .map_err(|e| format!("Invalid command: {}", e))
+    }
+
+    /// Whether the request may run. Without a configured token every request is allowed.
+    fn is_authorized(&self, token: Option<&str>) -> bool {
+        match token.filter(|t| !t.is_empty()) {
+            Some(expected) => self
+                .token
+                .as_deref()
+                .is_some_and(|received| tokens_match(received, expected)),
+            None => true,
+        }
+    }
+}
+
+/// Compare a received token with the configured one in constant time, so response
+/// timing doesn't reveal how much of a guess was right
+pub fn tokens_match(received: &str, expected: &str) -> bool {
+    let (received, expected) = (received.as_bytes(), expected.as_bytes());
+    let mut diff = received.len() ^ expected.len();
+    for (i, &byte) in expected.iter().enumerate() {
+        diff |= usize::from(byte ^ received.get(i).copied().unwrap_or(0));
+    }
+    std::hint::black_box(diff) == 0
+}
+
 /// IPC response from main app to CLI
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum IpcResponse {
@@ -49,28 +92,42 @@ pub enum IpcResponse {
     Error(String),
 }
 
-/// Start the IPC server
-pub async fn start_ipc_server<F>(handler: F) -> Result<(), String>
+/// Start the IPC server on `path`. The socket is only accessible to the current user,
+/// and with a `token` only requests carrying the same token are handled.
+pub async fn start_ipc_server<F>(
+    path: PathBuf,
+    token: Option<String>,
+    handler: F,
+) -> Result<(), String>
 where
     F: Fn(IpcCommand) -> IpcResponse + Send + Sync + 'static,
 {
-    let path = socket_path();
+    // One instance per socket: don't take over a socket another instance is serving
+    if UnixStream::connect(&path).await.is_ok() {
+        return Err(format!("Another ovim instance is listening on {:?}", path));
+    }
 
-    // Remove existing socket if present
+    // Remove a stale socket if present
     let _ = std::fs::remove_file(&path);
 
     let listener = UnixListener::bind(&path).map_err(|e| format!("Failed to bind socket: {}", e))?;
 
+    // Owner-only, so other users can't send commands
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict socket permissions: {}", e))?;
+
     log::info!("IPC server listening on {:?}", path);
 
     let handler = std::sync::Arc::new(handler);
+    let token: std::sync::Arc<Option<String>> = std::sync::Arc::new(token);
 
     loop {
         match listener.accept().await {
             Ok((stream, _)) => {
                 let handler = handler.clone();
+                let token = token.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = handle_client(stream, handler).await {
+                    if let Err(e) = handle_client(stream, handler, token.as_deref()).await {
                         log::error!("Error handling IPC client: {}", e);
                     }
                 });
@@ -82,7 +139,11 @@ where
     }
 }
 
-async fn handle_client<F>(stream: UnixStream, handler: std::sync::Arc<F>) -> Result<(), String>
+async fn handle_client<F>(
+    stream: UnixStream,
+    handler: std::sync::Arc<F>,
+    token: Option<&str>,
+) -> Result<(), String>
 where
     F: Fn(IpcCommand) -> IpcResponse,
 {
@@ -97,10 +158,13 @@ where
             continue;
         }
 
-        let cmd: IpcCommand = serde_json::from_str(trimmed)
-            .map_err(|e| format!("Invalid command: {}", e))?;
-
-        let response = handler(cmd);
+        let request = IpcRequest::parse(trimmed)?;
+        let response = if request.is_authorized(token) {
+            handler(request.command)
+        } else {
+            log::warn!("Rejected IPC command {:?}: invalid token", request.command);
+            IpcResponse::Error("Invalid IPC token".to_string())
+        };
         let response_str = serde_json::to_string(&response).map_err(|e| e.to_string())?;
 
         writer
@@ -116,18 +180,21 @@ where
     Ok(())
 }
 
-/// Send a command to the running ovim instance
-pub async fn send_command(cmd: IpcCommand) -> Result<IpcResponse, String> {
-    let path = socket_path();
-
-    let stream = UnixStream::connect(&path)
+/// Send a command to the ovim instance listening on `path`
+pub async fn send_command(
+    path: &Path,
+    token: Option<String>,
+    cmd: IpcCommand,
+) -> Result<IpcResponse, String> {
+    let stream = UnixStream::connect(path)
         .await
         .map_err(|e| format!("Failed to connect to ovim (is it running?): {}", e))?;
 
     let (reader, mut writer) = stream.into_split();
     let mut reader = BufReader::new(reader);
 
-    let cmd_str = serde_json::to_string(&cmd).map_err(|e| e.to_string())?;
+    let request = IpcRequest { command: cmd, token };
+    let cmd_str = serde_json::to_string(&request).map_err(|e| e.to_string())?;
     writer
         .write_all(cmd_str.as_bytes())
         .await
@@ -143,3 +210,41 @@ pub async fn send_command(cmd: IpcCommand) -> Result<IpcResponse, String> {
 
     Ok(response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bare_command_parses_as_request_without_token() {
+        let request = IpcRequest::parse(r#""Toggle""#).unwrap();
+        assert!(matches!(request.command, IpcCommand::Toggle));
+        assert_eq!(request.token, None);
+
+        let request = IpcRequest::parse(r#"{"command":"Toggle","token":"secret"}"#).unwrap();
+        assert_eq!(request.token.as_deref(), Some("secret"));
+        assert!(IpcRequest::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_token_is_required_only_when_configured() {
+        let request = |token: Option<&str>| IpcRequest {
+            command: IpcCommand::GetMode,
+            token: token.map(String::from),
+        };
+        assert!(request(None).is_authorized(None));
+        assert!(request(None).is_authorized(Some("")));
+        assert!(request(Some("secret")).is_authorized(Some("secret")));
+        assert!(!request(None).is_authorized(Some("secret")));
+        assert!(!request(Some("wrong")).is_authorized(Some("secret")));
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+        assert!(!tokens_match("secre", "secret"));
+        assert!(!tokens_match("", "secret"));
+    }
+}
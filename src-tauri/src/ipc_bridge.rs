@@ -0,0 +1,163 @@
+//! Loopback HTTP/WebSocket bridge for IPC commands
+//!
+//! Lets tools that can't speak a Unix socket (Stream Deck plugins, browser
+//! extensions) send the same commands as the CLI. Off by default; it only binds
+//! to 127.0.0.1 and requires the `ipc_token` setting.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+
+use crate::ipc::{tokens_match, IpcCommand, IpcResponse};
+
+/// Runs one IPC command; shared with the Unix socket server
+pub type IpcHandler = Arc<dyn Fn(IpcCommand) -> IpcResponse + Send + Sync>;
+
+#[derive(Clone)]
+struct BridgeState {
+    token: Arc<str>,
+    handler: IpcHandler,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Serve `POST /command` and `GET /ws` on 127.0.0.1:`port`
+pub async fn start_bridge_server(
+    port: u16,
+    token: Option<String>,
+    handler: IpcHandler,
+) -> Result<(), String> {
+    let token = token
+        .filter(|t| !t.is_empty())
+        .ok_or("The IPC bridge requires the ipc_token setting")?;
+
+    let app = Router::new()
+        .route("/command", post(post_command))
+        .route("/ws", get(websocket))
+        .with_state(BridgeState {
+            token: token.into(),
+            handler,
+        });
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+    log::info!("IPC bridge listening on http://{}", addr);
+
+    axum::serve(listener, app).await.map_err(|e| e.to_string())
+}
+
+/// Run a JSON-encoded `IpcCommand`, turning parse errors into an error response
+fn dispatch(handler: &IpcHandler, body: &str) -> IpcResponse {
+    match serde_json::from_str::<IpcCommand>(body) {
+        Ok(command) => handler(command),
+        Err(e) => IpcResponse::Error(format!("Invalid command: {}", e)),
+    }
+}
+
+/// Whether the request carries the token, as `Authorization: Bearer <token>` or `?token=`.
+/// Browsers can't set headers on WebSocket requests, hence the query parameter.
+fn is_authorized(headers: &HeaderMap, query: &TokenQuery, token: &str) -> bool {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer
+        .or(query.token.as_deref())
+        .is_some_and(|received| tokens_match(received, token))
+}
+
+fn unauthorized() -> Response {
+    log::warn!("Rejected IPC bridge request: invalid token");
+    let body = Json(IpcResponse::Error("Invalid IPC token".to_string()));
+    (StatusCode::UNAUTHORIZED, body).into_response()
+}
+
+async fn post_command(
+    State(state): State<BridgeState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    body: String,
+) -> Response {
+    if !is_authorized(&headers, &query, &state.token) {
+        return unauthorized();
+    }
+    Json(dispatch(&state.handler, &body)).into_response()
+}
+
+async fn websocket(
+    State(state): State<BridgeState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !is_authorized(&headers, &query, &state.token) {
+        return unauthorized();
+    }
+    upgrade.on_upgrade(move |socket| serve_websocket(socket, state.handler))
+}
+
+/// Answer each text message (an `IpcCommand`) with an `IpcResponse`
+async fn serve_websocket(mut socket: WebSocket, handler: IpcHandler) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let response = dispatch(&handler, text.as_str());
+        let Ok(json) = serde_json::to_string(&response) else {
+            continue;
+        };
+        if socket.send(Message::Text(json.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_parses_commands() {
+        let handler: IpcHandler = Arc::new(|command| match command {
+            IpcCommand::SetMode(mode) => IpcResponse::Mode(mode),
+            _ => IpcResponse::Ok,
+        });
+        assert!(matches!(dispatch(&handler, r#""Toggle""#), IpcResponse::Ok));
+        assert!(matches!(
+            dispatch(&handler, r#"{"SetMode":"normal"}"#),
+            IpcResponse::Mode(mode) if mode == "normal"
+        ));
+        assert!(matches!(dispatch(&handler, "toggle"), IpcResponse::Error(_)));
+    }
+
+    #[test]
+    fn test_token_from_header_or_query() {
+        let mut headers = HeaderMap::new();
+        let no_query = TokenQuery::default();
+        assert!(!is_authorized(&headers, &no_query, "secret"));
+
+        let query = TokenQuery {
+            token: Some("secret".to_string()),
+        };
+        assert!(is_authorized(&headers, &query, "secret"));
+
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(is_authorized(&headers, &no_query, "secret"));
+        headers.insert(AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!is_authorized(&headers, &no_query, "secret"));
+    }
+}
@@ -1,8 +1,10 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use core_foundation::base::TCFType;
+use core_foundation::mach_port::CFMachPortRef;
 use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
 use core_graphics::event::{
     CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
@@ -13,13 +15,46 @@ use core_graphics::event::{
 /// Return true to pass through, false to suppress (though suppressing mouse events is rare)
 pub type MouseEventCallback = Box<dyn Fn(MouseClickEvent) -> bool + Send + 'static>;
 
-/// Scroll event callback type - called for scroll wheel events
+/// Scroll event callback type - called for real (not injected) scroll wheel events
 pub type ScrollEventCallback = Box<dyn Fn() + Send + 'static>;
 
 /// Flags changed callback type - called when modifier keys are pressed/released
 /// Parameters are the current modifier state (command, option, shift, control)
 pub type FlagsChangedCallback = Box<dyn Fn(Modifiers) + Send + 'static>;
 
+/// Tap status callback type - called when the event tap stalls or recovers
+pub type TapStatusCallback = Box<dyn Fn(TapStatus) + Send + 'static>;
+
+/// Health of the underlying CGEventTap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapStatus {
+    /// macOS disabled the tap and it could not be re-enabled
+    Stalled,
+    /// The tap was recreated and is receiving events again
+    Recovered,
+}
+
+/// How often to verify the tap is still enabled, even without a disabled event
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventTapIsEnabled(tap: CFMachPortRef) -> bool;
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+}
+
+/// kCGEventSourceStateHIDSystemState: events from hardware, seen whether or not a tap gets them
+const HID_SYSTEM_STATE: i32 = 1;
+/// kCGEventKeyDown
+const KEY_DOWN_EVENT: u32 = 10;
+
+/// Whether a key was pressed on the keyboard since `since`, according to the HID system
+/// (independent of whether the event tap received it)
+pub fn keys_pressed_since(since: Instant) -> bool {
+    let idle = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, KEY_DOWN_EVENT) };
+    idle < since.elapsed().as_secs_f64()
+}
+
 /// Represents a mouse click event
 #[derive(Debug, Clone, Copy)]
 pub struct MouseClickEvent {
@@ -27,7 +62,7 @@ pub struct MouseClickEvent {
     pub is_right_click: bool,
 }
 
-use super::inject::INJECTED_EVENT_MARKER;
+use super::synthetic::is_injected;
 use super::keycode::{KeyEvent, Modifiers};
 
 pub type KeyEventCallback = Box<dyn Fn(KeyEvent) -> Option<KeyEvent> + Send + 'static>;
@@ -43,7 +78,12 @@ pub struct KeyboardCapture {
     mouse_callback: Arc<Mutex<Option<MouseEventCallback>>>,
     scroll_callback: Arc<Mutex<Option<ScrollEventCallback>>>,
     flags_changed_callback: Arc<Mutex<Option<FlagsChangedCallback>>>,
+    tap_status_callback: Arc<Mutex<Option<TapStatusCallback>>>,
     running: Arc<Mutex<bool>>,
+    /// Number of keyboard events received by the tap since start
+    events_received: Arc<AtomicU64>,
+    /// When the tap was last started
+    started_at: Mutex<Option<Instant>>,
 }
 
 impl KeyboardCapture {
@@ -53,7 +93,10 @@ impl KeyboardCapture {
             mouse_callback: Arc::new(Mutex::new(None)),
             scroll_callback: Arc::new(Mutex::new(None)),
             flags_changed_callback: Arc::new(Mutex::new(None)),
+            tap_status_callback: Arc::new(Mutex::new(None)),
             running: Arc::new(Mutex::new(false)),
+            events_received: Arc::new(AtomicU64::new(0)),
+            started_at: Mutex::new(None),
         }
     }
 
@@ -96,6 +139,15 @@ impl KeyboardCapture {
         *cb = Some(Box::new(callback));
     }
 
+    /// Set the callback for event tap health changes (stalled/recovered)
+    pub fn set_tap_status_callback<F>(&self, callback: F)
+    where
+        F: Fn(TapStatus) + Send + 'static,
+    {
+        let mut cb = self.tap_status_callback.lock().unwrap();
+        *cb = Some(Box::new(callback));
+    }
+
     /// Start capturing keyboard events
     /// This spawns a new thread with its own run loop
     pub fn start(&self) -> Result<(), String> {
@@ -106,168 +158,97 @@ impl KeyboardCapture {
         *running = true;
         drop(running);
 
-        let callback = Arc::clone(&self.callback);
-        let mouse_callback = Arc::clone(&self.mouse_callback);
-        let scroll_callback = Arc::clone(&self.scroll_callback);
-        let flags_changed_callback = Arc::clone(&self.flags_changed_callback);
+        let callbacks = TapCallbacks {
+            callback: Arc::clone(&self.callback),
+            mouse_callback: Arc::clone(&self.mouse_callback),
+            scroll_callback: Arc::clone(&self.scroll_callback),
+            flags_changed_callback: Arc::clone(&self.flags_changed_callback),
+            events_received: Arc::clone(&self.events_received),
+        };
+        let tap_status_callback = Arc::clone(&self.tap_status_callback);
         let running_flag = Arc::clone(&self.running);
+        self.events_received.store(0, Ordering::Relaxed);
+        *self.started_at.lock().unwrap() = Some(Instant::now());
 
         // Flag to signal that tap needs re-enabling
         let needs_reenable = Arc::new(AtomicBool::new(false));
-        let needs_reenable_for_callback = Arc::clone(&needs_reenable);
 
         thread::spawn(move || {
-            // Create the event tap - use HID tap location for reliable key suppression
-            // Also listen for mouse down events to detect clicks
-            let tap = CGEventTap::new(
-                CGEventTapLocation::HID,
-                CGEventTapPlacement::HeadInsertEventTap,
-                CGEventTapOptions::Default,
-                vec![
-                    CGEventType::KeyDown,
-                    CGEventType::KeyUp,
-                    CGEventType::FlagsChanged,
-                    CGEventType::LeftMouseDown,
-                    CGEventType::RightMouseDown,
-                    CGEventType::ScrollWheel,
-                ],
-                move |_proxy: CGEventTapProxy, event_type: CGEventType, event| -> CallbackResult {
-                    // Handle tap disabled by timeout - signal re-enable
-                    if is_event_type(event_type, CGEventType::TapDisabledByTimeout) {
-                        log::warn!("CGEventTap was disabled by timeout, signaling re-enable...");
-                        needs_reenable_for_callback.store(true, Ordering::SeqCst);
-                        return CallbackResult::Keep;
-                    }
-
-                    // Handle tap disabled by user - also re-enable
-                    if is_event_type(event_type, CGEventType::TapDisabledByUserInput) {
-                        log::warn!("CGEventTap was disabled by user input, signaling re-enable...");
-                        needs_reenable_for_callback.store(true, Ordering::SeqCst);
-                        return CallbackResult::Keep;
-                    }
-
-                    // Handle mouse click events
-                    if is_event_type(event_type, CGEventType::LeftMouseDown)
-                        || is_event_type(event_type, CGEventType::RightMouseDown)
-                    {
-                        let mouse_event = MouseClickEvent {
-                            is_left_click: is_event_type(event_type, CGEventType::LeftMouseDown),
-                            is_right_click: is_event_type(event_type, CGEventType::RightMouseDown),
-                        };
-                        let cb_lock = mouse_callback.lock().unwrap();
-                        if let Some(ref cb) = *cb_lock {
-                            cb(mouse_event);
-                        }
-                        // Always pass through mouse events
-                        return CallbackResult::Keep;
-                    }
-
-                    // Handle scroll wheel events
-                    if is_event_type(event_type, CGEventType::ScrollWheel) {
-                        let cb_lock = scroll_callback.lock().unwrap();
-                        if let Some(ref cb) = *cb_lock {
-                            cb();
-                        }
-                        // Always pass through scroll events
-                        return CallbackResult::Keep;
+            let notify_status = |status: TapStatus| {
+                let cb_lock = tap_status_callback.lock().unwrap();
+                if let Some(ref cb) = *cb_lock {
+                    cb(status);
+                }
+            };
+
+            // Outer loop recreates the tap when macOS refuses to re-enable it
+            let mut recreating = false;
+            while *running_flag.lock().unwrap() {
+                let tap = match create_event_tap(callbacks.clone(), Arc::clone(&needs_reenable)) {
+                    Ok(tap) => tap,
+                    Err(()) => {
+                        log::error!(
+                            "Failed to create CGEventTap. Make sure Input Monitoring permission is granted."
+                        );
+                        *running_flag.lock().unwrap() = false;
+                        break;
                     }
+                };
 
-                    // Skip events we injected ourselves
-                    let user_data = event.get_integer_value_field(EventField::EVENT_SOURCE_USER_DATA);
-                    if user_data == INJECTED_EVENT_MARKER {
-                        log::trace!("Skipping injected event");
-                        return CallbackResult::Keep;
-                    }
+                // Create run loop source and add to run loop
+                let loop_source = tap
+                    .mach_port()
+                    .create_runloop_source(0)
+                    .expect("Failed to create run loop source");
 
-                    // Handle FlagsChanged events (modifier key changes)
-                    if is_event_type(event_type, CGEventType::FlagsChanged) {
-                        let flags = event.get_flags();
-                        let modifiers = Modifiers::from_cg_flags(flags.bits());
-                        let cb_lock = flags_changed_callback.lock().unwrap();
-                        if let Some(ref cb) = *cb_lock {
-                            cb(modifiers);
-                        }
-                        // Always pass through modifier events
-                        return CallbackResult::Keep;
-                    }
-
-                    // Get key code and flags
-                    let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
-                    log::trace!("Key event: keycode={}, type={:?}", keycode, event_type);
-                    let flags = event.get_flags();
-                    let is_key_down = is_event_type(event_type, CGEventType::KeyDown);
-
-                    let key_event = KeyEvent {
-                        code: keycode,
-                        modifiers: Modifiers::from_cg_flags(flags.bits()),
-                        is_key_down,
-                    };
-
-                    // Call user callback
-                    let cb_lock = callback.lock().unwrap();
-                    if let Some(ref cb) = *cb_lock {
-                        match cb(key_event) {
-                            Some(_modified_event) => {
-                                // Pass through
-                                log::trace!("capture: passing through keycode={}", keycode);
-                                CallbackResult::Keep
-                            }
-                            None => {
-                                // Suppress the event - use Drop to return null_ptr
-                                log::trace!("capture: SUPPRESSING keycode={}", keycode);
-                                CallbackResult::Drop
-                            }
-                        }
-                    } else {
-                        // No callback set, pass through
-                        log::trace!("capture: no callback, passing through keycode={}", keycode);
-                        CallbackResult::Keep
-                    }
-                },
-            );
-
-            match tap {
-                Ok(tap) => {
-                    // Create run loop source and add to run loop
-                    let loop_source = tap
-                        .mach_port()
-                        .create_runloop_source(0)
-                        .expect("Failed to create run loop source");
-
-                    let run_loop = CFRunLoop::get_current();
-                    unsafe {
-                        run_loop.add_source(&loop_source, kCFRunLoopDefaultMode);
-                    }
+                let run_loop = CFRunLoop::get_current();
+                unsafe {
+                    run_loop.add_source(&loop_source, kCFRunLoopDefaultMode);
+                }
 
-                    // Enable the tap
-                    tap.enable();
+                // Enable the tap
+                tap.enable();
 
+                if recreating {
+                    log::info!("CGEventTap recreated successfully");
+                    notify_status(TapStatus::Recovered);
+                } else {
                     log::info!("CGEventTap started successfully");
+                }
+
+                let mut last_liveness_check = Instant::now();
 
-                    // Run the loop
-                    while *running_flag.lock().unwrap() {
-                        // Check if tap needs re-enabling
-                        if needs_reenable.swap(false, Ordering::SeqCst) {
+                // Run the loop
+                while *running_flag.lock().unwrap() {
+                    // Re-enable the tap when macOS disabled it, and verify periodically
+                    // since the tap can also die without delivering a disabled event
+                    let reenable_requested = needs_reenable.swap(false, Ordering::SeqCst);
+                    if reenable_requested || last_liveness_check.elapsed() >= LIVENESS_CHECK_INTERVAL {
+                        last_liveness_check = Instant::now();
+                        if !is_tap_enabled(&tap) {
                             log::info!("Re-enabling CGEventTap...");
                             tap.enable();
+                            if !is_tap_enabled(&tap) {
+                                log::warn!("CGEventTap could not be re-enabled, recreating...");
+                                notify_status(TapStatus::Stalled);
+                                break;
+                            }
                         }
-
-                        CFRunLoop::run_in_mode(
-                            unsafe { kCFRunLoopDefaultMode },
-                            Duration::from_millis(100),
-                            false,
-                        );
                     }
 
-                    log::info!("CGEventTap stopped");
-                }
-                Err(()) => {
-                    log::error!(
-                        "Failed to create CGEventTap. Make sure Input Monitoring permission is granted."
+                    CFRunLoop::run_in_mode(
+                        unsafe { kCFRunLoopDefaultMode },
+                        Duration::from_millis(100),
+                        false,
                     );
-                    *running_flag.lock().unwrap() = false;
                 }
+
+                // Dropping the tap invalidates its mach port and run loop source
+                drop(tap);
+                recreating = true;
             }
+
+            log::info!("CGEventTap stopped");
         });
 
         Ok(())
@@ -283,6 +264,173 @@ impl KeyboardCapture {
     pub fn is_running(&self) -> bool {
         *self.running.lock().unwrap()
     }
+
+    /// Whether the tap is running and receiving key events. A tap that got nothing while
+    /// keys were pressed since it started is not live (usually missing Input Monitoring);
+    /// one that got nothing because the user hasn't typed yet still counts as live.
+    pub fn is_live(&self) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        if self.events_received.load(Ordering::Relaxed) > 0 {
+            return true;
+        }
+        match *self.started_at.lock().unwrap() {
+            Some(started_at) => !keys_pressed_since(started_at),
+            None => true,
+        }
+    }
+
+    /// When the tap was last started
+    pub fn started_at(&self) -> Option<Instant> {
+        *self.started_at.lock().unwrap()
+    }
+
+    /// Shared counter of keyboard events seen by the tap since the last start.
+    /// A running tap that never sees events usually means Input Monitoring is missing.
+    pub fn events_received(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.events_received)
+    }
+}
+
+/// Callbacks shared between the capture thread and each (re)created tap
+#[derive(Clone)]
+struct TapCallbacks {
+    callback: Arc<Mutex<Option<KeyEventCallback>>>,
+    mouse_callback: Arc<Mutex<Option<MouseEventCallback>>>,
+    scroll_callback: Arc<Mutex<Option<ScrollEventCallback>>>,
+    flags_changed_callback: Arc<Mutex<Option<FlagsChangedCallback>>>,
+    events_received: Arc<AtomicU64>,
+}
+
+/// Check whether macOS still considers the tap enabled
+fn is_tap_enabled(tap: &CGEventTap) -> bool {
+    unsafe { CGEventTapIsEnabled(tap.mach_port().as_concrete_TypeRef()) }
+}
+
+/// Create the event tap - use HID tap location for reliable key suppression
+/// Also listen for mouse down events to detect clicks
+fn create_event_tap(
+    callbacks: TapCallbacks,
+    needs_reenable: Arc<AtomicBool>,
+) -> Result<CGEventTap<'static>, ()> {
+    let TapCallbacks {
+        callback,
+        mouse_callback,
+        scroll_callback,
+        flags_changed_callback,
+        events_received,
+    } = callbacks;
+
+    CGEventTap::new(
+        CGEventTapLocation::HID,
+        CGEventTapPlacement::HeadInsertEventTap,
+        CGEventTapOptions::Default,
+        vec![
+            CGEventType::KeyDown,
+            CGEventType::KeyUp,
+            CGEventType::FlagsChanged,
+            CGEventType::LeftMouseDown,
+            CGEventType::RightMouseDown,
+            CGEventType::ScrollWheel,
+        ],
+        move |_proxy: CGEventTapProxy, event_type: CGEventType, event| -> CallbackResult {
+            // Handle tap disabled by timeout - signal re-enable
+            if is_event_type(event_type, CGEventType::TapDisabledByTimeout) {
+                log::warn!("CGEventTap was disabled by timeout, signaling re-enable...");
+                needs_reenable.store(true, Ordering::SeqCst);
+                return CallbackResult::Keep;
+            }
+
+            // Handle tap disabled by user - also re-enable
+            if is_event_type(event_type, CGEventType::TapDisabledByUserInput) {
+                log::warn!("CGEventTap was disabled by user input, signaling re-enable...");
+                needs_reenable.store(true, Ordering::SeqCst);
+                return CallbackResult::Keep;
+            }
+
+            // Pass through events we posted ourselves (key injections, scrolls, clicks)
+            // so they never reach our own callbacks
+            if is_injected(event) {
+                log::trace!("Skipping injected event");
+                return CallbackResult::Keep;
+            }
+
+            // Handle mouse click events
+            if is_event_type(event_type, CGEventType::LeftMouseDown)
+                || is_event_type(event_type, CGEventType::RightMouseDown)
+            {
+                let mouse_event = MouseClickEvent {
+                    is_left_click: is_event_type(event_type, CGEventType::LeftMouseDown),
+                    is_right_click: is_event_type(event_type, CGEventType::RightMouseDown),
+                };
+                let cb_lock = mouse_callback.lock().unwrap();
+                if let Some(ref cb) = *cb_lock {
+                    cb(mouse_event);
+                }
+                // Always pass through mouse events
+                return CallbackResult::Keep;
+            }
+
+            // Handle scroll wheel events
+            if is_event_type(event_type, CGEventType::ScrollWheel) {
+                let cb_lock = scroll_callback.lock().unwrap();
+                if let Some(ref cb) = *cb_lock {
+                    cb();
+                }
+                // Always pass through scroll events
+                return CallbackResult::Keep;
+            }
+
+            events_received.fetch_add(1, Ordering::Relaxed);
+
+            // Handle FlagsChanged events (modifier key changes)
+            if is_event_type(event_type, CGEventType::FlagsChanged) {
+                let flags = event.get_flags();
+                let modifiers = Modifiers::from_cg_flags(flags.bits());
+
+                let cb_lock = flags_changed_callback.lock().unwrap();
+                if let Some(ref cb) = *cb_lock {
+                    cb(modifiers);
+                }
+                // Always pass through modifier events
+                return CallbackResult::Keep;
+            }
+
+            // Get key code and flags
+            let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
+            log::trace!("Key event: keycode={}, type={:?}", keycode, event_type);
+            let flags = event.get_flags();
+            let is_key_down = is_event_type(event_type, CGEventType::KeyDown);
+
+            let key_event = KeyEvent {
+                code: keycode,
+                modifiers: Modifiers::from_cg_flags(flags.bits()),
+                is_key_down,
+            };
+
+            // Call user callback
+            let cb_lock = callback.lock().unwrap();
+            if let Some(ref cb) = *cb_lock {
+                match cb(key_event) {
+                    Some(_modified_event) => {
+                        // Pass through
+                        log::trace!("capture: passing through keycode={}", keycode);
+                        CallbackResult::Keep
+                    }
+                    None => {
+                        // Suppress the event - use Drop to return null_ptr
+                        log::trace!("capture: SUPPRESSING keycode={}", keycode);
+                        CallbackResult::Drop
+                    }
+                }
+            } else {
+                // No callback set, pass through
+                log::trace!("capture: no callback, passing through keycode={}", keycode);
+                CallbackResult::Keep
+            }
+        },
+    )
 }
 
 impl Default for KeyboardCapture {
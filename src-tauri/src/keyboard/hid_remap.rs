@@ -0,0 +1,186 @@
+//! Caps Lock remapping at the HID level (`hidutil` UserKeyMapping)
+//!
+//! Dropping Caps Lock's FlagsChanged event in the tap can't undo the caps toggle
+//! macOS has already applied, and its release is only visible by polling. With
+//! the remap enabled, the keyboard driver sends Caps Lock as F18 instead: a plain
+//! key with real key down/up events and no caps state. The mapping lives until
+//! logout, so it is removed again on exit and when the remap is turned off;
+//! mappings set by other tools are kept.
+
+use std::process::Command;
+use std::sync::Mutex;
+
+use super::KeyCode;
+
+/// HID usage of Caps Lock (keyboard page 0x07, usage 0x39)
+const CAPS_LOCK_USAGE: u64 = 0x7_0000_0039;
+/// HID usage of F18 (keyboard page 0x07, usage 0x6D)
+const F18_USAGE: u64 = 0x7_0000_006D;
+
+/// The key Caps Lock arrives as while the remap is active
+pub const REMAPPED_CAPS_LOCK: KeyCode = KeyCode::F18;
+
+/// Last state applied, so saving unrelated settings doesn't run hidutil
+static APPLIED: Mutex<Option<bool>> = Mutex::new(None);
+
+/// A `UserKeyMapping` entry: source and destination HID usages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyMapping {
+    src: u64,
+    dst: u64,
+}
+
+const CAPS_LOCK_TO_F18: KeyMapping = KeyMapping {
+    src: CAPS_LOCK_USAGE,
+    dst: F18_USAGE,
+};
+
+/// Send Caps Lock as `REMAPPED_CAPS_LOCK` (or restore it)
+pub fn set_caps_lock_remapped(enabled: bool) {
+    let mut applied = APPLIED.lock().unwrap();
+    if *applied == Some(enabled) {
+        return;
+    }
+    match apply(enabled) {
+        Ok(()) => *applied = Some(enabled),
+        Err(e) => log::error!("Failed to update the Caps Lock key mapping: {}", e),
+    }
+}
+
+fn apply(enabled: bool) -> Result<(), String> {
+    let output = Command::new("hidutil")
+        .args(["property", "--get", "UserKeyMapping"])
+        .output()
+        .map_err(|e| format!("Failed to run hidutil: {}", e))?;
+    let current = parse_mappings(&String::from_utf8_lossy(&output.stdout));
+
+    let Some(mappings) = updated_mappings(&current, enabled) else {
+        return Ok(());
+    };
+    let status = Command::new("hidutil")
+        .args(["property", "--set", &mappings_json(&mappings)])
+        .output()
+        .map_err(|e| format!("Failed to run hidutil: {}", e))?
+        .status;
+    if !status.success() {
+        return Err(format!("hidutil exited with {}", status));
+    }
+    log::info!(
+        "Caps Lock {} at the HID level",
+        if enabled { "remapped" } else { "restored" }
+    );
+    Ok(())
+}
+
+/// The mappings to set, or None when nothing changes. Another tool's Caps Lock
+/// mapping wins over ours.
+fn updated_mappings(current: &[KeyMapping], enabled: bool) -> Option<Vec<KeyMapping>> {
+    let has_ours = current.contains(&CAPS_LOCK_TO_F18);
+    if enabled {
+        if has_ours {
+            return None;
+        }
+        if current.iter().any(|m| m.src == CAPS_LOCK_USAGE) {
+            log::warn!("Caps Lock is already remapped by another tool; leaving it alone");
+            return None;
+        }
+        let mut mappings = current.to_vec();
+        mappings.push(CAPS_LOCK_TO_F18);
+        Some(mappings)
+    } else if has_ours {
+        Some(
+            current
+                .iter()
+                .copied()
+                .filter(|m| *m != CAPS_LOCK_TO_F18)
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Parse `hidutil property --get UserKeyMapping` output, a plist-style array of
+/// `{ HIDKeyboardModifierMappingDst = N; HIDKeyboardModifierMappingSrc = N; }`
+fn parse_mappings(output: &str) -> Vec<KeyMapping> {
+    let value = |entry: &str, key: &str| -> Option<u64> {
+        let rest = &entry[entry.find(key)? + key.len()..];
+        let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        rest[..end].parse().ok()
+    };
+    output
+        .split('}')
+        .filter_map(|entry| {
+            Some(KeyMapping {
+                src: value(entry, "HIDKeyboardModifierMappingSrc")?,
+                dst: value(entry, "HIDKeyboardModifierMappingDst")?,
+            })
+        })
+        .collect()
+}
+
+fn mappings_json(mappings: &[KeyMapping]) -> String {
+    let entries: Vec<String> = mappings
+        .iter()
+        .map(|m| {
+            format!(
+                r#"{{"HIDKeyboardModifierMappingSrc":{},"HIDKeyboardModifierMappingDst":{}}}"#,
+                m.src, m.dst
+            )
+        })
+        .collect();
+    format!(r#"{{"UserKeyMapping":[{}]}}"#, entries.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTHER: KeyMapping = KeyMapping {
+        src: 0x7_0000_0064,
+        dst: 0x7_0000_0035,
+    };
+
+    #[test]
+    fn test_parse_hidutil_output() {
+        let output = "(\n        {\n        HIDKeyboardModifierMappingDst = 30064771181;\n        HIDKeyboardModifierMappingSrc = 30064771129;\n    },\n        {\n        HIDKeyboardModifierMappingDst = 30064771125;\n        HIDKeyboardModifierMappingSrc = 30064771172;\n    }\n)\n";
+        assert_eq!(parse_mappings(output), vec![CAPS_LOCK_TO_F18, OTHER]);
+        assert!(parse_mappings("(null)\n").is_empty());
+    }
+
+    #[test]
+    fn test_remap_keeps_other_mappings() {
+        assert_eq!(
+            updated_mappings(&[OTHER], true),
+            Some(vec![OTHER, CAPS_LOCK_TO_F18])
+        );
+        assert_eq!(updated_mappings(&[OTHER, CAPS_LOCK_TO_F18], true), None);
+        assert_eq!(
+            updated_mappings(&[CAPS_LOCK_TO_F18, OTHER], false),
+            Some(vec![OTHER])
+        );
+        assert_eq!(updated_mappings(&[OTHER], false), None);
+    }
+
+    #[test]
+    fn test_caps_lock_mapped_elsewhere_is_left_alone() {
+        let to_escape = KeyMapping {
+            src: CAPS_LOCK_USAGE,
+            dst: 0x7_0000_0029,
+        };
+        assert_eq!(updated_mappings(&[to_escape], true), None);
+        assert_eq!(updated_mappings(&[to_escape], false), None);
+    }
+
+    #[test]
+    fn test_mappings_json() {
+        assert_eq!(mappings_json(&[]), r#"{"UserKeyMapping":[]}"#);
+        assert_eq!(
+            mappings_json(&[CAPS_LOCK_TO_F18]),
+            r#"{"UserKeyMapping":[{"HIDKeyboardModifierMappingSrc":30064771129,"HIDKeyboardModifierMappingDst":30064771181}]}"#
+        );
+    }
+}
@@ -1,545 +1,571 @@
-use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, EventField, ScrollEventUnit};
+use std::sync::Arc;
+
+use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, ScrollEventUnit};
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
 use super::keycode::{KeyCode, Modifiers};
+use super::synthetic::mark_injected;
 
-/// Custom user data field to mark our injected events
-/// We use a high value that's unlikely to conflict with real keycodes
-pub const INJECTED_EVENT_MARKER: i64 = 0x54495649; // "TIVI" in hex
+/// Injector shared between the keyboard callback and the threads it starts
+pub type SharedInjector = Arc<dyn KeyInjector>;
 
-/// Inject a single key event
-pub fn inject_key(keycode: KeyCode, key_down: bool, modifiers: Modifiers) -> Result<(), String> {
-    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-        .map_err(|_| "Failed to create event source")?;
+/// Sends synthetic key and scroll events.
+/// `SystemInjector` posts them to macOS; tests substitute a recorder.
+pub trait KeyInjector: Send + Sync {
+    /// Inject a single key event
+    fn inject_key(&self, keycode: KeyCode, key_down: bool, modifiers: Modifiers) -> Result<(), String>;
 
-    let event = CGEvent::new_keyboard_event(source, keycode.as_raw(), key_down)
-        .map_err(|_| "Failed to create keyboard event")?;
+    /// Inject a scroll wheel event, in pixels
+    fn scroll_wheel(&self, delta_x: i32, delta_y: i32) -> Result<(), String>;
 
-    let flags = CGEventFlags::from_bits_truncate(modifiers.to_cg_flags());
-    event.set_flags(flags);
+    /// Whether vim actions may run inside the keyboard callback. Posted events have
+    /// to wait until the callback returns, so by default they run on another thread.
+    fn runs_actions_inline(&self) -> bool {
+        false
+    }
 
-    // Mark the event as injected by us so we don't capture it again
-    event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, INJECTED_EVENT_MARKER);
+    /// Inject a key press (down + up)
+    fn inject_key_press(&self, keycode: KeyCode, modifiers: Modifiers) -> Result<(), String> {
+        self.inject_key(keycode, true, modifiers)?;
+        self.inject_key(keycode, false, modifiers)?;
+        Ok(())
+    }
 
-    event.post(CGEventTapLocation::HID);
+    /// Inject arrow key with optional modifiers
+    fn inject_arrow(&self, direction: ArrowDirection, modifiers: Modifiers) -> Result<(), String> {
+        let keycode = match direction {
+            ArrowDirection::Left => KeyCode::Left,
+            ArrowDirection::Right => KeyCode::Right,
+            ArrowDirection::Up => KeyCode::Up,
+            ArrowDirection::Down => KeyCode::Down,
+        };
+        self.inject_key_press(keycode, modifiers)
+    }
 
-    Ok(())
-}
+    /// Inject a navigation keystroke `count` times
+    fn navigate(&self, navigation: Navigation, count: u32, select: bool) -> Result<(), String> {
+        let (keycode, modifiers) = navigation_keystroke(navigation, select);
+        for _ in 0..count {
+            self.inject_key_press(keycode, modifiers)?;
+        }
+        Ok(())
+    }
 
-/// Inject a key press (down + up)
-pub fn inject_key_press(keycode: KeyCode, modifiers: Modifiers) -> Result<(), String> {
-    inject_key(keycode, true, modifiers)?;
-    inject_key(keycode, false, modifiers)?;
-    Ok(())
-}
+    /// Move cursor left (h)
+    fn cursor_left(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::Left, count, select)
+    }
 
-/// Inject arrow key with optional modifiers
-pub fn inject_arrow(direction: ArrowDirection, modifiers: Modifiers) -> Result<(), String> {
-    let keycode = match direction {
-        ArrowDirection::Left => KeyCode::Left,
-        ArrowDirection::Right => KeyCode::Right,
-        ArrowDirection::Up => KeyCode::Up,
-        ArrowDirection::Down => KeyCode::Down,
-    };
-    inject_key_press(keycode, modifiers)
-}
+    /// Move cursor right (l)
+    fn cursor_right(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::Right, count, select)
+    }
 
-#[derive(Debug, Clone, Copy)]
-pub enum ArrowDirection {
-    Left,
-    Right,
-    Up,
-    Down,
-}
+    /// Move cursor up (k)
+    fn cursor_up(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::Up, count, select)
+    }
 
-/// Move cursor left (h)
-pub fn cursor_left(count: u32, select: bool) -> Result<(), String> {
-    let mods = if select {
-        Modifiers { shift: true, ..Default::default() }
-    } else {
-        Modifiers::default()
-    };
+    /// Move cursor down (j)
+    fn cursor_down(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::Down, count, select)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Left, mods)?;
+    /// Move to start of word (b) - Option+Left on macOS
+    fn word_backward(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::WordBackward, count, select)
     }
-    Ok(())
-}
 
-/// Move cursor right (l)
-pub fn cursor_right(count: u32, select: bool) -> Result<(), String> {
-    let mods = if select {
-        Modifiers { shift: true, ..Default::default() }
-    } else {
-        Modifiers::default()
-    };
+    /// Move to end of word (e) / next word (w) - Option+Right on macOS
+    fn word_forward(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::WordForward, count, select)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Right, mods)?;
+    /// Move to start of line (0/^) - Cmd+Left on macOS
+    fn line_start(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::LineStart, 1, select)
     }
-    Ok(())
-}
 
-/// Move cursor up (k)
-pub fn cursor_up(count: u32, select: bool) -> Result<(), String> {
-    let mods = if select {
-        Modifiers { shift: true, ..Default::default() }
-    } else {
-        Modifiers::default()
-    };
+    /// Move to end of line ($) - Cmd+Right on macOS
+    fn line_end(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::LineEnd, 1, select)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Up, mods)?;
+    /// Move to start of document (gg) - Cmd+Up on macOS
+    fn document_start(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::DocumentStart, 1, select)
     }
-    Ok(())
-}
 
-/// Move cursor down (j)
-pub fn cursor_down(count: u32, select: bool) -> Result<(), String> {
-    let mods = if select {
-        Modifiers { shift: true, ..Default::default() }
-    } else {
-        Modifiers::default()
-    };
+    /// Move to end of document (G) - Cmd+Down on macOS
+    fn document_end(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::DocumentEnd, 1, select)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Down, mods)?;
+    /// Page up (Ctrl+b or Ctrl+u)
+    fn page_up(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::PageUp, 1, select)
     }
-    Ok(())
-}
 
-/// Move to start of word (b) - Option+Left on macOS
-pub fn word_backward(count: u32, select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        option: true,
-        shift: select,
-        ..Default::default()
-    };
+    /// Page down (Ctrl+f or Ctrl+d)
+    fn page_down(&self, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::PageDown, 1, select)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Left, mods)?;
+    /// Delete character (x)
+    fn delete_char(&self) -> Result<(), String> {
+        self.inject_key_press(KeyCode::ForwardDelete, Modifiers::default())
     }
-    Ok(())
-}
 
-/// Move to end of word (e) / next word (w) - Option+Right on macOS
-pub fn word_forward(count: u32, select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        option: true,
-        shift: select,
-        ..Default::default()
-    };
+    /// Delete character before cursor (X)
+    fn backspace(&self) -> Result<(), String> {
+        self.inject_key_press(KeyCode::Delete, Modifiers::default())
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Right, mods)?;
+    /// Cut selection (Cmd+X)
+    fn cut(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::X,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
     }
-    Ok(())
-}
 
-/// Move to start of line (0/^) - Cmd+Left on macOS
-pub fn line_start(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        command: true,
-        shift: select,
-        ..Default::default()
-    };
-    inject_arrow(ArrowDirection::Left, mods)
-}
+    /// Copy selection (Cmd+C)
+    fn copy(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::C,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Move to end of line ($) - Cmd+Right on macOS
-pub fn line_end(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        command: true,
-        shift: select,
-        ..Default::default()
-    };
-    inject_arrow(ArrowDirection::Right, mods)
-}
+    /// Paste (Cmd+V)
+    fn paste(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::V,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Move to start of document (gg) - Cmd+Up on macOS
-pub fn document_start(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        command: true,
-        shift: select,
-        ..Default::default()
-    };
-    inject_arrow(ArrowDirection::Up, mods)
-}
+    /// Undo (Cmd+Z)
+    fn undo(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::Z,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Move to end of document (G) - Cmd+Down on macOS
-pub fn document_end(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        command: true,
-        shift: select,
-        ..Default::default()
-    };
-    inject_arrow(ArrowDirection::Down, mods)
-}
+    /// Redo (Cmd+Shift+Z)
+    fn redo(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::Z,
+            Modifiers {
+                command: true,
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Page up (Ctrl+b or Ctrl+u)
-pub fn page_up(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        shift: select,
-        ..Default::default()
-    };
-    inject_key_press(KeyCode::PageUp, mods)
-}
+    /// New line below (o) - Cmd+Right, Return
+    fn new_line_below(&self) -> Result<(), String> {
+        self.line_end(false)?;
+        self.inject_key_press(KeyCode::Return, Modifiers::default())
+    }
 
-/// Page down (Ctrl+f or Ctrl+d)
-pub fn page_down(select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        shift: select,
-        ..Default::default()
-    };
-    inject_key_press(KeyCode::PageDown, mods)
-}
+    /// New line above (O) - Cmd+Left, Return, Up
+    fn new_line_above(&self) -> Result<(), String> {
+        self.line_start(false)?;
+        self.inject_key_press(KeyCode::Return, Modifiers::default())?;
+        self.cursor_up(1, false)
+    }
 
-/// Delete character (x)
-pub fn delete_char() -> Result<(), String> {
-    inject_key_press(KeyCode::ForwardDelete, Modifiers::default())
-}
+    /// Paragraph up ({) - Option+Up on macOS
+    fn paragraph_up(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::ParagraphUp, count, select)
+    }
 
-/// Delete character before cursor (X)
-pub fn backspace() -> Result<(), String> {
-    inject_key_press(KeyCode::Delete, Modifiers::default())
-}
+    /// Paragraph down (}) - Option+Down on macOS
+    fn paragraph_down(&self, count: u32, select: bool) -> Result<(), String> {
+        self.navigate(Navigation::ParagraphDown, count, select)
+    }
 
-/// Cut selection (Cmd+X)
-pub fn cut() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::X,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Join lines (J) - go to end, delete newline, add space
+    fn join_lines(&self) -> Result<(), String> {
+        self.line_end(false)?;
+        self.delete_char()?;
+        self.inject_key_press(KeyCode::Space, Modifiers::default())
+    }
 
-/// Copy selection (Cmd+C)
-pub fn copy() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::C,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Select inner word (iw) - Option+Left to word start, Option+Shift+Right to select word
+    fn select_inner_word(&self) -> Result<(), String> {
+        self.word_backward(1, false)?;
+        self.word_forward(1, true)
+    }
 
-/// Paste (Cmd+V)
-pub fn paste() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::V,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Select around word (aw) - inner word + trailing space
+    fn select_around_word(&self) -> Result<(), String> {
+        self.word_backward(1, false)?;
+        self.word_forward(1, true)?;
+        self.cursor_right(1, true)
+    }
 
-/// Undo (Cmd+Z)
-pub fn undo() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::Z,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Indent line (>>) - Tab key
+    fn indent_line(&self) -> Result<(), String> {
+        self.line_start(false)?;
+        self.inject_key_press(KeyCode::Tab, Modifiers::default())
+    }
 
-/// Redo (Cmd+Shift+Z)
-pub fn redo() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::Z,
-        Modifiers {
-            command: true,
-            shift: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Outdent line (<<) - Shift+Tab
+    fn outdent_line(&self) -> Result<(), String> {
+        self.line_start(false)?;
+        self.inject_key_press(
+            KeyCode::Tab,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// New line below (o) - Cmd+Right, Return
-pub fn new_line_below() -> Result<(), String> {
-    line_end(false)?;
-    inject_key_press(KeyCode::Return, Modifiers::default())
-}
+    /// Type a character
+    fn type_char(&self, keycode: KeyCode, shift: bool) -> Result<(), String> {
+        let mods = if shift {
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            }
+        } else {
+            Modifiers::default()
+        };
+        self.inject_key_press(keycode, mods)
+    }
 
-/// New line above (O) - Cmd+Left, Return, Up
-pub fn new_line_above() -> Result<(), String> {
-    line_start(false)?;
-    inject_key_press(KeyCode::Return, Modifiers::default())?;
-    cursor_up(1, false)
-}
+    // ========================================================================
+    // Scroll Mode Functions (Vimium-style navigation)
+    // ========================================================================
 
-/// Paragraph up ({) - Option+Up on macOS
-pub fn paragraph_up(count: u32, select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        option: true,
-        shift: select,
-        ..Default::default()
-    };
+    /// Scroll down (j key in scroll mode)
+    fn scroll_down(&self, amount: u32) -> Result<(), String> {
+        // Negative delta scrolls content up, which means user scrolls down
+        self.scroll_wheel(0, -(amount as i32))
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Up, mods)?;
+    /// Scroll up (k key in scroll mode)
+    fn scroll_up(&self, amount: u32) -> Result<(), String> {
+        // Positive delta scrolls content down, which means user scrolls up
+        self.scroll_wheel(0, amount as i32)
     }
-    Ok(())
-}
 
-/// Paragraph down (}) - Option+Down on macOS
-pub fn paragraph_down(count: u32, select: bool) -> Result<(), String> {
-    let mods = Modifiers {
-        option: true,
-        shift: select,
-        ..Default::default()
-    };
+    /// Scroll left (h key in scroll mode)
+    fn scroll_left(&self, amount: u32) -> Result<(), String> {
+        // Positive delta scrolls content right, which means user scrolls left
+        self.scroll_wheel(amount as i32, 0)
+    }
 
-    for _ in 0..count {
-        inject_arrow(ArrowDirection::Down, mods)?;
+    /// Scroll right (l key in scroll mode)
+    fn scroll_right(&self, amount: u32) -> Result<(), String> {
+        // Negative delta scrolls content left, which means user scrolls right
+        self.scroll_wheel(-(amount as i32), 0)
     }
-    Ok(())
-}
 
-/// Join lines (J) - go to end, delete newline, add space
-pub fn join_lines() -> Result<(), String> {
-    line_end(false)?;
-    delete_char()?;
-    inject_key_press(KeyCode::Space, Modifiers::default())
-}
+    /// Half page scroll down (d key in scroll mode)
+    /// Uses PageDown key for half-page scroll behavior
+    fn half_page_scroll_down(&self) -> Result<(), String> {
+        // Use a larger scroll amount for half-page
+        self.scroll_wheel(0, -400)
+    }
 
-/// Select inner word (iw) - Option+Left to word start, Option+Shift+Right to select word
-pub fn select_inner_word() -> Result<(), String> {
-    word_backward(1, false)?;
-    word_forward(1, true)
-}
+    /// Half page scroll up (u key in scroll mode)
+    /// Uses PageUp key for half-page scroll behavior
+    fn half_page_scroll_up(&self) -> Result<(), String> {
+        // Use a larger scroll amount for half-page
+        self.scroll_wheel(0, 400)
+    }
 
-/// Select around word (aw) - inner word + trailing space
-pub fn select_around_word() -> Result<(), String> {
-    word_backward(1, false)?;
-    word_forward(1, true)?;
-    cursor_right(1, true)
-}
+    /// History back (H key in scroll mode) - Cmd+[
+    fn history_back(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::LeftBracket,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Indent line (>>) - Tab key
-pub fn indent_line() -> Result<(), String> {
-    line_start(false)?;
-    inject_key_press(KeyCode::Tab, Modifiers::default())
-}
+    /// History forward (L key in scroll mode) - Cmd+]
+    fn history_forward(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::RightBracket,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Outdent line (<<) - Shift+Tab
-pub fn outdent_line() -> Result<(), String> {
-    line_start(false)?;
-    inject_key_press(
-        KeyCode::Tab,
-        Modifiers {
-            shift: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Reload page (r key in scroll mode) - Cmd+R or Cmd+Shift+R for hard reload
+    fn reload_page(&self, hard: bool) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::R,
+            Modifiers {
+                command: true,
+                shift: hard,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Type a character
-pub fn type_char(keycode: KeyCode, shift: bool) -> Result<(), String> {
-    let mods = if shift {
-        Modifiers {
-            shift: true,
-            ..Default::default()
-        }
-    } else {
-        Modifiers::default()
-    };
-    inject_key_press(keycode, mods)
-}
+    /// Open find dialog (/ key in scroll mode) - Cmd+F
+    fn open_find(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::F,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        )
+    }
 
-// ============================================================================
-// Scroll Mode Functions (Vimium-style navigation)
-// ============================================================================
-
-/// Inject a scroll wheel event
-pub fn scroll_wheel(delta_x: i32, delta_y: i32) -> Result<(), String> {
-    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-        .map_err(|_| "Failed to create event source")?;
-
-    // Create scroll wheel event with pixel-based scrolling
-    // wheel_count=2 means we're providing both vertical and horizontal axes
-    // For vertical (wheel1): negative delta scrolls content down (user sees content move up, scrolling down)
-    // For horizontal (wheel2): positive delta scrolls content left
-    let event = CGEvent::new_scroll_event(
-        source,
-        ScrollEventUnit::PIXEL,
-        2, // wheel_count: 2 for both vertical and horizontal
-        delta_y,
-        delta_x,
-        0,
-    )
-    .map_err(|_| "Failed to create scroll event")?;
-
-    // Mark the event as injected by us
-    event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, INJECTED_EVENT_MARKER);
-
-    event.post(CGEventTapLocation::HID);
-    Ok(())
-}
+    /// Scroll to top of page (gg in scroll mode) - Cmd+Up
+    fn scroll_to_top(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::Home,
+            Modifiers::default(),
+        )
+    }
 
-/// Scroll down (j key in scroll mode)
-pub fn scroll_down(amount: u32) -> Result<(), String> {
-    // Negative delta scrolls content up, which means user scrolls down
-    scroll_wheel(0, -(amount as i32))
-}
+    /// Scroll to bottom of page (G in scroll mode) - Cmd+Down
+    fn scroll_to_bottom(&self) -> Result<(), String> {
+        self.inject_key_press(
+            KeyCode::End,
+            Modifiers::default(),
+        )
+    }
 
-/// Scroll up (k key in scroll mode)
-pub fn scroll_up(amount: u32) -> Result<(), String> {
-    // Positive delta scrolls content down, which means user scrolls up
-    scroll_wheel(0, amount as i32)
-}
+    // ========================================================================
+    // List Mode Functions - Arrow key navigation for list views
+    // ========================================================================
 
-/// Scroll left (h key in scroll mode)
-pub fn scroll_left(amount: u32) -> Result<(), String> {
-    // Positive delta scrolls content right, which means user scrolls left
-    scroll_wheel(amount as i32, 0)
-}
+    /// Move up in list (k in list mode) - Up Arrow
+    fn list_up(&self) -> Result<(), String> {
+        self.inject_arrow(ArrowDirection::Up, Modifiers::default())
+    }
 
-/// Scroll right (l key in scroll mode)
-pub fn scroll_right(amount: u32) -> Result<(), String> {
-    // Negative delta scrolls content left, which means user scrolls right
-    scroll_wheel(-(amount as i32), 0)
-}
+    /// Move down in list (j in list mode) - Down Arrow
+    fn list_down(&self) -> Result<(), String> {
+        self.inject_arrow(ArrowDirection::Down, Modifiers::default())
+    }
 
-/// Half page scroll down (d key in scroll mode)
-/// Uses PageDown key for half-page scroll behavior
-pub fn half_page_scroll_down() -> Result<(), String> {
-    // Use a larger scroll amount for half-page
-    scroll_wheel(0, -400)
-}
+    /// Move left in list (h in list mode) - Left Arrow
+    /// Also collapses folders in tree views like Finder
+    fn list_left(&self) -> Result<(), String> {
+        self.inject_arrow(ArrowDirection::Left, Modifiers::default())
+    }
 
-/// Half page scroll up (u key in scroll mode)
-/// Uses PageUp key for half-page scroll behavior
-pub fn half_page_scroll_up() -> Result<(), String> {
-    // Use a larger scroll amount for half-page
-    scroll_wheel(0, 400)
-}
+    /// Move right in list (l in list mode) - Right Arrow
+    /// Also expands folders in tree views like Finder
+    fn list_right(&self) -> Result<(), String> {
+        self.inject_arrow(ArrowDirection::Right, Modifiers::default())
+    }
 
-/// History back (H key in scroll mode) - Cmd+[
-pub fn history_back() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::LeftBracket,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Extend selection up (K in list mode) - Shift+Up Arrow
+    fn list_select_up(&self) -> Result<(), String> {
+        self.inject_arrow(
+            ArrowDirection::Up,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// History forward (L key in scroll mode) - Cmd+]
-pub fn history_forward() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::RightBracket,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Extend selection down (J in list mode) - Shift+Down Arrow
+    fn list_select_down(&self) -> Result<(), String> {
+        self.inject_arrow(
+            ArrowDirection::Down,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Reload page (r key in scroll mode) - Cmd+R or Cmd+Shift+R for hard reload
-pub fn reload_page(hard: bool) -> Result<(), String> {
-    inject_key_press(
-        KeyCode::R,
-        Modifiers {
-            command: true,
-            shift: hard,
-            ..Default::default()
-        },
-    )
-}
+    /// Extend selection left (H in grid list mode) - Shift+Left Arrow
+    fn list_select_left(&self) -> Result<(), String> {
+        self.inject_arrow(
+            ArrowDirection::Left,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Open find dialog (/ key in scroll mode) - Cmd+F
-pub fn open_find() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::F,
-        Modifiers {
-            command: true,
-            ..Default::default()
-        },
-    )
-}
+    /// Extend selection right (L in grid list mode) - Shift+Right Arrow
+    fn list_select_right(&self) -> Result<(), String> {
+        self.inject_arrow(
+            ArrowDirection::Right,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        )
+    }
 
-/// Scroll to top of page (gg in scroll mode) - Cmd+Up
-pub fn scroll_to_top() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::Home,
-        Modifiers::default(),
-    )
-}
+    /// Go to top of list (gg in list mode) - Home key
+    fn list_go_top(&self) -> Result<(), String> {
+        self.inject_key_press(KeyCode::Home, Modifiers::default())
+    }
 
-/// Scroll to bottom of page (G in scroll mode) - Cmd+Down
-pub fn scroll_to_bottom() -> Result<(), String> {
-    inject_key_press(
-        KeyCode::End,
-        Modifiers::default(),
-    )
+    /// Go to bottom of list (G in list mode) - End key
+    fn list_go_bottom(&self) -> Result<(), String> {
+        self.inject_key_press(KeyCode::End, Modifiers::default())
+    }
+
+    /// Inject Return key (for opening items with 'o')
+    fn inject_return(&self) -> Result<(), String> {
+        self.inject_key_press(KeyCode::Return, Modifiers::default())
+    }
 }
 
-// ============================================================================
-// List Mode Functions - Arrow key navigation for list views
-// ============================================================================
+/// Posts CGEvents marked as injected, so the event tap lets them through
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemInjector;
 
-/// Move up in list (k in list mode) - Up Arrow
-pub fn list_up() -> Result<(), String> {
-    inject_arrow(ArrowDirection::Up, Modifiers::default())
-}
+impl KeyInjector for SystemInjector {
+    fn inject_key(&self, keycode: KeyCode, key_down: bool, modifiers: Modifiers) -> Result<(), String> {
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| "Failed to create event source")?;
 
-/// Move down in list (j in list mode) - Down Arrow
-pub fn list_down() -> Result<(), String> {
-    inject_arrow(ArrowDirection::Down, Modifiers::default())
-}
+        let event = CGEvent::new_keyboard_event(source, keycode.as_raw(), key_down)
+            .map_err(|_| "Failed to create keyboard event")?;
 
-/// Move left in list (h in list mode) - Left Arrow
-/// Also collapses folders in tree views like Finder
-pub fn list_left() -> Result<(), String> {
-    inject_arrow(ArrowDirection::Left, Modifiers::default())
-}
+        let flags = CGEventFlags::from_bits_truncate(modifiers.to_cg_flags());
+        event.set_flags(flags);
 
-/// Move right in list (l in list mode) - Right Arrow
-/// Also expands folders in tree views like Finder
-pub fn list_right() -> Result<(), String> {
-    inject_arrow(ArrowDirection::Right, Modifiers::default())
-}
+        // Mark the event as injected by us so we don't capture it again
+        mark_injected(&event);
 
-/// Extend selection up (K in list mode) - Shift+Up Arrow
-pub fn list_select_up() -> Result<(), String> {
-    inject_arrow(
-        ArrowDirection::Up,
-        Modifiers {
-            shift: true,
-            ..Default::default()
-        },
-    )
-}
+        event.post(CGEventTapLocation::HID);
 
-/// Extend selection down (J in list mode) - Shift+Down Arrow
-pub fn list_select_down() -> Result<(), String> {
-    inject_arrow(
-        ArrowDirection::Down,
-        Modifiers {
-            shift: true,
-            ..Default::default()
-        },
-    )
-}
+        Ok(())
+    }
 
-/// Go to top of list (gg in list mode) - Home key
-pub fn list_go_top() -> Result<(), String> {
-    inject_key_press(KeyCode::Home, Modifiers::default())
+    fn scroll_wheel(&self, delta_x: i32, delta_y: i32) -> Result<(), String> {
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| "Failed to create event source")?;
+
+        // Create scroll wheel event with pixel-based scrolling
+        // wheel_count=2 means we're providing both vertical and horizontal axes
+        // For vertical (wheel1): negative delta scrolls content down (user sees content move up, scrolling down)
+        // For horizontal (wheel2): positive delta scrolls content left
+        let event = CGEvent::new_scroll_event(
+            source,
+            ScrollEventUnit::PIXEL,
+            2, // wheel_count: 2 for both vertical and horizontal
+            delta_y,
+            delta_x,
+            0,
+        )
+        .map_err(|_| "Failed to create scroll event")?;
+
+        // Mark the event as injected by us
+        mark_injected(&event);
+
+        event.post(CGEventTapLocation::HID);
+        Ok(())
+    }
 }
 
-/// Go to bottom of list (G in list mode) - End key
-pub fn list_go_bottom() -> Result<(), String> {
-    inject_key_press(KeyCode::End, Modifiers::default())
+#[derive(Debug, Clone, Copy)]
+pub enum ArrowDirection {
+    Left,
+    Right,
+    Up,
+    Down,
 }
 
-/// Inject Return key (for opening items with 'o')
-pub fn inject_return() -> Result<(), String> {
-    inject_key_press(KeyCode::Return, Modifiers::default())
+/// Cursor motions that macOS maps to a single navigation keystroke
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigation {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordBackward,
+    WordForward,
+    LineStart,
+    LineEnd,
+    DocumentStart,
+    DocumentEnd,
+    ParagraphUp,
+    ParagraphDown,
+    PageUp,
+    PageDown,
+}
+
+/// Keystroke for a navigation motion. Shift is added to extend the selection.
+pub fn navigation_keystroke(navigation: Navigation, select: bool) -> (KeyCode, Modifiers) {
+    let (keycode, modifiers) = match navigation {
+        Navigation::Left => (KeyCode::Left, Modifiers::default()),
+        Navigation::Right => (KeyCode::Right, Modifiers::default()),
+        Navigation::Up => (KeyCode::Up, Modifiers::default()),
+        Navigation::Down => (KeyCode::Down, Modifiers::default()),
+        // Option+Left/Right
+        Navigation::WordBackward => (KeyCode::Left, Modifiers { option: true, ..Default::default() }),
+        Navigation::WordForward => (KeyCode::Right, Modifiers { option: true, ..Default::default() }),
+        // Cmd+Left/Right
+        Navigation::LineStart => (KeyCode::Left, Modifiers { command: true, ..Default::default() }),
+        Navigation::LineEnd => (KeyCode::Right, Modifiers { command: true, ..Default::default() }),
+        // Cmd+Up/Down
+        Navigation::DocumentStart => (KeyCode::Up, Modifiers { command: true, ..Default::default() }),
+        Navigation::DocumentEnd => (KeyCode::Down, Modifiers { command: true, ..Default::default() }),
+        // Option+Up/Down
+        Navigation::ParagraphUp => (KeyCode::Up, Modifiers { option: true, ..Default::default() }),
+        Navigation::ParagraphDown => (KeyCode::Down, Modifiers { option: true, ..Default::default() }),
+        Navigation::PageUp => (KeyCode::PageUp, Modifiers::default()),
+        Navigation::PageDown => (KeyCode::PageDown, Modifiers::default()),
+    };
+    (keycode, Modifiers { shift: select, ..modifiers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_adds_shift_to_navigation() {
+        let cases = [
+            (Navigation::Left, KeyCode::Left, false, false),
+            (Navigation::Down, KeyCode::Down, false, false),
+            (Navigation::WordForward, KeyCode::Right, true, false),
+            (Navigation::WordBackward, KeyCode::Left, true, false),
+            (Navigation::LineStart, KeyCode::Left, false, true),
+            (Navigation::LineEnd, KeyCode::Right, false, true),
+            (Navigation::DocumentStart, KeyCode::Up, false, true),
+            (Navigation::DocumentEnd, KeyCode::Down, false, true),
+        ];
+        for (navigation, keycode, option, command) in cases {
+            for select in [false, true] {
+                let (key, mods) = navigation_keystroke(navigation, select);
+                assert_eq!(key, keycode, "{:?}", navigation);
+                assert_eq!(mods.shift, select, "{:?}", navigation);
+                assert_eq!(mods.option, option, "{:?}", navigation);
+                assert_eq!(mods.command, command, "{:?}", navigation);
+                assert!(!mods.control);
+            }
+        }
+    }
 }
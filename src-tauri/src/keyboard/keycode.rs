@@ -145,6 +145,22 @@ define_keycodes! {
     F10 = (0x6D, "f10", "F10"),
     F11 = (0x67, "f11", "F11"),
     F12 = (0x6F, "f12", "F12"),
+    F13 = (0x69, "f13", "F13"),
+    F14 = (0x6B, "f14", "F14"),
+    F15 = (0x71, "f15", "F15"),
+    F16 = (0x6A, "f16", "F16"),
+    F17 = (0x40, "f17", "F17"),
+    F18 = (0x4F, "f18", "F18"),
+    F19 = (0x50, "f19", "F19"),
+    F20 = (0x5A, "f20", "F20"),
+    Help = (0x72, "help", "Help"),
+    ContextualMenu = (0x6E, "contextual_menu", "Menu"),
+
+    // Media keys that arrive as key events (extended keyboards).
+    // Play/pause, brightness and the like are system-defined events and can't be bound.
+    VolumeUp = (0x48, "volume_up", "Volume Up"),
+    VolumeDown = (0x49, "volume_down", "Volume Down"),
+    Mute = (0x4A, "mute", "Mute"),
 
     // Navigation
     Home = (0x73, "home", "Home"),
@@ -167,6 +183,45 @@ define_keycodes! {
     Grave = (0x32, "grave", "`"),
 }
 
+impl KeyCode {
+    /// Keys that macOS always reports with the Fn flag set, whether or not Fn is held
+    pub fn implies_function_flag(self) -> bool {
+        matches!(
+            self,
+            Self::Left
+                | Self::Right
+                | Self::Down
+                | Self::Up
+                | Self::F1
+                | Self::F2
+                | Self::F3
+                | Self::F4
+                | Self::F5
+                | Self::F6
+                | Self::F7
+                | Self::F8
+                | Self::F9
+                | Self::F10
+                | Self::F11
+                | Self::F12
+                | Self::F13
+                | Self::F14
+                | Self::F15
+                | Self::F16
+                | Self::F17
+                | Self::F18
+                | Self::F19
+                | Self::F20
+                | Self::Help
+                | Self::Home
+                | Self::End
+                | Self::PageUp
+                | Self::PageDown
+                | Self::ForwardDelete
+        )
+    }
+}
+
 /// Modifier flags matching CGEventFlags
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Modifiers {
@@ -175,6 +230,8 @@ pub struct Modifiers {
     pub option: bool,
     pub command: bool,
     pub caps_lock: bool,
+    /// Fn/Globe key
+    pub function: bool,
 }
 
 impl Modifiers {
@@ -183,6 +240,7 @@ impl Modifiers {
     const OPTION_MASK: u64 = 0x00080000;
     const COMMAND_MASK: u64 = 0x00100000;
     const CAPS_LOCK_MASK: u64 = 0x00010000;
+    const FUNCTION_MASK: u64 = 0x00800000;
 
     pub fn from_cg_flags(flags: u64) -> Self {
         Self {
@@ -191,6 +249,7 @@ impl Modifiers {
             option: flags & Self::OPTION_MASK != 0,
             command: flags & Self::COMMAND_MASK != 0,
             caps_lock: flags & Self::CAPS_LOCK_MASK != 0,
+            function: flags & Self::FUNCTION_MASK != 0,
         }
     }
 
@@ -211,6 +270,9 @@ impl Modifiers {
         if self.caps_lock {
             flags |= Self::CAPS_LOCK_MASK;
         }
+        if self.function {
+            flags |= Self::FUNCTION_MASK;
+        }
         flags
     }
 }
@@ -228,3 +290,45 @@ impl KeyEvent {
         KeyCode::from_raw(self.code)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_and_media_key_names_round_trip() {
+        let keys = [
+            KeyCode::F1,
+            KeyCode::F12,
+            KeyCode::F13,
+            KeyCode::F14,
+            KeyCode::F15,
+            KeyCode::F16,
+            KeyCode::F17,
+            KeyCode::F18,
+            KeyCode::F19,
+            KeyCode::F20,
+            KeyCode::Help,
+            KeyCode::ContextualMenu,
+            KeyCode::VolumeUp,
+            KeyCode::VolumeDown,
+            KeyCode::Mute,
+        ];
+        for key in keys {
+            assert_eq!(KeyCode::from_name(key.to_name()), Some(key), "{}", key.to_name());
+            assert_eq!(KeyCode::from_raw(key.as_raw()), Some(key), "{}", key.to_name());
+        }
+        assert_eq!(KeyCode::from_name("F19"), Some(KeyCode::F19));
+        assert_eq!(KeyCode::F17.to_display_name(), "F17");
+        assert_eq!(KeyCode::from_name("f21"), None);
+    }
+
+    #[test]
+    fn test_function_flag_round_trip() {
+        let modifiers = Modifiers::from_cg_flags(0x00800000 | 0x00100000);
+        assert!(modifiers.function);
+        assert!(modifiers.command);
+        assert!(!modifiers.shift);
+        assert_eq!(modifiers.to_cg_flags(), 0x00800000 | 0x00100000);
+    }
+}
@@ -1,9 +1,19 @@
 mod capture;
+pub mod hid_remap;
 mod inject;
 pub mod keycode;
 mod permission;
+#[cfg(test)]
+pub mod recorder;
+mod synthetic;
 
-pub use capture::KeyboardCapture;
+pub use capture::{keys_pressed_since, KeyboardCapture, TapStatus};
 pub use inject::*;
 pub use keycode::{KeyCode, KeyEvent, Modifiers};
-pub use permission::{check_accessibility_permission, request_accessibility_permission};
+#[cfg(test)]
+pub use recorder::RecordingInjector;
+pub use synthetic::{is_injected, mark_injected, INJECTED_EVENT_MARKER};
+pub use permission::{
+    check_accessibility_permission, check_input_monitoring_permission,
+    request_accessibility_permission,
+};
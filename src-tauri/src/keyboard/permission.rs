@@ -9,13 +9,29 @@ extern "C" {
     fn AXIsProcessTrustedWithOptions(options: core_foundation::dictionary::CFDictionaryRef) -> bool;
 }
 
+// IOKit framework binding for Input Monitoring (macOS 10.15+)
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOHIDCheckAccess(request_type: u32) -> u32;
+}
+
 const K_AX_TRUSTED_CHECK_OPTION_PROMPT: &str = "AXTrustedCheckOptionPrompt";
 
+/// kIOHIDRequestTypeListenEvent
+const K_IOHID_REQUEST_TYPE_LISTEN_EVENT: u32 = 1;
+/// kIOHIDAccessTypeGranted
+const K_IOHID_ACCESS_TYPE_GRANTED: u32 = 0;
+
 /// Check if the app has accessibility/input monitoring permission
 pub fn check_accessibility_permission() -> bool {
     unsafe { AXIsProcessTrustedWithOptions(std::ptr::null()) }
 }
 
+/// Check if the app has Input Monitoring permission (needed by the event tap on some macOS versions)
+pub fn check_input_monitoring_permission() -> bool {
+    unsafe { IOHIDCheckAccess(K_IOHID_REQUEST_TYPE_LISTEN_EVENT) == K_IOHID_ACCESS_TYPE_GRANTED }
+}
+
 /// Request accessibility permission (shows system prompt)
 pub fn request_accessibility_permission() -> bool {
     unsafe {
@@ -0,0 +1,68 @@
+//! Key injector that records events instead of posting them (tests only)
+
+use std::sync::Mutex;
+
+use super::inject::KeyInjector;
+use super::keycode::{KeyCode, KeyEvent, Modifiers};
+
+/// Records injected key presses as `"shift+cmd+right"` and scrolls as `"scroll 0,-40"`.
+/// Key up events are not recorded.
+#[derive(Debug, Default)]
+pub struct RecordingInjector {
+    events: Mutex<Vec<String>>,
+}
+
+impl RecordingInjector {
+    /// Return the recorded events and start over
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl KeyInjector for RecordingInjector {
+    fn inject_key(&self, keycode: KeyCode, key_down: bool, modifiers: Modifiers) -> Result<(), String> {
+        if key_down {
+            let event = KeyEvent {
+                code: keycode.as_raw(),
+                modifiers,
+                is_key_down: true,
+            };
+            self.events.lock().unwrap().push(format_key(&event));
+        }
+        Ok(())
+    }
+
+    fn scroll_wheel(&self, delta_x: i32, delta_y: i32) -> Result<(), String> {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("scroll {},{}", delta_x, delta_y));
+        Ok(())
+    }
+
+    /// Nothing is posted, and tests read the keys as soon as the callback returns
+    fn runs_actions_inline(&self) -> bool {
+        true
+    }
+}
+
+/// Format a key as modifier prefixes and its name, e.g. `"shift+cmd+right"`
+pub fn format_key(event: &KeyEvent) -> String {
+    let modifiers = event.modifiers;
+    let name = event
+        .keycode()
+        .map(|keycode| keycode.to_name().to_string())
+        .unwrap_or_else(|| format!("0x{:02X}", event.code));
+    [
+        (modifiers.shift, "shift"),
+        (modifiers.control, "ctrl"),
+        (modifiers.option, "opt"),
+        (modifiers.command, "cmd"),
+        (modifiers.function, "fn"),
+    ]
+    .into_iter()
+    .filter_map(|(held, prefix)| held.then_some(prefix))
+    .chain(std::iter::once(name.as_str()))
+    .collect::<Vec<_>>()
+    .join("+")
+}
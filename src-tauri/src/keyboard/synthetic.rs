@@ -0,0 +1,56 @@
+//! Tagging of synthetic events posted by ovim
+//!
+//! Every CGEvent ovim creates (key injections, scrolls, clicks) carries a marker
+//! in its source user data so the event tap can pass it through untouched
+//! instead of feeding it back into vim mode, click mode or the mouse callbacks.
+
+use core_graphics::event::{CGEvent, EventField};
+
+/// Custom user data field to mark our injected events
+/// We use a high value that's unlikely to conflict with real keycodes
+pub const INJECTED_EVENT_MARKER: i64 = 0x54495649; // "TIVI" in hex
+
+/// Mark an event as created by ovim
+pub fn mark_injected(event: &CGEvent) {
+    event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, INJECTED_EVENT_MARKER);
+}
+
+/// Check whether an event was created by ovim
+pub fn is_injected(event: &CGEvent) -> bool {
+    is_injected_user_data(event.get_integer_value_field(EventField::EVENT_SOURCE_USER_DATA))
+}
+
+/// Check an event's source user data for the ovim marker
+pub fn is_injected_user_data(user_data: i64) -> bool {
+    user_data == INJECTED_EVENT_MARKER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marker_is_detected() {
+        assert!(is_injected_user_data(INJECTED_EVENT_MARKER));
+    }
+
+    #[test]
+    fn test_real_input_is_not_injected() {
+        // Hardware events leave the user data at zero
+        assert!(!is_injected_user_data(0));
+        assert!(!is_injected_user_data(INJECTED_EVENT_MARKER + 1));
+        assert!(!is_injected_user_data(-1));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_marked_event_round_trip() {
+        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).unwrap();
+        let event = CGEvent::new(source).unwrap();
+        assert!(!is_injected(&event));
+        mark_injected(&event);
+        assert!(is_injected(&event));
+    }
+}
@@ -0,0 +1,239 @@
+//! Caps Lock remapping (tap for Escape, hold for Control)
+//!
+//! While a remap is enabled, Caps Lock is remapped at the HID level (see
+//! `keyboard::hid_remap`) and arrives as an ordinary key with key down and up
+//! events, so there is no caps state to undo. Keys pressed while it is held are
+//! re-injected with Control added.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::CapsLockRemap;
+use crate::keyboard::hid_remap::REMAPPED_CAPS_LOCK;
+use crate::keyboard::{KeyCode, KeyEvent, KeyInjector, Modifiers};
+
+/// Maximum time Caps Lock can be held to count as a tap (Escape)
+const TAP_THRESHOLD: Duration = Duration::from_millis(200);
+
+/// Tracks a single Caps Lock press for tap-vs-hold detection
+pub struct CapsLockTracker {
+    /// Timestamp when Caps Lock was pressed, None when released
+    pressed_at: Option<Instant>,
+    /// Whether another key was pressed while Caps Lock was held
+    used_as_modifier: bool,
+}
+
+impl CapsLockTracker {
+    pub const fn new() -> Self {
+        Self {
+            pressed_at: None,
+            used_as_modifier: false,
+        }
+    }
+
+    /// Update tracker when Caps Lock is pressed
+    pub fn on_press(&mut self, now: Instant) {
+        self.pressed_at = Some(now);
+        self.used_as_modifier = false;
+    }
+
+    /// Whether Caps Lock is currently held
+    pub fn is_held(&self) -> bool {
+        self.pressed_at.is_some()
+    }
+
+    /// Record that Caps Lock acted as Control for another key
+    pub fn mark_used_as_modifier(&mut self) {
+        self.used_as_modifier = true;
+    }
+
+    /// Update tracker when Caps Lock is released.
+    /// Returns true if the press should be sent as Escape.
+    pub fn on_release(&mut self, now: Instant, remap: CapsLockRemap) -> bool {
+        let Some(pressed_at) = self.pressed_at.take() else {
+            return false;
+        };
+        let used_as_modifier = std::mem::take(&mut self.used_as_modifier);
+
+        remap == CapsLockRemap::EscapeOrControl
+            && !used_as_modifier
+            && now.duration_since(pressed_at) <= TAP_THRESHOLD
+    }
+}
+
+impl Default for CapsLockTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static TRACKER: Mutex<CapsLockTracker> = Mutex::new(CapsLockTracker::new());
+
+fn send_escape(injector: &dyn KeyInjector) {
+    if let Err(e) = injector.inject_key_press(KeyCode::Escape, Modifiers::default()) {
+        log::error!("Failed to inject Escape for Caps Lock: {}", e);
+    }
+}
+
+/// Handle the remapped Caps Lock key itself.
+/// Returns None when the event is not Caps Lock or no remap is enabled.
+fn handle_caps_lock_key(
+    event: &KeyEvent,
+    remap: CapsLockRemap,
+    injector: &dyn KeyInjector,
+) -> Option<Option<KeyEvent>> {
+    if event.keycode() != Some(REMAPPED_CAPS_LOCK) {
+        return None;
+    }
+
+    match remap {
+        CapsLockRemap::None => return None,
+        CapsLockRemap::Escape => {
+            if event.is_key_down {
+                send_escape(injector);
+            }
+        }
+        CapsLockRemap::Control | CapsLockRemap::EscapeOrControl => {
+            let mut tracker = TRACKER.lock().unwrap();
+            if event.is_key_down {
+                // Auto-repeat of a held Caps Lock doesn't restart the press
+                if !tracker.is_held() {
+                    tracker.on_press(Instant::now());
+                }
+            } else if tracker.on_release(Instant::now(), remap) {
+                drop(tracker);
+                send_escape(injector);
+            }
+        }
+    }
+
+    Some(None)
+}
+
+/// Handle the remapped Caps Lock key, and while it is held as Control re-inject
+/// other keys with Control added.
+/// Returns None when the event is left to the rest of the handler.
+pub fn apply_caps_lock_control(
+    event: &KeyEvent,
+    remap: CapsLockRemap,
+    injector: &dyn KeyInjector,
+) -> Option<Option<KeyEvent>> {
+    if let Some(result) = handle_caps_lock_key(event, remap, injector) {
+        return Some(result);
+    }
+    if !matches!(remap, CapsLockRemap::Control | CapsLockRemap::EscapeOrControl) {
+        return None;
+    }
+
+    let keycode = event.keycode()?;
+    {
+        let mut tracker = TRACKER.lock().unwrap();
+        if !tracker.is_held() {
+            return None;
+        }
+        tracker.mark_used_as_modifier();
+    }
+
+    let modifiers = Modifiers {
+        control: true,
+        caps_lock: false,
+        ..event.modifiers
+    };
+    if let Err(e) = injector.inject_key(keycode, event.is_key_down, modifiers) {
+        log::error!("Failed to inject Control+{} for Caps Lock: {}", keycode.to_name(), e);
+    }
+
+    Some(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_tap_sends_escape() {
+        let mut tracker = CapsLockTracker::new();
+        let now = Instant::now();
+
+        tracker.on_press(now);
+        assert!(tracker.is_held());
+        assert!(tracker.on_release(now + Duration::from_millis(50), CapsLockRemap::EscapeOrControl));
+        assert!(!tracker.is_held());
+    }
+
+    #[test]
+    fn test_hold_does_not_send_escape() {
+        let mut tracker = CapsLockTracker::new();
+        let now = Instant::now();
+
+        tracker.on_press(now);
+        assert!(!tracker.on_release(now + Duration::from_millis(300), CapsLockRemap::EscapeOrControl));
+    }
+
+    #[test]
+    fn test_used_as_modifier_does_not_send_escape() {
+        let mut tracker = CapsLockTracker::new();
+        let now = Instant::now();
+
+        tracker.on_press(now);
+        tracker.mark_used_as_modifier();
+        assert!(!tracker.on_release(now + Duration::from_millis(50), CapsLockRemap::EscapeOrControl));
+
+        // Next press starts clean
+        tracker.on_press(now);
+        assert!(tracker.on_release(now + Duration::from_millis(50), CapsLockRemap::EscapeOrControl));
+    }
+
+    #[test]
+    fn test_control_only_never_sends_escape() {
+        let mut tracker = CapsLockTracker::new();
+        let now = Instant::now();
+
+        tracker.on_press(now);
+        assert!(!tracker.on_release(now + Duration::from_millis(50), CapsLockRemap::Control));
+    }
+
+    fn key(keycode: KeyCode, is_key_down: bool) -> KeyEvent {
+        KeyEvent {
+            code: keycode.as_raw(),
+            modifiers: Modifiers::default(),
+            is_key_down,
+        }
+    }
+
+    #[test]
+    fn test_remapped_caps_lock_key() {
+        let injector = crate::keyboard::RecordingInjector::default();
+        let caps_down = key(REMAPPED_CAPS_LOCK, true);
+        let caps_up = key(REMAPPED_CAPS_LOCK, false);
+
+        // Without a remap the key is left alone
+        assert!(apply_caps_lock_control(&caps_down, CapsLockRemap::None, &injector).is_none());
+
+        assert!(matches!(
+            apply_caps_lock_control(&caps_down, CapsLockRemap::Escape, &injector),
+            Some(None)
+        ));
+        assert!(matches!(
+            apply_caps_lock_control(&caps_up, CapsLockRemap::Escape, &injector),
+            Some(None)
+        ));
+        assert_eq!(injector.take(), vec!["escape"]);
+
+        // Held as Control: auto-repeat doesn't restart the press, other keys get Control
+        let remap = CapsLockRemap::EscapeOrControl;
+        apply_caps_lock_control(&caps_down, remap, &injector);
+        apply_caps_lock_control(&caps_down, remap, &injector);
+        assert!(matches!(
+            apply_caps_lock_control(&key(KeyCode::D, true), remap, &injector),
+            Some(None)
+        ));
+        apply_caps_lock_control(&caps_up, remap, &injector);
+        assert_eq!(injector.take(), vec!["ctrl+d"]);
+
+        // A quick tap is Escape
+        apply_caps_lock_control(&caps_down, remap, &injector);
+        apply_caps_lock_control(&caps_up, remap, &injector);
+        assert_eq!(injector.take(), vec!["escape"]);
+    }
+}
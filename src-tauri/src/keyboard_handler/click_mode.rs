@@ -4,13 +4,85 @@ use std::thread;
 
 use tauri::Emitter;
 
+use crate::click_mode::browser_clickables::PageView;
 use crate::click_mode::native_hints;
-use crate::click_mode::{self, ClickAction, HintInputResult, SharedClickModeManager};
+use crate::click_mode::{
+    self, ClickAction, ClickModeManager, ClickTarget, HintInputResult, SearchMatch,
+    SharedClickModeManager,
+};
+use crate::config::click_mode::ClickModeSettings;
+use crate::config::VimKeyModifiers;
 use crate::get_app_handle;
-use crate::keyboard::{KeyCode, KeyEvent};
+use crate::keyboard::{KeyCode, KeyEvent, KeyInjector};
+
+use super::shortcuts::{modifiers_match, start_click_mode};
+
+/// Keys that switch between hint mode and search mode, and the other
+/// configurable hint mode keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchKeys {
+    /// Enters search mode from hint mode
+    pub enter: Option<KeyCode>,
+    /// Returns from search mode to hint mode
+    pub exit: Option<KeyCode>,
+    /// Switches between hint and search mode in either direction
+    pub toggle_search: Option<KeyCode>,
+    /// Cycles between elements stacked at the same position
+    pub cycle_overlap: Option<KeyCode>,
+    /// Pins or unpins the shown elements
+    pub pin: Option<KeyCode>,
+    /// Switches to batch copy, which collects element texts until Enter
+    pub batch_copy: Option<KeyCode>,
+}
+
+impl SearchKeys {
+    /// Resolve the configured key names (unknown names disable the key)
+    pub fn from_settings(settings: &ClickModeSettings) -> Self {
+        Self {
+            enter: KeyCode::from_name(&settings.search_key),
+            exit: KeyCode::from_name(&settings.exit_search_key),
+            toggle_search: KeyCode::from_name(&settings.toggle_search_key),
+            cycle_overlap: KeyCode::from_name(&settings.cycle_overlap_key),
+            pin: KeyCode::from_name(&settings.pin_key),
+            batch_copy: KeyCode::from_name(&settings.batch_copy_key),
+        }
+    }
+}
+
+/// Modifier+h/j/k/l scrolling while hints are shown
+#[derive(Debug, Clone)]
+pub struct ScrollKeys {
+    /// Modifiers that must be held with h/j/k/l
+    pub modifiers: VimKeyModifiers,
+    /// Scroll distance per key press
+    pub step: u32,
+}
+
+impl ScrollKeys {
+    /// Use the click mode scroll modifiers with the scroll mode step
+    pub fn from_settings(settings: &ClickModeSettings, step: u32) -> Self {
+        Self {
+            modifiers: settings.scroll_modifiers.clone(),
+            step,
+        }
+    }
+}
+
+/// Delay before re-querying elements so the scroll can settle
+const SCROLL_REQUERY_DELAY_MS: u64 = 150;
+
+/// Delay before hinting a menu opened from the menu bar so it can appear
+const MENU_OPEN_DELAY_MS: u64 = 150;
 
 /// Handle keyboard input when click mode is active
-pub fn handle_click_mode_key(event: KeyEvent, manager: SharedClickModeManager) -> Option<KeyEvent> {
+pub fn handle_click_mode_key(
+    event: KeyEvent,
+    manager: SharedClickModeManager,
+    search_keys: SearchKeys,
+    scroll_keys: ScrollKeys,
+    copy_modifiers: VimKeyModifiers,
+    injector: &dyn KeyInjector,
+) -> Option<KeyEvent> {
     // Only handle key down events
     if !event.is_key_down {
         return None; // Suppress key up events in click mode
@@ -18,6 +90,74 @@ pub fn handle_click_mode_key(event: KeyEvent, manager: SharedClickModeManager) -
 
     let keycode = event.keycode()?;
 
+    if Some(keycode) == search_keys.toggle_search && is_no_modifiers(&event) {
+        toggle_search_mode(&manager);
+        return None;
+    }
+
+    if manager.lock().unwrap().state().is_searching() {
+        return handle_search_key(&event, keycode, manager, search_keys);
+    }
+
+    // Scroll the page and re-show hints for the new position
+    if !is_no_modifiers(&event) && modifiers_match(&event, &scroll_keys.modifiers) {
+        if let Some(result) = scroll_and_requery(keycode, &manager, scroll_keys.step, injector) {
+            if let Err(e) = result {
+                log::error!("Click mode scroll failed: {}", e);
+            }
+            return None;
+        }
+    }
+
+    // Enter search mode before any hint has been typed
+    if Some(keycode) == search_keys.enter && is_no_modifiers(&event) {
+        let mut mgr = manager.lock().unwrap();
+        if mgr.get_current_input().is_empty() {
+            mgr.enter_search_mode();
+            log::info!("Click mode: entered search mode");
+            drop(mgr);
+            update_search_results(&manager);
+            return None;
+        }
+    }
+
+    // Cycle between elements stacked at the same position
+    if Some(keycode) == search_keys.cycle_overlap && is_no_modifiers(&event) {
+        let mut mgr = manager.lock().unwrap();
+        if mgr.get_current_input().is_empty() {
+            match mgr.cycle_overlapping() {
+                Some(visible) => {
+                    log::info!("Click mode: cycled overlapping hints");
+                    // Same positions and hints, but the labels follow the new targets
+                    native_hints::show_hints(&visible, &native_hints::current_style());
+                    if let Some(app) = get_app_handle() {
+                        let _ = app.emit("click-mode-filtered", (&visible, ""));
+                    }
+                }
+                None => native_hints::shake_hints(),
+            }
+            return None;
+        }
+    }
+
+    // Freeze the element set for repeated clicks on a stable UI
+    if Some(keycode) == search_keys.pin && is_no_modifiers(&event) {
+        match manager.lock().unwrap().toggle_pin() {
+            Ok(pinned) => log::info!("Click mode: elements {}", if pinned { "pinned" } else { "unpinned" }),
+            Err(e) => {
+                log::warn!("Click mode: could not pin elements: {}", e);
+                native_hints::shake_hints();
+            }
+        }
+        return None;
+    }
+
+    // Collect several element texts, copied together on Enter
+    if Some(keycode) == search_keys.batch_copy && is_no_modifiers(&event) {
+        switch_click_action(ClickAction::BatchCopy, &manager);
+        return None;
+    }
+
     // Handle special keys
     if let Some(result) = handle_special_keys(keycode, &manager) {
         return result;
@@ -35,6 +175,10 @@ pub fn handle_click_mode_key(event: KeyEvent, manager: SharedClickModeManager) -
     // Handle alphanumeric hint input
     if let Some(c) = keycode.to_char() {
         if c.is_alphanumeric() {
+            // Typing a hint with the copy modifiers copies the element's text
+            if !is_no_modifiers(&event) && modifiers_match(&event, &copy_modifiers) {
+                switch_click_action(ClickAction::Copy, &manager);
+            }
             return handle_hint_input(c, manager);
         }
     }
@@ -43,6 +187,178 @@ pub fn handle_click_mode_key(event: KeyEvent, manager: SharedClickModeManager) -
     None
 }
 
+/// Scroll for h/j/k/l, then query elements again and show fresh hints.
+/// Returns None if the key is not a scroll key.
+fn scroll_and_requery(
+    keycode: KeyCode,
+    manager: &SharedClickModeManager,
+    step: u32,
+    injector: &dyn KeyInjector,
+) -> Option<Result<(), String>> {
+    let result = match keycode {
+        KeyCode::H => injector.scroll_left(step),
+        KeyCode::J => injector.scroll_down(step),
+        KeyCode::K => injector.scroll_up(step),
+        KeyCode::L => injector.scroll_right(step),
+        _ => return None,
+    };
+    if result.is_err() {
+        return Some(result);
+    }
+    // Drop typed input right away rather than when the re-query lands
+    manager.lock().unwrap().clear_hint_input();
+
+    let manager = manager.clone();
+    thread::spawn(move || {
+        thread::sleep(std::time::Duration::from_millis(SCROLL_REQUERY_DELAY_MS));
+        // Scrolling moved the elements, so a pinned set is stale
+        click_mode::accessibility::unpin_elements();
+        click_mode::accessibility::invalidate_cache();
+
+        let mut mgr = manager.lock().unwrap();
+        if !mgr.state().is_showing_hints() {
+            return;
+        }
+        // Hint assignments change, so any typed input is dropped
+        match mgr.activate() {
+            Ok(elements) => {
+                log::info!("Click mode: re-queried {} elements after scroll", elements.len());
+                native_hints::show_hints(&elements, &native_hints::current_style());
+                if let Some(app) = get_app_handle() {
+                    let _ = app.emit("click-mode-filtered", (&elements, ""));
+                }
+            }
+            Err(e) => {
+                log::error!("Failed to re-query elements after scroll: {}", e);
+                click_mode::deactivate_with_guard(&mut mgr);
+            }
+        }
+    });
+
+    Some(Ok(()))
+}
+
+/// Handle keyboard input while in search mode
+fn handle_search_key(
+    event: &KeyEvent,
+    keycode: KeyCode,
+    manager: SharedClickModeManager,
+    search_keys: SearchKeys,
+) -> Option<KeyEvent> {
+    if Some(keycode) == search_keys.exit {
+        exit_search_mode(&manager);
+        return None;
+    }
+
+    match keycode {
+        KeyCode::Escape => {
+            deactivate_click_mode(&manager);
+            None
+        }
+        KeyCode::Delete => {
+            manager.lock().unwrap().clear_last_input();
+            update_search_results(&manager);
+            None
+        }
+        KeyCode::Return => choose_best_search_match(manager),
+        _ => {
+            if event.modifiers.command || event.modifiers.control {
+                return None;
+            }
+            if let Some(c) = keycode.to_char() {
+                {
+                    let mut mgr = manager.lock().unwrap();
+                    let mut query = mgr.get_current_input();
+                    query.push(c);
+                    mgr.handle_search_input(&query);
+                }
+                update_search_results(&manager);
+            }
+            None
+        }
+    }
+}
+
+/// Switch between hint and search mode for the same elements and re-render
+fn toggle_search_mode(manager: &SharedClickModeManager) {
+    let mut mgr = manager.lock().unwrap();
+    mgr.toggle_search_mode();
+    if mgr.state().is_searching() {
+        log::info!("Click mode: toggled to search mode");
+        drop(mgr);
+        update_search_results(manager);
+        return;
+    }
+
+    log::info!("Click mode: toggled to hint mode");
+    let elements = mgr.get_all_elements();
+    native_hints::show_hints(&elements, &native_hints::current_style());
+    if let Some(app) = get_app_handle() {
+        let _ = app.emit("click-mode-filtered", (&elements, ""));
+    }
+}
+
+/// Leave search mode and show every hint again
+fn exit_search_mode(manager: &SharedClickModeManager) {
+    let mut mgr = manager.lock().unwrap();
+    mgr.exit_search_mode();
+    log::info!("Click mode: exited search mode");
+
+    let all_elements = mgr.get_all_elements();
+    native_hints::filter_hints_with_input("", &all_elements);
+    if let Some(app) = get_app_handle() {
+        let _ = app.emit("click-mode-filtered", (&all_elements, ""));
+    }
+}
+
+/// Show only the hints matching the current search query
+fn update_search_results(manager: &SharedClickModeManager) {
+    let mgr = manager.lock().unwrap();
+    let query = mgr.get_current_input();
+    let matching = mgr.get_filtered_elements();
+
+    let visible_ids: Vec<usize> = matching.iter().map(|e| e.id).collect();
+    native_hints::filter_hints_by_ids(&visible_ids, &mgr.get_all_elements());
+
+    if let Some(app) = get_app_handle() {
+        let _ = app.emit("click-mode-filtered", (&matching, &query));
+    }
+}
+
+/// Act on the best-ranked search match as if its hint was typed, or narrow hints
+/// to the tied matches. In batch copy, Enter with an empty query copies the collected texts.
+fn choose_best_search_match(manager: SharedClickModeManager) -> Option<KeyEvent> {
+    let mut mgr = manager.lock().unwrap();
+    let click_action = mgr.get_click_action();
+    if click_action == ClickAction::BatchCopy && mgr.get_current_input().is_empty() {
+        drop(mgr);
+        finish_batch_copy(&manager);
+        return None;
+    }
+
+    match mgr.best_search_match() {
+        SearchMatch::None => {
+            log::debug!("Click mode: no search matches, Enter ignored");
+            native_hints::shake_hints();
+        }
+        SearchMatch::Best(element) => {
+            log::info!("Click mode: best search match '{}'", element.title);
+            return handle_hint_match(element, click_action, &mut mgr, manager.clone());
+        }
+        SearchMatch::Tied(elements) => {
+            log::info!("Click mode: {} equally ranked matches, showing their hints", elements.len());
+            let ids: Vec<usize> = elements.iter().map(|e| e.id).collect();
+            mgr.narrow_to_elements(&ids);
+            native_hints::show_hints(&elements, &native_hints::current_style());
+            if let Some(app) = get_app_handle() {
+                let _ = app.emit("click-mode-filtered", (&elements, ""));
+            }
+        }
+    }
+
+    None
+}
+
 /// Check if no modifiers are pressed
 fn is_no_modifiers(event: &KeyEvent) -> bool {
     !event.modifiers.shift
@@ -66,15 +382,50 @@ fn handle_special_keys(
             Some(None)
         }
         KeyCode::Return => {
-            // TODO: Implement selection confirmation
+            finish_batch_copy(manager);
             Some(None)
         }
         _ => None,
     }
 }
 
+/// Copy the texts collected in batch copy and close click mode
+fn finish_batch_copy(manager: &SharedClickModeManager) {
+    let mut mgr = manager.lock().unwrap();
+    match mgr.finish_batch_copy() {
+        Ok(count) => {
+            log::info!("Click mode: copied {} element texts", count);
+            click_mode::deactivate_with_guard(&mut mgr);
+        }
+        Err(e) => {
+            log::debug!("Click mode: Enter ignored: {}", e);
+            native_hints::shake_hints();
+        }
+    }
+}
+
+/// Cancel click mode with Escape while its elements are still being queried
+/// (hints not shown yet, `element_count == 0`). Checked before the manager lock is
+/// taken, since the activation thread holds it for the whole query.
+pub fn cancel_activation_on_escape(event: &KeyEvent) -> bool {
+    if !event.is_key_down || event.keycode() != Some(KeyCode::Escape) {
+        return false;
+    }
+    if !click_mode::cancel_activation() {
+        return false;
+    }
+    native_hints::hide_hints();
+    if let Some(app) = get_app_handle() {
+        let _ = app.emit("click-mode-deactivated", ());
+    }
+    log::info!("Click mode cancelled via Escape while querying elements");
+    true
+}
+
 /// Deactivate click mode and hide hints
 fn deactivate_click_mode(manager: &SharedClickModeManager) {
+    // The query may not have started yet; make sure its result is discarded
+    click_mode::cancel_activation();
     click_mode::deactivate_and_notify(manager);
     log::info!("Click mode cancelled via Escape");
 }
@@ -106,18 +457,26 @@ fn handle_action_switch(c: char, manager: &SharedClickModeManager) -> Option<Opt
     };
 
     if let Some(action) = new_action {
-        let mut mgr = manager.lock().unwrap();
-        mgr.set_click_action(action);
-        log::info!("Click mode: switched to {:?} action", action);
-        if let Some(app) = get_app_handle() {
-            let _ = app.emit("click-action-changed", action);
-        }
+        switch_click_action(action, manager);
         return Some(None);
     }
 
     None
 }
 
+/// Set the click action and notify the frontend
+fn switch_click_action(action: ClickAction, manager: &SharedClickModeManager) {
+    let mut mgr = manager.lock().unwrap();
+    if mgr.get_click_action() == action {
+        return;
+    }
+    mgr.set_click_action(action);
+    log::info!("Click mode: switched to {:?} action", action);
+    if let Some(app) = get_app_handle() {
+        let _ = app.emit("click-action-changed", action);
+    }
+}
+
 /// Handle alphanumeric hint input
 fn handle_hint_input(c: char, manager: SharedClickModeManager) -> Option<KeyEvent> {
     let mut mgr = manager.lock().unwrap();
@@ -142,12 +501,12 @@ fn handle_hint_input(c: char, manager: SharedClickModeManager) -> Option<KeyEven
     }
 }
 
-/// Handle exact hint match - perform click
+/// Handle exact hint match (or the chosen search match) - perform the click action
 fn handle_hint_match(
     element: crate::click_mode::ClickableElement,
     click_action: ClickAction,
     mgr: &mut std::sync::MutexGuard<crate::click_mode::ClickModeManager>,
-    _manager: SharedClickModeManager,
+    manager: SharedClickModeManager,
 ) -> Option<KeyEvent> {
     let action_name = click_action.display_name();
     log::info!(
@@ -158,18 +517,68 @@ fn handle_hint_match(
     );
 
     let element_id = element.id;
-    let position = mgr.get_element_position(element_id);
+    if click_action == ClickAction::Copy {
+        let result = mgr.copy_element(element_id);
+        click_mode::deactivate_with_guard(mgr);
+        match result {
+            Ok(text) => log::info!("Click mode: copied {} chars", text.chars().count()),
+            Err(e) => log::error!("Failed to copy element text: {}", e),
+        }
+        return None;
+    }
+
+    // Batch copy stays open for the next hint
+    if click_action == ClickAction::BatchCopy {
+        match mgr.add_to_batch_copy(element_id) {
+            Ok(count) => log::info!("Click mode: collected {} element texts", count),
+            Err(e) => {
+                log::warn!("Click mode: could not collect element text: {}", e);
+                native_hints::shake_hints();
+            }
+        }
+        let all_elements = mgr.get_all_elements();
+        if mgr.state().is_searching() {
+            // Start the next search over every element
+            let matching = mgr.handle_search_input("");
+            let ids: Vec<usize> = matching.iter().map(|e| e.id).collect();
+            native_hints::filter_hints_by_ids(&ids, &all_elements);
+            if let Some(app) = get_app_handle() {
+                let _ = app.emit("click-mode-filtered", (&matching, ""));
+            }
+        } else {
+            mgr.clear_hint_input();
+            native_hints::filter_hints_with_input("", &all_elements);
+            if let Some(app) = get_app_handle() {
+                let _ = app.emit("click-mode-filtered", (&all_elements, ""));
+            }
+        }
+        return None;
+    }
+
+    let target = mgr.click_target(element_id, click_action);
+    // Deactivating forgets the full-page scan the click may need to scroll
+    let page_view = click_mode::browser_clickables::page_view();
+    // Clicking a menu bar title opens its menu, which gets hints next
+    let chain_menu = click_action == ClickAction::Click && element.role == "AXMenuBarItem";
 
     // Deactivate click mode state, hide hints, and notify frontend
     click_mode::deactivate_with_guard(mgr);
 
     // Perform click on a separate thread with delay
-    if let Some((x, y)) = position {
+    if let Some(target) = target {
         thread::spawn(move || {
             thread::sleep(std::time::Duration::from_millis(50));
-            let result = perform_click(x, y, click_action);
+            let result = match target {
+                ClickTarget::Position(x, y) => perform_click(x, y, click_action, page_view.as_ref()),
+                ClickTarget::Press(handle) => press_element(&handle),
+            };
             if let Err(e) = result {
                 log::error!("Failed to {} element: {}", action_name, e);
+                return;
+            }
+            if chain_menu {
+                thread::sleep(std::time::Duration::from_millis(MENU_OPEN_DELAY_MS));
+                start_click_mode(manager, native_hints::current_style(), ClickModeManager::activate_menu_bar);
             }
         });
     } else {
@@ -180,15 +589,35 @@ fn handle_hint_match(
 }
 
 /// Perform click based on action type
-fn perform_click(x: f64, y: f64, action: ClickAction) -> Result<(), String> {
+fn perform_click(
+    x: f64,
+    y: f64,
+    action: ClickAction,
+    page_view: Option<&PageView>,
+) -> Result<(), String> {
     use crate::click_mode::accessibility;
 
+    // Elements from a full-page scan may be off-screen
+    let (x, y) = click_mode::browser_clickables::reveal_point(page_view, x, y);
+
     match action {
         ClickAction::Click => accessibility::perform_click_at_position(x, y),
         ClickAction::RightClick => accessibility::perform_right_click_at_position(x, y),
         ClickAction::CmdClick => accessibility::perform_cmd_click_at_position(x, y),
         ClickAction::DoubleClick => accessibility::perform_double_click_at_position(x, y),
-    }
+        ClickAction::Copy | ClickAction::BatchCopy => {
+            return Err("Copy actions do not click".to_string())
+        }
+    }?;
+    click_mode::undo::record_click();
+    Ok(())
+}
+
+/// Press an element through its AX handle
+fn press_element(handle: &crate::nvim_edit::accessibility::AXElementHandle) -> Result<(), String> {
+    crate::nvim_edit::accessibility::press_element(handle)?;
+    click_mode::undo::record_click();
+    Ok(())
 }
 
 /// Handle partial hint match
@@ -218,3 +647,76 @@ fn handle_no_match(mgr: &mut std::sync::MutexGuard<crate::click_mode::ClickModeM
     log::debug!("Click mode: no match, deactivating");
     click_mode::deactivate_with_guard(mgr);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_search_keys() {
+        let keys = SearchKeys::from_settings(&ClickModeSettings::default());
+        assert_eq!(keys.enter, Some(KeyCode::Slash));
+        assert_eq!(keys.exit, Some(KeyCode::Tab));
+    }
+
+    #[test]
+    fn test_default_hint_mode_keys() {
+        let keys = SearchKeys::from_settings(&ClickModeSettings::default());
+        assert_eq!(keys.cycle_overlap, Some(KeyCode::Tab));
+        assert_eq!(keys.pin, Some(KeyCode::Equal));
+        assert_eq!(keys.batch_copy, Some(KeyCode::Comma));
+    }
+
+    #[test]
+    fn test_unknown_search_key_is_disabled() {
+        let settings = ClickModeSettings {
+            search_key: "".to_string(),
+            exit_search_key: "Escape".to_string(),
+            ..Default::default()
+        };
+        let keys = SearchKeys::from_settings(&settings);
+        assert_eq!(keys.enter, None);
+        assert_eq!(keys.exit, Some(KeyCode::Escape));
+    }
+
+    #[test]
+    fn test_scroll_modifier_scrolls_and_clears_input() {
+        let manager = click_mode::create_manager();
+        *manager.lock().unwrap() = ClickModeManager::showing_hints(&["Save", "Open"]);
+        manager.lock().unwrap().handle_hint_input('h');
+        assert_eq!(manager.lock().unwrap().get_current_input(), "H");
+
+        let settings = ClickModeSettings::default();
+        let injector = crate::keyboard::RecordingInjector::default();
+        let ctrl_j = KeyEvent {
+            code: KeyCode::J.as_raw(),
+            modifiers: crate::keyboard::Modifiers {
+                control: true,
+                ..Default::default()
+            },
+            is_key_down: true,
+        };
+        let result = handle_click_mode_key(
+            ctrl_j,
+            manager.clone(),
+            SearchKeys::from_settings(&settings),
+            ScrollKeys::from_settings(&settings, 40),
+            settings.copy_modifiers.clone(),
+            &injector,
+        );
+
+        assert!(result.is_none());
+        assert_eq!(injector.take(), vec!["scroll 0,-40".to_string()]);
+        assert_eq!(manager.lock().unwrap().get_current_input(), "");
+        // Nothing left for the delayed re-query to show hints for
+        manager.lock().unwrap().deactivate();
+    }
+
+    #[test]
+    fn test_default_scroll_keys_use_control() {
+        let keys = ScrollKeys::from_settings(&ClickModeSettings::default(), 100);
+        assert!(keys.modifiers.control);
+        assert!(!keys.modifiers.shift && !keys.modifiers.option && !keys.modifiers.command);
+        assert_eq!(keys.step, 100);
+    }
+}
@@ -12,6 +12,7 @@ pub enum DoubleTapKey {
     Option,
     Control,
     Shift,
+    Function,
     Escape,
 }
 
@@ -110,12 +111,14 @@ pub struct DoubleTapManager {
     pub option_tracker: DoubleTapTracker,
     pub control_tracker: DoubleTapTracker,
     pub shift_tracker: DoubleTapTracker,
+    pub function_tracker: DoubleTapTracker,
     pub escape_tracker: DoubleTapTracker,
     /// Previous modifier state to detect transitions
     prev_command: bool,
     prev_option: bool,
     prev_control: bool,
     prev_shift: bool,
+    prev_function: bool,
 }
 
 impl DoubleTapManager {
@@ -125,11 +128,13 @@ impl DoubleTapManager {
             option_tracker: DoubleTapTracker::new(),
             control_tracker: DoubleTapTracker::new(),
             shift_tracker: DoubleTapTracker::new(),
+            function_tracker: DoubleTapTracker::new(),
             escape_tracker: DoubleTapTracker::new(),
             prev_command: false,
             prev_option: false,
             prev_control: false,
             prev_shift: false,
+            prev_function: false,
         }
     }
 
@@ -147,6 +152,9 @@ impl DoubleTapManager {
         if except != DoubleTapKey::Shift {
             self.shift_tracker.reset();
         }
+        if except != DoubleTapKey::Function {
+            self.function_tracker.reset();
+        }
         if except != DoubleTapKey::Escape {
             self.escape_tracker.reset();
         }
@@ -160,11 +168,12 @@ impl DoubleTapManager {
         option: bool,
         control: bool,
         shift: bool,
+        function: bool,
     ) -> Option<DoubleTapKey> {
         let mut result = None;
 
         // Count how many modifiers are currently pressed
-        let modifier_count = [command, option, control, shift].iter().filter(|&&x| x).count();
+        let modifier_count = [command, option, control, shift, function].iter().filter(|&&x| x).count();
 
         // If multiple modifiers are pressed, reset all trackers
         if modifier_count > 1 {
@@ -172,6 +181,7 @@ impl DoubleTapManager {
             self.option_tracker.reset();
             self.control_tracker.reset();
             self.shift_tracker.reset();
+            self.function_tracker.reset();
         } else {
             // Check Command key transitions
             if command != self.prev_command {
@@ -212,12 +222,23 @@ impl DoubleTapManager {
                     result = Some(DoubleTapKey::Shift);
                 }
             }
+
+            // Check Fn key transitions
+            if function != self.prev_function {
+                if function {
+                    self.reset_other_trackers(DoubleTapKey::Function);
+                    self.function_tracker.on_press();
+                } else if self.function_tracker.on_release() {
+                    result = Some(DoubleTapKey::Function);
+                }
+            }
         }
 
         self.prev_command = command;
         self.prev_option = option;
         self.prev_control = control;
         self.prev_shift = shift;
+        self.prev_function = function;
 
         result
     }
@@ -248,6 +269,7 @@ impl DoubleTapManager {
         self.option_tracker.reset();
         self.control_tracker.reset();
         self.shift_tracker.reset();
+        self.function_tracker.reset();
         self.escape_tracker.reset();
     }
 }
@@ -306,4 +328,29 @@ mod tests {
         sleep(Duration::from_millis(250));
         assert!(!tracker.on_release()); // Should reset due to hold
     }
+
+    #[test]
+    fn test_function_key_double_tap() {
+        let mut manager = DoubleTapManager::new();
+
+        assert_eq!(manager.process_flags_changed(false, false, false, false, true), None);
+        assert_eq!(manager.process_flags_changed(false, false, false, false, false), None);
+        assert_eq!(manager.process_flags_changed(false, false, false, false, true), None);
+        assert_eq!(
+            manager.process_flags_changed(false, false, false, false, false),
+            Some(DoubleTapKey::Function)
+        );
+    }
+
+    #[test]
+    fn test_function_with_other_modifier_is_not_a_tap() {
+        let mut manager = DoubleTapManager::new();
+
+        manager.process_flags_changed(false, false, false, false, true);
+        manager.process_flags_changed(false, false, false, false, false);
+        // Fn+Cmd held together resets the trackers
+        manager.process_flags_changed(true, false, false, false, true);
+        manager.process_flags_changed(false, false, false, false, true);
+        assert_eq!(manager.process_flags_changed(false, false, false, false, false), None);
+    }
 }
@@ -0,0 +1,152 @@
+//! ovim-managed repeat for held motion keys
+//!
+//! With `KeyRepeatMode::Managed` the OS auto-repeat events for a held motion key
+//! are suppressed and the motion is re-run at a steady cadence until key-up.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::KeyRepeatSettings;
+use crate::keyboard::KeyEvent;
+
+/// Lower bound for the repeat interval so a bad setting can't flood the event queue
+const MIN_INTERVAL_MS: u32 = 5;
+
+/// Tracks the held motion key and drives its repeat thread
+#[derive(Default)]
+pub struct KeyRepeater {
+    /// Keycode being repeated and the generation its repeat thread runs under
+    held: Option<(u16, u64)>,
+    /// Bumped whenever repeating stops so the running repeat thread exits
+    generation: Arc<AtomicU64>,
+}
+
+pub type SharedKeyRepeater = Arc<Mutex<KeyRepeater>>;
+
+impl KeyRepeater {
+    /// Check if the event is an OS auto-repeat of the key being repeated (suppress it).
+    /// Releasing the key or pressing any other key stops repeating.
+    pub fn is_os_repeat(&mut self, event: &KeyEvent) -> bool {
+        let Some(code) = self.held_code() else {
+            return false;
+        };
+        if event.code == code && event.is_key_down {
+            return true;
+        }
+        if event.code == code || event.is_key_down {
+            self.stop();
+        }
+        false
+    }
+
+    /// Repeat `tick` after the initial delay until the key is released.
+    /// `tick` returns false to stop early, e.g. when the vim mode changed.
+    pub fn start<F>(&mut self, code: u16, settings: &KeyRepeatSettings, mut tick: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        self.stop();
+        let generation = Arc::clone(&self.generation);
+        let current = generation.load(Ordering::SeqCst);
+        self.held = Some((code, current));
+
+        let delay = Duration::from_millis(settings.initial_delay_ms as u64);
+        let interval = Duration::from_millis(settings.interval_ms.max(MIN_INTERVAL_MS) as u64);
+        thread::spawn(move || {
+            thread::sleep(delay);
+            while generation.load(Ordering::SeqCst) == current {
+                if !tick() {
+                    let _ = generation.compare_exchange(
+                        current,
+                        current + 1,
+                        Ordering::SeqCst,
+                        Ordering::SeqCst,
+                    );
+                    break;
+                }
+                thread::sleep(interval);
+            }
+        });
+    }
+
+    /// Stop repeating
+    pub fn stop(&mut self) {
+        if self.held.take().is_some() {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Keycode still being repeated (None once the repeat thread gave up)
+    fn held_code(&self) -> Option<u16> {
+        self.held
+            .filter(|(_, generation)| *generation == self.generation.load(Ordering::SeqCst))
+            .map(|(code, _)| code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::{KeyCode, Modifiers};
+    use std::sync::atomic::AtomicUsize;
+    use std::thread::sleep;
+
+    fn key(code: KeyCode, is_key_down: bool) -> KeyEvent {
+        KeyEvent {
+            code: code.as_raw(),
+            modifiers: Modifiers::default(),
+            is_key_down,
+        }
+    }
+
+    fn fast() -> KeyRepeatSettings {
+        KeyRepeatSettings {
+            initial_delay_ms: 0,
+            interval_ms: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_repeats_until_key_up() {
+        let mut repeater = KeyRepeater::default();
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&ticks);
+        repeater.start(KeyCode::J.as_raw(), &fast(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+
+        sleep(Duration::from_millis(40));
+        assert!(repeater.is_os_repeat(&key(KeyCode::J, true)));
+        assert!(!repeater.is_os_repeat(&key(KeyCode::J, false)));
+        sleep(Duration::from_millis(10));
+
+        let after_release = ticks.load(Ordering::SeqCst);
+        assert!(after_release > 1);
+        sleep(Duration::from_millis(30));
+        assert_eq!(ticks.load(Ordering::SeqCst), after_release);
+        assert!(!repeater.is_os_repeat(&key(KeyCode::J, true)));
+    }
+
+    #[test]
+    fn test_other_key_stops_repeat() {
+        let mut repeater = KeyRepeater::default();
+        repeater.start(KeyCode::J.as_raw(), &fast(), || true);
+
+        assert!(!repeater.is_os_repeat(&key(KeyCode::K, true)));
+        assert!(!repeater.is_os_repeat(&key(KeyCode::J, true)));
+    }
+
+    #[test]
+    fn test_tick_returning_false_stops_repeat() {
+        let mut repeater = KeyRepeater::default();
+        repeater.start(KeyCode::J.as_raw(), &fast(), || false);
+
+        sleep(Duration::from_millis(20));
+        // The held key is no longer swallowed once the mode changed under it
+        assert!(!repeater.is_os_repeat(&key(KeyCode::J, true)));
+    }
+}
@@ -3,7 +3,7 @@
 //! Handles keyboard events for list mode (hjkl arrow key navigation).
 
 use crate::keyboard::keycode::KeyCode;
-use crate::keyboard::KeyEvent;
+use crate::keyboard::{KeyEvent, KeyInjector};
 use crate::list_mode::{ListResult, SharedListModeState};
 
 /// Handle a key event in list mode
@@ -12,6 +12,8 @@ use crate::list_mode::{ListResult, SharedListModeState};
 pub fn handle_list_mode_key(
     event: KeyEvent,
     list_state: &SharedListModeState,
+    grid: bool,
+    injector: &dyn KeyInjector,
 ) -> Option<KeyEvent> {
     // Only process key down events
     if !event.is_key_down {
@@ -39,11 +41,13 @@ pub fn handle_list_mode_key(
     // Process the key
     let mut list_state_guard = state.lock().unwrap();
     let result = list_state_guard.process_key(
+        injector,
         keycode,
         shift,
         control,
         option,
         command,
+        grid,
     );
     drop(list_state_guard);
 
@@ -1,36 +1,123 @@
 //! Keyboard event handler for vim mode processing
 
+pub mod caps_lock;
 mod click_mode;
 pub mod double_tap;
+mod key_repeat;
 mod list_mode;
+#[cfg(test)]
+mod replay;
 mod scroll_mode;
 mod shortcuts;
 
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use crate::click_mode::accessibility::{cached_frontmost_app_bundle_id, is_focus_settling};
 use crate::click_mode::SharedClickModeManager;
 use crate::commands::RecordedKey;
 use crate::config::click_mode::DoubleTapModifier;
-use crate::config::Settings;
-use crate::keyboard::{KeyCode, KeyEvent};
+use crate::config::scroll_mode::ScrollHoldModifier;
+use crate::config::{CapsLockRemap, KeyRepeatMode, KeyRepeatSettings, Settings};
+use crate::keyboard::{KeyCode, KeyEvent, SharedInjector};
 use crate::list_mode::SharedListModeState;
 use crate::nvim_edit::EditSessionManager;
 use crate::scroll_mode::SharedScrollModeState;
 use crate::vim::{VimMode, VimState};
 
-use click_mode::handle_click_mode_key;
+use caps_lock::apply_caps_lock_control;
+use click_mode::{cancel_activation_on_escape, handle_click_mode_key, ScrollKeys, SearchKeys};
 use double_tap::{DoubleTapKey, DoubleTapManager};
+pub use key_repeat::SharedKeyRepeater;
 use list_mode::handle_list_mode_key;
-use scroll_mode::handle_scroll_mode_key;
+use scroll_mode::{handle_scroll_mode_key, scroll_event, start_scroll_repeat};
+pub use scroll_mode::release_scroll_hold;
+pub use shortcuts::start_click_mode;
 use shortcuts::{
-    check_click_mode_shortcut, check_nvim_edit_shortcut, check_vim_key,
-    is_scroll_mode_enabled_for_app, process_vim_input,
+    check_click_mode_shortcut, check_click_undo_shortcut, check_menu_bar_shortcut,
+    check_nvim_edit_shortcut, check_vim_key, check_window_controls_shortcut, is_frontmost_app_in,
+    is_passthrough_shortcut, process_vim_input,
 };
 
 /// Callback type for when a double-tap triggers a mode activation
 pub type DoubleTapCallback = Box<dyn Fn(DoubleTapKey) + Send + 'static>;
 
-/// Create the keyboard callback that processes key events
+/// Callbacks slower than this are logged: the OS disables an event tap that keeps it waiting
+const SLOW_CALLBACK: Duration = Duration::from_millis(5);
+
+/// Scroll mode settings for an app where scroll mode applies
+struct ScrollRouting<'a> {
+    step: u32,
+    disabled_shortcuts: &'a [String],
+    continuous: bool,
+    hold_modifier: Option<ScrollHoldModifier>,
+}
+
+/// Settings the keyboard callback needs, read from the one settings guard it holds per event
+struct EventSettings<'a> {
+    /// The event matches a passthrough shortcut
+    passthrough: bool,
+    caps_lock_remap: CapsLockRemap,
+    /// A double Escape triggers click mode or the nvim edit popup
+    escape_double_tap: bool,
+    vim_enabled: bool,
+    /// The frontmost app is in `ignored_apps`
+    vim_ignored_for_app: bool,
+    key_repeat: &'a KeyRepeatSettings,
+    /// Grid layout flag, when list navigation applies to the frontmost app
+    list_navigation: Option<bool>,
+    /// Set when scroll mode applies to the frontmost app
+    scroll: Option<ScrollRouting<'a>>,
+    /// Apps whose overlay windows pause list navigation and scroll mode
+    overlay_blocklist: &'a [String],
+    /// Overlay window titles that don't pause them
+    overlay_title_allowlist: &'a HashMap<String, Vec<String>>,
+    /// Keys pass through this long after a focus change
+    focus_grace: Duration,
+}
+
+impl<'a> EventSettings<'a> {
+    fn read(settings: &'a Settings, event: &KeyEvent, frontmost: Option<&str>) -> Self {
+        let scroll_mode = &settings.scroll_mode;
+        // list_navigation_apps if non-empty, otherwise enabled_apps
+        let list_apps = if scroll_mode.list_navigation_apps.is_empty() {
+            &scroll_mode.enabled_apps
+        } else {
+            &scroll_mode.list_navigation_apps
+        };
+        let list_navigation = (scroll_mode.enabled
+            && scroll_mode.list_navigation
+            && is_frontmost_app_in(list_apps, frontmost))
+        .then_some(scroll_mode.list_navigation_grid);
+        let scroll = (scroll_mode.enabled && is_frontmost_app_in(&scroll_mode.enabled_apps, frontmost))
+            .then(|| ScrollRouting {
+                step: scroll_mode.scroll_step,
+                disabled_shortcuts: &scroll_mode.disabled_shortcuts,
+                continuous: scroll_mode.continuous_scroll,
+                hold_modifier: scroll_mode.scroll_hold_modifier,
+            });
+
+        Self {
+            passthrough: is_passthrough_shortcut(event, &settings.passthrough_shortcuts),
+            caps_lock_remap: settings.caps_lock_remap,
+            escape_double_tap: settings.click_mode.double_tap_modifier == DoubleTapModifier::Escape
+                || settings.nvim_edit.double_tap_modifier == DoubleTapModifier::Escape,
+            vim_enabled: settings.enabled,
+            vim_ignored_for_app: is_frontmost_app_in(&settings.ignored_apps, frontmost),
+            key_repeat: &settings.key_repeat,
+            list_navigation,
+            scroll,
+            overlay_blocklist: &scroll_mode.overlay_blocklist,
+            overlay_title_allowlist: &scroll_mode.overlay_title_allowlist,
+            focus_grace: Duration::from_millis(scroll_mode.focus_grace_ms),
+        }
+    }
+}
+
+/// Create the keyboard callback that processes key events.
+/// `key_repeater` is shared with the flags changed handler (see `release_scroll_hold`).
+#[allow(clippy::too_many_arguments)]
 pub fn create_keyboard_callback(
     vim_state: Arc<Mutex<VimState>>,
     settings: Arc<Mutex<Settings>>,
@@ -41,8 +128,43 @@ pub fn create_keyboard_callback(
     double_tap_callback: DoubleTapCallback,
     scroll_state: SharedScrollModeState,
     list_state: SharedListModeState,
+    key_repeater: SharedKeyRepeater,
+    injector: SharedInjector,
 ) -> impl Fn(KeyEvent) -> Option<KeyEvent> + Send + 'static {
-    move |event| {
+
+    let handle_event = move |event: KeyEvent| {
+        let frontmost = cached_frontmost_app_bundle_id();
+        // The only settings lock of the event; nothing below may lock the settings again
+        let settings_guard = settings.lock().unwrap();
+        let event_settings = EventSettings::read(&settings_guard, &event, frontmost.as_deref());
+
+        // Passthrough shortcuts always reach the app untouched, regardless of mode
+        if event_settings.passthrough {
+            return Some(event);
+        }
+
+        // Caps Lock held as Control: re-inject the key with Control added
+        if let Some(result) =
+            apply_caps_lock_control(&event, event_settings.caps_lock_remap, injector.as_ref())
+        {
+            return result;
+        }
+
+        // Managed key repeat: drop the OS auto-repeat of the motion key being repeated
+        if key_repeater.lock().unwrap().is_os_repeat(&event) {
+            return None;
+        }
+
+        // A continuous scroll ends when its key is released or any other key is pressed
+        if let Some(keycode) = event.keycode() {
+            let mut scroll_guard = scroll_state.lock().unwrap();
+            if !event.is_key_down {
+                scroll_guard.release_key(keycode);
+            } else if !scroll_guard.is_continuous_scrolling(keycode) {
+                scroll_guard.stop_continuous_scroll();
+            }
+        }
+
         // Reset modifier double-tap trackers when any non-modifier key is pressed.
         // This prevents false double-tap detection when using shortcuts like CMD+C
         // followed quickly by CMD+V (which would otherwise look like two CMD taps).
@@ -56,23 +178,26 @@ pub fn create_keyboard_callback(
                         dt_manager.option_tracker.reset();
                         dt_manager.control_tracker.reset();
                         dt_manager.shift_tracker.reset();
+                        dt_manager.function_tracker.reset();
                     }
                 }
             }
         }
 
+        // Escape while click mode elements are queried must not wait for the manager lock
+        if cancel_activation_on_escape(&event) {
+            return None;
+        }
+
         // Check for Escape key double-tap (for non-modifier double-tap shortcuts)
         if let Some(keycode) = event.keycode() {
             if keycode == KeyCode::Escape {
                 let mut dt_manager = double_tap_manager.lock().unwrap();
                 if let Some(double_tap_key) = dt_manager.process_key_event(DoubleTapKey::Escape, event.is_key_down) {
                     // Check if Escape double-tap is configured for either mode
-                    let settings_guard = settings.lock().unwrap();
-                    let click_uses_escape = settings_guard.click_mode.double_tap_modifier == DoubleTapModifier::Escape;
-                    let nvim_uses_escape = settings_guard.nvim_edit.double_tap_modifier == DoubleTapModifier::Escape;
-                    drop(settings_guard);
-
-                    if click_uses_escape || nvim_uses_escape {
+                    if event_settings.escape_double_tap {
+                        // The callback reads the settings itself
+                        drop(settings_guard);
                         double_tap_callback(double_tap_key);
                         return None; // Suppress the escape key
                     }
@@ -84,7 +209,15 @@ pub fn create_keyboard_callback(
             let click_manager = click_mode_manager.lock().unwrap();
             if click_manager.is_active() {
                 drop(click_manager);
-                return handle_click_mode_key(event, Arc::clone(&click_mode_manager));
+                let click_settings = &settings_guard.click_mode;
+                return handle_click_mode_key(
+                    event,
+                    Arc::clone(&click_mode_manager),
+                    SearchKeys::from_settings(click_settings),
+                    ScrollKeys::from_settings(click_settings, settings_guard.scroll_mode.scroll_step),
+                    click_settings.copy_modifiers.clone(),
+                    injector.as_ref(),
+                );
             }
         }
 
@@ -101,8 +234,6 @@ pub fn create_keyboard_callback(
 
         // Check shortcuts on key down
         if event.is_key_down {
-            let settings_guard = settings.lock().unwrap();
-
             // Check nvim edit shortcut
             if let Some(result) = check_nvim_edit_shortcut(
                 &event,
@@ -122,162 +253,120 @@ pub fn create_keyboard_callback(
                 return result;
             }
 
+            // Check window controls shortcut
+            if let Some(result) = check_window_controls_shortcut(
+                &event,
+                &settings_guard,
+                Arc::clone(&click_mode_manager),
+            ) {
+                return result;
+            }
+
+            // Check menu bar shortcut
+            if let Some(result) = check_menu_bar_shortcut(
+                &event,
+                &settings_guard,
+                Arc::clone(&click_mode_manager),
+            ) {
+                return result;
+            }
+
+            // Check click undo binding
+            if let Some(result) = check_click_undo_shortcut(&event, &settings_guard) {
+                return result;
+            }
+
             // Check vim key
-            if let Some(result) = check_vim_key(&event, &settings_guard, Arc::clone(&vim_state)) {
+            if let Some(result) =
+                check_vim_key(&event, &settings_guard, Arc::clone(&vim_state), &injector)
+            {
                 return result;
             }
         }
 
-        // Check list mode first - process if:
-        // 1. List navigation is enabled in scroll_mode settings
-        // 2. App is in list_navigation_apps list (or enabled_apps if list_navigation_apps is empty)
-        // 3. No overlay window from blocklisted apps is visible
-        // 4. No text field is currently focused
-        // 5. Vim mode is in Insert mode OR vim is disabled for this app
-        {
-            let settings_guard = settings.lock().unwrap();
-            let scroll_settings = &settings_guard.scroll_mode;
-
-            if scroll_settings.enabled && scroll_settings.list_navigation {
-                // Use list_navigation_apps if non-empty, otherwise check enabled_apps
-                let list_apps = if !scroll_settings.list_navigation_apps.is_empty() {
-                    &scroll_settings.list_navigation_apps
-                } else {
-                    &scroll_settings.enabled_apps
-                };
-                let app_enabled = is_scroll_mode_enabled_for_app(list_apps);
-
-                if app_enabled {
-                    // Skip list mode if an overlay from a blocklisted app is visible
-                    if crate::nvim_edit::accessibility::has_visible_overlay_window(&scroll_settings.overlay_blocklist) {
-                        // Overlay window visible, don't intercept keys
-                    } else if crate::nvim_edit::accessibility::is_text_field_focused() {
-                        // Text field is focused, don't intercept hjkl for navigation
-                    } else {
-                        let vim_mode = vim_state.lock().unwrap().mode();
-                        let vim_disabled_for_app =
-                            settings_guard.ignored_apps.iter().any(|app| {
-                                #[cfg(target_os = "macos")]
-                                {
-                                    if let Some(bundle_id) = get_frontmost_app_bundle_id() {
-                                        return app == &bundle_id;
-                                    }
-                                }
-                                false
-                            });
-
-                        // Only process list mode if vim is in Insert mode or vim is disabled for this app
-                        if vim_mode == VimMode::Insert || vim_disabled_for_app || !settings_guard.enabled
-                        {
-                            drop(settings_guard);
-
-                            // Process list mode key
-                            let result = handle_list_mode_key(event, &list_state);
-
-                            // If list mode handled the key, return the result
-                            if result.is_none() {
-                                return None;
-                            }
-                            // Otherwise continue to scroll/vim processing
-                        }
+        // List navigation and scroll mode run only if:
+        // 1. They are enabled for the frontmost app (see `EventSettings::read`)
+        // 2. Vim mode is in Insert mode (so they don't interfere with vim Normal mode)
+        //    OR vim mode is disabled for this app
+        // 3. The focus didn't just change (the text field check can be stale until it settles)
+        // 4. No focused overlay window from a blocklisted app is in front
+        // 5. No text field is currently focused (hjkl are typed there)
+        // The cheap checks come first: the overlay check queries accessibility, and the
+        // text field state is cached by an AX focus observer (see nvim_edit::text_focus).
+        if event_settings.list_navigation.is_some() || event_settings.scroll.is_some() {
+            let vim_passive = !event_settings.vim_enabled
+                || event_settings.vim_ignored_for_app
+                || vim_state.lock().unwrap().mode() == VimMode::Insert;
+
+            if vim_passive
+                && !is_focus_settling(event_settings.focus_grace)
+                && !crate::nvim_edit::accessibility::has_focused_overlay_window(
+                    event_settings.overlay_blocklist,
+                    event_settings.overlay_title_allowlist,
+                )
+                && !crate::nvim_edit::text_focus::is_text_field_focused()
+            {
+                // List mode first; keys it passes through continue to scroll/vim processing
+                if let Some(grid) = event_settings.list_navigation {
+                    if handle_list_mode_key(event, &list_state, grid, injector.as_ref()).is_none() {
+                        return None;
                     }
                 }
-            }
-        }
 
-        // Check scroll mode - process if:
-        // 1. Scroll mode is enabled
-        // 2. App is in enabled_apps list
-        // 3. No overlay window from blocklisted apps is visible
-        // 4. No text field is currently focused
-        // 5. Vim mode is in Insert mode (so scroll mode doesn't interfere with vim Normal mode)
-        //    OR vim mode is disabled for this app
-        {
-            let settings_guard = settings.lock().unwrap();
-            let scroll_settings = &settings_guard.scroll_mode;
-
-            if scroll_settings.enabled {
-                let app_enabled = is_scroll_mode_enabled_for_app(&scroll_settings.enabled_apps);
-
-                if app_enabled {
-                    // Skip scroll mode if an overlay from a blocklisted app is visible
-                    if crate::nvim_edit::accessibility::has_visible_overlay_window(&scroll_settings.overlay_blocklist) {
-                        // Overlay window visible, don't intercept keys
-                    } else if crate::nvim_edit::accessibility::is_text_field_focused() {
-                        // Text field is focused, don't intercept hjkl for scrolling
-                    } else {
-                        let vim_mode = vim_state.lock().unwrap().mode();
-                        let vim_disabled_for_app =
-                            settings_guard.ignored_apps.iter().any(|app| {
-                                #[cfg(target_os = "macos")]
-                                {
-                                    if let Some(bundle_id) = get_frontmost_app_bundle_id() {
-                                        return app == &bundle_id;
-                                    }
-                                }
-                                false
-                            });
-
-                        // Only process scroll mode if vim is in Insert mode or vim is disabled for this app
-                        if vim_mode == VimMode::Insert || vim_disabled_for_app || !settings_guard.enabled
-                        {
-                            let scroll_step = scroll_settings.scroll_step;
-                            let disabled_shortcuts = scroll_settings.disabled_shortcuts.clone();
-                            drop(settings_guard);
-
-                            // Process scroll mode key
-                            let result = handle_scroll_mode_key(
-                                event,
-                                &scroll_state,
-                                scroll_step,
-                                &disabled_shortcuts,
-                            );
-
-                            // If scroll mode handled the key, return the result
-                            if result.is_none() {
-                                return None;
+                if let Some(scroll) = &event_settings.scroll {
+                    // With a hold modifier, keys pressed without it skip scroll mode
+                    if let Some(routed) = scroll_event(event, scroll.hold_modifier) {
+                        let result = handle_scroll_mode_key(
+                            routed,
+                            &scroll_state,
+                            scroll.step,
+                            scroll.disabled_shortcuts,
+                            scroll.continuous,
+                            &injector,
+                        );
+
+                        // If scroll mode handled the key, return the result
+                        if result.is_none() {
+                            if event_settings.key_repeat.mode == KeyRepeatMode::Managed {
+                                start_scroll_repeat(
+                                    routed,
+                                    &scroll_state,
+                                    scroll.step,
+                                    scroll.disabled_shortcuts,
+                                    &vim_state,
+                                    &key_repeater,
+                                    event_settings.key_repeat,
+                                    &injector,
+                                );
                             }
-                            // Otherwise continue to vim processing
-                            return result;
+                            return None;
                         }
+                        // Unhandled keys skip vim processing while scroll mode applies
+                        return Some(event);
                     }
                 }
             }
         }
 
         // Process normal vim input
-        process_vim_input(event, &settings, &vim_state)
-    }
-}
-
-/// Get the bundle identifier of the frontmost application
-#[cfg(target_os = "macos")]
-fn get_frontmost_app_bundle_id() -> Option<String> {
-    use objc::{class, msg_send, sel, sel_impl};
+        process_vim_input(
+            event,
+            event_settings.vim_enabled,
+            event_settings.key_repeat,
+            &vim_state,
+            &key_repeater,
+            &injector,
+        )
+    };
 
-    unsafe {
-        let workspace: *mut objc::runtime::Object =
-            msg_send![class!(NSWorkspace), sharedWorkspace];
-        if workspace.is_null() {
-            return None;
-        }
-        let app: *mut objc::runtime::Object = msg_send![workspace, frontmostApplication];
-        if app.is_null() {
-            return None;
-        }
-        let bundle_id: *mut objc::runtime::Object = msg_send![app, bundleIdentifier];
-        if bundle_id.is_null() {
-            return None;
-        }
-        let utf8: *const std::os::raw::c_char = msg_send![bundle_id, UTF8String];
-        if utf8.is_null() {
-            return None;
+    move |event| {
+        let started = Instant::now();
+        let result = handle_event(event);
+        let elapsed = started.elapsed();
+        if elapsed > SLOW_CALLBACK {
+            log::warn!("Slow keyboard callback: {:?} for keycode {}", elapsed, event.code);
         }
-        Some(
-            std::ffi::CStr::from_ptr(utf8)
-                .to_string_lossy()
-                .into_owned(),
-        )
+        result
     }
 }
 
@@ -299,6 +388,7 @@ fn try_record_key(
                     control: event.modifiers.control,
                     option: event.modifiers.option,
                     command: event.modifiers.command,
+                    function: event.modifiers.function && !keycode.implies_function_flag(),
                 },
             });
         }
@@ -0,0 +1,156 @@
+//! Replays key sequences through the keyboard callback (tests only)
+//!
+//! A sequence is space-separated key names with optional modifier prefixes,
+//! e.g. `"caps_lock d d"` or `"ctrl+d"`; each key is pressed and released.
+//! Injected keys go to a `RecordingInjector`, which also runs vim actions
+//! inline, so a replay always produces the same output.
+
+use std::sync::{Arc, Mutex};
+
+use super::create_keyboard_callback;
+use super::double_tap::DoubleTapManager;
+use super::key_repeat::SharedKeyRepeater;
+use crate::config::Settings;
+use crate::keyboard::recorder::format_key;
+use crate::keyboard::{KeyCode, KeyEvent, Modifiers, RecordingInjector, SharedInjector};
+use crate::nvim_edit::EditSessionManager;
+use crate::vim::{VimMode, VimState};
+
+/// Key presses that reached the app, formatted like `"shift+cmd+right"`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Replay {
+    /// Keys injected by ovim
+    pub injected: Vec<String>,
+    /// Keys the callback let through unchanged
+    pub passed_through: Vec<String>,
+}
+
+/// Parse a key sequence into key down/up events
+pub fn parse_keys(sequence: &str) -> Vec<KeyEvent> {
+    sequence
+        .split_whitespace()
+        .flat_map(|key| {
+            let mut parts: Vec<&str> = key.split('+').collect();
+            let name = parts.pop().unwrap_or_default();
+            let keycode =
+                KeyCode::from_name(name).unwrap_or_else(|| panic!("Unknown key: {}", name));
+            let mut modifiers = Modifiers::default();
+            for part in parts {
+                match part {
+                    "shift" => modifiers.shift = true,
+                    "ctrl" => modifiers.control = true,
+                    "opt" => modifiers.option = true,
+                    "cmd" => modifiers.command = true,
+                    "fn" => modifiers.function = true,
+                    _ => panic!("Unknown modifier: {}", part),
+                }
+            }
+            [true, false].map(|is_key_down| KeyEvent {
+                code: keycode.as_raw(),
+                modifiers,
+                is_key_down,
+            })
+        })
+        .collect()
+}
+
+fn key_downs(events: &[KeyEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter(|e| e.is_key_down)
+        .map(format_key)
+        .collect()
+}
+
+/// Run `sequence` through a fresh keyboard callback (vim starts in `initial_mode`)
+pub fn replay(settings: Settings, sequence: &str) -> Replay {
+    let (vim_state, _mode_rx) = VimState::new(settings.initial_mode);
+    let injector = Arc::new(RecordingInjector::default());
+    let callback = create_keyboard_callback(
+        Arc::new(Mutex::new(vim_state)),
+        Arc::new(Mutex::new(settings)),
+        Arc::new(Mutex::new(None)),
+        Arc::new(EditSessionManager::new()),
+        crate::click_mode::create_manager(),
+        Arc::new(Mutex::new(DoubleTapManager::new())),
+        Box::new(|_| {}),
+        crate::scroll_mode::create_scroll_state(),
+        crate::list_mode::create_list_state(),
+        SharedKeyRepeater::default(),
+        Arc::clone(&injector) as SharedInjector,
+    );
+
+    let passed_through: Vec<KeyEvent> =
+        parse_keys(sequence).into_iter().filter_map(callback).collect();
+
+    Replay {
+        injected: injector.take(),
+        passed_through: key_downs(&passed_through),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        let events = parse_keys("d shift+cmd+right");
+        assert_eq!(events.len(), 4);
+        assert!(events[0].is_key_down && !events[1].is_key_down);
+        assert_eq!(key_downs(&events), keys(&["d", "shift+cmd+right"]));
+    }
+
+    #[test]
+    fn test_insert_mode_passes_keys_through() {
+        let output = replay(Settings::default(), "h i cmd+c");
+        assert_eq!(output.passed_through, keys(&["h", "i", "cmd+c"]));
+        assert!(output.injected.is_empty());
+    }
+
+    #[test]
+    fn test_dd_cuts_the_line() {
+        let output = replay(Settings::default(), "caps_lock d d");
+        assert_eq!(
+            output.injected,
+            keys(&["cmd+left", "shift+cmd+right", "cmd+x"])
+        );
+        assert!(output.passed_through.is_empty());
+    }
+
+    #[test]
+    fn test_count_then_insert() {
+        let output = replay(Settings::default(), "caps_lock 3 x i a");
+        assert_eq!(
+            output.injected,
+            keys(&["forward_delete", "forward_delete", "forward_delete"])
+        );
+        assert_eq!(output.passed_through, keys(&["a"]));
+    }
+
+    #[test]
+    fn test_initial_normal_mode() {
+        let settings = Settings {
+            initial_mode: VimMode::Normal,
+            ..Settings::default()
+        };
+        let output = replay(settings, "j i k");
+        assert_eq!(output.injected, keys(&["down"]));
+        assert_eq!(output.passed_through, keys(&["k"]));
+    }
+
+    #[test]
+    fn test_disabled_vim_injects_nothing() {
+        let settings = Settings {
+            enabled: false,
+            ..Settings::default()
+        };
+        let output = replay(settings, "caps_lock d d");
+        assert!(output.injected.is_empty());
+        assert_eq!(output.passed_through, keys(&["caps_lock", "d", "d"]));
+    }
+}
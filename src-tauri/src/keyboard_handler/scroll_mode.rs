@@ -2,9 +2,53 @@
 //!
 //! Handles keyboard events for scroll mode (Vimium-style navigation).
 
+use std::sync::{Arc, Mutex};
+
+use crate::config::scroll_mode::ScrollHoldModifier;
+use crate::config::KeyRepeatSettings;
 use crate::keyboard::keycode::KeyCode;
-use crate::keyboard::KeyEvent;
+use crate::keyboard::{KeyEvent, Modifiers, SharedInjector};
 use crate::scroll_mode::{ScrollResult, SharedScrollModeState};
+use crate::vim::VimState;
+
+use super::key_repeat::SharedKeyRepeater;
+
+/// The event scroll mode should handle. With a hold modifier, only keys pressed
+/// while it is held scroll, and the modifier is removed so they match as plain keys.
+pub fn scroll_event(event: KeyEvent, hold_modifier: Option<ScrollHoldModifier>) -> Option<KeyEvent> {
+    let Some(hold_modifier) = hold_modifier else {
+        return Some(event);
+    };
+    let mut modifiers = event.modifiers;
+    let held = std::mem::take(hold_flag(&mut modifiers, hold_modifier));
+    held.then_some(KeyEvent { modifiers, ..event })
+}
+
+/// End a continuous scroll, a managed key repeat and any pending gg when the hold
+/// modifier is released (called on flags changed)
+pub fn release_scroll_hold(
+    mut modifiers: Modifiers,
+    hold_modifier: Option<ScrollHoldModifier>,
+    scroll_state: &SharedScrollModeState,
+    key_repeater: &SharedKeyRepeater,
+) {
+    if hold_modifier.is_some_and(|hold| !*hold_flag(&mut modifiers, hold)) {
+        // The held scroll key keeps auto-repeating without the modifier
+        key_repeater.lock().unwrap().stop();
+        let mut state = scroll_state.lock().unwrap();
+        state.stop_continuous_scroll();
+        state.reset();
+    }
+}
+
+fn hold_flag(modifiers: &mut Modifiers, hold_modifier: ScrollHoldModifier) -> &mut bool {
+    match hold_modifier {
+        ScrollHoldModifier::Command => &mut modifiers.command,
+        ScrollHoldModifier::Option => &mut modifiers.option,
+        ScrollHoldModifier::Control => &mut modifiers.control,
+        ScrollHoldModifier::Function => &mut modifiers.function,
+    }
+}
 
 /// Handle a key event in scroll mode
 ///
@@ -14,6 +58,8 @@ pub fn handle_scroll_mode_key(
     scroll_state: &SharedScrollModeState,
     scroll_step: u32,
     disabled_shortcuts: &[String],
+    continuous_scroll: bool,
+    injector: &SharedInjector,
 ) -> Option<KeyEvent> {
     // Only process key down events
     if !event.is_key_down {
@@ -32,6 +78,19 @@ pub fn handle_scroll_mode_key(
         None => return Some(event),
     };
 
+    // Held j/k scrolls smoothly until released
+    if continuous_scroll && is_continuous_scroll_key(keycode, &event, disabled_shortcuts) {
+        let mut scroll_state_guard = scroll_state.lock().unwrap();
+        if scroll_state_guard.is_continuous_scrolling(keycode) {
+            // OS auto-repeat of the held key
+            return None;
+        }
+        if !scroll_state_guard.has_pending_g() {
+            scroll_state_guard.start_continuous_scroll(Arc::clone(injector), keycode, scroll_step);
+            return None;
+        }
+    }
+
     // Clone state for async execution
     let state = scroll_state.clone();
     let shift = event.modifiers.shift;
@@ -42,6 +101,7 @@ pub fn handle_scroll_mode_key(
     // Process the key
     let mut scroll_state_guard = state.lock().unwrap();
     let result = scroll_state_guard.process_key(
+        injector.as_ref(),
         keycode,
         shift,
         control,
@@ -58,6 +118,67 @@ pub fn handle_scroll_mode_key(
     }
 }
 
+/// Check if the key starts a continuous scroll (plain j/k with hjkl enabled)
+fn is_continuous_scroll_key(keycode: KeyCode, event: &KeyEvent, disabled_shortcuts: &[String]) -> bool {
+    let m = event.modifiers;
+    matches!(keycode, KeyCode::J | KeyCode::K)
+        && !(m.shift || m.control || m.option || m.command)
+        && !disabled_shortcuts.iter().any(|s| s == "hjkl")
+}
+
+/// Keep scrolling while a handled h/j/k/l/d/u key is held and the vim mode stays the same
+pub fn start_scroll_repeat(
+    event: KeyEvent,
+    scroll_state: &SharedScrollModeState,
+    scroll_step: u32,
+    disabled_shortcuts: &[String],
+    vim_state: &Arc<Mutex<VimState>>,
+    key_repeater: &SharedKeyRepeater,
+    key_repeat: &KeyRepeatSettings,
+    injector: &SharedInjector,
+) {
+    let m = event.modifiers;
+    if !event.is_key_down || m.shift || m.control || m.option || m.command {
+        return;
+    }
+    let Some(keycode) = KeyCode::from_raw(event.code) else {
+        return;
+    };
+    if !matches!(
+        keycode,
+        KeyCode::H | KeyCode::J | KeyCode::K | KeyCode::L | KeyCode::D | KeyCode::U
+    ) {
+        return;
+    }
+
+    // Continuous scrolling already covers this key
+    if scroll_state.lock().unwrap().is_continuous_scrolling(keycode) {
+        return;
+    }
+
+    let scroll_state = Arc::clone(scroll_state);
+    let disabled_shortcuts = disabled_shortcuts.to_vec();
+    let vim_state = Arc::clone(vim_state);
+    let injector = Arc::clone(injector);
+    let mode = vim_state.lock().unwrap().mode();
+    key_repeater.lock().unwrap().start(event.code, key_repeat, move || {
+        if vim_state.lock().unwrap().mode() != mode {
+            return false;
+        }
+        let result = scroll_state.lock().unwrap().process_key(
+            injector.as_ref(),
+            keycode,
+            false,
+            false,
+            false,
+            false,
+            scroll_step,
+            &disabled_shortcuts,
+        );
+        result == ScrollResult::Handled
+    });
+}
+
 /// Check if a key is a potential scroll mode key
 /// Used to determine if we should suppress key up events
 fn is_scroll_key(keycode: KeyCode, shift: bool, disabled_shortcuts: &[String]) -> bool {
@@ -87,3 +208,87 @@ fn is_scroll_key(keycode: KeyCode, shift: bool, disabled_shortcuts: &[String]) -
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_event(keycode: KeyCode, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent {
+            code: keycode.as_raw(),
+            modifiers,
+            is_key_down: true,
+        }
+    }
+
+    #[test]
+    fn test_hold_modifier_gates_scroll_keys() {
+        let plain = key_event(KeyCode::J, Modifiers::default());
+        let option = Modifiers {
+            option: true,
+            ..Default::default()
+        };
+        let held = key_event(KeyCode::J, option);
+
+        // Without a hold modifier, events go to scroll mode unchanged
+        assert!(scroll_event(plain, None).is_some_and(|e| !e.modifiers.option));
+        assert!(scroll_event(held, None).is_some_and(|e| e.modifiers.option));
+
+        // With one, only held keys scroll, as plain keys
+        let hold = Some(ScrollHoldModifier::Option);
+        assert!(scroll_event(plain, hold).is_none());
+        let routed = scroll_event(held, hold).unwrap();
+        assert_eq!(routed.code, KeyCode::J.as_raw());
+        assert!(!routed.modifiers.option);
+
+        // Other modifiers are kept, so Option+Shift+G still means G
+        let shifted = Modifiers {
+            shift: true,
+            ..option
+        };
+        let routed = scroll_event(key_event(KeyCode::G, shifted), hold).unwrap();
+        assert!(routed.modifiers.shift && !routed.modifiers.option);
+        assert!(scroll_event(held, Some(ScrollHoldModifier::Command)).is_none());
+    }
+
+    #[test]
+    fn test_releasing_hold_modifier_cancels_pending_gg() {
+        let state = crate::scroll_mode::create_scroll_state();
+        let key_repeater = SharedKeyRepeater::default();
+        let injector = crate::keyboard::RecordingInjector::default();
+        state
+            .lock()
+            .unwrap()
+            .process_key(&injector, KeyCode::G, false, false, false, false, 100, &[]);
+
+        let hold = Some(ScrollHoldModifier::Option);
+        let option = Modifiers {
+            option: true,
+            ..Default::default()
+        };
+        release_scroll_hold(option, hold, &state, &key_repeater);
+        assert!(state.lock().unwrap().has_pending_g());
+        release_scroll_hold(Modifiers::default(), hold, &state, &key_repeater);
+        assert!(!state.lock().unwrap().has_pending_g());
+    }
+
+    #[test]
+    fn test_releasing_hold_modifier_stops_key_repeat() {
+        let state = crate::scroll_mode::create_scroll_state();
+        let key_repeater = SharedKeyRepeater::default();
+        let settings = KeyRepeatSettings {
+            initial_delay_ms: 0,
+            interval_ms: 5,
+            ..Default::default()
+        };
+        key_repeater
+            .lock()
+            .unwrap()
+            .start(KeyCode::J.as_raw(), &settings, || true);
+        let repeat = key_event(KeyCode::J, Modifiers::default());
+        assert!(key_repeater.lock().unwrap().is_os_repeat(&repeat));
+
+        release_scroll_hold(Modifiers::default(), Some(ScrollHoldModifier::Option), &state, &key_repeater);
+        assert!(!key_repeater.lock().unwrap().is_os_repeat(&repeat));
+    }
+}
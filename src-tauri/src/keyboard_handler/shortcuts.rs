@@ -5,87 +5,67 @@ use std::thread;
 
 use tauri::Emitter;
 
+use crate::click_mode::accessibility::cached_frontmost_app_bundle_id;
 use crate::click_mode::native_hints::{self, HintStyle};
-use crate::click_mode::SharedClickModeManager;
-use crate::config::Settings;
+use crate::click_mode::{self, ClickModeManager, ClickableElement, SharedClickModeManager};
+use crate::config::{KeyBinding, KeyRepeatMode, KeyRepeatSettings, Settings};
 use crate::get_app_handle;
-use crate::keyboard::{KeyCode, KeyEvent};
+use crate::keyboard::{KeyCode, KeyEvent, SharedInjector};
 use crate::nvim_edit::{self, EditSessionManager};
 use crate::vim::{ProcessResult, VimAction, VimMode, VimState};
 
-#[cfg(target_os = "macos")]
-use objc::{class, msg_send, sel, sel_impl};
+use super::key_repeat::SharedKeyRepeater;
 
-/// Execute a VimAction on a separate thread with a small delay
-fn execute_action_async(action: VimAction) {
+/// Execute a VimAction on a separate thread with a small delay,
+/// unless the injector takes actions inline (`KeyInjector::runs_actions_inline`)
+fn execute_action_async(action: VimAction, injector: &SharedInjector) {
+    if injector.runs_actions_inline() {
+        if let Err(e) = action.execute(injector.as_ref()) {
+            log::error!("Failed to execute vim action: {}", e);
+        }
+        return;
+    }
+
+    let injector = Arc::clone(injector);
     thread::spawn(move || {
         thread::sleep(std::time::Duration::from_micros(500));
-        if let Err(e) = action.execute() {
+        if let Err(e) = action.execute(injector.as_ref()) {
             log::error!("Failed to execute vim action: {}", e);
         }
     });
 }
 
-/// Get the bundle identifier of the frontmost (currently focused) application
-#[cfg(target_os = "macos")]
-fn get_frontmost_app_bundle_id() -> Option<String> {
-    unsafe {
-        let workspace: *mut objc::runtime::Object =
-            msg_send![class!(NSWorkspace), sharedWorkspace];
-        if workspace.is_null() {
-            return None;
-        }
-        let app: *mut objc::runtime::Object = msg_send![workspace, frontmostApplication];
-        if app.is_null() {
-            return None;
-        }
-        let bundle_id: *mut objc::runtime::Object = msg_send![app, bundleIdentifier];
-        if bundle_id.is_null() {
-            return None;
-        }
-        let utf8: *const std::os::raw::c_char = msg_send![bundle_id, UTF8String];
-        if utf8.is_null() {
-            return None;
-        }
-        Some(
-            std::ffi::CStr::from_ptr(utf8)
-                .to_string_lossy()
-                .into_owned(),
-        )
-    }
-}
-
 /// Check if the frontmost app is in the ignored apps list
 fn is_frontmost_app_ignored(ignored_apps: &[String]) -> bool {
-    if ignored_apps.is_empty() {
-        return false;
-    }
-    #[cfg(target_os = "macos")]
-    {
-        if let Some(bundle_id) = get_frontmost_app_bundle_id() {
-            return ignored_apps.iter().any(|id| id == &bundle_id);
-        }
-    }
-    false
+    is_frontmost_app_in(ignored_apps, cached_frontmost_app_bundle_id().as_deref())
 }
 
-/// Check if scroll mode is enabled for the frontmost app
-pub fn is_scroll_mode_enabled_for_app(enabled_apps: &[String]) -> bool {
-    #[cfg(target_os = "macos")]
-    {
-        if let Some(bundle_id) = get_frontmost_app_bundle_id() {
-            return enabled_apps.iter().any(|id| id == &bundle_id);
-        }
-    }
-    false
+/// Check if `frontmost` (a bundle ID) is in `apps`
+pub fn is_frontmost_app_in(apps: &[String], frontmost: Option<&str>) -> bool {
+    frontmost.is_some_and(|bundle_id| apps.iter().any(|id| id == bundle_id))
 }
 
-/// Check if event modifiers match the configured modifiers
-fn modifiers_match(event: &KeyEvent, mods: &crate::config::VimKeyModifiers) -> bool {
+/// Check if event modifiers match the configured modifiers.
+/// Arrows, F-keys and navigation keys always carry the Fn flag, so for them
+/// Fn is only checked when the binding asks for it.
+pub(super) fn modifiers_match(event: &KeyEvent, mods: &crate::config::VimKeyModifiers) -> bool {
+    let function_matches = event.modifiers.function == mods.function
+        || (!mods.function && event.keycode().is_some_and(KeyCode::implies_function_flag));
+
     event.modifiers.shift == mods.shift
         && event.modifiers.control == mods.control
         && event.modifiers.option == mods.option
         && event.modifiers.command == mods.command
+        && function_matches
+}
+
+/// Check if the event matches one of the passthrough shortcuts.
+/// Uses the same key parsing and exact modifier matching as `check_vim_key`.
+pub fn is_passthrough_shortcut(event: &KeyEvent, bindings: &[KeyBinding]) -> bool {
+    bindings.iter().any(|binding| {
+        KeyCode::from_name(&binding.key).is_some_and(|key| event.keycode() == Some(key))
+            && modifiers_match(event, &binding.modifiers)
+    })
 }
 
 /// Check if this is the configured nvim edit shortcut and handle it
@@ -112,7 +92,7 @@ pub fn check_nvim_edit_shortcut(
 
     let nvim_settings_clone = nvim_settings.clone();
     thread::spawn(move || {
-        if let Err(e) = nvim_edit::trigger_nvim_edit(edit_session_manager, nvim_settings_clone, Some(shared_settings)) {
+        if let Err(e) = nvim_edit::trigger_nvim_edit(edit_session_manager, nvim_settings_clone, shared_settings) {
             log::error!("Failed to trigger nvim edit: {}", e);
         }
     });
@@ -120,6 +100,33 @@ pub fn check_nvim_edit_shortcut(
     Some(None) // Consume the event
 }
 
+/// Check for the click undo binding shortly after a click mode click
+pub fn check_click_undo_shortcut(event: &KeyEvent, settings: &Settings) -> Option<Option<KeyEvent>> {
+    let click_settings = &settings.click_mode;
+    if !click_settings.enabled || !click_settings.click_undo_enabled {
+        return None;
+    }
+
+    let undo_key = KeyCode::from_name(&click_settings.click_undo_key)?;
+    if event.keycode() != Some(undo_key) || !modifiers_match(event, &click_settings.click_undo_modifiers) {
+        return None;
+    }
+
+    // Outside the undo window the key belongs to the app
+    if !click_mode::undo::take_recent_click() {
+        return None;
+    }
+
+    thread::spawn(|| {
+        // Inject after the undo key event has been consumed
+        thread::sleep(std::time::Duration::from_millis(50));
+        if let Err(e) = click_mode::undo::undo_last_click() {
+            log::error!("Failed to undo last click: {}", e);
+        }
+    });
+    Some(None)
+}
+
 /// Check if this is the configured click mode shortcut and handle it
 pub fn check_click_mode_shortcut(
     event: &KeyEvent,
@@ -141,6 +148,88 @@ pub fn check_click_mode_shortcut(
         return None;
     }
 
+    let bundle_id = cached_frontmost_app_bundle_id();
+    if !click_settings.is_enabled_for_app(bundle_id.as_deref()) {
+        log::debug!("Click mode shortcut: disabled for {:?}, passing through", bundle_id);
+        return Some(Some(*event));
+    }
+
+    start_click_mode(click_mode_manager, HintStyle::from_settings(click_settings), ClickModeManager::activate);
+    Some(None) // Consume the event
+}
+
+/// Check if this is the configured window controls shortcut and handle it
+pub fn check_window_controls_shortcut(
+    event: &KeyEvent,
+    settings: &Settings,
+    click_mode_manager: SharedClickModeManager,
+) -> Option<Option<KeyEvent>> {
+    let click_settings = &settings.click_mode;
+
+    if !click_settings.enabled {
+        return None;
+    }
+
+    let key = KeyCode::from_name(&click_settings.window_controls_shortcut_key)?;
+    if event.keycode() != Some(key)
+        || !modifiers_match(event, &click_settings.window_controls_shortcut_modifiers)
+    {
+        return None;
+    }
+
+    let bundle_id = cached_frontmost_app_bundle_id();
+    if !click_settings.is_enabled_for_app(bundle_id.as_deref()) {
+        log::debug!("Window controls shortcut: disabled for {:?}, passing through", bundle_id);
+        return Some(Some(*event));
+    }
+
+    start_click_mode(
+        click_mode_manager,
+        HintStyle::from_settings(click_settings),
+        ClickModeManager::activate_window_controls,
+    );
+    Some(None) // Consume the event
+}
+
+/// Check if this is the configured menu bar shortcut and handle it
+pub fn check_menu_bar_shortcut(
+    event: &KeyEvent,
+    settings: &Settings,
+    click_mode_manager: SharedClickModeManager,
+) -> Option<Option<KeyEvent>> {
+    let click_settings = &settings.click_mode;
+
+    if !click_settings.enabled {
+        return None;
+    }
+
+    let key = KeyCode::from_name(&click_settings.menu_bar_shortcut_key)?;
+    if event.keycode() != Some(key)
+        || !modifiers_match(event, &click_settings.menu_bar_shortcut_modifiers)
+    {
+        return None;
+    }
+
+    let bundle_id = cached_frontmost_app_bundle_id();
+    if !click_settings.is_enabled_for_app(bundle_id.as_deref()) {
+        log::debug!("Menu bar shortcut: disabled for {:?}, passing through", bundle_id);
+        return Some(Some(*event));
+    }
+
+    start_click_mode(
+        click_mode_manager,
+        HintStyle::from_settings(click_settings),
+        ClickModeManager::activate_menu_bar,
+    );
+    Some(None) // Consume the event
+}
+
+/// Enter click mode and query elements with `activate` on a separate thread
+pub fn start_click_mode(
+    click_mode_manager: SharedClickModeManager,
+    style: HintStyle,
+    activate: fn(&mut ClickModeManager) -> Result<Vec<ClickableElement>, String>,
+) {
     // Set click mode to activating state IMMEDIATELY
     {
         let mut mgr = click_mode_manager.lock().unwrap();
@@ -152,10 +241,9 @@ pub fn check_click_mode_shortcut(
     thread::spawn(move || {
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             let mut mgr = manager.lock().unwrap();
-            match mgr.activate() {
+            match activate(&mut mgr) {
                 Ok(elements) => {
                     log::info!("Click mode activated with {} elements", elements.len());
-                    let style = HintStyle::default();
                     native_hints::show_hints(&elements, &style);
                     if let Some(app) = get_app_handle() {
                         let _ = app.emit("click-mode-activated", ());
@@ -175,8 +263,6 @@ pub fn check_click_mode_shortcut(
             }
         }
     });
-
-    Some(None) // Consume the event
 }
 
 /// Check if this is the configured vim key and handle it
@@ -184,6 +270,7 @@ pub fn check_vim_key(
     event: &KeyEvent,
     settings: &Settings,
     vim_state: Arc<Mutex<VimState>>,
+    injector: &SharedInjector,
 ) -> Option<Option<KeyEvent>> {
     if !settings.enabled {
         return None;
@@ -215,7 +302,7 @@ pub fn check_vim_key(
         ProcessResult::ModeChanged(_mode, action) => {
             log::debug!("Vim key: ModeChanged");
             if let Some(action) = action {
-                execute_action_async(action);
+                execute_action_async(action, injector);
             }
             Some(None)
         }
@@ -226,20 +313,21 @@ pub fn check_vim_key(
 /// Process vim input for non-shortcut keys
 pub fn process_vim_input(
     event: KeyEvent,
-    settings: &Arc<Mutex<Settings>>,
+    vim_enabled: bool,
+    key_repeat: &KeyRepeatSettings,
     vim_state: &Arc<Mutex<VimState>>,
+    key_repeater: &SharedKeyRepeater,
+    injector: &SharedInjector,
 ) -> Option<KeyEvent> {
     // Check if vim mode is disabled
-    {
-        let settings_guard = settings.lock().unwrap();
-        if !settings_guard.enabled {
-            return Some(event);
-        }
+    if !vim_enabled {
+        return Some(event);
     }
 
-    let result = {
+    let (result, mode) = {
         let mut state = vim_state.lock().unwrap();
-        state.process_key(event)
+        let result = state.process_key(event);
+        (result, state.mode())
     };
 
     match result {
@@ -249,7 +337,10 @@ pub fn process_vim_input(
         }
         ProcessResult::SuppressWithAction(ref action) => {
             log::debug!("SuppressWithAction: keycode={}, action={:?}", event.code, action);
-            execute_action_async(action.clone());
+            if key_repeat.mode == KeyRepeatMode::Managed && action.is_repeatable_motion() {
+                start_motion_repeat(event, mode, vim_state, key_repeater, key_repeat, injector);
+            }
+            execute_action_async(action.clone(), injector);
             None
         }
         ProcessResult::PassThrough => {
@@ -259,9 +350,229 @@ pub fn process_vim_input(
         ProcessResult::ModeChanged(_mode, action) => {
             log::debug!("ModeChanged: keycode={}", event.code);
             if let Some(action) = action {
-                execute_action_async(action);
+                execute_action_async(action, injector);
             }
             None
         }
     }
 }
+
+/// Keep replaying a held motion key while the vim mode stays the same
+fn start_motion_repeat(
+    event: KeyEvent,
+    mode: VimMode,
+    vim_state: &Arc<Mutex<VimState>>,
+    key_repeater: &SharedKeyRepeater,
+    key_repeat: &KeyRepeatSettings,
+    injector: &SharedInjector,
+) {
+    let vim_state = Arc::clone(vim_state);
+    let injector = Arc::clone(injector);
+    key_repeater.lock().unwrap().start(event.code, key_repeat, move || {
+        let action = {
+            let mut state = vim_state.lock().unwrap();
+            if state.mode() != mode {
+                return false;
+            }
+            match state.process_key(event) {
+                ProcessResult::SuppressWithAction(action) if action.is_repeatable_motion() => action,
+                _ => return false,
+            }
+        };
+        if let Err(e) = action.execute(injector.as_ref()) {
+            log::error!("Failed to repeat vim action: {}", e);
+            return false;
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VimKeyModifiers;
+    use crate::keyboard::Modifiers;
+
+    fn key_event(code: KeyCode, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent {
+            code: code.as_raw(),
+            modifiers,
+            is_key_down: true,
+        }
+    }
+
+    fn cmd_shift_k() -> KeyBinding {
+        KeyBinding {
+            key: "k".to_string(),
+            modifiers: VimKeyModifiers {
+                shift: true,
+                command: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_passthrough_exact_match() {
+        let bindings = vec![cmd_shift_k()];
+        let event = key_event(
+            KeyCode::K,
+            Modifiers {
+                shift: true,
+                command: true,
+                ..Default::default()
+            },
+        );
+        assert!(is_passthrough_shortcut(&event, &bindings));
+    }
+
+    #[test]
+    fn test_passthrough_modifier_subset_does_not_match() {
+        let bindings = vec![cmd_shift_k()];
+
+        // Only a subset of the binding's modifiers is held
+        let subset = key_event(
+            KeyCode::K,
+            Modifiers {
+                command: true,
+                ..Default::default()
+            },
+        );
+        assert!(!is_passthrough_shortcut(&subset, &bindings));
+
+        // Extra modifiers on top of the binding's modifiers
+        let superset = key_event(
+            KeyCode::K,
+            Modifiers {
+                shift: true,
+                command: true,
+                option: true,
+                ..Default::default()
+            },
+        );
+        assert!(!is_passthrough_shortcut(&superset, &bindings));
+    }
+
+    #[test]
+    fn test_passthrough_ignores_caps_lock_and_other_keys() {
+        let bindings = vec![cmd_shift_k()];
+        let with_caps = key_event(
+            KeyCode::K,
+            Modifiers {
+                shift: true,
+                command: true,
+                caps_lock: true,
+                ..Default::default()
+            },
+        );
+        assert!(is_passthrough_shortcut(&with_caps, &bindings));
+
+        let other_key = key_event(
+            KeyCode::J,
+            Modifiers {
+                shift: true,
+                command: true,
+                ..Default::default()
+            },
+        );
+        assert!(!is_passthrough_shortcut(&other_key, &bindings));
+    }
+
+    #[test]
+    fn test_passthrough_unknown_key_name_never_matches() {
+        let bindings = vec![KeyBinding {
+            key: "not_a_key".to_string(),
+            modifiers: VimKeyModifiers::default(),
+        }];
+        let event = key_event(KeyCode::A, Modifiers::default());
+        assert!(!is_passthrough_shortcut(&event, &bindings));
+    }
+
+    #[test]
+    fn test_modifiers_match_function_key() {
+        let fn_mods = VimKeyModifiers {
+            function: true,
+            ..Default::default()
+        };
+        let with_fn = key_event(
+            KeyCode::E,
+            Modifiers {
+                function: true,
+                ..Default::default()
+            },
+        );
+        let without_fn = key_event(KeyCode::E, Modifiers::default());
+
+        assert!(modifiers_match(&with_fn, &fn_mods));
+        assert!(!modifiers_match(&without_fn, &fn_mods));
+        assert!(!modifiers_match(&with_fn, &VimKeyModifiers::default()));
+    }
+
+    #[test]
+    fn test_modifiers_match_hyper_with_function() {
+        let hyper = Modifiers {
+            shift: true,
+            control: true,
+            option: true,
+            command: true,
+            ..Default::default()
+        };
+        let hyper_mods = VimKeyModifiers {
+            shift: true,
+            control: true,
+            option: true,
+            command: true,
+            function: false,
+        };
+        assert!(modifiers_match(&key_event(KeyCode::K, hyper), &hyper_mods));
+
+        let hyper_fn = Modifiers {
+            function: true,
+            ..hyper
+        };
+        assert!(!modifiers_match(&key_event(KeyCode::K, hyper_fn), &hyper_mods));
+        assert!(modifiers_match(
+            &key_event(KeyCode::K, hyper_fn),
+            &VimKeyModifiers {
+                function: true,
+                ..hyper_mods
+            }
+        ));
+    }
+
+    #[test]
+    fn test_modifiers_match_ignores_implied_function_flag() {
+        // macOS sets the Fn flag on arrow and F-key events even when Fn isn't held
+        let arrow = key_event(
+            KeyCode::Left,
+            Modifiers {
+                function: true,
+                ..Default::default()
+            },
+        );
+        assert!(modifiers_match(&arrow, &VimKeyModifiers::default()));
+
+        let f5 = key_event(
+            KeyCode::F5,
+            Modifiers {
+                command: true,
+                function: true,
+                ..Default::default()
+            },
+        );
+        let cmd = VimKeyModifiers {
+            command: true,
+            ..Default::default()
+        };
+        assert!(modifiers_match(&f5, &cmd));
+    }
+
+    #[test]
+    fn test_is_frontmost_app_in() {
+        let apps = vec!["com.apple.finder".to_string()];
+        assert!(is_frontmost_app_in(&apps, Some("com.apple.finder")));
+        assert!(!is_frontmost_app_in(&apps, Some("com.apple.Safari")));
+        assert!(!is_frontmost_app_in(&apps, None));
+        assert!(!is_frontmost_app_in(&[], Some("com.apple.finder")));
+    }
+}
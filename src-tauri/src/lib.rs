@@ -5,39 +5,49 @@ mod click_mode;
 mod commands;
 mod config;
 pub mod ipc;
+mod ipc_bridge;
 mod keyboard;
 mod keyboard_handler;
 pub mod launcher_callback;
 mod list_mode;
 mod nvim_edit;
+mod osascript;
 mod scroll_mode;
+mod socket_path;
 mod updater;
 mod vim;
 mod widgets;
 mod window;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use tauri::{
     image::Image,
     menu::{CheckMenuItem, Menu, MenuItem},
     tray::TrayIcon,
-    AppHandle, Emitter, Listener, Manager, State,
+    AppHandle, Emitter, Listener, Manager, RunEvent, State,
 };
 
 use click_mode::SharedClickModeManager;
 use commands::RecordedKey;
 use config::click_mode::DoubleTapModifier;
-use config::Settings;
+use config::{CapsLockRemap, Settings};
 use ipc::{IpcCommand, IpcResponse};
-use keyboard::{check_accessibility_permission, request_accessibility_permission, KeyboardCapture};
-use keyboard_handler::create_keyboard_callback;
+use keyboard::{
+    check_accessibility_permission, check_input_monitoring_permission,
+    request_accessibility_permission, KeyCode, KeyInjector, KeyboardCapture, Modifiers,
+    SystemInjector, TapStatus,
+};
+use keyboard_handler::{create_keyboard_callback, SharedKeyRepeater};
 use keyboard_handler::double_tap::{DoubleTapKey, DoubleTapManager};
+use nvim_edit::accessibility::FieldDirection;
 use nvim_edit::prewarm::PrewarmManager;
 use nvim_edit::terminals::install_scripts;
 use nvim_edit::EditSessionManager;
 use scroll_mode::SharedScrollModeState;
-use vim::{VimMode, VimState};
+use vim::{AppModes, VimMode, VimState};
 use window::{setup_click_overlay_window, setup_indicator_window};
 
 use std::fs::OpenOptions;
@@ -90,16 +100,16 @@ pub struct AppState {
     pub vim_state: Arc<Mutex<VimState>>,
     pub keyboard_capture: KeyboardCapture,
     pub record_key_tx: Arc<Mutex<Option<tokio::sync::oneshot::Sender<RecordedKey>>>>,
-    #[allow(dead_code)]
-    edit_session_manager: Arc<EditSessionManager>,
+    pub edit_session_manager: Arc<EditSessionManager>,
     pub click_mode_manager: SharedClickModeManager,
     #[allow(dead_code)]
     pub scroll_state: SharedScrollModeState,
 }
 
+/// Mode changes are not emitted here: the mode broadcast emits `mode-change`,
+/// coalescing bursts (e.g. a script toggling modes quickly)
 fn handle_ipc_command(
     state: &mut VimState,
-    app_handle: &AppHandle,
     settings: &Arc<Mutex<Settings>>,
     edit_session_manager: &Arc<EditSessionManager>,
     click_mode_manager: &SharedClickModeManager,
@@ -109,26 +119,23 @@ fn handle_ipc_command(
         IpcCommand::GetMode => IpcResponse::Mode(state.mode().as_str().to_string()),
         IpcCommand::Toggle => {
             let new_mode = state.toggle_mode();
-            let _ = app_handle.emit("mode-change", new_mode.as_str());
             IpcResponse::Mode(new_mode.as_str().to_string())
         }
         IpcCommand::Insert => {
             state.set_mode_external(VimMode::Insert);
-            let _ = app_handle.emit("mode-change", "insert");
             IpcResponse::Ok
         }
         IpcCommand::Normal => {
             state.set_mode_external(VimMode::Normal);
-            let _ = app_handle.emit("mode-change", "normal");
-            IpcResponse::Ok
+            send_normal_mode_keys(settings)
         }
         IpcCommand::Visual => {
             state.set_mode_external(VimMode::Visual);
-            let _ = app_handle.emit("mode-change", "visual");
             IpcResponse::Ok
         }
-        IpcCommand::SetMode(mode_str) => handle_set_mode(state, app_handle, &mode_str),
+        IpcCommand::SetMode(mode_str) => handle_set_mode(state, settings, &mode_str),
         IpcCommand::EditPopup => {
+            // Scoped so the lock is released before the edit thread saves a learned filetype
             let nvim_settings = {
                 let s = settings.lock().unwrap();
                 if !s.nvim_edit.enabled {
@@ -139,20 +146,28 @@ fn handle_ipc_command(
             let manager = Arc::clone(edit_session_manager);
             let shared_settings = Arc::clone(settings);
             std::thread::spawn(move || {
-                if let Err(e) = nvim_edit::trigger_nvim_edit(manager, nvim_settings, Some(shared_settings)) {
+                if let Err(e) = nvim_edit::trigger_nvim_edit(manager, nvim_settings, shared_settings) {
                     log::error!("Failed to trigger nvim edit via IPC: {}", e);
                 }
             });
             IpcResponse::Ok
         }
         IpcCommand::ClickMode => {
-            let is_enabled = {
+            let (is_enabled, enabled_for_app, style) = {
                 let s = settings.lock().unwrap();
-                s.click_mode.enabled
+                let bundle_id = click_mode::accessibility::get_frontmost_app_bundle_id();
+                (
+                    s.click_mode.enabled,
+                    s.click_mode.is_enabled_for_app(bundle_id.as_deref()),
+                    click_mode::native_hints::HintStyle::from_settings(&s.click_mode),
+                )
             };
             if !is_enabled {
                 return IpcResponse::Error("Click Mode is disabled".to_string());
             }
+            if !enabled_for_app {
+                return IpcResponse::Error("Click Mode is disabled for this app".to_string());
+            }
 
             // Set click mode to activating state
             {
@@ -170,7 +185,6 @@ fn handle_ipc_command(
                     match mgr.activate() {
                         Ok(elements) => {
                             log::info!("Click mode activated via IPC with {} elements", elements.len());
-                            let style = click_mode::native_hints::HintStyle::default();
                             click_mode::native_hints::show_hints(&elements, &style);
                             if let Some(app) = get_app_handle() {
                                 let _ = app.emit("click-mode-activated", ());
@@ -192,6 +206,8 @@ fn handle_ipc_command(
             });
             IpcResponse::Ok
         }
+        IpcCommand::FocusNextField => focus_text_field(FieldDirection::Next),
+        IpcCommand::FocusPreviousField => focus_text_field(FieldDirection::Previous),
         IpcCommand::LauncherHandled {
             session_id,
             editor_pid,
@@ -220,27 +236,97 @@ fn handle_ipc_command(
     }
 }
 
-fn handle_set_mode(state: &mut VimState, app_handle: &AppHandle, mode_str: &str) -> IpcResponse {
+fn focus_text_field(direction: FieldDirection) -> IpcResponse {
+    match nvim_edit::accessibility::focus_adjacent_text_field(direction) {
+        Ok(()) => IpcResponse::Ok,
+        Err(e) => {
+            log::warn!("Failed to focus text field: {}", e);
+            IpcResponse::Error(e)
+        }
+    }
+}
+
+fn handle_set_mode(
+    state: &mut VimState,
+    settings: &Arc<Mutex<Settings>>,
+    mode_str: &str,
+) -> IpcResponse {
     match mode_str.to_lowercase().as_str() {
         "insert" | "i" => {
             state.set_mode_external(VimMode::Insert);
-            let _ = app_handle.emit("mode-change", "insert");
             IpcResponse::Ok
         }
         "normal" | "n" => {
             state.set_mode_external(VimMode::Normal);
-            let _ = app_handle.emit("mode-change", "normal");
-            IpcResponse::Ok
+            send_normal_mode_keys(settings)
         }
         "visual" | "v" => {
             state.set_mode_external(VimMode::Visual);
-            let _ = app_handle.emit("mode-change", "visual");
             IpcResponse::Ok
         }
         _ => IpcResponse::Error(format!("Unknown mode: {}", mode_str)),
     }
 }
 
+/// Switch to the mode the frontmost app was left in (`remember_mode_per_app`, which also
+/// remembers the mode of the app that lost focus) or to `default_mode_on_new_app`.
+/// The mode broadcast emits `mode-change`, which updates the indicator and tray.
+fn restore_app_mode(
+    settings: &Arc<Mutex<Settings>>,
+    vim_state: &Arc<Mutex<VimState>>,
+    app_modes: &Mutex<AppModes>,
+) {
+    let (remember, default_mode) = {
+        let settings = settings.lock().unwrap();
+        (settings.remember_mode_per_app, settings.default_mode_on_new_app)
+    };
+    if !remember && default_mode.is_none() {
+        return;
+    }
+    let Some(bundle_id) = click_mode::accessibility::cached_frontmost_app_bundle_id() else {
+        return;
+    };
+
+    let mut state = vim_state.lock().unwrap();
+    let current_mode = state.mode();
+    let mode = if remember {
+        app_modes
+            .lock()
+            .unwrap()
+            .switch_to(&bundle_id, current_mode, default_mode)
+    } else {
+        default_mode.unwrap_or(current_mode)
+    };
+    if mode != current_mode {
+        log::info!("Switching to {} mode for {}", mode, bundle_id);
+        state.set_mode_external(mode);
+    }
+}
+
+/// Send the `ipc_normal_keys` to the focused app after an IPC command switched to normal mode.
+/// ovim's mode is an overlay on top of the app, so this is the only way to also move an
+/// app that has its own insert mode.
+fn send_normal_mode_keys(settings: &Arc<Mutex<Settings>>) -> IpcResponse {
+    let keys = settings.lock().unwrap().ipc_normal_keys.clone();
+    for binding in &keys {
+        let Some(key) = KeyCode::from_name(&binding.key) else {
+            return IpcResponse::Error(format!("Unknown key in ipc_normal_keys: {}", binding.key));
+        };
+        let modifiers = Modifiers {
+            shift: binding.modifiers.shift,
+            control: binding.modifiers.control,
+            option: binding.modifiers.option,
+            command: binding.modifiers.command,
+            function: binding.modifiers.function,
+            caps_lock: false,
+        };
+        if let Err(e) = SystemInjector.inject_key_press(key, modifiers) {
+            return IpcResponse::Error(format!("Switched to normal mode, but sending keys failed: {}", e));
+        }
+    }
+    IpcResponse::Ok
+}
+
 /// Helper to check if a double-tap key matches a setting
 fn matches_double_tap_setting(setting: &DoubleTapModifier, key: &DoubleTapKey) -> bool {
     match (setting, key) {
@@ -248,6 +334,7 @@ fn matches_double_tap_setting(setting: &DoubleTapModifier, key: &DoubleTapKey) -
         (DoubleTapModifier::Option, DoubleTapKey::Option) => true,
         (DoubleTapModifier::Control, DoubleTapKey::Control) => true,
         (DoubleTapModifier::Shift, DoubleTapKey::Shift) => true,
+        (DoubleTapModifier::Function, DoubleTapKey::Function) => true,
         (DoubleTapModifier::Escape, DoubleTapKey::Escape) => true,
         _ => false,
     }
@@ -277,7 +364,13 @@ fn handle_double_tap_activation(
     // Don't allow both to be triggered by the same key
     // Click mode takes priority if both are set to the same key
     if click_mode_trigger && settings_guard.click_mode.enabled {
+        let bundle_id = click_mode::accessibility::get_frontmost_app_bundle_id();
+        if !settings_guard.click_mode.is_enabled_for_app(bundle_id.as_deref()) {
+            log::info!("Double-tap {:?} ignored - click mode disabled for {:?}", double_tap_key, bundle_id);
+            return;
+        }
         log::info!("Double-tap {:?} detected - activating click mode", double_tap_key);
+        let style = click_mode::native_hints::HintStyle::from_settings(&settings_guard.click_mode);
         drop(settings_guard);
 
         // Activate click mode
@@ -298,7 +391,6 @@ fn handle_double_tap_activation(
                 match mgr.activate() {
                     Ok(elements) => {
                         log::info!("[TIMING] activate() done at {}ms with {} elements", dt_start.elapsed().as_millis(), elements.len());
-                        let style = click_mode::native_hints::HintStyle::default();
                         click_mode::native_hints::show_hints(&elements, &style);
                         log::info!("[TIMING] show_hints() returned at {}ms", dt_start.elapsed().as_millis());
                         if let Some(app) = get_app_handle() {
@@ -324,45 +416,154 @@ fn handle_double_tap_activation(
         log::info!("Double-tap {:?} detected - activating nvim edit", double_tap_key);
         let nvim_settings = settings_guard.nvim_edit.clone();
         let shared_settings = Arc::clone(settings);
+        // The edit thread locks settings again to save a learned filetype
         drop(settings_guard);
 
         // Trigger nvim edit
         let manager = Arc::clone(edit_session_manager);
         std::thread::spawn(move || {
-            if let Err(e) = nvim_edit::trigger_nvim_edit(manager, nvim_settings, Some(shared_settings)) {
+            if let Err(e) = nvim_edit::trigger_nvim_edit(manager, nvim_settings, shared_settings) {
                 log::error!("Failed to trigger nvim edit via double-tap: {}", e);
             }
         });
     }
 }
 
-fn update_tray_icon(tray: &TrayIcon, mode: &str, show_mode: bool) {
-    let icon_bytes: &[u8] = if show_mode {
-        match mode {
-            "insert" => include_bytes!("../icons/tray-icon-insert.png"),
-            "normal" => include_bytes!("../icons/tray-icon-normal.png"),
-            "visual" => include_bytes!("../icons/tray-icon-visual.png"),
-            _ => include_bytes!("../icons/tray-icon.png"),
+/// Wait after an app switch before auto-activating click mode, so passing
+/// through an app (e.g. with Cmd+Tab) doesn't activate it
+const CLICK_MODE_AUTO_ACTIVATE_DELAY: Duration = Duration::from_millis(300);
+
+/// Incremented on every app switch; only the latest switch may auto-activate
+static FOCUS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Activate click mode if the new frontmost app is in `click_mode.auto_activate_apps`
+/// and it is still frontmost after the debounce delay
+fn schedule_click_mode_auto_activation(
+    settings: &Arc<Mutex<Settings>>,
+    click_mode_manager: &SharedClickModeManager,
+) {
+    let generation = FOCUS_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let settings = Arc::clone(settings);
+    let manager = Arc::clone(click_mode_manager);
+    std::thread::spawn(move || {
+        std::thread::sleep(CLICK_MODE_AUTO_ACTIVATE_DELAY);
+        if FOCUS_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
         }
-    } else {
-        include_bytes!("../icons/tray-icon.png")
-    };
 
-    match image::load_from_memory(icon_bytes) {
+        let bundle_id = click_mode::accessibility::get_frontmost_app_bundle_id();
+        let style = {
+            let s = settings.lock().unwrap();
+            if !s.click_mode.auto_activates_for_app(bundle_id.as_deref()) {
+                return;
+            }
+            click_mode::native_hints::HintStyle::from_settings(&s.click_mode)
+        };
+        if manager.lock().unwrap().is_active() {
+            return;
+        }
+
+        log::info!("Auto-activating click mode for {:?}", bundle_id);
+        keyboard_handler::start_click_mode(manager, style, click_mode::ClickModeManager::activate);
+    });
+}
+
+/// Warn when keys are pressed but the tap has received no event since it started.
+/// This usually means Input Monitoring is missing and capture is silently dead.
+/// An idle user (no key presses at all) doesn't trigger it.
+fn warn_if_no_events_flowing(
+    events_received: Arc<std::sync::atomic::AtomicU64>,
+    started_at: std::time::Instant,
+) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+    /// Stop watching after this long; a broken tap shows up long before
+    const GIVE_UP_AFTER: Duration = Duration::from_secs(600);
+
+    std::thread::spawn(move || {
+        while started_at.elapsed() < GIVE_UP_AFTER {
+            std::thread::sleep(CHECK_INTERVAL);
+            if events_received.load(std::sync::atomic::Ordering::Relaxed) > 0 {
+                return;
+            }
+            if !keyboard::keys_pressed_since(started_at) {
+                continue;
+            }
+            if check_input_monitoring_permission() {
+                log::warn!("Keys were pressed but keyboard capture received no events");
+            } else {
+                log::warn!(
+                    "Keys were pressed but keyboard capture received no events - Input Monitoring permission is not granted"
+                );
+            }
+            return;
+        }
+    });
+}
+
+/// Window in which further mode changes are coalesced into one `mode-change` event
+const MODE_EVENT_COALESCE: Duration = Duration::from_millis(30);
+
+/// Tray icons, decoded once at startup instead of on every mode change
+struct TrayIcons {
+    plain: Option<Image<'static>>,
+    insert: Option<Image<'static>>,
+    normal: Option<Image<'static>>,
+    visual: Option<Image<'static>>,
+}
+
+impl TrayIcons {
+    fn get() -> &'static Self {
+        static ICONS: OnceLock<TrayIcons> = OnceLock::new();
+        ICONS.get_or_init(|| Self {
+            plain: decode_tray_icon(include_bytes!("../icons/tray-icon.png")),
+            insert: decode_tray_icon(include_bytes!("../icons/tray-icon-insert.png")),
+            normal: decode_tray_icon(include_bytes!("../icons/tray-icon-normal.png")),
+            visual: decode_tray_icon(include_bytes!("../icons/tray-icon-visual.png")),
+        })
+    }
+
+    fn for_mode(&self, mode: &str, show_mode: bool) -> Option<&Image<'static>> {
+        match (show_mode, mode) {
+            (true, "insert") => self.insert.as_ref(),
+            (true, "normal") => self.normal.as_ref(),
+            (true, "visual") => self.visual.as_ref(),
+            _ => self.plain.as_ref(),
+        }
+    }
+}
+
+fn decode_tray_icon(bytes: &[u8]) -> Option<Image<'static>> {
+    match image::load_from_memory(bytes) {
         Ok(img) => {
             let rgba = img.to_rgba8();
             let (width, height) = rgba.dimensions();
-            let icon = Image::new_owned(rgba.into_raw(), width, height);
-            if let Err(e) = tray.set_icon(Some(icon)) {
-                log::error!("Failed to set tray icon: {}", e);
-            }
+            Some(Image::new_owned(rgba.into_raw(), width, height))
         }
         Err(e) => {
             log::error!("Failed to decode tray icon: {}", e);
+            None
         }
     }
 }
 
+fn update_tray_icon(tray: &TrayIcon, mode: &str, show_mode: bool) {
+    // Remember the last icon so repeated events don't reset it (and flicker)
+    static CURRENT: Mutex<Option<(bool, String)>> = Mutex::new(None);
+    let key = (show_mode, if show_mode { mode } else { "" }.to_string());
+    let mut current = CURRENT.lock().unwrap();
+    if current.as_ref() == Some(&key) {
+        return;
+    }
+
+    let Some(icon) = TrayIcons::get().for_mode(mode, show_mode) else {
+        return;
+    };
+    match tray.set_icon(Some(icon.clone())) {
+        Ok(()) => *current = Some(key),
+        Err(e) => log::error!("Failed to set tray icon: {}", e),
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     init_file_logger();
@@ -374,11 +575,11 @@ pub fn run() {
     // Pre-create hint window pool for fast click mode activation
     click_mode::native_hints::init_pool();
 
-    let (vim_state, mode_rx) = VimState::new();
-    let vim_state = Arc::new(Mutex::new(vim_state));
-
     let settings = Arc::new(Mutex::new(Settings::load()));
 
+    let (vim_state, mode_rx) = VimState::new(settings.lock().unwrap().initial_mode);
+    let vim_state = Arc::new(Mutex::new(vim_state));
+
     // Initialize click mode settings from loaded settings
     {
         let s = settings.lock().unwrap();
@@ -388,12 +589,22 @@ pub fn run() {
             s.click_mode.max_depth,
             s.click_mode.max_elements,
         );
+        click_mode::accessibility::set_hint_order(s.click_mode.hint_order);
+        click_mode::accessibility::set_full_page_scan(s.click_mode.full_page_scan);
+        click_mode::accessibility::set_title_attributes(&s.click_mode.title_attributes);
+        click_mode::accessibility::set_app_clickable_roles(s.click_mode.app_clickable_roles.clone());
     }
 
     let record_key_tx: Arc<Mutex<Option<tokio::sync::oneshot::Sender<RecordedKey>>>> =
         Arc::new(Mutex::new(None));
     let mut edit_session_manager = EditSessionManager::new();
 
+    // Reclaim temp files and sockets left behind by crashed edit sessions
+    {
+        let socket_dir = settings.lock().unwrap().nvim_edit.socket_dir.clone();
+        std::thread::spawn(move || nvim_edit::sweep_stale_files(&socket_dir));
+    }
+
     // Initialize prewarm manager if enabled and using alacritty
     {
         let s = settings.lock().unwrap();
@@ -431,6 +642,7 @@ pub fn run() {
         }) as keyboard_handler::DoubleTapCallback
     };
 
+    let key_repeater = SharedKeyRepeater::default();
     let keyboard_capture = KeyboardCapture::new();
     keyboard_capture.set_callback(create_keyboard_callback(
         Arc::clone(&vim_state),
@@ -442,13 +654,22 @@ pub fn run() {
         double_tap_callback,
         Arc::clone(&scroll_state),
         Arc::clone(&list_state),
+        Arc::clone(&key_repeater),
+        Arc::new(SystemInjector),
     ));
 
-    // Set up mouse click callback to hide click mode on any mouse click
+    // Set up mouse click callback to hide click mode on real mouse clicks and note the focus change
     {
         let click_manager_for_mouse = Arc::clone(&click_mode_manager);
-        keyboard_capture.set_mouse_callback(move |_event| {
-            if click_mode::deactivate_and_notify(&click_manager_for_mouse) {
+        let settings_for_mouse = Arc::clone(&settings);
+        keyboard_capture.set_mouse_callback(move |event| {
+            click_mode::accessibility::record_focus_change();
+            let deactivate = settings_for_mouse
+                .lock()
+                .unwrap()
+                .click_mode
+                .deactivates_on_click(event.is_left_click, event.is_right_click);
+            if deactivate && click_mode::deactivate_and_notify(&click_manager_for_mouse) {
                 log::info!("Mouse click detected - deactivating click mode");
             }
             true // Always pass through mouse events
@@ -458,21 +679,47 @@ pub fn run() {
     // Set up scroll callback to hide click mode on scroll
     {
         let click_manager_for_scroll = Arc::clone(&click_mode_manager);
+        let settings_for_scroll = Arc::clone(&settings);
         keyboard_capture.set_scroll_callback(move || {
-            if click_mode::deactivate_and_notify(&click_manager_for_scroll) {
+            let deactivate = settings_for_scroll.lock().unwrap().click_mode.deactivate_on_scroll;
+            if deactivate && click_mode::deactivate_and_notify(&click_manager_for_scroll) {
                 log::info!("Scroll detected - deactivating click mode");
             }
         });
     }
 
-    // Set up flags changed callback for double-tap modifier shortcuts
+    // Caps Lock remapping (Escape on tap, Control on hold) needs the key sent as F18
+    let caps_lock_remapped = settings.lock().unwrap().caps_lock_remap != CapsLockRemap::None;
+    keyboard::hid_remap::set_caps_lock_remapped(caps_lock_remapped);
+
+    // Surface event tap stalls so the indicator can warn that keys aren't captured
+    keyboard_capture.set_tap_status_callback(|status| {
+        if let Some(app) = get_app_handle() {
+            let event = match status {
+                TapStatus::Stalled => "capture-stalled",
+                TapStatus::Recovered => "capture-recovered",
+            };
+            let _ = app.emit(event, ());
+        }
+    });
+
+    // Set up flags changed callback for double-tap modifier shortcuts and the scroll hold modifier
     {
         let settings_for_flags = Arc::clone(&settings);
         let click_manager_for_flags = Arc::clone(&click_mode_manager);
         let edit_session_manager_for_flags = Arc::clone(&edit_session_manager);
         let double_tap_manager_for_flags = Arc::clone(&double_tap_manager);
+        let scroll_state_for_flags = Arc::clone(&scroll_state);
 
         keyboard_capture.set_flags_changed_callback(move |modifiers| {
+            let hold_modifier = settings_for_flags.lock().unwrap().scroll_mode.scroll_hold_modifier;
+            keyboard_handler::release_scroll_hold(
+                modifiers,
+                hold_modifier,
+                &scroll_state_for_flags,
+                &key_repeater,
+            );
+
             let mut dt_manager = double_tap_manager_for_flags.lock().unwrap();
 
             // Process the flags change and check for double-tap
@@ -481,6 +728,7 @@ pub fn run() {
                 modifiers.option,
                 modifiers.control,
                 modifiers.shift,
+                modifiers.function,
             ) {
                 drop(dt_manager);
                 handle_double_tap_activation(
@@ -493,18 +741,39 @@ pub fn run() {
         });
     }
 
-    // Set up focus change observer to hide click mode when app loses focus
-    // and prefetch elements for the new app
+    // Set up focus change observer to hide click mode when app loses focus,
+    // prefetch elements for the new app and auto-activate click mode in listed apps
     {
         let click_manager_for_focus = Arc::clone(&click_mode_manager);
+        let settings_for_focus = Arc::clone(&settings);
+        let scroll_state_for_focus = Arc::clone(&scroll_state);
+        let vim_state_for_focus = Arc::clone(&vim_state);
+        let app_modes = Mutex::new(AppModes::new(
+            click_mode::accessibility::cached_frontmost_app_bundle_id(),
+        ));
+        if let Some(pid) = click_mode::accessibility::cached_frontmost_app_pid() {
+            nvim_edit::text_focus::observe_app(pid);
+        }
         click_mode::start_focus_observer(move || {
+            click_mode::accessibility::refresh_frontmost_app_cache();
+            click_mode::accessibility::record_focus_change();
+            if let Some(pid) = click_mode::accessibility::cached_frontmost_app_pid() {
+                nvim_edit::text_focus::observe_app(pid);
+                if pid != std::process::id() as i32 {
+                    restore_app_mode(&settings_for_focus, &vim_state_for_focus, &app_modes);
+                }
+            }
+            click_mode::accessibility::unpin_elements();
             click_mode::accessibility::invalidate_cache();
+            click_mode::accessibility::record_frontmost_app();
+            scroll_state_for_focus.lock().unwrap().stop_continuous_scroll();
 
             if click_mode::deactivate_and_notify(&click_manager_for_focus) {
                 log::info!("App focus changed - deactivating click mode");
             }
 
             click_mode::accessibility::prefetch_elements();
+            schedule_click_mode_auto_activation(&settings_for_focus, &click_manager_for_focus);
         });
     }
 
@@ -538,13 +807,17 @@ pub fn run() {
             commands::open_input_monitoring_settings,
             commands::get_vim_mode,
             commands::get_settings,
+            commands::get_setting,
+            commands::set_setting,
             commands::set_settings,
             commands::start_capture,
             commands::stop_capture,
             commands::is_capture_running,
             commands::open_settings_window,
             commands::pick_app,
+            commands::add_frontmost_app_to_list,
             commands::get_selection_info,
+            commands::get_selection_content,
             commands::get_battery_info,
             commands::get_caps_lock_state,
             commands::run_shell_widget,
@@ -555,8 +828,14 @@ pub fn run() {
             commands::webview_log,
             commands::validate_nvim_edit_paths,
             commands::open_launcher_script,
-            commands::remove_domain_filetype,
-            commands::get_domain_filetypes,
+            commands::set_filetype_mapping,
+            commands::remove_filetype_mapping,
+            commands::get_filetype_mappings,
+            commands::clear_learned_data,
+            commands::get_supported_editors,
+            commands::list_edit_sessions,
+            commands::kill_edit_session,
+            commands::preview_popup_geometry,
             commands::set_indicator_ignores_mouse,
             commands::is_command_key_pressed,
             commands::is_mouse_over_indicator,
@@ -569,10 +848,13 @@ pub fn run() {
             commands::activate_click_mode,
             commands::deactivate_click_mode,
             commands::get_click_mode_state,
+            commands::toggle_click_mode_pin,
             commands::click_mode_click_element,
             commands::click_mode_right_click_element,
+            commands::click_mode_copy_element,
             commands::click_mode_input_hint,
             commands::get_click_mode_elements,
+            commands::dump_ax_tree,
         ])
         .setup(move |app| {
             #[cfg(target_os = "macos")]
@@ -603,17 +885,19 @@ pub fn run() {
                 tray.on_menu_event(|app, event| match event.id.as_ref() {
                     "show_indicator" => {
                         let state: State<AppState> = app.state();
-                        let mut settings = state.settings.lock().unwrap();
-                        settings.indicator_visible = !settings.indicator_visible;
-                        let _ = settings.save();
-                        let new_settings = settings.clone();
-                        drop(settings);
-                        let _ = app.emit("settings-changed", new_settings);
+                        match Settings::update(&state.settings, |settings| {
+                            settings.indicator_visible = !settings.indicator_visible;
+                            Ok(())
+                        }) {
+                            Ok(new_settings) => {
+                                let _ = app.emit("settings-changed", new_settings);
+                            }
+                            Err(e) => log::error!("Failed to save indicator visibility: {}", e),
+                        }
                     }
                     "settings" => {
-                        if let Some(window) = app.get_webview_window("settings") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
+                        if let Err(e) = window::show_settings_window(app) {
+                            log::error!("Failed to open settings: {}", e);
                         }
                     }
                     "quit" => {
@@ -622,6 +906,9 @@ pub fn run() {
                     _ => {}
                 });
 
+                // Decode the tray icons now so mode changes never decode images
+                TrayIcons::get();
+
                 let initial_settings = Settings::load();
                 if let Err(e) = tray.set_visible(initial_settings.show_in_menu_bar) {
                     log::error!("Failed to set initial tray visibility: {}", e);
@@ -629,6 +916,7 @@ pub fn run() {
 
                 let tray_clone = tray.clone();
                 let show_indicator_item_clone = show_indicator_item.clone();
+                let app_handle_for_settings = app.handle().clone();
                 app.listen("settings-changed", move |event| {
                     if let Ok(new_settings) = serde_json::from_str::<Settings>(event.payload()) {
                         if let Err(e) = tray_clone.set_visible(new_settings.show_in_menu_bar) {
@@ -637,7 +925,9 @@ pub fn run() {
                         // Sync the check menu item with indicator_visible setting
                         let _ = show_indicator_item_clone.set_checked(new_settings.indicator_visible);
                         // Update tray icon when show_mode_in_menu_bar changes
-                        update_tray_icon(&tray_clone, "insert", new_settings.show_mode_in_menu_bar);
+                        let state: State<AppState> = app_handle_for_settings.state();
+                        let mode = state.vim_state.lock().map(|v| v.mode()).unwrap_or_default();
+                        update_tray_icon(&tray_clone, mode.as_str(), new_settings.show_mode_in_menu_bar);
                     }
                 });
 
@@ -689,20 +979,49 @@ pub fn run() {
 
             let app_handle = app.handle().clone();
             let mut rx = mode_rx.lock().unwrap().resubscribe();
+            let scroll_state_for_mode = Arc::clone(&app.state::<AppState>().scroll_state);
 
             tauri::async_runtime::spawn(async move {
                 while let Ok(mode) = rx.recv().await {
                     log::info!("Mode changed to: {:?}", mode);
+                    scroll_state_for_mode.lock().unwrap().stop_continuous_scroll();
                     let _ = app_handle.emit("mode-change", mode.as_str());
+
+                    // Changes right after this one (e.g. from a key sequence) only emit
+                    // the mode they end in, so the indicator and tray don't churn
+                    tokio::time::sleep(MODE_EVENT_COALESCE).await;
+                    let mut latest = None;
+                    loop {
+                        match rx.try_recv() {
+                            Ok(next) => latest = Some(next),
+                            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_)) => continue,
+                            Err(_) => break,
+                        }
+                    }
+                    if let Some(mode) = latest {
+                        log::info!("Mode changed to: {:?} (coalesced)", mode);
+                        scroll_state_for_mode.lock().unwrap().stop_continuous_scroll();
+                        let _ = app_handle.emit("mode-change", mode.as_str());
+                    }
                 }
             });
 
+            // Show the `initial_mode` in the indicator and tray
+            let initial_mode = app.state::<AppState>().vim_state.lock().unwrap().mode();
+            let _ = app.handle().emit("mode-change", initial_mode.as_str());
+
             if check_accessibility_permission() {
                 let state: State<AppState> = app.state();
                 if let Err(e) = state.keyboard_capture.start() {
                     log::error!("Failed to start keyboard capture: {}", e);
                 } else {
                     log::info!("Keyboard capture started automatically");
+                    if let Some(started_at) = state.keyboard_capture.started_at() {
+                        warn_if_no_events_flowing(
+                            state.keyboard_capture.events_received(),
+                            started_at,
+                        );
+                    }
                 }
             } else {
                 log::warn!("Accessibility permission not granted, requesting...");
@@ -715,21 +1034,39 @@ pub fn run() {
             }
 
             let vim_state_for_ipc2 = Arc::clone(&vim_state);
-            let app_handle_for_ipc = app.handle().clone();
+            let (ipc_socket, ipc_token, bridge_port) = {
+                let s = settings_for_ipc.lock().unwrap();
+                (
+                    socket_path::ipc_socket_path(s.ipc_socket_path.as_deref()),
+                    s.ipc_token.clone(),
+                    s.ipc_bridge_enabled.then_some(s.ipc_bridge_port),
+                )
+            };
+            // One command path for the Unix socket and the HTTP bridge
+            let ipc_handler: ipc_bridge::IpcHandler = Arc::new(move |cmd: IpcCommand| -> IpcResponse {
+                let mut state = vim_state_for_ipc2.lock().unwrap();
+                handle_ipc_command(
+                    &mut state,
+                    &settings_for_ipc,
+                    &edit_session_manager_for_ipc,
+                    &click_mode_manager_for_ipc,
+                    cmd,
+                )
+            });
+
+            if let Some(port) = bridge_port {
+                let handler = Arc::clone(&ipc_handler);
+                let token = ipc_token.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = ipc_bridge::start_bridge_server(port, token, handler).await {
+                        log::error!("IPC bridge error: {}", e);
+                    }
+                });
+            }
+
             tauri::async_runtime::spawn(async move {
-                let handler = move |cmd: IpcCommand| -> IpcResponse {
-                    let mut state = vim_state_for_ipc2.lock().unwrap();
-                    handle_ipc_command(
-                        &mut state,
-                        &app_handle_for_ipc,
-                        &settings_for_ipc,
-                        &edit_session_manager_for_ipc,
-                        &click_mode_manager_for_ipc,
-                        cmd,
-                    )
-                };
-
-                if let Err(e) = ipc::start_ipc_server(handler).await {
+                let handler = move |cmd: IpcCommand| ipc_handler(cmd);
+                if let Err(e) = ipc::start_ipc_server(ipc_socket, ipc_token, handler).await {
                     log::error!("IPC server error: {}", e);
                 }
             });
@@ -740,6 +1077,12 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // The HID mapping outlives the process, so give Caps Lock back on quit
+            if let RunEvent::Exit = event {
+                keyboard::hid_remap::set_caps_lock_remapped(false);
+            }
+        });
 }
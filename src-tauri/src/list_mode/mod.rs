@@ -6,7 +6,7 @@
 
 use std::sync::{Arc, Mutex};
 
-use crate::keyboard::{self, KeyCode};
+use crate::keyboard::{KeyCode, KeyInjector};
 
 /// State for list mode processing
 #[derive(Debug, Default)]
@@ -39,13 +39,17 @@ impl ListModeState {
     ///
     /// Returns whether the key was handled or should pass through.
     /// Keys with modifiers (except Shift for selection) are passed through.
+    /// With `grid`, H/L extend the selection sideways instead of navigating history.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_key(
         &mut self,
+        injector: &dyn KeyInjector,
         keycode: KeyCode,
         shift: bool,
         control: bool,
         option: bool,
         command: bool,
+        grid: bool,
     ) -> ListResult {
         // If any modifier besides shift is pressed, pass through
         // (We need shift for selection and G)
@@ -59,7 +63,7 @@ impl ListModeState {
             self.pending_g = false;
             if keycode == KeyCode::G && !shift {
                 // gg - go to top of list (Home key)
-                if let Err(e) = keyboard::list_go_top() {
+                if let Err(e) = injector.list_go_top() {
                     log::error!("Failed to go to top: {}", e);
                 }
                 return ListResult::Handled;
@@ -71,7 +75,7 @@ impl ListModeState {
         match keycode {
             // h - left arrow (or collapse in tree views)
             KeyCode::H if !shift => {
-                if let Err(e) = keyboard::list_left() {
+                if let Err(e) = injector.list_left() {
                     log::error!("Failed to move left: {}", e);
                 }
                 ListResult::Handled
@@ -79,7 +83,7 @@ impl ListModeState {
 
             // j - down arrow (select next item)
             KeyCode::J if !shift => {
-                if let Err(e) = keyboard::list_down() {
+                if let Err(e) = injector.list_down() {
                     log::error!("Failed to move down: {}", e);
                 }
                 ListResult::Handled
@@ -87,7 +91,7 @@ impl ListModeState {
 
             // k - up arrow (select previous item)
             KeyCode::K if !shift => {
-                if let Err(e) = keyboard::list_up() {
+                if let Err(e) = injector.list_up() {
                     log::error!("Failed to move up: {}", e);
                 }
                 ListResult::Handled
@@ -95,7 +99,7 @@ impl ListModeState {
 
             // l - right arrow (or expand in tree views)
             KeyCode::L if !shift => {
-                if let Err(e) = keyboard::list_right() {
+                if let Err(e) = injector.list_right() {
                     log::error!("Failed to move right: {}", e);
                 }
                 ListResult::Handled
@@ -103,7 +107,7 @@ impl ListModeState {
 
             // J (shift+j) - extend selection down
             KeyCode::J if shift => {
-                if let Err(e) = keyboard::list_select_down() {
+                if let Err(e) = injector.list_select_down() {
                     log::error!("Failed to extend selection down: {}", e);
                 }
                 ListResult::Handled
@@ -111,7 +115,7 @@ impl ListModeState {
 
             // K (shift+k) - extend selection up
             KeyCode::K if shift => {
-                if let Err(e) = keyboard::list_select_up() {
+                if let Err(e) = injector.list_select_up() {
                     log::error!("Failed to extend selection up: {}", e);
                 }
                 ListResult::Handled
@@ -119,7 +123,7 @@ impl ListModeState {
 
             // G (shift+g) - go to bottom of list (End key)
             KeyCode::G if shift => {
-                if let Err(e) = keyboard::list_go_bottom() {
+                if let Err(e) = injector.list_go_bottom() {
                     log::error!("Failed to go to bottom: {}", e);
                 }
                 ListResult::Handled
@@ -131,9 +135,25 @@ impl ListModeState {
                 ListResult::Handled
             }
 
+            // H (shift+h) in a grid - extend selection left
+            KeyCode::H if shift && grid => {
+                if let Err(e) = injector.list_select_left() {
+                    log::error!("Failed to extend selection left: {}", e);
+                }
+                ListResult::Handled
+            }
+
+            // L (shift+l) in a grid - extend selection right
+            KeyCode::L if shift && grid => {
+                if let Err(e) = injector.list_select_right() {
+                    log::error!("Failed to extend selection right: {}", e);
+                }
+                ListResult::Handled
+            }
+
             // H (shift+h) - go back (Cmd+[)
             KeyCode::H if shift => {
-                if let Err(e) = keyboard::history_back() {
+                if let Err(e) = injector.history_back() {
                     log::error!("Failed to go back: {}", e);
                 }
                 ListResult::Handled
@@ -141,7 +161,7 @@ impl ListModeState {
 
             // L (shift+l) - go forward (Cmd+])
             KeyCode::L if shift => {
-                if let Err(e) = keyboard::history_forward() {
+                if let Err(e) = injector.history_forward() {
                     log::error!("Failed to go forward: {}", e);
                 }
                 ListResult::Handled
@@ -149,7 +169,7 @@ impl ListModeState {
 
             // o - open item (Return key)
             KeyCode::O if !shift => {
-                if let Err(e) = keyboard::inject_return() {
+                if let Err(e) = injector.inject_return() {
                     log::error!("Failed to open item: {}", e);
                 }
                 ListResult::Handled
@@ -157,7 +177,7 @@ impl ListModeState {
 
             // / - open search (Cmd+F)
             KeyCode::Slash if !shift => {
-                if let Err(e) = keyboard::open_find() {
+                if let Err(e) = injector.open_find() {
                     log::error!("Failed to open search: {}", e);
                 }
                 ListResult::Handled
@@ -176,3 +196,37 @@ pub type SharedListModeState = Arc<Mutex<ListModeState>>;
 pub fn create_list_state() -> SharedListModeState {
     Arc::new(Mutex::new(ListModeState::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::RecordingInjector;
+
+    fn press(state: &mut ListModeState, keys: &[(KeyCode, bool)], grid: bool) -> Vec<String> {
+        let injector = RecordingInjector::default();
+        for &(keycode, shift) in keys {
+            state.process_key(&injector, keycode, shift, false, false, false, grid);
+        }
+        injector.take()
+    }
+
+    #[test]
+    fn test_list_keys_inject_arrows() {
+        let mut state = ListModeState::new();
+        let keys = [
+            (KeyCode::J, false),
+            (KeyCode::K, true),
+            (KeyCode::G, false),
+            (KeyCode::G, false),
+            (KeyCode::O, false),
+        ];
+        assert_eq!(press(&mut state, &keys, false), ["down", "shift+up", "home", "return"]);
+    }
+
+    #[test]
+    fn test_grid_changes_shift_h() {
+        let mut state = ListModeState::new();
+        assert_eq!(press(&mut state, &[(KeyCode::H, true)], false), ["cmd+left_bracket"]);
+        assert_eq!(press(&mut state, &[(KeyCode::H, true)], true), ["shift+left"]);
+    }
+}
@@ -1,12 +1,18 @@
 //! Accessibility APIs for getting text from focused UI elements
 
-use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+use std::collections::HashMap;
+
+use core_foundation::base::{CFEqual, CFGetTypeID, CFRange, CFRelease, CFTypeRef, TCFType};
 use core_foundation::string::CFString;
 
+use super::error::EditError;
+
 #[allow(non_upper_case_globals)]
 const kAXValueCGPointType: i32 = 1;
 #[allow(non_upper_case_globals)]
 const kAXValueCGSizeType: i32 = 2;
+#[allow(non_upper_case_globals)]
+const kAXValueCFRangeType: i32 = 4;
 
 #[link(name = "ApplicationServices", kind = "framework")]
 extern "C" {
@@ -22,11 +28,13 @@ extern "C" {
         attribute: CFTypeRef,
         value: CFTypeRef,
     ) -> i32;
+    fn AXUIElementPerformAction(element: CFTypeRef, action: CFTypeRef) -> i32;
     fn AXValueGetValue(
         value: CFTypeRef,
         the_type: i32,
         value_ptr: *mut std::ffi::c_void,
     ) -> bool;
+    fn AXUIElementGetPid(element: CFTypeRef, pid: *mut i32) -> i32;
     fn CFRetain(cf: CFTypeRef) -> CFTypeRef;
 }
 
@@ -50,6 +58,18 @@ impl AXElementHandle {
     pub fn as_ptr(&self) -> CFTypeRef {
         self.element
     }
+
+    /// Borrow the element as an owned CFHandle (retains it again)
+    fn to_cf_handle(&self) -> Option<CFHandle> {
+        unsafe { CFRetain(self.element) };
+        CFHandle::new(self.element)
+    }
+
+    /// Whether the element is a text input field or editable area
+    pub fn is_text_field(&self) -> bool {
+        self.to_cf_handle()
+            .is_some_and(|element| is_text_element(&element))
+    }
 }
 
 impl Drop for AXElementHandle {
@@ -143,6 +163,31 @@ impl CFHandle {
         }
     }
 
+    /// Extract a CFRange from an AXValue
+    fn extract_range(&self) -> Option<CFRange> {
+        let mut range = CFRange {
+            location: 0,
+            length: 0,
+        };
+        let extracted = unsafe {
+            AXValueGetValue(
+                self.0,
+                kAXValueCFRangeType,
+                &mut range as *mut _ as *mut std::ffi::c_void,
+            )
+        };
+        if extracted {
+            Some(range)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether this value is a CFString
+    fn is_string(&self) -> bool {
+        unsafe { CFGetTypeID(self.0) == CFString::type_id() }
+    }
+
     /// Convert to CFString and get as Rust String.
     /// Note: This consumes the handle to avoid double-free.
     fn into_string(self) -> Option<String> {
@@ -168,10 +213,13 @@ impl Drop for CFHandle {
 #[derive(Debug, Clone)]
 pub struct FocusContext {
     pub app_pid: i32,
-    #[allow(dead_code)]
     pub app_bundle_id: String,
     /// The focused UI element (if captured) for live text updates
     pub focused_element: Option<AXElementHandle>,
+    /// The app's focused window, raised again on restore
+    pub focused_window: Option<AXElementHandle>,
+    /// AXIdentifier of the focused element, used to find it again if the handle goes stale
+    pub element_identifier: Option<String>,
 }
 
 /// Capture the current focus context (which app is focused)
@@ -208,11 +256,20 @@ pub fn capture_focus_context() -> Option<FocusContext> {
 
         // Also try to capture the focused UI element for live text updates
         let focused_element = capture_focused_element();
+        let element_identifier = focused_element
+            .as_ref()
+            .and_then(AXElementHandle::to_cf_handle)
+            .and_then(|e| e.get_attribute("AXIdentifier"))
+            .filter(CFHandle::is_string)
+            .and_then(CFHandle::into_string)
+            .filter(|id| !id.is_empty());
 
         Some(FocusContext {
             app_pid: pid,
             app_bundle_id: bundle_id_str,
             focused_element,
+            focused_window: capture_focused_window(pid),
+            element_identifier,
         })
     }
 }
@@ -265,28 +322,114 @@ fn capture_focused_element() -> Option<AXElementHandle> {
     }
 }
 
-/// Restore focus to a previously captured application and element
-pub fn restore_focus(context: &FocusContext) -> Result<(), String> {
-    log::info!("Attempting to restore focus to PID {}", context.app_pid);
+/// Capture a handle to the focused window of an app
+fn capture_focused_window(pid: i32) -> Option<AXElementHandle> {
+    let app = CFHandle::new(unsafe { AXUIElementCreateApplication(pid) })?;
+    let window = app.get_attribute("AXFocusedWindow")?;
+    unsafe { AXElementHandle::new(window.0) }
+}
+
+/// Whether an element still exists (closed windows report an invalid element)
+fn element_exists(element: &CFHandle) -> bool {
+    element.get_attribute("AXRole").is_some()
+}
+
+/// Outcome of raising a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowRaise {
+    Raised,
+    /// The window exists but refused to come forward
+    NotRaised,
+    /// The window was closed
+    Gone,
+}
+
+/// Raise a window and make it the app's main window
+fn raise_window(window: &AXElementHandle) -> WindowRaise {
+    let Some(handle) = window.to_cf_handle() else {
+        return WindowRaise::Gone;
+    };
+    if !element_exists(&handle) {
+        return WindowRaise::Gone;
+    }
 
+    let raise = CFString::new("AXRaise");
+    let main_attr = CFString::new("AXMain");
+    let cf_true = core_foundation::boolean::CFBoolean::true_value();
+    for attempt in 0..3 {
+        let result = unsafe {
+            AXUIElementSetAttributeValue(handle.0, main_attr.as_CFTypeRef(), cf_true.as_CFTypeRef());
+            AXUIElementPerformAction(handle.0, raise.as_CFTypeRef())
+        };
+        if result == 0 {
+            return WindowRaise::Raised;
+        }
+        log::info!("Retry {} raising window (error {})", attempt + 1, result);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    WindowRaise::NotRaised
+}
+
+/// Limits for searching a window for an element by identifier
+const IDENTIFIER_SEARCH_MAX_DEPTH: usize = 30;
+const IDENTIFIER_SEARCH_MAX_NODES: usize = 2000;
+
+/// Find a descendant of `root` whose AXIdentifier is `identifier`
+fn find_by_identifier(root: CFHandle, identifier: &str) -> Option<CFHandle> {
+    let mut stack = vec![(root, 0)];
+    let mut visited = 0;
+    while let Some((element, depth)) = stack.pop() {
+        visited += 1;
+        if visited > IDENTIFIER_SEARCH_MAX_NODES {
+            return None;
+        }
+        let matches = element
+            .get_attribute("AXIdentifier")
+            .filter(CFHandle::is_string)
+            .and_then(CFHandle::into_string)
+            .is_some_and(|id| id == identifier);
+        if matches {
+            return Some(element);
+        }
+        if depth >= IDENTIFIER_SEARCH_MAX_DEPTH {
+            continue;
+        }
+        let Some(children) = element.get_attribute("AXChildren") else {
+            continue;
+        };
+        let count = unsafe { core_foundation::array::CFArrayGetCount(children.0 as _) };
+        for i in 0..count.max(0) {
+            let child = unsafe { core_foundation::array::CFArrayGetValueAtIndex(children.0 as _, i) };
+            if child.is_null() {
+                continue;
+            }
+            unsafe { CFRetain(child) };
+            stack.push((CFHandle(child), depth + 1));
+        }
+    }
+    None
+}
+
+/// Set AXFocused on an element, returning the AX error code (0 on success)
+fn focus_element(element: CFTypeRef) -> i32 {
+    let focused_attr = CFString::new("AXFocused");
+    let cf_true = core_foundation::boolean::CFBoolean::true_value();
+    unsafe { AXUIElementSetAttributeValue(element, focused_attr.as_CFTypeRef(), cf_true.as_CFTypeRef()) }
+}
+
+/// Activate a running app, bringing it in front of other apps
+fn activate_app(pid: i32) -> Result<(), EditError> {
     unsafe {
         use objc::{class, msg_send, sel, sel_impl};
 
         let running_app_class = class!(NSRunningApplication);
         let app: *mut objc::runtime::Object = msg_send![
             running_app_class,
-            runningApplicationWithProcessIdentifier: context.app_pid
+            runningApplicationWithProcessIdentifier: pid
         ];
 
         if app.is_null() {
-            log::error!(
-                "Could not find running application with PID {}",
-                context.app_pid
-            );
-            return Err(format!(
-                "Could not find running application with PID {}",
-                context.app_pid
-            ));
+            return Err(EditError::AppNotRunning(pid));
         }
 
         // NSApplicationActivateIgnoringOtherApps = 1 << 1 = 2
@@ -294,39 +437,166 @@ pub fn restore_focus(context: &FocusContext) -> Result<(), String> {
         let success: bool = msg_send![app, activateWithOptions: options];
 
         if !success {
-            log::error!("Failed to activate application");
-            return Err("Failed to activate application".to_string());
+            return Err(EditError::ActivationFailed);
         }
+    }
+    Ok(())
+}
+
+/// Restore focus to a previously captured application and element
+pub fn restore_focus(context: &FocusContext) -> Result<(), EditError> {
+    log::info!("Attempting to restore focus to PID {}", context.app_pid);
+
+    if let Err(e) = activate_app(context.app_pid) {
+        log::error!("{}", e);
+        return Err(e);
+    }
 
-        log::info!("Successfully activated application");
+    log::info!("Successfully activated application");
 
-        // Try to restore focus to the specific element if we have it
-        if let Some(ref element) = context.focused_element {
-            // Small delay for app activation to complete
-            std::thread::sleep(std::time::Duration::from_millis(50));
+    // Small delay for app activation to complete
+    std::thread::sleep(std::time::Duration::from_millis(50));
 
-            // Try to set focus on the element using AXFocused attribute
-            let focused_attr = CFString::new("AXFocused");
-            let cf_true = core_foundation::boolean::CFBoolean::true_value();
-            let result = AXUIElementSetAttributeValue(
-                element.as_ptr(),
-                focused_attr.as_CFTypeRef(),
-                cf_true.as_CFTypeRef(),
-            );
+    // Raise the exact window the text came from, unless it was closed meanwhile
+    let window_alive = match context.focused_window {
+        Some(ref window) => match raise_window(window) {
+            WindowRaise::Raised => true,
+            WindowRaise::NotRaised => {
+                log::warn!("Could not raise the original window, falling back to app focus");
+                true
+            }
+            WindowRaise::Gone => {
+                log::warn!("Original window was closed while editing, falling back to app focus");
+                false
+            }
+        },
+        None => false,
+    };
+
+    // Try to restore focus to the specific element if we have it
+    if let Some(ref element) = context.focused_element {
+        let mut result = focus_element(element.as_ptr());
+
+        // The handle can go stale when the app rebuilds its UI; look the element up again
+        if result != 0 && window_alive {
+            let found = context
+                .element_identifier
+                .as_deref()
+                .zip(context.focused_window.as_ref().and_then(AXElementHandle::to_cf_handle))
+                .and_then(|(identifier, window)| find_by_identifier(window, identifier));
+            if let Some(found) = found {
+                result = focus_element(found.0);
+            }
+        }
 
-            if result == 0 {
-                log::info!("Successfully set focus on element");
+        if result == 0 {
+            log::info!("Successfully set focus on element");
+        } else {
+            log::info!("Could not set focus on element (error {}), app is active though", result);
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert an NSString to a Rust String
+unsafe fn ns_string_to_string(ns_string: *mut objc::runtime::Object) -> Option<String> {
+    use objc::{msg_send, sel, sel_impl};
+
+    if ns_string.is_null() {
+        return None;
+    }
+    let utf8: *const std::os::raw::c_char = msg_send![ns_string, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+    Some(std::ffi::CStr::from_ptr(utf8).to_string_lossy().into_owned())
+}
+
+/// PIDs of running apps whose executable or display name is one of `names`
+fn running_app_pids(names: &[&str]) -> Vec<i32> {
+    unsafe {
+        use objc::{class, msg_send, sel, sel_impl};
+
+        let workspace: *mut objc::runtime::Object =
+            msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return Vec::new();
+        }
+        let running_apps: *mut objc::runtime::Object = msg_send![workspace, runningApplications];
+        if running_apps.is_null() {
+            return Vec::new();
+        }
+
+        let count: usize = msg_send![running_apps, count];
+        let mut pids = Vec::new();
+        for i in 0..count {
+            let app: *mut objc::runtime::Object = msg_send![running_apps, objectAtIndex: i];
+            if app.is_null() {
+                continue;
+            }
+            let display_name = ns_string_to_string(msg_send![app, localizedName]);
+            let executable_url: *mut objc::runtime::Object = msg_send![app, executableURL];
+            let executable_name = if executable_url.is_null() {
+                None
             } else {
-                log::info!("Could not set focus on element (error {}), app is active though", result);
+                ns_string_to_string(msg_send![executable_url, lastPathComponent])
+            };
+            let matches = [display_name, executable_name]
+                .iter()
+                .flatten()
+                .any(|name| names.contains(&name.as_str()));
+            if matches {
+                let pid: i32 = msg_send![app, processIdentifier];
+                pids.push(pid);
             }
         }
+        pids
+    }
+}
+
+/// Find a window of `pid` whose title contains `title`
+fn find_window_with_title(pid: i32, title: &str) -> Option<AXElementHandle> {
+    let app = CFHandle::new(unsafe { AXUIElementCreateApplication(pid) })?;
+    let windows = app.get_attribute("AXWindows")?;
+    let count = unsafe { core_foundation::array::CFArrayGetCount(windows.0 as _) };
+    for i in 0..count.max(0) {
+        let window = unsafe { core_foundation::array::CFArrayGetValueAtIndex(windows.0 as _, i) };
+        if window.is_null() {
+            continue;
+        }
+        unsafe { CFRetain(window) };
+        let window = CFHandle(window);
+        let matches = window
+            .get_attribute("AXTitle")
+            .filter(CFHandle::is_string)
+            .and_then(CFHandle::into_string)
+            .is_some_and(|window_title| window_title.contains(title));
+        if matches {
+            return unsafe { AXElementHandle::new(window.0) };
+        }
+    }
+    None
+}
 
-        Ok(())
+/// Activate the app owning the window whose title contains `title` and raise that
+/// window. `app_names` are the app's process or display names.
+/// Returns false when no such window is found or it could not be raised.
+pub fn raise_window_with_title(app_names: &[&str], title: &str) -> bool {
+    for pid in running_app_pids(app_names) {
+        let Some(window) = find_window_with_title(pid, title) else {
+            continue;
+        };
+        if let Err(e) = activate_app(pid) {
+            log::warn!("Could not activate app owning window '{}': {}", title, e);
+        }
+        return raise_window(&window) == WindowRaise::Raised;
     }
+    false
 }
 
 /// Position and size of a UI element
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ElementFrame {
     pub x: f64,
     pub y: f64,
@@ -417,6 +687,72 @@ pub fn get_focused_element_text() -> Option<String> {
     value.into_string()
 }
 
+/// Get only the selected text of the focused UI element (cheap enough to poll)
+pub fn get_focused_selected_text() -> Option<String> {
+    let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })?;
+    let focused_app = system_wide.get_attribute("AXFocusedApplication")?;
+    let focused_element = focused_app.get_attribute("AXFocusedUIElement")?;
+    focused_element
+        .get_attribute("AXSelectedText")
+        .filter(CFHandle::is_string)
+        .and_then(CFHandle::into_string)
+}
+
+/// Text content and selection of the focused UI element.
+/// Each field is None when the element does not expose it.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct TextSelection {
+    /// Full text value (AXValue)
+    pub value: Option<String>,
+    /// Selected substring (AXSelectedText, or sliced from the value)
+    pub selected_text: Option<String>,
+    /// Selection start offset in UTF-16 code units (AXSelectedTextRange)
+    pub selection_start: Option<usize>,
+    /// Selection end offset (exclusive) in UTF-16 code units
+    pub selection_end: Option<usize>,
+}
+
+/// Get the value and selection of the currently focused UI element
+pub fn get_focused_text_selection() -> Option<TextSelection> {
+    let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })?;
+    let focused_app = system_wide.get_attribute("AXFocusedApplication")?;
+    let focused_element = focused_app.get_attribute("AXFocusedUIElement")?;
+
+    let value = focused_element
+        .get_attribute("AXValue")
+        .filter(CFHandle::is_string)
+        .and_then(CFHandle::into_string);
+
+    let range = focused_element
+        .get_attribute("AXSelectedTextRange")
+        .and_then(|r| r.extract_range())
+        .filter(|r| r.location >= 0 && r.length >= 0);
+    let selection_start = range.map(|r| r.location as usize);
+    let selection_end = range.map(|r| (r.location + r.length) as usize);
+
+    let selected_text = focused_element
+        .get_attribute("AXSelectedText")
+        .filter(CFHandle::is_string)
+        .and_then(CFHandle::into_string)
+        .or_else(|| utf16_slice(value.as_deref()?, selection_start?, selection_end?));
+
+    Some(TextSelection {
+        value,
+        selected_text,
+        selection_start,
+        selection_end,
+    })
+}
+
+/// Slice a string by UTF-16 offsets (the unit used by AX text ranges)
+fn utf16_slice(text: &str, start: usize, end: usize) -> Option<String> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    if start > end || end > units.len() {
+        return None;
+    }
+    String::from_utf16(&units[start..end]).ok()
+}
+
 /// Get the AXRole of the currently focused UI element
 pub fn get_focused_element_role() -> Option<String> {
     let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })?;
@@ -435,6 +771,44 @@ pub fn get_focused_element_subrole() -> Option<String> {
     subrole.into_string()
 }
 
+/// Get the AXIdentifier of the currently focused UI element (if any)
+pub fn get_focused_element_identifier() -> Option<String> {
+    let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })?;
+    let focused_app = system_wide.get_attribute("AXFocusedApplication")?;
+    let focused_element = focused_app.get_attribute("AXFocusedUIElement")?;
+    let identifier = focused_element.get_attribute("AXIdentifier")?;
+    identifier.into_string()
+}
+
+/// Check if the currently focused UI element is part of web page content
+/// (has an AXWebArea ancestor) rather than the app's own UI
+pub fn is_focused_element_in_web_area() -> bool {
+    const MAX_ANCESTOR_DEPTH: usize = 50;
+
+    let Some(system_wide) = CFHandle::new(unsafe { AXUIElementCreateSystemWide() }) else {
+        return false;
+    };
+    let Some(focused_app) = system_wide.get_attribute("AXFocusedApplication") else {
+        return false;
+    };
+    let Some(mut current) = focused_app.get_attribute("AXFocusedUIElement") else {
+        return false;
+    };
+
+    for _ in 0..MAX_ANCESTOR_DEPTH {
+        let Some(parent) = current.get_attribute("AXParent") else {
+            return false;
+        };
+        let role = parent.get_attribute("AXRole").and_then(CFHandle::into_string);
+        if role.as_deref() == Some("AXWebArea") {
+            return true;
+        }
+        current = parent;
+    }
+
+    false
+}
+
 /// Check if the currently focused element is a text input field or editable area
 /// Returns true if a text field is focused, false otherwise
 pub fn is_text_field_focused() -> bool {
@@ -450,6 +824,10 @@ pub fn is_text_field_focused() -> bool {
         Some(el) => el,
         None => return false,
     };
+    is_text_element(&focused_element)
+}
+
+fn is_text_element(focused_element: &CFHandle) -> bool {
     let role = match focused_element.get_attribute("AXRole") {
         Some(r) => r,
         None => return false,
@@ -491,31 +869,27 @@ pub fn is_text_field_focused() -> bool {
 
     // For web content and other elements, check if the element is editable
     // This handles contenteditable elements in browsers (email composers, etc.)
-    // Note: We need to get focused_element again since it was consumed by into_string
-    if let Some(focused_app) = system_wide.get_attribute("AXFocusedApplication") {
-        if let Some(focused_element) = focused_app.get_attribute("AXFocusedUIElement") {
-            // Check AXEditable attribute
-            if let Some(editable_attr) = focused_element.get_attribute("AXEditable") {
-                if is_cf_boolean_true(&editable_attr) {
-                    return true;
-                }
-            }
-
-            // Check AXSelectedTextRange, but only for roles that could plausibly
-            // be text inputs (e.g. AXGroup used by Chromium for address bar).
-            // Exclude AXWebArea which is the main page content area.
-            if role_str != "AXWebArea" {
-                if focused_element.get_attribute("AXSelectedTextRange").is_some() {
-                    log::debug!(
-                        "is_text_field_focused: role={} has AXSelectedTextRange, treating as text field",
-                        role_str
-                    );
-                    return true;
-                }
-            }
+    if let Some(editable_attr) = focused_element.get_attribute("AXEditable") {
+        if is_cf_boolean_true(&editable_attr) {
+            return true;
         }
     }
 
+    // Check AXSelectedTextRange, but only for roles that could plausibly
+    // be text inputs (e.g. AXGroup used by Chromium for address bar).
+    // Exclude AXWebArea which is the main page content area.
+    if role_str != "AXWebArea"
+        && focused_element
+            .get_attribute("AXSelectedTextRange")
+            .is_some()
+    {
+        log::debug!(
+            "is_text_field_focused: role={} has AXSelectedTextRange, treating as text field",
+            role_str
+        );
+        return true;
+    }
+
     log::debug!("is_text_field_focused: role={} (not a text field)", role_str);
     false
 }
@@ -530,11 +904,91 @@ fn is_cf_boolean_true(handle: &CFHandle) -> bool {
     }
 }
 
-/// Check if any app in the blocklist has a window appearing in front of the
-/// frontmost app's window. CGWindowListCopyWindowInfo returns windows in
-/// front-to-back order, so if we see a blocklisted app's window before the
-/// frontmost app's window, it's an overlay on top.
-pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
+/// A window from `CGWindowListCopyWindowInfo`: owner PID and window layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WindowInfo {
+    owner_pid: i32,
+    layer: i32,
+}
+
+/// The window with keyboard focus: its app's PID and its title
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct FocusedWindow {
+    pid: i32,
+    title: Option<String>,
+}
+
+/// PID of the first overlay window in front of the frontmost app's windows.
+/// `windows` is in front-to-back order; windows with a negative layer are skipped.
+fn front_overlay_pid(
+    windows: &[WindowInfo],
+    frontmost_pid: i32,
+    overlay_pids: &[i32],
+) -> Option<i32> {
+    windows
+        .iter()
+        .filter(|window| window.layer >= 0)
+        .map(|window| window.owner_pid)
+        .take_while(|&pid| pid != frontmost_pid)
+        .find(|pid| overlay_pids.contains(pid))
+}
+
+/// Whether an overlay window from `bundle_id` (owned by `overlay_pid`) should pause
+/// scroll and list keys: only when it has keyboard focus and its title isn't allowlisted
+fn overlay_blocks_keys(
+    overlay_pid: i32,
+    bundle_id: &str,
+    focused: Option<&FocusedWindow>,
+    title_allowlist: &HashMap<String, Vec<String>>,
+) -> bool {
+    let Some(focused) = focused.filter(|focused| focused.pid == overlay_pid) else {
+        return false;
+    };
+    let allowed_titles = title_allowlist
+        .get(bundle_id)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    !focused
+        .title
+        .as_deref()
+        .is_some_and(|title| is_title_allowed(title, allowed_titles))
+}
+
+/// Case-insensitive substring match against the allowlisted titles (empty entries never match)
+fn is_title_allowed(title: &str, allowed_titles: &[String]) -> bool {
+    let title = title.to_lowercase();
+    allowed_titles
+        .iter()
+        .filter(|allowed| !allowed.is_empty())
+        .any(|allowed| title.contains(&allowed.to_lowercase()))
+}
+
+/// Get the window that has keyboard focus. Overlay panels (Raycast, Alfred) take
+/// keyboard focus without becoming the frontmost app, so this can differ from it.
+fn focused_window() -> Option<FocusedWindow> {
+    let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })?;
+    let focused_app = system_wide.get_attribute("AXFocusedApplication")?;
+    let mut pid: i32 = 0;
+    if unsafe { AXUIElementGetPid(focused_app.0, &mut pid) } != 0 {
+        return None;
+    }
+    let title = focused_app
+        .get_attribute("AXFocusedWindow")
+        .and_then(|window| window.get_attribute("AXTitle"))
+        .filter(CFHandle::is_string)
+        .and_then(CFHandle::into_string);
+    Some(FocusedWindow { pid, title })
+}
+
+/// Check if a blocklisted app has a focused overlay window in front of the frontmost
+/// app's window. CGWindowListCopyWindowInfo returns windows in front-to-back order,
+/// so a blocklisted app's window seen before the frontmost app's window is on top.
+/// It only pauses scroll mode while it has keyboard focus and its title isn't in
+/// `title_allowlist` (bundle ID -> titles), so scrollable popovers keep working.
+pub fn has_focused_overlay_window(
+    blocklist: &[String],
+    title_allowlist: &HashMap<String, Vec<String>>,
+) -> bool {
     if blocklist.is_empty() {
         return false;
     }
@@ -543,23 +997,13 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
         kCGNullWindowID, kCGWindowListOptionOnScreenOnly, CGWindowListCopyWindowInfo,
     };
 
-    // Get the frontmost app's PID
-    let frontmost_pid: i32 = unsafe {
-        use objc::{class, msg_send, sel, sel_impl};
-        let workspace: *mut objc::runtime::Object =
-            msg_send![class!(NSWorkspace), sharedWorkspace];
-        if workspace.is_null() {
-            return false;
-        }
-        let frontmost_app: *mut objc::runtime::Object = msg_send![workspace, frontmostApplication];
-        if frontmost_app.is_null() {
-            return false;
-        }
-        msg_send![frontmost_app, processIdentifier]
+    // Get the frontmost app's PID (cached, this runs on every key press)
+    let Some(frontmost_pid) = crate::click_mode::accessibility::cached_frontmost_app_pid() else {
+        return false;
     };
 
-    // Build a set of PIDs for blocklisted apps
-    let blocklist_pids: Vec<i32> = unsafe {
+    // PIDs and bundle IDs of running blocklisted apps
+    let blocklisted_apps: Vec<(i32, String)> = unsafe {
         use objc::{class, msg_send, sel, sel_impl};
         let workspace: *mut objc::runtime::Object =
             msg_send![class!(NSWorkspace), sharedWorkspace];
@@ -571,7 +1015,7 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
             return false;
         }
         let count: usize = msg_send![running_apps, count];
-        let mut pids = Vec::new();
+        let mut apps = Vec::new();
         for i in 0..count {
             let app: *mut objc::runtime::Object = msg_send![running_apps, objectAtIndex: i];
             if app.is_null() {
@@ -592,19 +1036,19 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
                 let pid: i32 = msg_send![app, processIdentifier];
                 // Don't check the frontmost app against itself
                 if pid != frontmost_pid {
-                    pids.push(pid);
+                    apps.push((pid, bundle_str));
                 }
             }
         }
-        pids
+        apps
     };
 
-    if blocklist_pids.is_empty() {
+    if blocklisted_apps.is_empty() {
         return false;
     }
+    let overlay_pids: Vec<i32> = blocklisted_apps.iter().map(|(pid, _)| *pid).collect();
 
-    // Walk the window list (front-to-back order). If we see a blocklisted
-    // app's window before the frontmost app's window, it's an overlay.
+    let mut windows = Vec::new();
     unsafe {
         let window_list =
             CGWindowListCopyWindowInfo(kCGWindowListOptionOnScreenOnly, kCGNullWindowID);
@@ -613,6 +1057,8 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
         }
 
         let count = core_foundation::array::CFArrayGetCount(window_list as _);
+        let pid_key = core_foundation::string::CFString::new("kCGWindowOwnerPID");
+        let layer_key = core_foundation::string::CFString::new("kCGWindowLayer");
 
         for i in 0..count {
             let window_info = core_foundation::array::CFArrayGetValueAtIndex(window_list as _, i)
@@ -621,9 +1067,7 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
                 continue;
             }
 
-            let pid_key = core_foundation::string::CFString::new("kCGWindowOwnerPID");
             let mut pid_value: *const std::ffi::c_void = std::ptr::null();
-
             if core_foundation::dictionary::CFDictionaryGetValueIfPresent(
                 window_info,
                 pid_key.as_CFTypeRef() as _,
@@ -642,8 +1086,7 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
                 continue;
             }
 
-            // Check window layer — skip non-normal windows (menubar, etc.)
-            let layer_key = core_foundation::string::CFString::new("kCGWindowLayer");
+            // Non-normal windows (menubar, etc.) keep layer -1 and are skipped
             let mut layer_value: *const std::ffi::c_void = std::ptr::null();
             let mut layer: i32 = -1;
             if core_foundation::dictionary::CFDictionaryGetValueIfPresent(
@@ -658,31 +1101,26 @@ pub fn has_visible_overlay_window(blocklist: &[String]) -> bool {
                     &mut layer as *mut i32 as *mut std::ffi::c_void,
                 );
             }
-            if layer < 0 {
-                continue;
-            }
-
-            // If we hit the frontmost app's window first, no overlay is on top
-            if owner_pid == frontmost_pid {
-                core_foundation::base::CFRelease(window_list as _);
-                return false;
-            }
 
-            // If we hit a blocklisted app's window before the frontmost app, it's an overlay
-            if blocklist_pids.contains(&owner_pid) {
-                core_foundation::base::CFRelease(window_list as _);
-                return true;
-            }
+            windows.push(WindowInfo { owner_pid, layer });
         }
 
         core_foundation::base::CFRelease(window_list as _);
     }
 
-    false
+    let Some(overlay_pid) = front_overlay_pid(&windows, frontmost_pid, &overlay_pids) else {
+        return false;
+    };
+    let Some((_, bundle_id)) = blocklisted_apps.iter().find(|(pid, _)| *pid == overlay_pid) else {
+        return false;
+    };
+    // Only now query accessibility: an overlay in front is the rare case
+    let focused = focused_window();
+    overlay_blocks_keys(overlay_pid, bundle_id, focused.as_ref(), title_allowlist)
 }
 
-/// Get the bounds of the screen containing a given point
-/// Returns the screen frame (x, y, width, height) in screen coordinates
+/// Get the visible bounds of the screen containing a given point, excluding the
+/// menu bar and Dock. Returns (x, y, width, height) in screen coordinates
 pub fn get_screen_bounds_for_point(x: f64, y: f64) -> Option<ElementFrame> {
     unsafe {
         use objc::{class, msg_send, sel, sel_impl};
@@ -698,6 +1136,23 @@ pub fn get_screen_bounds_for_point(x: f64, y: f64) -> Option<ElementFrame> {
             return None;
         }
 
+        // In Cocoa, y=0 is at the bottom, but we work with y=0 at top
+        // The main screen's height gives us the reference
+        let main_screen: *mut objc::runtime::Object = msg_send![class!(NSScreen), mainScreen];
+        if main_screen.is_null() {
+            return None;
+        }
+        let main_frame: core_graphics::geometry::CGRect = msg_send![main_screen, frame];
+        let main_height = main_frame.size.height;
+
+        // Convert a Cocoa rect to screen coordinates: screen_y = main_height - cocoa_y - height
+        let to_screen_frame = |rect: core_graphics::geometry::CGRect| ElementFrame {
+            x: rect.origin.x,
+            y: main_height - rect.origin.y - rect.size.height,
+            width: rect.size.width,
+            height: rect.size.height,
+        };
+
         // Find the screen containing the point
         for i in 0..count {
             let screen: *mut objc::runtime::Object = msg_send![screens, objectAtIndex: i];
@@ -705,49 +1160,14 @@ pub fn get_screen_bounds_for_point(x: f64, y: f64) -> Option<ElementFrame> {
                 continue;
             }
 
-            // Get the screen's frame (in Cocoa coordinates - origin at bottom-left)
-            let frame: core_graphics::geometry::CGRect = msg_send![screen, frame];
-
-            // Convert Cocoa coordinates to screen coordinates (origin at top-left)
-            // In Cocoa, y=0 is at the bottom, but we work with y=0 at top
-            // The main screen's height gives us the reference
-            let main_screen: *mut objc::runtime::Object = msg_send![class!(NSScreen), mainScreen];
-            let main_frame: core_graphics::geometry::CGRect = msg_send![main_screen, frame];
-            let main_height = main_frame.size.height;
-
-            // Convert y coordinate: screen_y = main_height - cocoa_y - screen_height
-            let screen_y = main_height - frame.origin.y - frame.size.height;
-
-            // Check if point is within this screen (using screen coordinates)
-            let screen_left = frame.origin.x;
-            let screen_right = frame.origin.x + frame.size.width;
-            let screen_top = screen_y;
-            let screen_bottom = screen_y + frame.size.height;
-
-            if x >= screen_left && x < screen_right && y >= screen_top && y < screen_bottom {
-                return Some(ElementFrame {
-                    x: screen_left,
-                    y: screen_top,
-                    width: frame.size.width,
-                    height: frame.size.height,
-                });
+            let frame = to_screen_frame(msg_send![screen, frame]);
+            if x >= frame.x && x < frame.x + frame.width && y >= frame.y && y < frame.y + frame.height {
+                return Some(to_screen_frame(msg_send![screen, visibleFrame]));
             }
         }
 
         // Fallback to main screen if point not found on any screen
-        let main_screen: *mut objc::runtime::Object = msg_send![class!(NSScreen), mainScreen];
-        if main_screen.is_null() {
-            return None;
-        }
-
-        let frame: core_graphics::geometry::CGRect = msg_send![main_screen, frame];
-
-        Some(ElementFrame {
-            x: frame.origin.x,
-            y: 0.0, // Main screen top is always 0 in screen coordinates
-            width: frame.size.width,
-            height: frame.size.height,
-        })
+        Some(to_screen_frame(msg_send![main_screen, visibleFrame]))
     }
 }
 
@@ -760,7 +1180,7 @@ pub fn get_screen_bounds_for_point(x: f64, y: f64) -> Option<ElementFrame> {
 /// - Web browsers (use JavaScript injection instead)
 /// - Some Electron apps
 /// - Apps that don't properly implement accessibility
-pub fn set_element_text(element: &AXElementHandle, text: &str) -> Result<(), String> {
+pub fn set_element_text(element: &AXElementHandle, text: &str) -> Result<(), EditError> {
     unsafe {
         let value_attr = CFString::new("AXValue");
         let cf_text = CFString::new(text);
@@ -788,7 +1208,223 @@ pub fn set_element_text(element: &AXElementHandle, text: &str) -> Result<(), Str
             // -25210: kAXErrorIllegalArgument
             // -25211: kAXErrorCannotComplete
             // -25212: kAXErrorFailure
-            Err(format!("AXUIElementSetAttributeValue failed with error code: {}", result))
+            Err(EditError::SetTextFailed(result))
+        }
+    }
+}
+
+/// Attributes holding the text an element displays, in order of preference
+const ELEMENT_TEXT_ATTRIBUTES: &[&str] = &["AXValue", "AXTitle", "AXDescription"];
+
+/// Get the text an element displays (its string value, title or description)
+pub fn get_element_text(element: &AXElementHandle) -> Option<String> {
+    let element = element.to_cf_handle()?;
+    ELEMENT_TEXT_ATTRIBUTES.iter().find_map(|attr| {
+        element
+            .get_attribute(attr)
+            .filter(CFHandle::is_string)
+            .and_then(CFHandle::into_string)
+            .filter(|text| !text.trim().is_empty())
+    })
+}
+
+/// Perform AXPress on an element, clicking it without moving the mouse
+pub fn press_element(element: &AXElementHandle) -> Result<(), String> {
+    let action = CFString::new("AXPress");
+    let result = unsafe { AXUIElementPerformAction(element.as_ptr(), action.as_CFTypeRef()) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!("AXUIElementPerformAction failed with error code: {}", result))
+    }
+}
+
+/// Roles that take typed text, visited by text field navigation
+const TEXT_INPUT_ROLES: &[&str] = &["AXTextField", "AXTextArea", "AXComboBox"];
+
+/// Direction for moving focus between text fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldDirection {
+    Next,
+    Previous,
+}
+
+/// Index of the field to focus, wrapping around. Without a focused field,
+/// Next starts at the first field and Previous at the last.
+fn adjacent_index(current: Option<usize>, len: usize, direction: FieldDirection) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current, direction) {
+        (Some(i), FieldDirection::Next) => (i + 1) % len,
+        (Some(i), FieldDirection::Previous) => (i + len - 1) % len,
+        (None, FieldDirection::Next) => 0,
+        (None, FieldDirection::Previous) => len - 1,
+    })
+}
+
+/// Text inputs under `root` in document order (same limits as the identifier search)
+fn collect_text_inputs(root: CFHandle) -> Vec<CFHandle> {
+    let mut inputs = Vec::new();
+    let mut stack = vec![(root, 0)];
+    let mut visited = 0;
+    while let Some((element, depth)) = stack.pop() {
+        visited += 1;
+        if visited > IDENTIFIER_SEARCH_MAX_NODES {
+            break;
         }
+        let role = element
+            .get_attribute("AXRole")
+            .filter(CFHandle::is_string)
+            .and_then(CFHandle::into_string);
+        if role.is_some_and(|r| TEXT_INPUT_ROLES.contains(&r.as_str())) {
+            // A combo box's inner text field is the same input, so don't descend
+            inputs.push(element);
+            continue;
+        }
+        if depth >= IDENTIFIER_SEARCH_MAX_DEPTH {
+            continue;
+        }
+        let Some(children) = element.get_attribute("AXChildren") else {
+            continue;
+        };
+        let count = unsafe { core_foundation::array::CFArrayGetCount(children.0 as _) };
+        // Pushed in reverse so children are visited in order
+        for i in (0..count.max(0)).rev() {
+            let child = unsafe { core_foundation::array::CFArrayGetValueAtIndex(children.0 as _, i) };
+            if child.is_null() {
+                continue;
+            }
+            unsafe { CFRetain(child) };
+            stack.push((CFHandle(child), depth + 1));
+        }
+    }
+    inputs
+}
+
+/// Focus the next or previous text input in the focused window, wrapping around.
+///
+/// This walks the tree in-process: the click mode helper subprocess only returns
+/// element positions, not handles that `AXFocused` could be set on.
+pub fn focus_adjacent_text_field(direction: FieldDirection) -> Result<(), String> {
+    let system_wide = CFHandle::new(unsafe { AXUIElementCreateSystemWide() })
+        .ok_or("Could not create system-wide AX element")?;
+    let focused_app = system_wide
+        .get_attribute("AXFocusedApplication")
+        .ok_or("No focused application")?;
+    let window = focused_app
+        .get_attribute("AXFocusedWindow")
+        .ok_or("No focused window")?;
+    let focused = focused_app.get_attribute("AXFocusedUIElement");
+
+    let inputs = collect_text_inputs(window);
+    let current = focused
+        .as_ref()
+        .and_then(|f| inputs.iter().position(|input| unsafe { CFEqual(input.0, f.0) } != 0));
+    let index = adjacent_index(current, inputs.len(), direction)
+        .ok_or("No text fields in the focused window")?;
+    log::info!("Focusing text field {} of {} ({:?})", index + 1, inputs.len(), direction);
+
+    match focus_element(inputs[index].0) {
+        0 => Ok(()),
+        code => Err(format!("Failed to focus text field (error {})", code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_index_wraps_around() {
+        assert_eq!(adjacent_index(Some(0), 3, FieldDirection::Next), Some(1));
+        assert_eq!(adjacent_index(Some(2), 3, FieldDirection::Next), Some(0));
+        assert_eq!(adjacent_index(Some(0), 3, FieldDirection::Previous), Some(2));
+        assert_eq!(adjacent_index(Some(1), 3, FieldDirection::Previous), Some(0));
+    }
+
+    #[test]
+    fn test_adjacent_index_without_focused_field() {
+        assert_eq!(adjacent_index(None, 3, FieldDirection::Next), Some(0));
+        assert_eq!(adjacent_index(None, 3, FieldDirection::Previous), Some(2));
+        assert_eq!(adjacent_index(None, 0, FieldDirection::Next), None);
+    }
+
+    #[test]
+    fn test_utf16_slice_ascii() {
+        assert_eq!(utf16_slice("hello world", 6, 11), Some("world".to_string()));
+        assert_eq!(utf16_slice("hello", 2, 2), Some(String::new()));
+    }
+
+    #[test]
+    fn test_utf16_slice_surrogate_pairs() {
+        // The emoji takes two UTF-16 code units
+        assert_eq!(utf16_slice("a😀b", 1, 3), Some("😀".to_string()));
+        assert_eq!(utf16_slice("a😀b", 3, 4), Some("b".to_string()));
+        // Splitting a surrogate pair is not valid UTF-16
+        assert_eq!(utf16_slice("a😀b", 1, 2), None);
+    }
+
+    #[test]
+    fn test_utf16_slice_out_of_range() {
+        assert_eq!(utf16_slice("abc", 2, 5), None);
+        assert_eq!(utf16_slice("abc", 2, 1), None);
+    }
+
+    fn window(owner_pid: i32, layer: i32) -> WindowInfo {
+        WindowInfo { owner_pid, layer }
+    }
+
+    #[test]
+    fn test_front_overlay_pid() {
+        let frontmost = 100;
+        let overlays = [200, 300];
+        // Menu bar (negative layer) and other apps' windows are skipped
+        let windows = [window(1, -1), window(50, 0), window(200, 8), window(100, 0)];
+        assert_eq!(front_overlay_pid(&windows, frontmost, &overlays), Some(200));
+        // An overlay behind the frontmost app's window isn't on top
+        let windows = [window(100, 0), window(300, 3)];
+        assert_eq!(front_overlay_pid(&windows, frontmost, &overlays), None);
+        let windows = [window(300, -1), window(100, 0)];
+        assert_eq!(front_overlay_pid(&windows, frontmost, &overlays), None);
+        assert_eq!(front_overlay_pid(&[], frontmost, &overlays), None);
+    }
+
+    /// Whether an overlay from `bundle_id` in pid 200 blocks keys while `focused` has focus
+    fn blocks(
+        bundle_id: &str,
+        focused: Option<(i32, Option<&str>)>,
+        allowlist: &HashMap<String, Vec<String>>,
+    ) -> bool {
+        let focused = focused.map(|(pid, title)| FocusedWindow {
+            pid,
+            title: title.map(str::to_string),
+        });
+        overlay_blocks_keys(200, bundle_id, focused.as_ref(), allowlist)
+    }
+
+    #[test]
+    fn test_overlay_blocks_keys_only_when_focused() {
+        let raycast = "com.raycast.macos";
+        let none = HashMap::new();
+        assert!(blocks(raycast, Some((200, Some("Raycast"))), &none));
+        assert!(blocks(raycast, Some((200, None)), &none));
+        // Visible, but the keyboard focus is in the frontmost app (or unknown)
+        assert!(!blocks(raycast, Some((100, Some("Docs"))), &none));
+        assert!(!blocks(raycast, None, &none));
+    }
+
+    #[test]
+    fn test_overlay_title_allowlist() {
+        let raycast = "com.raycast.macos";
+        let allowlist = HashMap::from([(
+            raycast.to_string(),
+            vec!["find results".to_string(), String::new()],
+        )]);
+        assert!(!blocks(raycast, Some((200, Some("Find Results — 12 matches"))), &allowlist));
+        assert!(blocks(raycast, Some((200, Some("Clipboard History"))), &allowlist));
+        assert!(blocks(raycast, Some((200, None)), &allowlist));
+        // The allowlist is per app
+        assert!(blocks("com.alfredapp.Alfred", Some((200, Some("Find Results"))), &allowlist));
     }
 }
@@ -2,7 +2,8 @@
 
 use super::javascript::GET_ELEMENT_RECT_JS;
 use super::types::BrowserType;
-use std::process::Command;
+use super::super::error::EditError;
+use crate::osascript;
 
 /// Build AppleScript for Safari to get element rect
 pub fn build_safari_script() -> String {
@@ -108,10 +109,7 @@ end tell"#,
         app_name, app_name
     );
 
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT)
         .ok()?;
 
     if !output.status.success() {
@@ -138,16 +136,13 @@ end tell"#,
 }
 
 /// Execute an AppleScript command and return output
-pub fn execute_applescript(script: &str) -> Result<String, String> {
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output()
-        .map_err(|e| format!("Failed to execute AppleScript: {}", e))?;
+pub fn execute_applescript(script: &str) -> Result<String, EditError> {
+    let output = osascript::run(script, osascript::DEFAULT_TIMEOUT)
+        .map_err(|e| EditError::Script(format!("Failed to execute AppleScript: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("AppleScript failed: {}", stderr));
+        return Err(EditError::Script(format!("AppleScript failed: {}", stderr)));
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
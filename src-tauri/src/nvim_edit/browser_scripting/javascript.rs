@@ -9,9 +9,11 @@ const GET_CURSOR_POSITION_JS_SRC: &str = include_str!("js/get_cursor_position.js
 const GET_TEXT_AND_CURSOR_JS_SRC: &str = include_str!("js/get_text_and_cursor.js");
 const SET_CURSOR_POSITION_JS_TEMPLATE: &str = include_str!("js/set_cursor_position.js");
 const SET_ELEMENT_TEXT_JS_TEMPLATE: &str = include_str!("js/set_element_text.js");
+const GET_FOCUSED_HTML_JS_SRC: &str = include_str!("js/get_focused_html.js");
+const SET_FOCUSED_HTML_JS_TEMPLATE: &str = include_str!("js/set_focused_html.js");
 
 /// Minify JavaScript for AppleScript execution (removes comments and unnecessary whitespace)
-fn minify_js(js: &str) -> String {
+pub(crate) fn minify_js(js: &str) -> String {
     let mut result = String::with_capacity(js.len());
     let mut in_string = false;
     let mut string_char = ' ';
@@ -123,6 +125,8 @@ pub static GET_CURSOR_POSITION_JS: LazyLock<String> =
     LazyLock::new(|| minify_js(GET_CURSOR_POSITION_JS_SRC));
 pub static GET_TEXT_AND_CURSOR_JS: LazyLock<String> =
     LazyLock::new(|| minify_js(GET_TEXT_AND_CURSOR_JS_SRC));
+pub static GET_FOCUSED_HTML_JS: LazyLock<String> =
+    LazyLock::new(|| minify_js(GET_FOCUSED_HTML_JS_SRC));
 
 /// JavaScript to set cursor position (line, column) in focused element
 pub fn build_set_cursor_position_js(line: usize, column: usize) -> String {
@@ -144,6 +148,14 @@ pub fn build_set_element_text_js(text: &str, target_element_id: Option<&str>) ->
     minify_js(&js)
 }
 
+/// JavaScript to replace the HTML of the focused rich text field (for `rich_text_mode`)
+/// Returns "ok_rich_*" on success, error message on failure
+pub fn build_set_focused_html_js(html: &str) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    let encoded = STANDARD.encode(html.as_bytes());
+    minify_js(&SET_FOCUSED_HTML_JS_TEMPLATE.replace("{{BASE64_HTML}}", &encoded))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,5 +197,7 @@ comment */ var y = 2;"#;
         let _ = build_set_cursor_position_js(0, 0);
         let _ = build_set_element_text_js("test", None);
         let _ = build_set_element_text_js("test", Some("my-element-id"));
+        let _ = &*GET_FOCUSED_HTML_JS;
+        assert!(!build_set_focused_html_js("<b>test</b>").contains("{{BASE64_HTML}}"));
     }
 }
@@ -1,5 +1,5 @@
 // Get cursor position (line, column) from focused element
-// Returns JSON: {line: 0-based, column: 0-based} or null
+// Returns JSON: {line: 0-based, column: 0-based in UTF-16 code units} or null
 // Note: Simplified version focusing on CodeMirror 6
 (function () {
   var e = document.querySelector(".cm-editor");
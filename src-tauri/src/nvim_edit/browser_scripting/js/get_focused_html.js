@@ -0,0 +1,29 @@
+// Get the HTML of the focused rich text (contenteditable) field
+// Returns "html:" + base64 encoded UTF-8 innerHTML, or "not_rich" when the
+// focused element is a plain input/textarea or not editable at all
+(function () {
+  // Recursively traverse shadow DOM to find the actual focused element
+  function findDeepActiveElement(el) {
+    if (el.shadowRoot && el.shadowRoot.activeElement) {
+      return findDeepActiveElement(el.shadowRoot.activeElement);
+    }
+    return el;
+  }
+
+  // Walk up to the outermost contenteditable element (the editing host)
+  function findEditingHost(el) {
+    var host = null;
+    while (el && el.isContentEditable) {
+      host = el;
+      el = el.parentElement;
+    }
+    return host;
+  }
+
+  var active = document.activeElement;
+  if (!active) return "not_rich";
+  var host = findEditingHost(findDeepActiveElement(active));
+  if (!host) return "not_rich";
+
+  return "html:" + btoa(unescape(encodeURIComponent(host.innerHTML)));
+})();
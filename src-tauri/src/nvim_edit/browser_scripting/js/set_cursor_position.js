@@ -1,5 +1,5 @@
 // Set cursor position (line, column) in focused element
-// Template variables: {{TARGET_LINE}}, {{TARGET_COL}}
+// Template variables: {{TARGET_LINE}}, {{TARGET_COL}} (column in UTF-16 code units)
 // Returns status string: ok_cm6, ok_monaco, ok_input, ok_ce, unsupported, etc.
 (function () {
   var NL = String.fromCharCode(10);
@@ -0,0 +1,46 @@
+// Replace the HTML of the focused rich text (contenteditable) field
+// Template variables:
+//   {{BASE64_HTML}} - base64 encoded UTF-8 HTML to set
+// Returns "ok_rich_*" on success, error message on failure
+(function () {
+  // Recursively traverse shadow DOM to find the actual focused element
+  function findDeepActiveElement(el) {
+    if (el.shadowRoot && el.shadowRoot.activeElement) {
+      return findDeepActiveElement(el.shadowRoot.activeElement);
+    }
+    return el;
+  }
+
+  // Walk up to the outermost contenteditable element (the editing host)
+  function findEditingHost(el) {
+    var host = null;
+    while (el && el.isContentEditable) {
+      host = el;
+      el = el.parentElement;
+    }
+    return host;
+  }
+
+  var html = decodeURIComponent(escape(atob("{{BASE64_HTML}}")));
+
+  var active = document.activeElement;
+  if (!active) return "no_active_element";
+  var host = findEditingHost(findDeepActiveElement(active));
+  if (!host) return "not_rich";
+
+  // insertHTML goes through the editor's input handling and undo stack
+  host.focus();
+  var selection = window.getSelection();
+  var range = document.createRange();
+  range.selectNodeContents(host);
+  selection.removeAllRanges();
+  selection.addRange(range);
+  if (document.execCommand("insertHTML", false, html)) {
+    return "ok_rich_insert";
+  }
+
+  // Last resort: set innerHTML directly
+  host.innerHTML = html;
+  host.dispatchEvent(new Event("input", { bubbles: true }));
+  return "ok_rich_innerhtml";
+})();
@@ -5,9 +5,10 @@ mod javascript;
 mod parsing;
 mod types;
 
-use std::process::Command;
-
 use super::accessibility::ElementFrame;
+use super::error::EditError;
+use crate::osascript;
+pub(crate) use javascript::minify_js;
 pub use types::{detect_browser_type, BrowserType, CursorPosition, TextAndCursor};
 
 use applescript::{
@@ -15,25 +16,26 @@ use applescript::{
     get_browser_window_bounds,
 };
 use javascript::{
-    build_set_cursor_position_js, build_set_element_text_js, GET_CURSOR_POSITION_JS,
-    GET_TEXT_AND_CURSOR_JS,
+    build_set_cursor_position_js, build_set_element_text_js, build_set_focused_html_js,
+    GET_CURSOR_POSITION_JS, GET_FOCUSED_HTML_JS, GET_TEXT_AND_CURSOR_JS,
+};
+use parsing::{
+    parse_cursor_position_json, parse_focused_html_response, parse_text_and_cursor_json,
+    parse_viewport_frame_json,
 };
-use parsing::{parse_cursor_position_json, parse_text_and_cursor_json, parse_viewport_frame_json};
 use types::viewport_to_element_frame;
 
 /// Set text on the focused element in a browser using AppleScript + JavaScript
-/// Returns Ok(Option<element_id>) on success, Err with message on failure
+/// Returns Ok(Option<element_id>) on success
 /// The element_id can be passed to subsequent calls to target the same element
 pub fn set_browser_element_text(
     browser_type: BrowserType,
     text: &str,
     target_element_id: Option<&str>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, EditError> {
     let js = build_set_element_text_js(text, target_element_id);
     let script = build_execute_script(browser_type, &js);
 
-    // Debug: write script to file for inspection
-    let _ = std::fs::write("/tmp/set_text_script.txt", &script);
     log::info!(
         "set_browser_element_text: browser={:?}, text_len={}, script_len={}, target_id={:?}",
         browser_type,
@@ -42,11 +44,10 @@ pub fn set_browser_element_text(
         target_element_id
     );
 
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
-        .map_err(|e| format!("Failed to execute AppleScript: {}", e))?;
+    // Live sync calls this from its worker for each applied update; the timeout
+    // keeps a hung browser from stalling the worker and the end of the session
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT)
+        .map_err(|e| EditError::Script(format!("Failed to execute AppleScript: {}", e)))?;
 
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
     let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -59,7 +60,7 @@ pub fn set_browser_element_text(
     );
 
     if !output.status.success() {
-        return Err(format!("AppleScript failed: {}", stderr));
+        return Err(EditError::Script(format!("AppleScript failed: {}", stderr)));
     }
 
     if stdout.starts_with("ok") {
@@ -72,7 +73,7 @@ pub fn set_browser_element_text(
         };
         Ok(element_id)
     } else {
-        Err(format!("JavaScript returned: {}", stdout))
+        Err(EditError::ScriptRejected(stdout))
     }
 }
 
@@ -108,19 +109,16 @@ pub fn set_browser_cursor_position(
     browser_type: BrowserType,
     line: usize,
     column: usize,
-) -> Result<(), String> {
+) -> Result<(), EditError> {
     let js = build_set_cursor_position_js(line, column);
     let script = build_execute_script(browser_type, &js);
 
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
-        .map_err(|e| format!("Failed to execute AppleScript: {}", e))?;
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT)
+        .map_err(|e| EditError::Script(format!("Failed to execute AppleScript: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("AppleScript failed: {}", stderr));
+        return Err(EditError::Script(format!("AppleScript failed: {}", stderr)));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -129,7 +127,7 @@ pub fn set_browser_cursor_position(
         log::debug!("Set browser cursor position: {}", stdout);
         Ok(())
     } else {
-        Err(format!("JavaScript returned: {}", stdout))
+        Err(EditError::ScriptRejected(stdout))
     }
 }
 
@@ -160,6 +158,38 @@ pub fn get_browser_text_and_cursor(browser_type: BrowserType) -> Option<TextAndC
     Some(result)
 }
 
+/// Get the HTML of the focused rich text (contenteditable) field.
+/// Returns None for plain inputs/textareas or when the browser can't be scripted.
+pub fn get_browser_focused_html(browser_type: BrowserType) -> Option<String> {
+    let script = build_execute_script(browser_type, &GET_FOCUSED_HTML_JS);
+
+    let stdout = match execute_applescript(&script) {
+        Ok(s) => s,
+        Err(e) => {
+            log::debug!("get_browser_focused_html AppleScript failed: {}", e);
+            return None;
+        }
+    };
+
+    let html = parse_focused_html_response(&stdout)?;
+    log::info!("Got focused rich text field HTML ({} bytes)", html.len());
+    Some(html)
+}
+
+/// Replace the HTML of the focused rich text (contenteditable) field
+pub fn set_browser_focused_html(browser_type: BrowserType, html: &str) -> Result<(), EditError> {
+    let js = build_set_focused_html_js(html);
+    let script = build_execute_script(browser_type, &js);
+    let stdout = execute_applescript(&script)?;
+
+    if stdout.starts_with("ok") {
+        log::info!("Set focused rich text field HTML: {}", stdout);
+        Ok(())
+    } else {
+        Err(EditError::ScriptRejected(stdout))
+    }
+}
+
 /// Get the hostname from the current browser tab
 pub fn get_browser_hostname(browser_type: BrowserType) -> Option<String> {
     let js = "window.location.hostname";
@@ -89,6 +89,14 @@ pub fn parse_text_and_cursor_json(json: &str) -> Option<TextAndCursor> {
     Some(TextAndCursor { text, cursor })
 }
 
+/// Parse the focused field's HTML from a `"html:<base64>"` response
+pub fn parse_focused_html_response(response: &str) -> Option<String> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    let encoded = response.trim().strip_prefix("html:")?;
+    let bytes = STANDARD.decode(encoded).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +119,14 @@ mod tests {
         assert_eq!(extract_json_number(json, "y"), Some(456.0));
         assert_eq!(extract_json_number(json, "z"), None);
     }
+
+    #[test]
+    fn test_parse_focused_html_response() {
+        assert_eq!(
+            parse_focused_html_response("html:PGI+aMOpPC9iPg==").as_deref(),
+            Some("<b>hé</b>")
+        );
+        assert_eq!(parse_focused_html_response("not_rich"), None);
+        assert_eq!(parse_focused_html_response("html:%%%"), None);
+    }
 }
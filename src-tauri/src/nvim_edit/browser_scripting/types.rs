@@ -41,13 +41,51 @@ pub fn detect_browser_type(bundle_id: &str) -> Option<BrowserType> {
     }
 }
 
-/// Cursor position (0-based line and column)
-#[derive(Debug, Clone, Copy, Default)]
+/// Cursor position (0-based line and column).
+/// Browser JavaScript counts columns in UTF-16 code units, nvim in bytes;
+/// convert with `utf16_to_byte_column` / `byte_to_utf16_column` when crossing over.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct CursorPosition {
     pub line: usize,
     pub column: usize,
 }
 
+impl CursorPosition {
+    /// Convert a UTF-16 column (JavaScript) to a byte column (nvim) on its line of `text`.
+    /// A column inside a surrogate pair moves to the start of the character.
+    pub fn utf16_to_byte_column(self, text: &str) -> Self {
+        let line = nth_line(text, self.line);
+        let mut units = 0;
+        let column = line
+            .char_indices()
+            .find(|(_, ch)| {
+                units += ch.len_utf16();
+                units > self.column
+            })
+            .map_or(line.len(), |(byte, _)| byte);
+        Self { column, ..self }
+    }
+
+    /// Convert a byte column (nvim) to a UTF-16 column (JavaScript) on its line of `text`.
+    /// A column inside a multibyte character moves to the start of the character.
+    pub fn byte_to_utf16_column(self, text: &str) -> Self {
+        let line = nth_line(text, self.line);
+        let mut byte = self.column.min(line.len());
+        while !line.is_char_boundary(byte) {
+            byte -= 1;
+        }
+        Self {
+            column: line[..byte].encode_utf16().count(),
+            ..self
+        }
+    }
+}
+
+/// The `index`th line of `text`, or "" past the end
+fn nth_line(text: &str, index: usize) -> &str {
+    text.split('\n').nth(index).unwrap_or("")
+}
+
 /// Result of getting text and cursor in one call
 pub struct TextAndCursor {
     pub text: String,
@@ -111,4 +149,51 @@ mod tests {
         assert!(detect_browser_type("org.mozilla.firefox").is_none());
         assert!(detect_browser_type("com.apple.TextEdit").is_none());
     }
+
+    fn at(line: usize, column: usize) -> CursorPosition {
+        CursorPosition { line, column }
+    }
+
+    #[test]
+    fn test_cursor_columns_with_emoji() {
+        // 😀 is 2 UTF-16 units and 4 bytes
+        let text = "first\na😀b";
+        assert_eq!(at(1, 3).utf16_to_byte_column(text), at(1, 5));
+        assert_eq!(at(1, 5).byte_to_utf16_column(text), at(1, 3));
+        // Inside the surrogate pair / the UTF-8 sequence: start of the emoji
+        assert_eq!(at(1, 2).utf16_to_byte_column(text), at(1, 1));
+        assert_eq!(at(1, 3).byte_to_utf16_column(text), at(1, 1));
+    }
+
+    #[test]
+    fn test_cursor_columns_with_combining_characters() {
+        // e + U+0301 (combining acute) is 2 UTF-16 units and 3 bytes; ü is 1 unit and 2 bytes
+        let text = "cafe\u{301} über";
+        assert_eq!(at(0, 5).utf16_to_byte_column(text), at(0, 6));
+        assert_eq!(at(0, 7).utf16_to_byte_column(text), at(0, 9));
+        assert_eq!(at(0, 9).byte_to_utf16_column(text), at(0, 7));
+    }
+
+    #[test]
+    fn test_cursor_columns_round_trip() {
+        let text = "plain ascii\n👍🏽 thumbs ✓\nnaïve e\u{301}";
+        for (line_index, line) in text.split('\n').enumerate() {
+            let mut utf16_column = 0;
+            for ch in line.chars() {
+                let cursor = at(line_index, utf16_column);
+                let nvim = cursor.utf16_to_byte_column(text);
+                assert!(line.is_char_boundary(nvim.column));
+                assert_eq!(nvim.byte_to_utf16_column(text), cursor, "line {:?}", line);
+                utf16_column += ch.len_utf16();
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_columns_out_of_range() {
+        let text = "ab😀\ncd";
+        assert_eq!(at(0, 99).utf16_to_byte_column(text), at(0, 6));
+        assert_eq!(at(0, 99).byte_to_utf16_column(text), at(0, 4));
+        assert_eq!(at(5, 3).utf16_to_byte_column(text), at(5, 0));
+    }
 }
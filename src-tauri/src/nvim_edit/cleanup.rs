@@ -0,0 +1,136 @@
+//! Startup sweep for files left behind by crashed edit sessions
+//!
+//! `complete_edit_session` removes the temp file and socket only when a session
+//! finishes normally. Leftovers are reclaimed at startup once they are old and
+//! nothing uses them anymore, so a second running ovim instance keeps its files.
+
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::socket::socket_dirs;
+
+/// Files younger than this are never reclaimed
+const STALE_AFTER: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A file an edit session leaves behind
+#[derive(Debug, PartialEq)]
+enum Leftover<'a> {
+    /// `edit_<id>.txt`, with the session id
+    TempFile(&'a str),
+    /// `nvim_<id>.sock`, or any socket in the ovim-owned fallback directory
+    Socket,
+    /// `nvim_prewarm_<pid>.sock`, with the pid of the ovim instance that created it
+    PrewarmSocket(u32),
+}
+
+/// Recognize ovim's files by name. Outside `owned_dir`, only `nvim_`/`edit_`
+/// prefixed names match so other sockets in a shared directory are left alone.
+fn classify(name: &str, owned_dir: bool) -> Option<Leftover<'_>> {
+    if let Some(id) = name.strip_prefix("edit_").and_then(|n| n.strip_suffix(".txt")) {
+        return Some(Leftover::TempFile(id));
+    }
+    let stem = name.strip_suffix(".sock")?;
+    if let Some(pid) = stem.strip_prefix("nvim_prewarm_") {
+        return pid.parse().ok().map(Leftover::PrewarmSocket);
+    }
+    (owned_dir || stem.starts_with("nvim_")).then_some(Leftover::Socket)
+}
+
+/// Whether a file last modified at `modified` is old enough to reclaim
+fn is_stale(modified: SystemTime, now: SystemTime) -> bool {
+    now.duration_since(modified).is_ok_and(|age| age >= STALE_AFTER)
+}
+
+/// Whether something still accepts connections on the socket
+fn socket_is_live(path: &Path) -> bool {
+    UnixStream::connect(path).is_ok()
+}
+
+fn process_alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+/// Whether a leftover still belongs to a running session or ovim instance
+fn in_use(dir: &Path, path: &Path, leftover: &Leftover) -> bool {
+    match leftover {
+        Leftover::TempFile(id) => socket_is_live(&dir.join(format!("nvim_{}.sock", id))),
+        Leftover::Socket => socket_is_live(path),
+        Leftover::PrewarmSocket(pid) => process_alive(*pid) || socket_is_live(path),
+    }
+}
+
+/// Delete stale ovim files in `dir` and return how many were removed
+fn sweep_dir(dir: &Path, owned_dir: bool, now: SystemTime) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(leftover) = name.to_str().and_then(|n| classify(n, owned_dir)) else {
+            continue;
+        };
+        let modified = entry.metadata().and_then(|m| m.modified());
+        if !modified.is_ok_and(|m| is_stale(m, now)) {
+            continue;
+        }
+        let path = entry.path();
+        if in_use(dir, &path, &leftover) {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => log::debug!("Could not remove stale file {:?}: {}", path, e),
+        }
+    }
+    removed
+}
+
+/// Remove temp files and sockets left by crashed edit sessions.
+/// `socket_dir` is the configured socket directory (empty = cache directory).
+pub fn sweep_stale_files(socket_dir: &str) {
+    let Some(cache_dir) = dirs::cache_dir().map(|dir| dir.join("ovim")) else {
+        return;
+    };
+
+    let now = SystemTime::now();
+    let mut removed = sweep_dir(&cache_dir, false, now);
+    let (configured_dir, fallback_dir) = socket_dirs(socket_dir, &cache_dir);
+    if configured_dir != cache_dir {
+        removed += sweep_dir(&configured_dir, false, now);
+    }
+    if let Some(fallback_dir) = fallback_dir {
+        removed += sweep_dir(&fallback_dir, true, now);
+    }
+
+    if removed > 0 {
+        log::info!("Reclaimed {} stale edit session file(s)", removed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_only_matches_ovim_files() {
+        assert_eq!(classify("edit_abc.txt", false), Some(Leftover::TempFile("abc")));
+        assert_eq!(classify("nvim_abc.sock", false), Some(Leftover::Socket));
+        assert_eq!(classify("nvim_prewarm_42.sock", false), Some(Leftover::PrewarmSocket(42)));
+        assert_eq!(classify("1a2b3c4d.sock", true), Some(Leftover::Socket));
+        assert_eq!(classify("1a2b3c4d.sock", false), None);
+        assert_eq!(classify("notes.txt", true), None);
+        assert_eq!(classify("nvim_prewarm_x.sock", true), None);
+    }
+
+    #[test]
+    fn test_only_old_files_are_stale() {
+        let now = SystemTime::now();
+        assert!(is_stale(now - STALE_AFTER, now));
+        assert!(!is_stale(now - Duration::from_secs(60), now));
+        // Clock skew: files from the future are kept
+        assert!(!is_stale(now + Duration::from_secs(60), now));
+    }
+}
@@ -1,45 +1,120 @@
 //! Clipboard operations for text capture and restoration
 
-use crate::keyboard::{inject_key_press, KeyCode, Modifiers};
+use super::accessibility::{get_element_text, AXElementHandle};
+use super::error::EditError;
+use super::sanitize::decode_lossy;
+use crate::keyboard::{KeyCode, KeyInjector, Modifiers, SystemInjector};
 use std::process::Command;
 use std::thread;
 use std::time::Duration;
 
-/// Replace text in the focused field using clipboard
-pub fn replace_text_via_clipboard(text: &str) -> Result<(), String> {
+/// Delay before the original clipboard is put back after pasting
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(500);
+
+/// Delay after each chunk so the app reads the clipboard before it changes
+const CHUNK_DELAY: Duration = Duration::from_millis(150);
+
+/// Replace text in the focused field using clipboard.
+/// Texts longer than `chunk_threshold` bytes (0 = never) are pasted in chunks of that size,
+/// and the result is checked against `element`'s AX value when available.
+pub fn replace_text_via_clipboard(
+    text: &str,
+    chunk_threshold: usize,
+    element: Option<&AXElementHandle>,
+) -> Result<(), EditError> {
     log::info!("Saving current clipboard and setting new content ({} chars)", text.len());
 
     // Save current clipboard
     let original_clipboard = get_clipboard_content();
 
-    // Set new clipboard content
-    set_clipboard_content(text)?;
-
-    log::info!("Clipboard set, now sending Cmd+A");
-
-    // Select all and paste
-    thread::sleep(Duration::from_millis(100));
-    inject_key_press(
-        KeyCode::A,
-        Modifiers { command: true, ..Default::default() },
-    )?;
+    let chunks = split_into_chunks(text, chunk_threshold);
+    if chunks.len() > 1 {
+        log::info!("Pasting in {} chunks of up to {} bytes", chunks.len(), chunk_threshold);
+    }
+    let mut result = paste_chunks(&chunks);
+
+    // A dropped or repeated chunk changes the length; redo it as a single paste
+    if result.is_ok() && chunks.len() > 1 {
+        thread::sleep(CHUNK_DELAY);
+        if let Some(pasted) = element.and_then(get_element_text) {
+            let (expected, actual) = (text.chars().count(), pasted.chars().count());
+            if actual != expected {
+                log::warn!(
+                    "Chunked paste left {} chars instead of {}, pasting all at once",
+                    actual,
+                    expected
+                );
+                result = paste_chunks(&[text]);
+            }
+        }
+    }
 
-    log::info!("Sent Cmd+A, now sending Cmd+V");
+    // Restore original clipboard after a delay. This waits instead of restoring in the
+    // background so a session finishing right after this one saves the real clipboard.
+    if let Some(original) = original_clipboard {
+        thread::sleep(CLIPBOARD_RESTORE_DELAY);
+        let _ = set_clipboard_content(&original);
+    }
 
-    thread::sleep(Duration::from_millis(100));
-    inject_key_press(
-        KeyCode::V,
-        Modifiers { command: true, ..Default::default() },
-    )?;
+    result
+}
 
-    log::info!("Sent Cmd+V");
+/// Select all and paste the first chunk, then paste the rest at the cursor to append them
+fn paste_chunks(chunks: &[&str]) -> Result<(), EditError> {
+    for (i, chunk) in chunks.iter().enumerate() {
+        set_clipboard_content(chunk)?;
+        thread::sleep(Duration::from_millis(100));
+
+        if i == 0 {
+            log::info!("Clipboard set, now sending Cmd+A");
+            SystemInjector.inject_key_press(
+                KeyCode::A,
+                Modifiers { command: true, ..Default::default() },
+            ).map_err(EditError::Clipboard)?;
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        SystemInjector.inject_key_press(
+            KeyCode::V,
+            Modifiers { command: true, ..Default::default() },
+        ).map_err(EditError::Clipboard)?;
+        log::info!("Sent Cmd+V ({}/{})", i + 1, chunks.len());
+
+        if i + 1 < chunks.len() {
+            thread::sleep(CHUNK_DELAY);
+        }
+    }
+    Ok(())
+}
 
-    // Restore original clipboard after a delay
-    if let Some(original) = original_clipboard {
-        restore_clipboard_async(original);
+/// Split text into chunks of at most `max_bytes` (0 = no limit), preferring to end
+/// each chunk after a newline. Chunks always concatenate back to `text`.
+fn split_into_chunks(text: &str, max_bytes: usize) -> Vec<&str> {
+    if max_bytes == 0 || text.len() <= max_bytes {
+        return vec![text];
     }
 
-    Ok(())
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if let Some(newline) = rest[..end].rfind('\n') {
+            end = newline + 1;
+        }
+        if end == 0 {
+            // A single character is longer than max_bytes
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        chunks.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
 }
 
 /// Capture text from focused element via clipboard (fallback for web text fields)
@@ -54,7 +129,7 @@ pub fn capture_text_via_clipboard() -> Option<String> {
     thread::sleep(Duration::from_millis(50));
 
     // Select all (Cmd+A)
-    if inject_key_press(
+    if SystemInjector.inject_key_press(
         KeyCode::A,
         Modifiers { command: true, ..Default::default() },
     ).is_err() {
@@ -64,7 +139,7 @@ pub fn capture_text_via_clipboard() -> Option<String> {
     thread::sleep(Duration::from_millis(50));
 
     // Copy (Cmd+C)
-    if inject_key_press(
+    if SystemInjector.inject_key_press(
         KeyCode::C,
         Modifiers { command: true, ..Default::default() },
     ).is_err() {
@@ -77,7 +152,7 @@ pub fn capture_text_via_clipboard() -> Option<String> {
     let captured_text = get_clipboard_content();
 
     // Deselect by pressing Right arrow (moves cursor to end of selection)
-    let _ = inject_key_press(
+    let _ = SystemInjector.inject_key_press(
         KeyCode::Right,
         Modifiers::default(),
     );
@@ -91,35 +166,42 @@ pub fn capture_text_via_clipboard() -> Option<String> {
     captured_text.filter(|text| text != marker)
 }
 
+/// `pbcopy`/`pbpaste` convert using the locale, which apps started from Finder don't set
+fn pasteboard_command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    command.env("LANG", "en_US.UTF-8");
+    command
+}
+
 /// Get current clipboard content
 fn get_clipboard_content() -> Option<String> {
-    Command::new("pbpaste")
+    pasteboard_command("pbpaste")
         .output()
         .ok()
-        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|o| decode_lossy(o.stdout, "Clipboard"))
 }
 
 /// Set clipboard content
-fn set_clipboard_content(text: &str) -> Result<(), String> {
-    let mut pbcopy = Command::new("pbcopy")
+pub fn set_clipboard_content(text: &str) -> Result<(), EditError> {
+    let mut pbcopy = pasteboard_command("pbcopy")
         .stdin(std::process::Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn pbcopy: {}", e))?;
+        .map_err(EditError::io("spawn pbcopy"))?;
 
     if let Some(mut stdin) = pbcopy.stdin.take() {
         use std::io::Write;
         stdin.write_all(text.as_bytes())
-            .map_err(|e| format!("Failed to write to pbcopy: {}", e))?;
+            .map_err(EditError::io("write to pbcopy"))?;
     }
-    pbcopy.wait().map_err(|e| format!("pbcopy failed: {}", e))?;
+    pbcopy.wait().map_err(|e| EditError::Clipboard(format!("pbcopy failed: {}", e)))?;
     Ok(())
 }
 
 /// Restore clipboard content asynchronously after a delay
 fn restore_clipboard_async(content: String) {
     thread::spawn(move || {
-        thread::sleep(Duration::from_millis(500));
-        let _ = Command::new("pbcopy")
+        thread::sleep(CLIPBOARD_RESTORE_DELAY);
+        let _ = pasteboard_command("pbcopy")
             .stdin(std::process::Stdio::piped())
             .spawn()
             .and_then(|mut p| {
@@ -131,3 +213,25 @@ fn restore_clipboard_async(content: String) {
             });
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_chunks_keeps_all_text() {
+        let text = "first line\nsecond line\nthird";
+        assert_eq!(split_into_chunks(text, 0), vec![text]);
+        assert_eq!(split_into_chunks(text, 100), vec![text]);
+
+        let chunks = split_into_chunks(text, 16);
+        assert_eq!(chunks, vec!["first line\n", "second line\n", "third"]);
+
+        // No newline to split at, multi-byte characters stay whole
+        let text = "ééééé";
+        let chunks = split_into_chunks(text, 3);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 3));
+        assert_eq!(chunks.concat(), text);
+        assert_eq!(split_into_chunks("é", 1), vec!["é"]);
+    }
+}
@@ -0,0 +1,87 @@
+//! Debug overlay for Edit Popup geometry (`edit_popup_debug`)
+//!
+//! Draws the detected element/window frames and the computed popup geometry
+//! on the click overlay window for a couple of seconds.
+
+use std::thread;
+use std::time::Duration;
+
+use tauri::{Emitter, Manager};
+
+use super::accessibility::ElementFrame;
+use super::terminals::WindowGeometry;
+use crate::window::{
+    position_click_overlay_fullscreen, set_indicator_ignores_mouse, show_window_without_focus,
+};
+use crate::AppState;
+
+/// How long the debug rectangles stay on screen
+const DISPLAY_DURATION: Duration = Duration::from_secs(2);
+
+/// Payload for the edit-popup-debug event
+#[derive(Clone, serde::Serialize)]
+struct EditPopupDebugPayload {
+    element_frame: Option<ElementFrame>,
+    window_frame: Option<ElementFrame>,
+    popup_geometry: Option<WindowGeometry>,
+    window_offset: (f64, f64),
+}
+
+/// Log the frames and draw them on the overlay window
+pub fn show_geometry(
+    element_frame: Option<ElementFrame>,
+    window_frame: Option<ElementFrame>,
+    popup_geometry: Option<WindowGeometry>,
+) {
+    log::info!(
+        "Edit popup debug: element={:?}, window={:?}, popup={:?}",
+        element_frame,
+        window_frame,
+        popup_geometry
+    );
+
+    let Some(app) = crate::get_app_handle() else {
+        return;
+    };
+    let Some(overlay) = app.get_webview_window("click-overlay") else {
+        log::warn!("Edit popup debug: click overlay window not found");
+        return;
+    };
+
+    let window_offset = position_click_overlay_fullscreen(&overlay).unwrap_or_else(|e| {
+        log::warn!("Edit popup debug: failed to position overlay: {}", e);
+        (0.0, 0.0)
+    });
+
+    // Let clicks fall through to the app underneath while the rectangles are shown
+    let _ = set_indicator_ignores_mouse(&overlay, true);
+    let _ = app.emit(
+        "edit-popup-debug",
+        EditPopupDebugPayload {
+            element_frame,
+            window_frame,
+            popup_geometry,
+            window_offset,
+        },
+    );
+    if let Err(e) = show_window_without_focus(&overlay) {
+        log::warn!("Edit popup debug: failed to show overlay: {}", e);
+    }
+
+    thread::spawn(move || {
+        thread::sleep(DISPLAY_DURATION);
+        let _ = app.emit("edit-popup-debug-hide", ());
+        let _ = set_indicator_ignores_mouse(&overlay, false);
+
+        // Click mode may have taken over the overlay in the meantime
+        let state = app.state::<AppState>();
+        let click_mode_active = state
+            .click_mode_manager
+            .lock()
+            .map(|m| m.is_active())
+            .unwrap_or(false);
+        if !click_mode_active {
+            let _ = overlay.hide();
+        }
+    });
+}
@@ -0,0 +1,151 @@
+//! Errors returned by the Edit Popup flow
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+/// Failure modes of the Edit Popup flow
+#[derive(Debug, Error)]
+pub enum EditError {
+    /// ovim is not trusted for accessibility
+    #[error("Accessibility permission not granted")]
+    PermissionDenied,
+    /// No focused application to edit text in
+    #[error("No focused application found")]
+    NoFocusedApp,
+    /// The cache directory for temp files could not be determined
+    #[error("Could not determine cache directory")]
+    NoCacheDir,
+    /// A file operation on the temp file or its directory failed
+    #[error("Failed to {action}: {source}")]
+    Io {
+        action: &'static str,
+        source: std::io::Error,
+    },
+    /// The configured socket directory can't be created or written to
+    #[error("Socket directory {dir:?} is not writable: {source}")]
+    SocketDirNotWritable {
+        dir: PathBuf,
+        source: std::io::Error,
+    },
+    /// The socket path exceeds the Unix socket limit even in the fallback directory
+    #[error("Socket path {0:?} is longer than {max} bytes", max = crate::socket_path::MAX_SOCKET_PATH_LEN)]
+    SocketPathTooLong(PathBuf),
+    /// The terminal or launcher script could not be started
+    #[error("{0}")]
+    Spawn(String),
+    /// The session disappeared right after it was created
+    #[error("Session not found immediately after creation")]
+    SessionNotFound,
+    /// No active session has this id
+    #[error("No edit session with id {0}")]
+    UnknownSession(String),
+    /// nvim did not open its RPC socket in time
+    #[error("Failed to connect to nvim socket after {waited:?}: {reason}")]
+    RpcTimeout { waited: Duration, reason: String },
+    /// An RPC call to nvim failed
+    #[error("{0}")]
+    Rpc(String),
+    /// Nothing listens on the editor's socket: the editor exited or never started
+    #[error("{0}")]
+    EditorNotFound(String),
+    /// The app the text came from is no longer running
+    #[error("Could not find running application with PID {0}")]
+    AppNotRunning(i32),
+    /// The app the text came from refused to come forward
+    #[error("Failed to activate application")]
+    ActivationFailed,
+    /// Setting the clipboard or pasting it into the field failed
+    #[error("{0}")]
+    Clipboard(String),
+    /// The field rejected the new AX value (AX error code)
+    #[error("AXUIElementSetAttributeValue failed with error code: {0}")]
+    SetTextFailed(i32),
+    /// The browser's AppleScript could not be run or failed
+    #[error("{0}")]
+    Script(String),
+    /// The page script ran but did not apply the change (the script's result)
+    #[error("JavaScript returned: {0}")]
+    ScriptRejected(String),
+    /// The format command failed; the text is restored unformatted
+    #[error("{0}")]
+    Formatter(String),
+    /// The format command was killed after running too long
+    #[error("Formatter timed out after {0:?}")]
+    FormatterTimedOut(Duration),
+}
+
+impl EditError {
+    /// Stable error code the frontend can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            EditError::PermissionDenied => "permission_denied",
+            EditError::NoFocusedApp => "no_focused_app",
+            EditError::NoCacheDir => "no_cache_dir",
+            EditError::Io { .. } => "io",
+            EditError::SocketDirNotWritable { .. } => "socket_dir_not_writable",
+            EditError::SocketPathTooLong(_) => "socket_path_too_long",
+            EditError::Spawn(_) => "spawn_failed",
+            EditError::SessionNotFound => "session_not_found",
+            EditError::UnknownSession(_) => "unknown_session",
+            EditError::RpcTimeout { .. } => "rpc_timeout",
+            EditError::Rpc(_) => "rpc",
+            EditError::EditorNotFound(_) => "editor_not_found",
+            EditError::AppNotRunning(_) => "app_not_running",
+            EditError::ActivationFailed => "activation_failed",
+            EditError::Clipboard(_) => "clipboard",
+            EditError::SetTextFailed(_) => "set_text_failed",
+            EditError::Script(_) => "script",
+            EditError::ScriptRejected(_) => "script_rejected",
+            EditError::Formatter(_) => "formatter",
+            EditError::FormatterTimedOut(_) => "formatter_timed_out",
+        }
+    }
+
+    /// Wrap an I/O error with the action that failed (e.g., "write temp file")
+    pub fn io(action: &'static str) -> impl FnOnce(std::io::Error) -> Self {
+        move |source| EditError::Io { action, source }
+    }
+}
+
+/// Serialized as `{ code, message }` for commands and events
+impl Serialize for EditError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("EditError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_keeps_existing_messages() {
+        assert_eq!(EditError::NoFocusedApp.to_string(), "No focused application found");
+        let io = EditError::io("write temp file")(std::io::Error::other("disk full"));
+        assert_eq!(io.to_string(), "Failed to write temp file: disk full");
+        assert_eq!(io.code(), "io");
+        assert_eq!(
+            EditError::AppNotRunning(42).to_string(),
+            "Could not find running application with PID 42"
+        );
+        assert_eq!(EditError::ActivationFailed.to_string(), "Failed to activate application");
+    }
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let json = serde_json::to_value(EditError::RpcTimeout {
+            waited: Duration::from_millis(100),
+            reason: "refused".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["code"], "rpc_timeout");
+        assert_eq!(json["message"], "Failed to connect to nvim socket after 100ms: refused");
+    }
+}
@@ -0,0 +1,145 @@
+//! Optional formatter run on the edited file before the text is restored
+//!
+//! `format_command` is a shell command template where `{file}` is replaced by
+//! the quoted temp file path, e.g. `prettier --write {file}`. It runs in a login
+//! shell so tools installed through Homebrew or version managers are on PATH.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{ChildStderr, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::error::EditError;
+
+/// A formatter that takes longer than this is killed and the text is restored unformatted
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often to check whether the formatter has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for the rest of stderr after the formatter exits
+/// (a background process it left behind can keep the pipe open)
+const STDERR_GRACE: Duration = Duration::from_millis(500);
+
+/// Run the formatter on `file`. On failure the file's original content is put back.
+pub fn format_file(command_template: &str, file: &Path) -> Result<(), EditError> {
+    format_file_with_timeout(command_template, file, FORMAT_TIMEOUT)
+}
+
+fn format_file_with_timeout(command_template: &str, file: &Path, timeout: Duration) -> Result<(), EditError> {
+    let original = std::fs::read(file)
+        .map_err(|e| EditError::Formatter(format!("Failed to read {:?}: {}", file, e)))?;
+
+    let result = run_formatter(&expand_template(command_template, file), timeout);
+    if result.is_err() {
+        if let Err(e) = std::fs::write(file, &original) {
+            log::warn!("Failed to restore unformatted text: {}", e);
+        }
+    }
+    result
+}
+
+/// Run the command and wait up to `timeout` for it to succeed
+fn run_formatter(command: &str, timeout: Duration) -> Result<(), EditError> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    log::info!("Running formatter: {}", command);
+
+    let mut child = Command::new(&shell)
+        .arg("-lc")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(EditError::io("start formatter"))?;
+    // Read stderr while the formatter runs, so a chatty one can't fill the pipe and block
+    let stderr = drain_stderr(child.stderr.take());
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                let stderr = stderr.recv_timeout(STDERR_GRACE).unwrap_or_default();
+                return Err(EditError::Formatter(format!("Formatter exited with {}: {}", status, stderr.trim())));
+            }
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(EditError::FormatterTimedOut(timeout));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(EditError::io("wait for formatter")(e)),
+        }
+    }
+}
+
+/// Read `pipe` to the end on a separate thread; the receiver gets the text once it closes
+fn drain_stderr(pipe: Option<ChildStderr>) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = pipe.read_to_end(&mut output);
+            let _ = tx.send(String::from_utf8_lossy(&output).into_owned());
+        });
+    }
+    rx
+}
+
+/// Replace `{file}` in the template with the shell-quoted path
+fn expand_template(command_template: &str, file: &Path) -> String {
+    let path = file.to_string_lossy();
+    command_template.replace("{file}", &format!("'{}'", path.replace('\'', "'\\''")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("ovim_format_{}_{}.txt", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_expand_template_quotes_path() {
+        assert_eq!(
+            expand_template("gofmt -w {file}", Path::new("/tmp/it's here.txt")),
+            "gofmt -w '/tmp/it'\\''s here.txt'"
+        );
+    }
+
+    #[test]
+    fn test_formatter_rewrites_file() {
+        let path = temp_file("ok", "a  b\n");
+        format_file("sed -i.bak 's/  / /' {file} && rm {file}.bak", &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a b\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_failed_or_hung_formatter_keeps_original_text() {
+        let path = temp_file("fail", "original\n");
+        let err = format_file("echo broken > {file}; echo 'bad syntax' >&2; exit 2", &path).unwrap_err();
+        assert!(err.to_string().contains("bad syntax"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original\n");
+
+        let err = format_file_with_timeout("echo partial > {file}; sleep 5", &path, Duration::from_millis(200))
+            .unwrap_err();
+        assert!(matches!(err, EditError::FormatterTimedOut(_)), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_noisy_formatter_does_not_block() {
+        // More stderr than a pipe buffer holds
+        let path = temp_file("noisy", "a\n");
+        format_file("head -c 200000 /dev/zero >&2", &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,9 +1,65 @@
 //! Window geometry calculations for popup positioning
 
+use serde::Serialize;
+
 use super::accessibility::{self, ElementFrame};
+use super::error::EditError;
 use super::terminals::WindowGeometry;
 use crate::config::NvimEditSettings;
 
+/// Which frame the popup is positioned against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopupAnchor {
+    /// Below (or above) the focused text field
+    Element,
+    /// Centered in the focused window, when the field has no frame (e.g., web views)
+    Window,
+}
+
+/// Where the popup would open for the current focus, with the inputs it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct PopupGeometryPreview {
+    pub app_bundle_id: String,
+    pub element_frame: Option<ElementFrame>,
+    pub window_frame: Option<ElementFrame>,
+    pub anchor: Option<PopupAnchor>,
+    pub geometry: Option<WindowGeometry>,
+}
+
+/// Compute the popup geometry for the focused field without capturing text or
+/// spawning an editor. Only accessibility frames are used, so browser fields
+/// without one fall back to the window like they would before scripting kicks in.
+pub fn preview_popup_geometry(settings: &NvimEditSettings) -> Result<PopupGeometryPreview, EditError> {
+    if !crate::keyboard::check_accessibility_permission() {
+        return Err(EditError::PermissionDenied);
+    }
+    let focus_context = accessibility::capture_focus_context().ok_or(EditError::NoFocusedApp)?;
+
+    let element_frame = accessibility::get_focused_element_frame();
+    let window_frame = accessibility::get_focused_window_frame();
+    let anchor = choose_anchor(element_frame.as_ref(), window_frame.as_ref()).map(|(anchor, _)| anchor);
+    let geometry = calculate_popup_geometry(settings, element_frame.clone(), window_frame.clone());
+
+    Ok(PopupGeometryPreview {
+        app_bundle_id: focus_context.app_bundle_id,
+        element_frame,
+        window_frame,
+        anchor,
+        geometry,
+    })
+}
+
+/// The element frame if there is one, otherwise the window frame
+fn choose_anchor<'a>(
+    element_frame: Option<&'a ElementFrame>,
+    window_frame: Option<&'a ElementFrame>,
+) -> Option<(PopupAnchor, &'a ElementFrame)> {
+    element_frame
+        .map(|frame| (PopupAnchor::Element, frame))
+        .or_else(|| window_frame.map(|frame| (PopupAnchor::Window, frame)))
+}
+
 /// Calculate window geometry for popup mode based on element and window frames
 pub fn calculate_popup_geometry(
     settings: &NvimEditSettings,
@@ -15,45 +71,46 @@ pub fn calculate_popup_geometry(
         return None;
     }
 
-    // Try to get element frame from accessibility API
-    let frame_geometry = element_frame.map(|frame| {
-        calculate_geometry_from_element(&frame, settings)
-    });
-
-    // If element frame not available (e.g., web views), center in the focused window
-    let result = frame_geometry.or_else(|| {
-        window_frame.map(|wf| {
-            calculate_geometry_centered_in_window(&wf, settings)
-        })
-    });
-
-    if result.is_none() {
+    // Position relative to the element; if its frame isn't available (e.g., web views),
+    // center in the focused window
+    let Some((anchor, frame)) = choose_anchor(element_frame.as_ref(), window_frame.as_ref()) else {
         log::warn!("No geometry available - window will open at default size/position");
-    }
+        return None;
+    };
+    let screen = accessibility::get_screen_bounds_for_point(frame.x, frame.y);
+
+    let geometry = match anchor {
+        PopupAnchor::Element => calculate_geometry_from_element(frame, screen.as_ref(), settings),
+        PopupAnchor::Window => calculate_geometry_centered_in_window(frame, screen.as_ref(), settings),
+    };
 
-    result
+    // Keep the popup fully on the visible screen
+    Some(match screen {
+        Some(ref screen) => clamp_to_screen(geometry, screen, settings.popup_margin),
+        None => geometry,
+    })
 }
 
 /// Calculate geometry positioning the popup relative to an element
-fn calculate_geometry_from_element(frame: &ElementFrame, settings: &NvimEditSettings) -> WindowGeometry {
+fn calculate_geometry_from_element(
+    frame: &ElementFrame,
+    screen: Option<&ElementFrame>,
+    settings: &NvimEditSettings,
+) -> WindowGeometry {
     let gap = 5;
     let x = frame.x as i32;
 
     // Use configured width, or match text field width (min 400)
-    let width = if settings.popup_width > 0 {
-        settings.popup_width
-    } else {
-        (frame.width as u32).max(400)
-    };
+    let width = configured_width(settings, screen).unwrap_or((frame.width as u32).max(400));
 
-    let height = settings.popup_height;
+    let height = configured_height(settings, screen);
     let popup_height = height as i32;
 
     // Default: position below the text field
     let mut y = (frame.y + frame.height) as i32 + gap;
 
-    // Get screen bounds to check if popup fits
-    if let Some(screen) = accessibility::get_screen_bounds_for_point(frame.x, frame.y) {
+    // Check against the screen bounds if the popup fits
+    if let Some(screen) = screen {
         let screen_bottom = (screen.y + screen.height) as i32;
         let popup_bottom = y + popup_height;
 
@@ -79,14 +136,15 @@ fn calculate_geometry_from_element(frame: &ElementFrame, settings: &NvimEditSett
 }
 
 /// Calculate geometry centering the popup in a window
-fn calculate_geometry_centered_in_window(wf: &ElementFrame, settings: &NvimEditSettings) -> WindowGeometry {
-    let width = if settings.popup_width > 0 {
-        settings.popup_width
-    } else {
-        500 // Default width for web views
-    };
+fn calculate_geometry_centered_in_window(
+    wf: &ElementFrame,
+    screen: Option<&ElementFrame>,
+    settings: &NvimEditSettings,
+) -> WindowGeometry {
+    // Default width for web views
+    let width = configured_width(settings, screen).unwrap_or(500);
 
-    let height = settings.popup_height;
+    let height = configured_height(settings, screen);
 
     // Center popup in the focused window
     let x = (wf.x + (wf.width - width as f64) / 2.0) as i32;
@@ -95,3 +153,158 @@ fn calculate_geometry_centered_in_window(wf: &ElementFrame, settings: &NvimEditS
     log::info!("Using window frame geometry (centered): x={}, y={}, w={}, h={}", x, y, width, height);
     WindowGeometry { x, y, width, height }
 }
+
+/// Configured popup width in pixels, or None to size by the text field.
+/// `popup_width_pct` of the screen takes precedence over `popup_width`.
+fn configured_width(settings: &NvimEditSettings, screen: Option<&ElementFrame>) -> Option<u32> {
+    match screen {
+        Some(screen) if settings.popup_width_pct > 0 => {
+            Some(percent_of(screen.width, settings.popup_width_pct))
+        }
+        _ if settings.popup_width > 0 => Some(settings.popup_width),
+        _ => None,
+    }
+}
+
+/// Configured popup height in pixels; `popup_height_pct` takes precedence over `popup_height`
+fn configured_height(settings: &NvimEditSettings, screen: Option<&ElementFrame>) -> u32 {
+    match screen {
+        Some(screen) if settings.popup_height_pct > 0 => {
+            percent_of(screen.height, settings.popup_height_pct)
+        }
+        _ => settings.popup_height,
+    }
+}
+
+/// `pct` percent (capped at 100) of a screen extent, in pixels
+fn percent_of(extent: f64, pct: u32) -> u32 {
+    (extent * pct.min(100) as f64 / 100.0) as u32
+}
+
+/// Move and shrink the popup so it stays inside `screen` (the visible frame, below
+/// the menu bar) with `margin` pixels to spare on each side
+fn clamp_to_screen(geometry: WindowGeometry, screen: &ElementFrame, margin: u32) -> WindowGeometry {
+    let margin = margin as f64;
+    let left = screen.x + margin;
+    let top = screen.y + margin;
+    let available_width = (screen.width - 2.0 * margin).max(1.0);
+    let available_height = (screen.height - 2.0 * margin).max(1.0);
+
+    let width = (geometry.width as f64).min(available_width);
+    let height = (geometry.height as f64).min(available_height);
+    let x = (geometry.x as f64).clamp(left, left + available_width - width);
+    let y = (geometry.y as f64).clamp(top, top + available_height - height);
+
+    let clamped = WindowGeometry {
+        x: x as i32,
+        y: y as i32,
+        width: width as u32,
+        height: height as u32,
+    };
+    if clamped != geometry {
+        log::info!("Clamped popup geometry to screen: {:?} -> {:?}", geometry, clamped);
+    }
+    clamped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1440x900 screen with a 25px menu bar
+    fn screen_below_menu_bar() -> ElementFrame {
+        ElementFrame {
+            x: 0.0,
+            y: 25.0,
+            width: 1440.0,
+            height: 875.0,
+        }
+    }
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry { x, y, width, height }
+    }
+
+    #[test]
+    fn test_clamp_keeps_popup_inside_margins() {
+        let screen = screen_below_menu_bar();
+        assert_eq!(
+            clamp_to_screen(geometry(100, 200, 600, 300), &screen, 8),
+            geometry(100, 200, 600, 300)
+        );
+
+        // Flush against the right edge and under the menu bar
+        assert_eq!(
+            clamp_to_screen(geometry(1200, 0, 600, 300), &screen, 8),
+            geometry(1440 - 8 - 600, 25 + 8, 600, 300)
+        );
+
+        // Off the bottom-left corner
+        assert_eq!(
+            clamp_to_screen(geometry(-50, 800, 600, 300), &screen, 8),
+            geometry(8, 900 - 8 - 300, 600, 300)
+        );
+    }
+
+    #[test]
+    fn test_clamp_shrinks_popup_larger_than_screen() {
+        let screen = screen_below_menu_bar();
+        assert_eq!(
+            clamp_to_screen(geometry(0, 0, 2000, 1200), &screen, 10),
+            geometry(10, 35, 1420, 855)
+        );
+    }
+
+    #[test]
+    fn test_anchor_prefers_element_frame() {
+        let element = screen_below_menu_bar();
+        let window = screen_below_menu_bar();
+        assert_eq!(
+            choose_anchor(Some(&element), Some(&window)).map(|(anchor, _)| anchor),
+            Some(PopupAnchor::Element)
+        );
+        assert_eq!(
+            choose_anchor(None, Some(&window)).map(|(anchor, _)| anchor),
+            Some(PopupAnchor::Window)
+        );
+        assert!(choose_anchor(None, None).is_none());
+    }
+
+    #[test]
+    fn test_element_near_bottom_opens_above() {
+        let settings = NvimEditSettings::default();
+        let screen = screen_below_menu_bar();
+        let element = ElementFrame {
+            x: 100.0,
+            y: 800.0,
+            width: 500.0,
+            height: 30.0,
+        };
+        let popup = calculate_geometry_from_element(&element, Some(&screen), &settings);
+        assert_eq!(popup.y, 800 - settings.popup_height as i32 - 5);
+    }
+
+    #[test]
+    fn test_percentage_size_takes_precedence_over_pixels() {
+        let screen = screen_below_menu_bar();
+        let mut settings = NvimEditSettings {
+            popup_width: 600,
+            popup_height: 300,
+            ..Default::default()
+        };
+        assert_eq!(configured_width(&settings, Some(&screen)), Some(600));
+        assert_eq!(configured_height(&settings, Some(&screen)), 300);
+
+        settings.popup_width_pct = 80;
+        settings.popup_height_pct = 40;
+        assert_eq!(configured_width(&settings, Some(&screen)), Some(1152));
+        assert_eq!(configured_height(&settings, Some(&screen)), 350);
+
+        // Without a screen to resolve against, fall back to pixels
+        assert_eq!(configured_width(&settings, None), Some(600));
+        assert_eq!(configured_height(&settings, None), 300);
+
+        settings.popup_width_pct = 150;
+        assert_eq!(configured_width(&settings, Some(&screen)), Some(1440));
+    }
+}
@@ -0,0 +1,189 @@
+//! Applies live sync updates on a dedicated thread, latest buffer only
+//!
+//! nvim sends a buffer event per change, and applying one (browser JS through
+//! AppleScript, or AX) can take longer than the gap between keystrokes. The RPC
+//! handler only stores the latest buffer and returns; the worker applies it once
+//! the previous update is done. A burst of keystrokes costs one update instead
+//! of one each, and the text field never lags behind a queue of stale states.
+
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long `finish` waits for the update in flight and the last buffer. An update
+/// that takes longer (a hung browser) is left to finish on the worker thread.
+const FINISH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Update counts and timing for one session, logged when it ends
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SyncStats {
+    /// Buffers written to the text field
+    pub applied: u64,
+    /// Buffers replaced by a newer one before they were applied
+    pub superseded: u64,
+    /// Time spent applying updates
+    pub busy: Duration,
+    /// Time from the first update to the last one finishing
+    pub elapsed: Duration,
+}
+
+impl SyncStats {
+    /// Applied updates per second of wall time
+    pub fn rate(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.applied as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+impl std::fmt::Display for SyncStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let average = self.busy.checked_div(self.applied as u32).unwrap_or_default();
+        write!(
+            f,
+            "{} applied, {} superseded in {:.1?} ({:.1}/s, {:.1?} per update)",
+            self.applied,
+            self.superseded,
+            self.elapsed,
+            self.rate(),
+            average
+        )
+    }
+}
+
+#[derive(Default)]
+struct Pending {
+    lines: Option<Vec<String>>,
+    superseded: u64,
+    closed: bool,
+}
+
+/// Handle to the worker thread; `submit` never blocks on an update being applied
+pub struct LiveSyncWorker {
+    pending: Arc<(Mutex<Pending>, Condvar)>,
+    done: Mutex<Option<mpsc::Receiver<SyncStats>>>,
+}
+
+impl LiveSyncWorker {
+    /// Start the worker; `apply` runs on its thread, one buffer at a time
+    pub fn spawn(mut apply: impl FnMut(Vec<String>) + Send + 'static) -> Self {
+        let pending = Arc::new((Mutex::new(Pending::default()), Condvar::new()));
+        let worker_pending = Arc::clone(&pending);
+        let (done_tx, done) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (lock, ready) = &*worker_pending;
+            let mut stats = SyncStats::default();
+            let mut first_update: Option<Instant> = None;
+            loop {
+                let lines = {
+                    let mut state = lock.lock().unwrap();
+                    while state.lines.is_none() && !state.closed {
+                        state = ready.wait(state).unwrap();
+                    }
+                    match state.lines.take() {
+                        Some(lines) => lines,
+                        None => {
+                            stats.superseded = state.superseded;
+                            break;
+                        }
+                    }
+                };
+
+                let started = Instant::now();
+                let first = *first_update.get_or_insert(started);
+                apply(lines);
+                stats.applied += 1;
+                stats.busy += started.elapsed();
+                stats.elapsed = first.elapsed();
+            }
+            let _ = done_tx.send(stats);
+        });
+
+        Self {
+            pending,
+            done: Mutex::new(Some(done)),
+        }
+    }
+
+    /// Queue `lines`, replacing any buffer that hasn't been applied yet
+    pub fn submit(&self, lines: Vec<String>) {
+        let (lock, ready) = &*self.pending;
+        let mut state = lock.lock().unwrap();
+        if state.lines.replace(lines).is_some() {
+            state.superseded += 1;
+        }
+        ready.notify_one();
+    }
+
+    /// Apply the last queued buffer, stop the worker and return its stats.
+    /// Gives up after `FINISH_TIMEOUT`, so a hung update can't block the caller.
+    pub fn finish(&self) -> Option<SyncStats> {
+        self.finish_within(FINISH_TIMEOUT)
+    }
+
+    fn finish_within(&self, timeout: Duration) -> Option<SyncStats> {
+        {
+            let (lock, ready) = &*self.pending;
+            lock.lock().unwrap().closed = true;
+            ready.notify_one();
+        }
+        let done = self.done.lock().unwrap().take()?;
+        match done.recv_timeout(timeout) {
+            Ok(stats) => Some(stats),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::warn!("Live sync update still running after {:?}, not waiting for it", timeout);
+                None
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bursts_are_coalesced_to_the_latest_buffer() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let applied_by_worker = Arc::clone(&applied);
+        let worker = LiveSyncWorker::spawn(move |lines: Vec<String>| {
+            thread::sleep(Duration::from_millis(50));
+            applied_by_worker.lock().unwrap().push(lines.join("\n"));
+        });
+
+        for i in 0..20 {
+            worker.submit(vec![format!("text {}", i)]);
+            thread::sleep(Duration::from_millis(2));
+        }
+        let stats = worker.finish().unwrap();
+
+        let applied = applied.lock().unwrap();
+        assert_eq!(applied.last().map(String::as_str), Some("text 19"));
+        assert!(applied.len() < 20, "applied {} of 20", applied.len());
+        assert_eq!(stats.applied as usize, applied.len());
+        assert_eq!(stats.applied + stats.superseded, 20);
+    }
+
+    #[test]
+    fn test_finish_gives_up_on_a_hung_update() {
+        let worker = LiveSyncWorker::spawn(|_| thread::sleep(Duration::from_secs(5)));
+        worker.submit(vec!["text".to_string()]);
+        thread::sleep(Duration::from_millis(20));
+
+        let started = Instant::now();
+        assert!(worker.finish_within(Duration::from_millis(100)).is_none());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_finish_without_updates() {
+        let worker = LiveSyncWorker::spawn(|_| panic!("nothing to apply"));
+        let stats = worker.finish().unwrap();
+        assert_eq!(stats.applied, 0);
+        assert_eq!(stats.rate(), 0.0);
+        assert!(worker.finish().is_none());
+    }
+}
@@ -0,0 +1,324 @@
+//! Markdown round-trip for rich text fields (`rich_text_mode`)
+//!
+//! Only a small subset survives the round-trip: bold, italic, links, line
+//! breaks and flat bulleted/numbered lists. Any other markup (headings, colors,
+//! tables, images, nested lists) is dropped and only its text is kept, one line
+//! per block element. Markdown characters in the text are backslash-escaped.
+
+/// An open list while converting HTML: `None` for bullets, or the next number
+type ListState = Option<usize>;
+
+/// Elements that start a new line of text (lists and items are handled separately)
+const BLOCK_TAGS: &[&str] = &[
+    "div", "p", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "pre", "table", "tr",
+];
+
+/// Characters that would be read as Markdown if left in text unescaped
+const MARKDOWN_CHARS: &[char] = &['\\', '*', '_', '[', ']', '`'];
+
+/// Convert the `innerHTML` of a rich text field to Markdown
+pub fn html_to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut lists: Vec<ListState> = Vec::new();
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(tag_start) = rest.find('<') else {
+            push_text(&mut out, rest);
+            break;
+        };
+        push_text(&mut out, &rest[..tag_start]);
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            // Unterminated tag: keep it as text
+            push_text(&mut out, &rest[tag_start..]);
+            break;
+        };
+        let tag = &rest[tag_start + 1..tag_start + tag_len];
+        rest = &rest[tag_start + tag_len + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match (name.as_str(), closing) {
+            ("b" | "strong", _) => out.push_str("**"),
+            ("i" | "em", _) => out.push('*'),
+            ("a", false) => {
+                links.push(attribute(tag, "href").unwrap_or_default());
+                out.push('[');
+            }
+            ("a", true) => {
+                let href = links.pop().unwrap_or_default();
+                out.push_str(&format!("]({})", href));
+            }
+            ("br", _) => out.push('\n'),
+            (block, _) if BLOCK_TAGS.contains(&block) => end_line(&mut out),
+            ("ul", false) => {
+                end_line(&mut out);
+                lists.push(None);
+            }
+            ("ol", false) => {
+                end_line(&mut out);
+                lists.push(Some(1));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                end_line(&mut out);
+            }
+            ("li", false) => {
+                end_line(&mut out);
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        out.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            ("li", true) => end_line(&mut out),
+            _ => {}
+        }
+    }
+
+    tidy_blank_lines(&out)
+}
+
+/// Convert Markdown back to HTML for a rich text field.
+/// Each line becomes a `<div>`, matching what contenteditable fields produce.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut open_list: Option<&str> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let item = match list_item(trimmed) {
+            Some((list, text)) => {
+                if open_list != Some(list) {
+                    if let Some(previous) = open_list {
+                        html.push_str(&format!("</{}>", previous));
+                    }
+                    html.push_str(&format!("<{}>", list));
+                    open_list = Some(list);
+                }
+                Some(text)
+            }
+            None => {
+                if let Some(previous) = open_list.take() {
+                    html.push_str(&format!("</{}>", previous));
+                }
+                None
+            }
+        };
+
+        match item {
+            Some(text) => html.push_str(&format!("<li>{}</li>", inline_to_html(text))),
+            None if line.trim().is_empty() => html.push_str("<div><br></div>"),
+            None => html.push_str(&format!("<div>{}</div>", inline_to_html(line))),
+        }
+    }
+    if let Some(list) = open_list {
+        html.push_str(&format!("</{}>", list));
+    }
+    html
+}
+
+/// Append an HTML text node: decode entities, collapse whitespace like a browser
+/// and escape Markdown characters
+fn push_text(out: &mut String, text: &str) {
+    let decoded = decode_entities(text);
+    for c in decoded.chars() {
+        if c.is_whitespace() && c != '\u{a0}' {
+            if !out.is_empty() && !out.ends_with([' ', '\n']) {
+                out.push(' ');
+            }
+        } else {
+            if MARKDOWN_CHARS.contains(&c) {
+                out.push('\\');
+            }
+            out.push(if c == '\u{a0}' { ' ' } else { c });
+        }
+    }
+}
+
+/// Start a new line unless already at the start of one
+fn end_line(out: &mut String) {
+    while out.ends_with(' ') {
+        out.pop();
+    }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Trim trailing spaces on each line and keep at most one blank line in a row
+fn tidy_blank_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Value of a quoted attribute in a tag's source, e.g. `href` in `a href="..."`.
+/// The name must start after whitespace, so `href` doesn't match `data-href`.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let pattern = format!("{}=", name);
+    let (index, _) = lower.match_indices(&pattern).find(|(index, _)| {
+        lower[..*index].ends_with(|c: char| c.is_whitespace())
+    })?;
+    let rest = &tag[index + pattern.len()..];
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let end = rest[1..].find(quote)?;
+    Some(decode_entities(&rest[1..=end]))
+}
+
+/// Decode the entities contenteditable fields commonly produce
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&nbsp;", "\u{a0}")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Escape text for HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Recognize a list item line: `- text`, `* text` or `1. text`
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(("ul", text));
+    }
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ").map(|text| ("ol", text))
+}
+
+/// Convert inline Markdown (links, bold, italic, backslash escapes) to HTML.
+/// A marker without a closing partner stays literal text.
+fn inline_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    let mut bold = false;
+    let mut italic = false;
+
+    while let Some(c) = rest.chars().next() {
+        let escaped = rest.strip_prefix('\\').and_then(|after| after.chars().next());
+        if let Some(escaped) = escaped.filter(|e| MARKDOWN_CHARS.contains(e)) {
+            html.push_str(&escape_html(&escaped.to_string()));
+            rest = &rest[1 + escaped.len_utf8()..];
+        } else if let Some(after) = rest.strip_prefix("**").filter(|after| bold || after.contains("**")) {
+            html.push_str(if bold { "</strong>" } else { "<strong>" });
+            bold = !bold;
+            rest = after;
+        } else if c == '*' && (italic || rest[1..].contains('*')) {
+            html.push_str(if italic { "</em>" } else { "<em>" });
+            italic = !italic;
+            rest = &rest[1..];
+        } else if let Some((label, href, after)) = (c == '[').then(|| link(rest)).flatten() {
+            html.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(href),
+                inline_to_html(label)
+            ));
+            rest = after;
+        } else {
+            html.push_str(&escape_html(&rest[..c.len_utf8()]));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    // Close unbalanced markers so the field's HTML stays well-formed
+    if italic {
+        html.push_str("</em>");
+    }
+    if bold {
+        html.push_str("</strong>");
+    }
+    html
+}
+
+/// Parse `[label](href)` at the start of `text`, returning the rest after it
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let label_end = text.find("](")?;
+    let href_end = text[label_end + 2..].find(')')? + label_end + 2;
+    Some((&text[1..label_end], &text[label_end + 2..href_end], &text[href_end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown_subset() {
+        let html = "<div>Hi <b>there</b>, see <a href=\"https://x.dev/?a=1&amp;b=2\">the <i>docs</i></a></div>\
+                    <div><br></div><ul><li>one</li><li>two</li></ul><ol><li>first</li><li>second</li></ol>";
+        assert_eq!(
+            html_to_markdown(html),
+            "Hi **there**, see [the *docs*](https://x.dev/?a=1&b=2)\n\n- one\n- two\n1. first\n2. second"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_markup_keeps_text() {
+        let html = "<h1 style=\"color: red\">Title</h1><table><tr><td>cell</td></tr></table>";
+        assert_eq!(html_to_markdown(html), "Title\ncell");
+        assert_eq!(html_to_markdown("<p>one</p><p>two</p>"), "one\ntwo");
+        assert_eq!(html_to_markdown("a &lt;b&gt;&nbsp;c"), "a <b> c");
+    }
+
+    #[test]
+    fn test_markdown_to_html_subset() {
+        let markdown = "Hi **there**, see [the *docs*](https://x.dev)\n\n- one\n- two\n1. first";
+        assert_eq!(
+            markdown_to_html(markdown),
+            "<div>Hi <strong>there</strong>, see <a href=\"https://x.dev\">the <em>docs</em></a></div>\
+             <div><br></div><ul><li>one</li><li>two</li></ul><ol><li>first</li></ol>"
+        );
+        assert_eq!(markdown_to_html("a < b & *open"), "<div>a &lt; b &amp; *open</div>");
+        assert_eq!(markdown_to_html(r"2 \* 3 \[x\]"), "<div>2 * 3 [x]</div>");
+    }
+
+    #[test]
+    fn test_markdown_characters_in_text_are_escaped() {
+        let html = "<div>2 * 3 = snake_case [x] `code` a\\b é</div>";
+        let markdown = html_to_markdown(html);
+        assert_eq!(markdown, r"2 \* 3 = snake\_case \[x\] \`code\` a\\b é");
+        assert_eq!(markdown_to_html(&markdown), html);
+    }
+
+    #[test]
+    fn test_attribute_needs_whole_name() {
+        assert_eq!(attribute("a data-href=\"x\" href=\"y\"", "href"), Some("y".to_string()));
+        assert_eq!(attribute("a data-href=\"x\"", "href"), None);
+    }
+
+    #[test]
+    fn test_round_trip_is_stable() {
+        let markdown = "Hello **bold** and *italic*\n\n- [link](https://example.com)\n- item";
+        assert_eq!(html_to_markdown(&markdown_to_html(markdown)), markdown);
+    }
+}
@@ -2,32 +2,51 @@
 
 pub mod accessibility;
 mod browser_scripting;
-mod clipboard;
+mod cleanup;
+pub mod clipboard;
+mod debug_overlay;
+mod error;
+mod format;
 mod geometry;
+mod live_sync;
+mod markdown;
 pub mod prewarm;
 mod rpc;
+mod sanitize;
 mod session;
+pub mod socket;
+mod status;
 pub mod terminals;
 mod text_capture;
+pub mod text_focus;
 
-pub use session::EditSessionManager;
+pub(crate) use browser_scripting::minify_js;
+pub use cleanup::sweep_stale_files;
+pub use error::EditError;
+pub use geometry::{preview_popup_geometry, PopupGeometryPreview};
+pub use session::{EditSessionInfo, EditSessionManager};
 
-use crate::config::{NvimEditSettings, Settings};
+use crate::config::{CancelBehavior, NvimEditSettings, Settings};
+use status::SyncStatus;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 /// Trigger the "Edit with Neovim" flow
-/// `shared_settings` is optional - if provided, filetype changes will update the in-memory state
+/// Learned filetypes are saved through `shared_settings`, the app's settings state
 pub fn trigger_nvim_edit(
     manager: Arc<EditSessionManager>,
-    settings: NvimEditSettings,
-    shared_settings: Option<Arc<Mutex<Settings>>>,
-) -> Result<(), String> {
+    mut settings: NvimEditSettings,
+    shared_settings: Arc<Mutex<Settings>>,
+) -> Result<(), EditError> {
+    if !crate::keyboard::check_accessibility_permission() {
+        return Err(EditError::PermissionDenied);
+    }
+
     // 1. Capture focus context (which app we're in)
     let focus_context = accessibility::capture_focus_context()
-        .ok_or("No focused application found")?;
+        .ok_or(EditError::NoFocusedApp)?;
     log::info!("Captured focus context: {:?}", focus_context);
 
     // 2. Capture geometry info BEFORE any clipboard operations (which may change focus)
@@ -43,11 +62,35 @@ pub fn trigger_nvim_edit(
         element_frame,
         settings.clipboard_mode,
     );
-    let text = capture_result.text;
+    let mut text = capture_result.text;
+    if settings.sanitize_control_chars {
+        let stripped = sanitize::strip_control_chars(&text);
+        if stripped.len() != text.len() {
+            log::info!("Stripped {} bytes of control characters", text.len() - stripped.len());
+            text = stripped;
+        }
+    }
+    let mut line_ending = capture_result.line_ending;
     let element_frame = capture_result.element_frame;
-    let initial_cursor = capture_result.cursor_position;
+    let mut initial_cursor = capture_result.cursor_position;
     let browser_type = capture_result.browser_type;
 
+    // Rich text fields are edited as Markdown and restored as HTML
+    let rich_text_browser = browser_type.filter(|_| settings.rich_text_mode).and_then(|bt| {
+        let html = browser_scripting::get_browser_focused_html(bt)?;
+        text = markdown::html_to_markdown(&html);
+        Some(bt)
+    });
+    if rich_text_browser.is_some() {
+        log::info!("Editing rich text field as Markdown ({} chars)", text.len());
+        // Live sync and cursor mapping work on plain text, which would drop the formatting
+        settings.live_sync_enabled = false;
+        initial_cursor = None;
+        line_ending = text_capture::LineEnding::Lf;
+    }
+
+    // The browser reports UTF-16 columns; nvim and its command line use byte columns
+    let initial_cursor = initial_cursor.map(|cursor| cursor.utf16_to_byte_column(&text));
     if let Some(ref cursor) = initial_cursor {
         log::info!("Initial cursor position: line={}, col={}", cursor.line, cursor.column);
     }
@@ -67,26 +110,45 @@ pub fn trigger_nvim_edit(
     if let Some(ref ft) = saved_filetype {
         log::info!("Found saved filetype for domain '{}': {}", domain_key, ft);
     }
+    let saved_filetype = saved_filetype.or_else(|| rich_text_browser.map(|_| "markdown".to_string()));
 
     // 6. Calculate window geometry if popup mode is enabled
+    let debug_frames = settings
+        .edit_popup_debug
+        .then(|| (element_frame.clone(), window_frame.clone()));
     let geometry = geometry::calculate_popup_geometry(&settings, element_frame, window_frame);
     log::info!("Final geometry: {:?}", geometry);
 
+    if let Some((element_frame, window_frame)) = debug_frames {
+        debug_overlay::show_geometry(element_frame, window_frame, geometry.clone());
+    }
+
+    // Kept to briefly re-focus the original app after spawning
+    let original_focus = settings
+        .reactivate_original_immediately
+        .then(|| focus_context.clone());
+
     // 7. Start edit session (writes temp file, spawns terminal)
     let session_id = manager.start_session(
         focus_context,
         text.clone(),
+        line_ending,
         settings.clone(),
         geometry,
         domain_key,
         saved_filetype.as_deref(),
+        initial_cursor.as_ref().map(|cursor| (cursor.line, cursor.column)),
     )?;
     log::info!("Started edit session: {}", session_id);
 
     // 8. Start RPC connection and live sync in background
     // If clipboard_mode is enabled, skip live sync entirely
     let session = manager.get_session(&session_id)
-        .ok_or("Session not found immediately after creation")?;
+        .ok_or(EditError::SessionNotFound)?;
+
+    if let Some(original_focus) = original_focus {
+        reactivate_original(&original_focus, &session);
+    }
 
     let live_sync_worked = Arc::new(AtomicBool::new(false));
     let clipboard_mode = settings.clipboard_mode;
@@ -94,6 +156,7 @@ pub fn trigger_nvim_edit(
     let rpc_handle = if clipboard_mode {
         // In clipboard mode, don't do live sync - but still wait for editor to exit
         log::info!("Clipboard mode enabled, skipping live sync");
+        status::emit_status(&session_id, SyncStatus::Clipboard);
         let process_id = session.process_id;
         thread::spawn(move || {
             wait_for_editor_exit(process_id);
@@ -116,6 +179,7 @@ pub fn trigger_nvim_edit(
         rpc_handle,
         live_sync_worked,
         browser_type,
+        rich_text_browser,
         clipboard_mode,
         shared_settings,
     );
@@ -123,6 +187,21 @@ pub fn trigger_nvim_edit(
     Ok(())
 }
 
+/// Delay before the editor is brought forward after re-focusing the original app
+const EDITOR_FOREGROUND_DELAY: Duration = Duration::from_millis(300);
+
+/// Re-focus the original app right after spawning so apps that clear their
+/// selection on blur keep it, then bring the editor window forward
+fn reactivate_original(original_focus: &accessibility::FocusContext, session: &session::EditSession) {
+    log::info!("Re-focusing original app before showing the editor");
+    if let Err(e) = accessibility::restore_focus(original_focus) {
+        log::warn!("Failed to re-focus original app: {}", e);
+    }
+
+    thread::sleep(EDITOR_FOREGROUND_DELAY);
+    terminals::focus_popup_window(&session.terminal_type, session.window_title.as_deref());
+}
+
 /// Result from RPC handler including final cursor position and filetype
 struct RpcResult {
     final_cursor: Option<browser_scripting::CursorPosition>,
@@ -159,14 +238,17 @@ fn spawn_rpc_handler(
     browser_type: Option<browser_scripting::BrowserType>,
     initial_cursor: Option<browser_scripting::CursorPosition>,
 ) -> thread::JoinHandle<Option<RpcResult>> {
+    let session_id = session.id;
     let socket_path = session.socket_path.clone();
     let focus_element = session.focus_context.focused_element.clone();
+    let line_ending = session.line_ending;
     let live_sync_enabled = settings.live_sync_enabled;
     let process_id = session.process_id;
 
     thread::spawn(move || {
         if !live_sync_enabled {
             log::info!("Live sync disabled, skipping RPC connection");
+            status::emit_status(&session_id, SyncStatus::Clipboard);
             // Still need to wait for editor to exit
             wait_for_editor_exit(process_id);
             return None;
@@ -190,20 +272,31 @@ fn spawn_rpc_handler(
             let element_for_callback = focus_element.clone();
             let cached_element_id = Arc::new(std::sync::Mutex::new(None::<String>));
             let cached_id_for_callback = Arc::clone(&cached_element_id);
+            // Whether the indicator currently shows live sync, to only emit changes
+            let showing_live = AtomicBool::new(true);
 
-            let on_lines = Arc::new(move |lines: Vec<String>| {
-                handle_live_sync_update(
+            // Updates are applied off the RPC handler, latest buffer only
+            let worker = Arc::new(live_sync::LiveSyncWorker::spawn(move |lines: Vec<String>| {
+                let applied = handle_live_sync_update(
                     &lines,
+                    line_ending,
                     browser_type,
                     element_for_callback.as_ref(),
                     &sync_flag,
                     &cached_id_for_callback,
                 );
-            });
+                if showing_live.swap(applied, Ordering::SeqCst) != applied {
+                    let status = if applied { SyncStatus::Live } else { SyncStatus::Clipboard };
+                    status::emit_status(&session_id, status);
+                }
+            }));
+            let worker_for_rpc = Arc::clone(&worker);
+            let on_lines = Arc::new(move |lines: Vec<String>| worker_for_rpc.submit(lines));
 
             match rpc::connect_to_nvim(&socket_path, on_lines).await {
                 Ok(rpc_session) => {
                     log::info!("RPC connected, live sync enabled");
+                    status::emit_status(&session_id, SyncStatus::Live);
 
                     // Set nvim cursor to match browser's initial cursor position
                     if let Some(cursor) = initial_cursor {
@@ -266,10 +359,17 @@ fn spawn_rpc_handler(
 
                     let _ = rpc_session.detach().await;
 
+                    // Apply the last buffer before the completion handler restores text
+                    if let Some(stats) = worker.finish() {
+                        log::info!("Live sync: {}", stats);
+                    }
+
                     Some(RpcResult { final_cursor: last_cursor, filetype })
                 }
                 Err(e) => {
                     log::warn!("RPC connection failed, falling back to clipboard-only mode: {}", e);
+                    worker.finish();
+                    status::emit_status(&session_id, SyncStatus::Clipboard);
                     None
                 }
             }
@@ -277,15 +377,17 @@ fn spawn_rpc_handler(
     })
 }
 
-/// Handle a live sync update from nvim
+/// Handle a live sync update from nvim.
+/// Returns whether the text field was updated.
 fn handle_live_sync_update(
     lines: &[String],
+    line_ending: text_capture::LineEnding,
     browser_type: Option<browser_scripting::BrowserType>,
     focus_element: Option<&accessibility::AXElementHandle>,
     sync_flag: &AtomicBool,
     cached_element_id: &std::sync::Mutex<Option<String>>,
-) {
-    let text = lines.join("\n");
+) -> bool {
+    let text = line_ending.restore(&lines.join("\n")).into_owned();
     let preview: String = text.lines().take(3).collect::<Vec<_>>().join("\\n");
     log::info!("Live sync update: {} lines, {} chars, browser={:?}, preview: {}",
         lines.len(), text.len(), browser_type, preview);
@@ -305,13 +407,15 @@ fn handle_live_sync_update(
                         *guard = Some(id);
                     }
                 }
-                return;
+                return true;
             }
             Err(e) => {
                 log::info!("Browser live sync failed: {}", e);
                 // Lexical and Monaco editors don't respond to AX value changes,
                 // so skip the AX fallback and rely on clipboard mode
-                if e.contains("unsupported_lexical") || e.contains("monaco_dom") {
+                if matches!(&e, EditError::ScriptRejected(result)
+                    if result.contains("unsupported_lexical") || result.contains("monaco_dom"))
+                {
                     log::info!("Lexical/Monaco editor detected - will use clipboard mode on exit");
                     skip_ax_fallback = true;
                 }
@@ -327,6 +431,7 @@ fn handle_live_sync_update(
                 Ok(()) => {
                     sync_flag.store(true, Ordering::SeqCst);
                     log::info!("Live sync (AX): updated text field ({} chars)", text.len());
+                    return true;
                 }
                 Err(e) => {
                     log::debug!("Accessibility live sync failed: {}", e);
@@ -334,17 +439,23 @@ fn handle_live_sync_update(
             }
         }
     }
+    false
 }
 
+/// Held while a finished session restores focus and its text
+static RESTORE_LOCK: Mutex<()> = Mutex::new(());
+
 /// Spawn the completion handler thread that waits for nvim and restores text
+#[allow(clippy::too_many_arguments)]
 fn spawn_completion_handler(
     manager: Arc<EditSessionManager>,
     session_id: uuid::Uuid,
     rpc_handle: thread::JoinHandle<Option<RpcResult>>,
     live_sync_worked: Arc<AtomicBool>,
     browser_type: Option<browser_scripting::BrowserType>,
+    rich_text_browser: Option<browser_scripting::BrowserType>,
     clipboard_mode: bool,
-    shared_settings: Option<Arc<Mutex<Settings>>>,
+    shared_settings: Arc<Mutex<Settings>>,
 ) {
     thread::spawn(move || {
         let Some(session) = manager.get_session(&session_id) else {
@@ -359,22 +470,27 @@ fn spawn_completion_handler(
         let final_cursor = rpc_result.as_ref().and_then(|r| r.final_cursor);
         let final_filetype = rpc_result.and_then(|r| r.filetype);
 
-        // Save the filetype for this domain if we got one
-        if let Some(ref ft) = final_filetype {
-            log::info!("Saving filetype '{}' for domain '{}'", ft, session.domain_key);
+        if manager.get_session(&session_id).is_none() {
+            log::info!("Edit session {} was killed, skipping restore", session_id);
+            status::emit_status(&session_id, SyncStatus::Ended);
+            return;
+        }
 
-            // Update in-memory settings if we have shared state
-            if let Some(ref shared) = shared_settings {
-                let mut settings = shared.lock().unwrap();
-                settings.nvim_edit.set_filetype_for_domain(session.domain_key.clone(), ft.clone());
-                log::info!("Updated in-memory settings with filetype");
-            } else {
-                // Fallback: just save to file (will be loaded on next restart)
-                let mut settings = Settings::load();
+        // Save the filetype for this domain if we got one, unless learning is turned off
+        let learn_filetype = shared_settings.lock().unwrap().nvim_edit.learn_filetype;
+        if let Some(ft) = final_filetype.as_ref().filter(|_| learn_filetype) {
+            log::info!("Saving filetype '{}' for domain '{}'", ft, session.domain_key);
+            if let Err(e) = Settings::update(&shared_settings, |settings| {
                 settings.nvim_edit.set_filetype_for_domain(session.domain_key.clone(), ft.clone());
+                Ok(())
+            }) {
+                log::warn!("Failed to save filetype: {}", e);
             }
         }
 
+        // Sessions in other apps may finish at the same time; restoring focus and
+        // pasting must not interleave or keystrokes land in the wrong app
+        let _restore_guard = RESTORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         log::info!("Nvim exited, restoring focus");
 
         // Small delay to let the system settle after window close
@@ -384,6 +500,11 @@ fn spawn_completion_handler(
         for attempt in 0..3 {
             match accessibility::restore_focus(&session.focus_context) {
                 Ok(()) => break,
+                Err(e @ EditError::AppNotRunning(_)) => {
+                    // The app quit while editing, retrying cannot bring it back
+                    log::warn!("Not restoring focus: {}", e);
+                    break;
+                }
                 Err(e) => {
                     if attempt < 2 {
                         log::info!("Retry {} restoring focus: {}", attempt + 1, e);
@@ -404,12 +525,18 @@ fn spawn_completion_handler(
         log::info!("Live sync status: {}, clipboard_mode: {}, browser_type: {:?}", if did_live_sync { "worked" } else { "not used" }, clipboard_mode, browser_type);
 
         // Complete the session - skip clipboard paste if live sync worked
-        if let Err(e) = complete_edit_session(&manager, &session_id, did_live_sync) {
-            log::error!("Error completing edit session: {}", e);
-        }
+        let final_text = match complete_edit_session(&manager, &session_id, did_live_sync, rich_text_browser) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("Error completing edit session: {}", e);
+                session.original_text.clone()
+            }
+        };
 
-        // Restore cursor position in browser if we have it
-        if let (Some(bt), Some(cursor)) = (browser_type, final_cursor) {
+        // Restore cursor position in browser if we have it (Markdown lines don't map to rich text)
+        if let (Some(bt), Some(cursor), None) = (browser_type, final_cursor, rich_text_browser) {
+            // nvim's byte column, converted to UTF-16 on the line as nvim left it
+            let cursor = cursor.byte_to_utf16_column(&final_text);
             log::info!("Restoring browser cursor to line={}, col={}", cursor.line, cursor.column);
             match browser_scripting::set_browser_cursor_position(bt, cursor.line, cursor.column) {
                 Ok(()) => log::info!("Browser cursor restored successfully"),
@@ -417,52 +544,62 @@ fn spawn_completion_handler(
             }
         }
 
-        // Clean up socket file
-        let _ = std::fs::remove_file(&session.socket_path);
-
-        // Clean up session
-        manager.remove_session(&session_id);
+        // Clean up the session and its socket (only this session's files)
+        manager.finish_session(&session_id);
+        status::emit_status(&session_id, SyncStatus::Ended);
     });
 }
 
-/// Complete the edit session: clean up temp file and optionally restore text via clipboard
+/// Complete the edit session: clean up temp file and optionally restore text via clipboard.
+/// `rich_text_browser` is set when the field was edited as Markdown and is restored as HTML.
+/// Returns the final text as nvim left it (`\n` line breaks), since the temp file is gone.
 fn complete_edit_session(
     manager: &EditSessionManager,
     session_id: &uuid::Uuid,
     live_sync_worked: bool,
-) -> Result<(), String> {
+    rich_text_browser: Option<browser_scripting::BrowserType>,
+) -> Result<String, EditError> {
     let session = manager.get_session(session_id)
-        .ok_or("Session not found")?;
+        .ok_or_else(|| EditError::UnknownSession(session_id.to_string()))?;
 
     log::info!("Reading temp file: {:?}", session.temp_file);
 
-    // Debug: write to temp file for troubleshooting
-    let debug_log = |msg: &str| {
-        use std::io::Write;
-        if let Ok(mut f) = std::fs::OpenOptions::new().create(true).append(true).open("/tmp/ovim_debug.log") {
-            let _ = writeln!(f, "{}: {}", chrono::Local::now().format("%H:%M:%S%.3f"), msg);
-        }
-        log::info!("{}", msg);
-    };
-
     debug_log(&format!("complete_edit_session: live_sync_worked={}", live_sync_worked));
 
     // Check if file was modified by comparing modification times
     let current_mtime = std::fs::metadata(&session.temp_file)
         .and_then(|m| m.modified())
-        .map_err(|e| format!("Failed to get current file mtime: {}", e))?;
+        .map_err(EditError::io("get current file mtime"))?;
 
     if current_mtime == session.file_mtime {
-        debug_log("File not modified (nvim quit without saving), skipping restoration");
         let _ = std::fs::remove_file(&session.temp_file);
-        return Ok(());
+        if live_sync_worked && session.cancel_behavior == CancelBehavior::RevertToOriginal {
+            debug_log("File not saved, reverting live-synced changes to the original text");
+            let original_text = session.line_ending.restore(&session.original_text);
+            restore_text(&session, &original_text, None)?;
+            return Ok(session.original_text.clone());
+        }
+        debug_log("File not modified (nvim quit without saving), skipping restoration");
+        return Ok(session.original_text.clone());
+    }
+
+    // With live sync the field already has the unformatted text, so only format before pasting
+    if let (Some(command), false) = (&session.format_command, live_sync_worked) {
+        match format::format_file(command, &session.temp_file) {
+            Ok(()) => debug_log("Formatted edited text"),
+            Err(e) => log::warn!("Formatter failed, restoring unformatted text: {}", e),
+        }
     }
 
-    let edited_text = std::fs::read_to_string(&session.temp_file)
-        .map_err(|e| format!("Failed to read temp file: {}", e))?;
+    // nvim may write bytes that aren't UTF-8 (e.g. after `:set fileencoding=latin1`)
+    let saved_text = sanitize::read_lossy(&session.temp_file)
+        .map_err(EditError::io("read temp file"))?;
 
-    // Strip trailing newline that nvim adds (fixeol option)
-    let edited_text = edited_text.strip_suffix('\n').unwrap_or(&edited_text).to_string();
+    // nvim adds a trailing newline (fixeol option) unless the text had one;
+    // handle it per the trailing_newline setting
+    let edited_text = session.trailing_newline.apply(&saved_text, &session.original_text);
+    // nvim saw `\n` line breaks; give the field back its own
+    let edited_text = session.line_ending.restore(edited_text).into_owned();
 
     debug_log(&format!("Read {} chars from temp file", edited_text.len()));
 
@@ -472,16 +609,62 @@ fn complete_edit_session(
     // If live sync worked, text is already in the field - no need for clipboard paste
     if live_sync_worked {
         debug_log("Live sync worked, skipping clipboard paste");
-        return Ok(());
+        return Ok(saved_text);
     }
 
+    restore_text(&session, &edited_text, rich_text_browser)?;
+    Ok(saved_text)
+}
+
+/// Put `text` back into the session's original field
+fn restore_text(
+    session: &session::EditSession,
+    text: &str,
+    rich_text_browser: Option<browser_scripting::BrowserType>,
+) -> Result<(), EditError> {
     // Longer delay for focus to settle - browsers like Chrome need more time
     debug_log("Waiting 300ms for focus to settle...");
     thread::sleep(Duration::from_millis(300));
 
-    debug_log(&format!("Replacing text via clipboard, {} chars", edited_text.len()));
-    clipboard::replace_text_via_clipboard(&edited_text)?;
+    // The address bar is a native text field, so set it via AX instead of pasting
+    if text_capture::is_browser_address_bar(&session.focus_context.app_bundle_id) {
+        if let Some(element) = &session.focus_context.focused_element {
+            match accessibility::set_element_text(element, text) {
+                Ok(()) => {
+                    debug_log("Restored address bar text via accessibility");
+                    return Ok(());
+                }
+                Err(e) => debug_log(&format!("AX restore of address bar failed: {}", e)),
+            }
+        }
+    }
+
+    if let Some(bt) = rich_text_browser {
+        match browser_scripting::set_browser_focused_html(bt, &markdown::markdown_to_html(text)) {
+            Ok(()) => {
+                debug_log("Restored rich text field from Markdown");
+                return Ok(());
+            }
+            Err(e) => debug_log(&format!("Rich text restore failed, pasting Markdown instead: {}", e)),
+        }
+    }
+
+    debug_log(&format!("Replacing text via clipboard, {} chars", text.len()));
+    clipboard::replace_text_via_clipboard(
+        text,
+        session.clipboard_chunk_threshold,
+        session.focus_context.focused_element.as_ref(),
+    )?;
 
-    debug_log("Successfully restored edited text");
+    debug_log("Successfully restored text");
     Ok(())
 }
+
+/// Log a restore step, also appending it to /tmp/ovim_debug.log for troubleshooting
+fn debug_log(msg: &str) {
+    use std::io::Write;
+    if let Ok(mut f) = std::fs::OpenOptions::new().create(true).append(true).open("/tmp/ovim_debug.log") {
+        let _ = writeln!(f, "{}: {}", chrono::Local::now().format("%H:%M:%S%.3f"), msg);
+    }
+    log::info!("{}", msg);
+}
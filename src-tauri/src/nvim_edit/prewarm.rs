@@ -9,6 +9,7 @@ use std::process::Child;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use super::error::EditError;
 use super::terminals::applescript_utils;
 use super::terminals::{AlacrittySpawner, WindowGeometry};
 use crate::config::NvimEditSettings;
@@ -166,11 +167,11 @@ pub fn load_file_via_rpc(
     file_path: &std::path::Path,
     filetype: Option<&str>,
     text_is_empty: bool,
-) -> Result<(), String> {
+) -> Result<(), EditError> {
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
-        .map_err(|e| format!("Failed to create tokio runtime: {}", e))?;
+        .map_err(EditError::io("create tokio runtime"))?;
 
     rt.block_on(async {
         load_file_via_rpc_async(socket_path, file_path, filetype, text_is_empty).await
@@ -182,7 +183,7 @@ async fn load_file_via_rpc_async(
     file_path: &std::path::Path,
     filetype: Option<&str>,
     text_is_empty: bool,
-) -> Result<(), String> {
+) -> Result<(), EditError> {
     use nvim_rs::create::tokio::new_path;
 
     // Simple no-op handler for this one-shot RPC call
@@ -190,7 +191,7 @@ async fn load_file_via_rpc_async(
 
     let (neovim, io_handler) = new_path(socket_path, handler)
         .await
-        .map_err(|e| format!("Failed to connect to prewarm nvim: {}", e))?;
+        .map_err(|e| EditError::EditorNotFound(format!("Failed to connect to prewarm nvim: {}", e)))?;
 
     tokio::spawn(async move {
         let _ = io_handler.await;
@@ -201,14 +202,14 @@ async fn load_file_via_rpc_async(
     neovim
         .command(&format!("edit {}", file_str))
         .await
-        .map_err(|e| format!("Failed to open file in prewarm nvim: {}", e))?;
+        .map_err(|e| EditError::Rpc(format!("Failed to open file in prewarm nvim: {}", e)))?;
 
     // Set filetype if provided
     if let Some(ft) = filetype {
         neovim
             .command(&format!("set ft={}", ft))
             .await
-            .map_err(|e| format!("Failed to set filetype: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to set filetype: {}", e)))?;
     }
 
     // Start insert mode if text is empty
@@ -216,13 +217,13 @@ async fn load_file_via_rpc_async(
         neovim
             .command("startinsert")
             .await
-            .map_err(|e| format!("Failed to start insert mode: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to start insert mode: {}", e)))?;
     } else {
         // Position cursor at end of file
         neovim
             .command("normal G$")
             .await
-            .map_err(|e| format!("Failed to position cursor: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to position cursor: {}", e)))?;
     }
 
     Ok(())
@@ -16,6 +16,8 @@ use tokio::io::WriteHalf;
 use tokio::net::UnixStream;
 use tokio::sync::Mutex;
 
+use super::error::EditError;
+
 /// Type alias for the neovim connection writer
 type NvimWriter = Compat<WriteHalf<UnixStream>>;
 
@@ -164,26 +166,26 @@ impl NvimRpcSession {
 
     /// Get the full buffer content from neovim
     #[allow(dead_code)]
-    pub async fn get_buffer_content(&self) -> Result<String, String> {
+    pub async fn get_buffer_content(&self) -> Result<String, EditError> {
         let lines = self
             .buffer
             .get_lines(0, -1, false)
             .await
-            .map_err(|e| format!("Failed to get buffer lines: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to get buffer lines: {}", e)))?;
         Ok(lines.join("\n"))
     }
 
     /// Detach from the buffer
-    pub async fn detach(&self) -> Result<(), String> {
+    pub async fn detach(&self) -> Result<(), EditError> {
         self.buffer
             .detach()
             .await
-            .map_err(|e| format!("Failed to detach: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to detach: {}", e)))?;
         Ok(())
     }
 
-    /// Set cursor position in nvim (1-based line and column)
-    pub async fn set_cursor(&self, line: usize, column: usize) -> Result<(), String> {
+    /// Set cursor position in nvim (0-based line, byte column)
+    pub async fn set_cursor(&self, line: usize, column: usize) -> Result<(), EditError> {
         // nvim uses 1-based line numbers and 0-based column
         let nvim_line = (line + 1) as i64;
         let nvim_col = column as i64;
@@ -191,41 +193,41 @@ impl NvimRpcSession {
         self.neovim
             .get_current_win()
             .await
-            .map_err(|e| format!("Failed to get current window: {}", e))?
+            .map_err(|e| EditError::Rpc(format!("Failed to get current window: {}", e)))?
             .set_cursor((nvim_line, nvim_col))
             .await
-            .map_err(|e| format!("Failed to set cursor: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to set cursor: {}", e)))?;
 
         Ok(())
     }
 
-    /// Get cursor position from nvim (returns 0-based line and column)
-    pub async fn get_cursor(&self) -> Result<(usize, usize), String> {
+    /// Get cursor position from nvim (returns 0-based line and byte column)
+    pub async fn get_cursor(&self) -> Result<(usize, usize), EditError> {
         let (line, col) = self.neovim
             .get_current_win()
             .await
-            .map_err(|e| format!("Failed to get current window: {}", e))?
+            .map_err(|e| EditError::Rpc(format!("Failed to get current window: {}", e)))?
             .get_cursor()
             .await
-            .map_err(|e| format!("Failed to get cursor: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to get cursor: {}", e)))?;
 
         // Convert from nvim's 1-based line to 0-based
         Ok(((line - 1) as usize, col as usize))
     }
 
     /// Get the current filetype from nvim
-    pub async fn get_filetype(&self) -> Result<String, String> {
+    pub async fn get_filetype(&self) -> Result<String, EditError> {
         let opts = vec![(Value::from("scope"), Value::from("local"))];
         let value = self
             .neovim
             .get_option_value("filetype", opts)
             .await
-            .map_err(|e| format!("Failed to get filetype: {}", e))?;
+            .map_err(|e| EditError::Rpc(format!("Failed to get filetype: {}", e)))?;
 
         value
             .as_str()
             .map(|s| s.to_string())
-            .ok_or_else(|| "Filetype is not a string".to_string())
+            .ok_or_else(|| EditError::Rpc("Filetype is not a string".to_string()))
     }
 }
 
@@ -236,7 +238,7 @@ impl NvimRpcSession {
 pub async fn connect_to_nvim(
     socket_path: &Path,
     on_lines: OnLinesCallback,
-) -> Result<NvimRpcSession, String> {
+) -> Result<NvimRpcSession, EditError> {
     let handler = BufferHandler::new(on_lines);
 
     // Retry with exponential backoff
@@ -249,10 +251,10 @@ pub async fn connect_to_nvim(
             Ok(result) => break result,
             Err(e) => {
                 if total_waited >= max_delay {
-                    return Err(format!(
-                        "Failed to connect to nvim socket after {:?}: {}",
-                        total_waited, e
-                    ));
+                    return Err(EditError::RpcTimeout {
+                        waited: total_waited,
+                        reason: e.to_string(),
+                    });
                 }
                 log::debug!(
                     "Waiting for nvim socket ({}ms elapsed): {}",
@@ -286,13 +288,13 @@ pub async fn connect_to_nvim(
     let buffer = neovim
         .get_current_buf()
         .await
-        .map_err(|e| format!("Failed to get current buffer: {}", e))?;
+        .map_err(|e| EditError::Rpc(format!("Failed to get current buffer: {}", e)))?;
 
     // Get initial buffer content
     let initial_lines = buffer
         .get_lines(0, -1, false)
         .await
-        .map_err(|e| format!("Failed to get initial lines: {}", e))?;
+        .map_err(|e| EditError::Rpc(format!("Failed to get initial lines: {}", e)))?;
 
     handler.set_initial_content(initial_lines).await;
 
@@ -301,10 +303,10 @@ pub async fn connect_to_nvim(
     let attached = buffer
         .attach(true, vec![])
         .await
-        .map_err(|e| format!("Failed to attach to buffer: {}", e))?;
+        .map_err(|e| EditError::Rpc(format!("Failed to attach to buffer: {}", e)))?;
 
     if !attached {
-        return Err("Buffer attach returned false".to_string());
+        return Err(EditError::Rpc("Buffer attach returned false".to_string()));
     }
 
     log::info!("Attached to buffer for live sync");
@@ -0,0 +1,105 @@
+//! Guards for captured text with invalid UTF-8 or terminal control sequences
+
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+/// Decode `bytes` as UTF-8, replacing invalid sequences with U+FFFD.
+/// `source` names where the bytes came from, for the warning.
+pub fn decode_lossy(bytes: Vec<u8>, source: &str) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => {
+            log::warn!(
+                "{} is not valid UTF-8 (first invalid byte at {}), replacing invalid sequences",
+                source,
+                e.utf8_error().valid_up_to()
+            );
+            String::from_utf8_lossy(e.as_bytes()).into_owned()
+        }
+    }
+}
+
+/// Read a file as text, replacing invalid UTF-8 instead of failing
+pub fn read_lossy(path: &Path) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode_lossy(bytes, &path.display().to_string()))
+}
+
+/// Remove ANSI escape sequences and non-printable control characters,
+/// keeping tabs and line breaks (`sanitize_control_chars` setting)
+pub fn strip_control_chars(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' | '\n' | '\r' => stripped.push(c),
+            '\x1b' => skip_escape_sequence(&mut chars),
+            c if c.is_control() => {}
+            c => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+/// Skip the rest of an escape sequence after ESC: CSI (`ESC [ ... m`) or OSC (`ESC ] ... BEL`)
+fn skip_escape_sequence(chars: &mut Peekable<Chars>) {
+    match chars.peek() {
+        Some('[') => {
+            chars.next();
+            // Parameter and intermediate bytes, up to the final byte in @..~
+            while let Some(&c) = chars.peek() {
+                if !(' '..='~').contains(&c) {
+                    break;
+                }
+                chars.next();
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+        Some(']') => {
+            chars.next();
+            // Terminated by BEL or ST (ESC \)
+            while let Some(c) = chars.next() {
+                if c == '\x07' {
+                    break;
+                }
+                if c == '\x1b' {
+                    chars.next_if_eq(&'\\');
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_utf8_is_replaced() {
+        let bytes = b"caf\xe9 ok\n\xff".to_vec();
+        assert_eq!(decode_lossy(bytes, "test"), "caf\u{fffd} ok\n\u{fffd}");
+        assert_eq!(decode_lossy("café".as_bytes().to_vec(), "test"), "café");
+
+        let path = std::env::temp_dir().join(format!("ovim_sanitize_{}.txt", std::process::id()));
+        std::fs::write(&path, b"line\n\x80end").unwrap();
+        assert_eq!(read_lossy(&path).unwrap(), "line\n\u{fffd}end");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        assert_eq!(strip_control_chars("a\tb\nc\r\nd"), "a\tb\nc\r\nd");
+        assert_eq!(strip_control_chars("nul\0 bell\x07 del\x7f c1\u{85}"), "nul bell del c1");
+        assert_eq!(strip_control_chars("\x1b[1;31merror\x1b[0m: failed"), "error: failed");
+        assert_eq!(strip_control_chars("\x1b]0;title\x07prompt $ "), "prompt $ ");
+        assert_eq!(strip_control_chars("\x1b]8;;https://x\x1b\\link"), "link");
+        // An unterminated CSI doesn't swallow the next line
+        assert_eq!(strip_control_chars("\x1b[12\nnext"), "\nnext");
+        assert_eq!(strip_control_chars("ünïcödé ✓"), "ünïcödé ✓");
+    }
+}
@@ -1,15 +1,20 @@
 //! Edit session management for "Edit with Neovim" feature
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
 use uuid::Uuid;
 
-use super::accessibility::FocusContext;
+use super::accessibility::{restore_focus, FocusContext};
+use super::error::EditError;
 use super::prewarm::PrewarmManager;
-use super::terminals::{spawn_terminal, SpawnInfo, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use super::socket::session_socket_path;
+use super::terminals::{popup_window_title, spawn_terminal, SpawnInfo, TerminalType, WindowGeometry};
+use super::text_capture::LineEnding;
+use crate::config::{CancelBehavior, EditorStart, NvimEditSettings, TrailingNewline};
 
 /// An active edit session
 pub struct EditSession {
@@ -25,6 +30,46 @@ pub struct EditSession {
     pub socket_path: PathBuf,
     /// Domain key for filetype persistence (browser hostname or app bundle ID)
     pub domain_key: String,
+    /// Trailing newline handling when restoring the edited text
+    pub trailing_newline: TrailingNewline,
+    /// Line endings of the field; the temp file and `original_text` use `\n`
+    pub line_ending: LineEnding,
+    /// Texts longer than this many bytes are pasted in chunks (0 = never)
+    pub clipboard_chunk_threshold: usize,
+    /// Formatter command run on the temp file before restoring
+    pub format_command: Option<String>,
+    /// What happens to live-synced changes when the editor quits without saving
+    pub cancel_behavior: CancelBehavior,
+    /// When the session was started
+    pub started_at: SystemTime,
+}
+
+/// Summary of an active session for the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct EditSessionInfo {
+    pub id: String,
+    /// Bundle ID of the app the text came from
+    pub app: String,
+    pub editor_pid: Option<u32>,
+    /// Start time in milliseconds since the Unix epoch
+    pub started_at: u64,
+    pub terminal_type: &'static str,
+}
+
+impl From<&EditSession> for EditSessionInfo {
+    fn from(session: &EditSession) -> Self {
+        Self {
+            id: session.id.to_string(),
+            app: session.focus_context.app_bundle_id.clone(),
+            editor_pid: session.process_id,
+            started_at: session
+                .started_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            terminal_type: session.terminal_type.as_str(),
+        }
+    }
 }
 
 /// Manager for edit sessions
@@ -46,44 +91,47 @@ impl EditSessionManager {
         self.prewarm = Some(prewarm);
     }
 
-    /// Start a new edit session
+    /// Start a new edit session.
+    /// `text` uses `\n` line breaks; `line_ending` is the field's own convention.
+    /// `initial_cursor` is the text field's 0-based (line, column), if known.
+    #[allow(clippy::too_many_arguments)]
     pub fn start_session(
         &self,
         focus_context: FocusContext,
         text: String,
+        line_ending: LineEnding,
         settings: NvimEditSettings,
         geometry: Option<WindowGeometry>,
         domain_key: String,
         saved_filetype: Option<&str>,
-    ) -> Result<Uuid, String> {
+        initial_cursor: Option<(usize, usize)>,
+    ) -> Result<Uuid, EditError> {
         // Create temp directory if needed
         let cache_dir = dirs::cache_dir()
-            .ok_or("Could not determine cache directory")?
+            .ok_or(EditError::NoCacheDir)?
             .join("ovim");
         std::fs::create_dir_all(&cache_dir)
-            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+            .map_err(EditError::io("create cache directory"))?;
 
-        // Generate session ID and temp file
+        // Generate session ID, temp file and RPC socket path
         let session_id = Uuid::new_v4();
-        let temp_file = cache_dir.join(format!("edit_{}.txt", session_id));
-
-        // Generate socket path for RPC
-        let socket_path = cache_dir.join(format!("nvim_{}.sock", session_id));
+        let (temp_file, socket_path) = session_paths(&cache_dir, &settings.socket_dir, &session_id)?;
 
         // Clean up any stale socket file
         let _ = std::fs::remove_file(&socket_path);
 
         // Write text to temp file
         std::fs::write(&temp_file, &text)
-            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+            .map_err(EditError::io("write temp file"))?;
 
         // Get file modification time after writing
         let file_mtime = std::fs::metadata(&temp_file)
             .and_then(|m| m.modified())
-            .map_err(|e| format!("Failed to get file mtime: {}", e))?;
+            .map_err(EditError::io("get file mtime"))?;
 
         // Consider whitespace-only text as empty (start in insert mode)
         let text_is_empty = text.trim().is_empty();
+        let start = settings.editor_start(text_is_empty, initial_cursor);
 
         // Try the pre-warmed terminal path first
         let (terminal_type, process_id, window_title) =
@@ -105,10 +153,10 @@ impl EditSessionManager {
                             if let Some(ref geo) = geometry {
                                 super::prewarm::show_and_position(&prewarm_title, geo);
                             } else {
-                                // No geometry - just focus the window via AppleScript
-                                super::terminals::applescript_utils::focus_window_by_title(
-                                    &["alacritty", "Alacritty"],
-                                    &prewarm_title,
+                                // No geometry - just focus the window
+                                super::terminals::focus_popup_window(
+                                    &TerminalType::Alacritty,
+                                    Some(&prewarm_title),
                                 );
                             }
 
@@ -139,10 +187,15 @@ impl EditSessionManager {
                                 window_title: Some(prewarm_title),
                                 socket_path: actual_socket,
                                 domain_key,
+                                trailing_newline: settings.trailing_newline,
+                                line_ending,
+                                clipboard_chunk_threshold: settings.clipboard_chunk_threshold,
+                                format_command: settings.format_command.clone(),
+                                cancel_behavior: settings.cancel_behavior,
+                                started_at: SystemTime::now(),
                             };
 
-                            let mut sessions = self.sessions.lock().unwrap();
-                            sessions.insert(session_id, session);
+                            self.insert_session(session);
                             return Ok(session_id);
                         }
                         Err(e) => {
@@ -152,9 +205,9 @@ impl EditSessionManager {
                     }
                 }
                 // Prewarm not available, fall through
-                self.normal_spawn(&settings, &temp_file, geometry, &socket_path, text_is_empty, saved_filetype)?
+                self.normal_spawn(&settings, &temp_file, &session_id, geometry, &socket_path, start, saved_filetype)?
             } else {
-                self.normal_spawn(&settings, &temp_file, geometry, &socket_path, text_is_empty, saved_filetype)?
+                self.normal_spawn(&settings, &temp_file, &session_id, geometry, &socket_path, start, saved_filetype)?
             };
 
         // Create session
@@ -169,31 +222,39 @@ impl EditSessionManager {
             window_title,
             socket_path,
             domain_key,
+            trailing_newline: settings.trailing_newline,
+            line_ending,
+            clipboard_chunk_threshold: settings.clipboard_chunk_threshold,
+            format_command: settings.format_command.clone(),
+            cancel_behavior: settings.cancel_behavior,
+            started_at: SystemTime::now(),
         };
 
         // Store session
-        let mut sessions = self.sessions.lock().unwrap();
-        sessions.insert(session_id, session);
+        self.insert_session(session);
 
         Ok(session_id)
     }
 
     /// Normal terminal spawn (non-prewarm path)
+    #[allow(clippy::too_many_arguments)]
     fn normal_spawn(
         &self,
         settings: &NvimEditSettings,
         temp_file: &std::path::Path,
+        session_id: &Uuid,
         geometry: Option<WindowGeometry>,
         socket_path: &std::path::Path,
-        text_is_empty: bool,
+        start: EditorStart,
         saved_filetype: Option<&str>,
-    ) -> Result<(TerminalType, Option<u32>, Option<String>), String> {
+    ) -> Result<(TerminalType, Option<u32>, Option<String>), EditError> {
         let SpawnInfo {
             terminal_type,
             process_id,
             child: _,
             window_title,
-        } = spawn_terminal(settings, temp_file, geometry, Some(socket_path), text_is_empty, saved_filetype)?;
+        } = spawn_terminal(settings, temp_file, &popup_window_title(session_id), geometry, Some(socket_path), start, saved_filetype)
+            .map_err(EditError::Spawn)?;
         Ok((terminal_type, process_id, window_title))
     }
 
@@ -211,13 +272,62 @@ impl EditSessionManager {
             window_title: s.window_title.clone(),
             socket_path: s.socket_path.clone(),
             domain_key: s.domain_key.clone(),
+            trailing_newline: s.trailing_newline,
+            line_ending: s.line_ending,
+            clipboard_chunk_threshold: s.clipboard_chunk_threshold,
+            format_command: s.format_command.clone(),
+            cancel_behavior: s.cancel_behavior,
+            started_at: s.started_at,
         })
     }
 
-    /// Remove a session after completion
-    pub fn remove_session(&self, id: &Uuid) {
-        let mut sessions = self.sessions.lock().unwrap();
-        sessions.remove(id);
+    /// List active sessions, oldest first
+    pub fn list_sessions(&self) -> Vec<EditSessionInfo> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut list: Vec<&EditSession> = sessions.values().collect();
+        list.sort_by_key(|s| s.started_at);
+        list.into_iter().map(EditSessionInfo::from).collect()
+    }
+
+    /// Force-end a stuck session: terminate the editor, restore focus to the
+    /// original app and delete the temp file and socket. The text is not restored.
+    pub fn kill_session(&self, id: &Uuid) -> Result<(), EditError> {
+        // Remove first so the completion handler skips restoring the text
+        let session = self
+            .sessions
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| EditError::UnknownSession(id.to_string()))?;
+        log::info!("Killing edit session {} (editor PID {:?})", id, session.process_id);
+
+        if let Some(pid) = session.process_id {
+            unsafe { libc::kill(pid as i32, libc::SIGTERM) };
+        }
+        let _ = std::fs::remove_file(&session.temp_file);
+        let _ = std::fs::remove_file(&session.socket_path);
+
+        if let Err(e) = restore_focus(&session.focus_context) {
+            log::warn!("Failed to restore focus after killing session {}: {}", id, e);
+        }
+        Ok(())
+    }
+
+    /// Track a started session. Sessions are keyed by id, so concurrent sessions don't collide.
+    fn insert_session(&self, session: EditSession) -> Uuid {
+        let id = session.id;
+        self.sessions.lock().unwrap().insert(id, session);
+        id
+    }
+
+    /// Remove a session after completion, deleting its temp file and socket.
+    /// Other sessions and their files are left alone.
+    pub fn finish_session(&self, id: &Uuid) {
+        let Some(session) = self.sessions.lock().unwrap().remove(id) else {
+            return;
+        };
+        let _ = std::fs::remove_file(&session.temp_file);
+        let _ = std::fs::remove_file(&session.socket_path);
     }
 }
 
@@ -226,3 +336,93 @@ impl Default for EditSessionManager {
         Self::new()
     }
 }
+
+/// Temp file and RPC socket path of session `session_id`. The temp file lives in
+/// `cache_dir`; the socket in `socket_dir` (empty for `cache_dir`), kept short
+/// enough for the Unix socket limit.
+fn session_paths(
+    cache_dir: &Path,
+    socket_dir: &str,
+    session_id: &Uuid,
+) -> Result<(PathBuf, PathBuf), EditError> {
+    let temp_file = cache_dir.join(format!("edit_{}.txt", session_id));
+    let socket_path = session_socket_path(socket_dir, cache_dir, session_id)?;
+    Ok((temp_file, socket_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::socket_path::fits_socket_limit;
+    use std::thread;
+
+    fn mock_session(dir: &Path) -> EditSession {
+        let id = Uuid::new_v4();
+        let (temp_file, socket_path) = session_paths(dir, "", &id).unwrap();
+        std::fs::write(&temp_file, "text").unwrap();
+        std::fs::write(&socket_path, "").unwrap();
+        EditSession {
+            id,
+            focus_context: FocusContext {
+                app_pid: 1,
+                app_bundle_id: "com.example.app".to_string(),
+                focused_element: None,
+                focused_window: None,
+                element_identifier: None,
+            },
+            original_text: "text".to_string(),
+            temp_file,
+            file_mtime: SystemTime::now(),
+            terminal_type: TerminalType::Alacritty,
+            process_id: None,
+            window_title: None,
+            socket_path,
+            domain_key: "com.example.app".to_string(),
+            trailing_newline: TrailingNewline::default(),
+            line_ending: LineEnding::default(),
+            clipboard_chunk_threshold: 0,
+            format_command: None,
+            cancel_behavior: CancelBehavior::default(),
+            started_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_concurrent_sessions_clean_up_independently() {
+        let dir = std::env::temp_dir().join(format!("ovim_sessions_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manager = Arc::new(EditSessionManager::new());
+
+        let ids: Vec<Uuid> = (0..2)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                let dir = dir.clone();
+                thread::spawn(move || manager.insert_session(mock_session(&dir)))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(manager.list_sessions().len(), 2);
+
+        // Both sessions got their own paths from session_paths
+        let first = manager.get_session(&ids[0]).unwrap();
+        let second = manager.get_session(&ids[1]).unwrap();
+        assert_ne!(first.socket_path, second.socket_path);
+        assert_ne!(first.temp_file, second.temp_file);
+        for session in [&first, &second] {
+            assert_eq!(session.temp_file.parent(), Some(dir.as_path()));
+            assert!(fits_socket_limit(&session.socket_path));
+        }
+
+        manager.finish_session(&ids[0]);
+        assert!(!first.temp_file.exists() && !first.socket_path.exists());
+        assert!(second.temp_file.exists() && second.socket_path.exists());
+        assert!(manager.get_session(&ids[1]).is_some());
+
+        manager.finish_session(&ids[1]);
+        assert!(!second.temp_file.exists() && !second.socket_path.exists());
+        assert!(manager.list_sessions().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,89 @@
+//! Location of the per-session nvim RPC socket
+//!
+//! Unix socket paths are limited to 104 bytes on macOS (including the NUL),
+//! and nvim fails to listen on longer paths without a useful error. Sockets
+//! that would be too long move to a short directory under the home folder.
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::error::EditError;
+use crate::socket_path::{ensure_writable, expand_home, fits_socket_limit};
+
+/// Fallback socket directory, relative to the home folder
+const FALLBACK_SOCKET_DIR: &str = ".ovim/sockets";
+
+/// Resolve and prepare the socket path for a session.
+/// `socket_dir` comes from the settings; empty means `default_dir`.
+pub fn session_socket_path(
+    socket_dir: &str,
+    default_dir: &Path,
+    session_id: &Uuid,
+) -> Result<PathBuf, EditError> {
+    let (dir, fallback_dir) = socket_dirs(socket_dir, default_dir);
+    let path = choose_socket_path(&dir, fallback_dir.as_deref(), session_id)?;
+
+    let parent = path.parent().unwrap_or(&dir);
+    ensure_writable(parent).map_err(|source| EditError::SocketDirNotWritable {
+        dir: parent.to_path_buf(),
+        source,
+    })?;
+    Ok(path)
+}
+
+/// The configured socket directory (or `default_dir`) and the short fallback directory
+pub fn socket_dirs(socket_dir: &str, default_dir: &Path) -> (PathBuf, Option<PathBuf>) {
+    let dir = match socket_dir.trim() {
+        "" => default_dir.to_path_buf(),
+        dir => expand_home(dir),
+    };
+    (dir, dirs::home_dir().map(|home| home.join(FALLBACK_SOCKET_DIR)))
+}
+
+/// `<dir>/nvim_<id>.sock`, or `<fallback_dir>/<short-id>.sock` when that is too long
+fn choose_socket_path(
+    dir: &Path,
+    fallback_dir: Option<&Path>,
+    session_id: &Uuid,
+) -> Result<PathBuf, EditError> {
+    let path = dir.join(format!("nvim_{}.sock", session_id));
+    if fits_socket_limit(&path) {
+        return Ok(path);
+    }
+
+    let short_id = &session_id.simple().to_string()[..8];
+    match fallback_dir.map(|fallback| fallback.join(format!("{}.sock", short_id))) {
+        Some(short) if fits_socket_limit(&short) => {
+            log::warn!("Socket path {:?} is too long, using {:?}", path, short);
+            Ok(short)
+        }
+        _ => Err(EditError::SocketPathTooLong(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_socket_path_falls_back_to_short_dir() {
+        let id = Uuid::new_v4();
+        let short_dir = Path::new("/tmp/ovim");
+        assert_eq!(
+            choose_socket_path(short_dir, None, &id).unwrap(),
+            short_dir.join(format!("nvim_{}.sock", id))
+        );
+
+        let long_dir = PathBuf::from(format!("/tmp/{}", "d".repeat(80)));
+        let fallback = Path::new("/Users/me/.ovim/sockets");
+        let path = choose_socket_path(&long_dir, Some(fallback), &id).unwrap();
+        assert_eq!(path.parent(), Some(fallback));
+        assert!(fits_socket_limit(&path));
+
+        assert!(matches!(
+            choose_socket_path(&long_dir, None, &id),
+            Err(EditError::SocketPathTooLong(_))
+        ));
+    }
+}
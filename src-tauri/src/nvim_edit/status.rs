@@ -0,0 +1,41 @@
+//! `edit-session-status` events for the indicator
+//!
+//! Live sync and the clipboard fallback behave very differently for the user,
+//! so the indicator shows which one the running edit session uses.
+
+use serde::Serialize;
+use tauri::Emitter;
+use uuid::Uuid;
+
+/// How the edited text gets back into the text field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// Edits sync into the field while typing
+    Live,
+    /// The text is pasted when the editor exits
+    Clipboard,
+    /// The session finished
+    Ended,
+}
+
+/// Payload for the edit-session-status event
+#[derive(Clone, Serialize)]
+struct EditSessionStatusPayload {
+    session_id: String,
+    status: SyncStatus,
+}
+
+/// Tell the frontend how the session restores its text
+pub fn emit_status(session_id: &Uuid, status: SyncStatus) {
+    log::info!("Edit session {} status: {:?}", session_id, status);
+    if let Some(app) = crate::get_app_handle() {
+        let _ = app.emit(
+            "edit-session-status",
+            EditSessionStatusPayload {
+                session_id: session_id.to_string(),
+                status,
+            },
+        );
+    }
+}
@@ -6,13 +6,13 @@ use std::process::Command;
 
 use super::process_utils::{find_editor_pid_for_file, resolve_command_path, resolve_terminal_path};
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
 
 
 pub struct AlacrittySpawner;
 
 /// Configuration for spawning an Alacritty window
-struct SpawnConfig {
+pub(super) struct SpawnConfig {
     title: String,
     columns: u32,
     lines: u32,
@@ -25,7 +25,7 @@ struct SpawnConfig {
 }
 
 impl SpawnConfig {
-    fn new(settings: &NvimEditSettings, file_path: &str, socket_path: Option<&Path>, text_is_empty: bool, filetype: Option<&str>) -> Self {
+    fn new(settings: &NvimEditSettings, file_path: &str, window_title: &str, socket_path: Option<&Path>, start: EditorStart, filetype: Option<&str>) -> Self {
         let editor_path = settings.editor_path();
         let resolved_editor = resolve_command_path(&editor_path);
         log::info!("Resolved editor path: {} -> {}", editor_path, resolved_editor);
@@ -54,15 +54,18 @@ impl SpawnConfig {
         }
 
         // Add editor args from settings (insert mode if text is empty)
-        for arg in settings.editor_args(text_is_empty) {
-            editor_cmd.push(arg.to_string());
-        }
+        editor_cmd.extend(settings.editor_args(start));
 
         // Add file path
         editor_cmd.push(file_path.to_string());
 
+        Self::with_command(window_title, editor_cmd, terminal_path)
+    }
+
+    /// Default-sized window titled `title` that runs `editor_cmd`
+    pub(super) fn with_command(title: &str, editor_cmd: Vec<String>, terminal_path: String) -> Self {
         Self {
-            title: format!("ovim-edit-{}", std::process::id()),
+            title: title.to_string(),
             columns: 80,
             lines: 24,
             x: None,
@@ -74,7 +77,7 @@ impl SpawnConfig {
         }
     }
 
-    fn with_geometry(mut self, geometry: Option<&WindowGeometry>) -> Self {
+    pub(super) fn with_geometry(mut self, geometry: Option<&WindowGeometry>) -> Self {
         if let Some(geo) = geometry {
             self.columns = (geo.width / 8).max(40);
             self.lines = (geo.height / 16).max(10);
@@ -96,13 +99,14 @@ impl TerminalSpawner for AlacrittySpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
-        let config = SpawnConfig::new(settings, file_path, socket_path, text_is_empty, filetype)
+        let config = SpawnConfig::new(settings, file_path, window_title, socket_path, start, filetype)
             .with_geometry(geometry.as_ref());
 
         // Try msg create-window first (faster, reuses existing daemon)
@@ -200,9 +204,7 @@ impl AlacrittySpawner {
         config: &SpawnConfig,
         custom_env: Option<&HashMap<String, String>>,
     ) -> Result<std::process::Child, String> {
-        let mut args = self.window_options(config);
-        args.push("-e".to_string());
-        args.extend(config.editor_cmd.clone());
+        let args = self.process_args(config);
 
         let mut cmd = Command::new(&config.terminal_path);
         cmd.args(&args);
@@ -216,6 +218,14 @@ impl AlacrittySpawner {
             .map_err(|e| format!("Failed to spawn alacritty: {}", e))
     }
 
+    /// Arguments for a new Alacritty process running the editor
+    pub(super) fn process_args(&self, config: &SpawnConfig) -> Vec<String> {
+        let mut args = self.window_options(config);
+        args.push("-e".to_string());
+        args.extend(config.editor_cmd.clone());
+        args
+    }
+
     /// Common window options for Alacritty
     fn window_options(&self, config: &SpawnConfig) -> Vec<String> {
         let mut args = vec![
@@ -1,6 +1,6 @@
 //! AppleScript utilities for window management
 
-use std::process::Command;
+use crate::osascript;
 
 /// Set window size using AppleScript
 pub fn set_window_size(app_name: &str, width: u32, height: u32) {
@@ -17,7 +17,7 @@ pub fn set_window_size(app_name: &str, width: u32, height: u32) {
         app_name, width, height
     );
 
-    let _ = Command::new("osascript").arg("-e").arg(&script).output();
+    let _ = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 }
 
 /// Move a window to a specific position using AppleScript
@@ -38,7 +38,7 @@ pub fn move_window_to_position(app_name: &str, x: i32, y: i32) {
         app_name, x, y
     );
 
-    let _ = Command::new("osascript").arg("-e").arg(&script).output();
+    let _ = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 }
 
 /// Find a window by title across all processes matching the given names (case-insensitive)
@@ -70,7 +70,7 @@ pub fn find_window_by_title(process_names: &[&str], title: &str) -> Option<usize
         condition, title
     );
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output();
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 
     if let Ok(out) = output {
         if out.status.success() {
@@ -111,7 +111,7 @@ pub fn get_window_position_by_title(process_names: &[&str], title: &str) -> Opti
         condition, title
     );
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output().ok()?;
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT).ok()?;
     if !output.status.success() {
         return None;
     }
@@ -157,7 +157,7 @@ pub fn set_window_size_by_title(process_names: &[&str], title: &str, width: u32,
 
     log::debug!("Setting window '{}' size to {}x{}", title, width, height);
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output();
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 
     if let Ok(out) = output {
         if !out.status.success() {
@@ -203,7 +203,7 @@ pub fn set_window_bounds_by_title(process_names: &[&str], title: &str, x: i32, y
         title, width, height, x, y
     );
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output();
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 
     if let Ok(out) = output {
         if !out.status.success() {
@@ -247,7 +247,7 @@ pub fn focus_window_by_title(process_names: &[&str], title: &str) {
 
     log::info!("Focusing window with title '{}'", title);
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output();
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 
     if let Ok(out) = output {
         if !out.status.success() {
@@ -302,7 +302,7 @@ pub fn show_and_position_window_by_title(
         title, x, y, width, height
     );
 
-    let output = Command::new("osascript").arg("-e").arg(&script).output();
+    let output = osascript::run(&script, osascript::DEFAULT_TIMEOUT);
 
     match output {
         Ok(out) => {
@@ -325,7 +325,6 @@ pub fn show_and_position_window_by_title(
 }
 
 /// Convert pixel dimensions to approximate terminal cell dimensions
-#[allow(dead_code)]
 pub fn pixels_to_cells(width: u32, height: u32) -> (u32, u32) {
     // Approximate: 8px per column, 16px per row
     let cols = (width / 8).max(10);
@@ -16,7 +16,7 @@ use std::time::Duration;
 
 use super::process_utils::find_editor_pid_for_file_no_delay;
 use super::{ensure_launcher_script, SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
 use crate::launcher_callback::{self, LauncherCallback};
 
 /// Result of running the launcher script
@@ -39,6 +39,7 @@ pub enum LauncherResult {
 pub fn run_launcher_script(
     settings: &NvimEditSettings,
     file_path: &str,
+    window_title: &str,
     geometry: Option<&WindowGeometry>,
     socket_path: Option<&Path>,
 ) -> LauncherResult {
@@ -85,6 +86,7 @@ pub fn run_launcher_script(
         .env("OVIM_CLI", &ovim_cli)
         .env("OVIM_SESSION_ID", &session_id)
         .env("OVIM_FILE", file_path)
+        .env("OVIM_WINDOW_TITLE", window_title)
         .env("OVIM_EDITOR", &editor_path)
         .env("OVIM_WIDTH", width.to_string())
         .env("OVIM_HEIGHT", height.to_string())
@@ -111,6 +113,7 @@ pub fn run_launcher_script(
         &mut child,
         &session_id,
         file_path,
+        window_title,
         process_name,
         timeout,
     );
@@ -127,6 +130,7 @@ fn wait_for_callback_or_exit(
     child: &mut Child,
     session_id: &str,
     file_path: &str,
+    window_title: &str,
     process_name: &str,
     timeout: Duration,
 ) -> LauncherResult {
@@ -166,7 +170,7 @@ fn wait_for_callback_or_exit(
                         terminal_type: TerminalType::Custom,
                         process_id: editor_pid,
                         child: None,
-                        window_title: None,
+                        window_title: Some(window_title.to_string()),
                     })
                 }
                 LauncherCallback::Fallthrough => {
@@ -198,7 +202,7 @@ fn wait_for_callback_or_exit(
                     terminal_type: TerminalType::Custom,
                     process_id: Some(pid),
                     child: None,
-                    window_title: None,
+                    window_title: Some(window_title.to_string()),
                 });
             }
 
@@ -232,19 +236,20 @@ impl TerminalSpawner for CustomSpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         _custom_env: Option<&HashMap<String, String>>,
-        _text_is_empty: bool,
+        _start: EditorStart,
         _filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
         // When terminal=custom, use run_launcher_script which handles IPC callbacks
-        match run_launcher_script(settings, file_path, geometry.as_ref(), socket_path) {
+        match run_launcher_script(settings, file_path, window_title, geometry.as_ref(), socket_path) {
             LauncherResult::Handled(info) => Ok(info),
             LauncherResult::Fallthrough => {
                 // Fallthrough doesn't make sense for terminal=custom
                 // Fall back to spawning script directly without IPC
-                spawn_script_directly(settings, file_path, geometry, socket_path)
+                spawn_script_directly(settings, file_path, window_title, geometry, socket_path)
             }
             LauncherResult::Error(e) => Err(e),
         }
@@ -255,6 +260,7 @@ impl TerminalSpawner for CustomSpawner {
 fn spawn_script_directly(
     settings: &NvimEditSettings,
     file_path: &str,
+    window_title: &str,
     geometry: Option<WindowGeometry>,
     socket_path: Option<&Path>,
 ) -> Result<SpawnInfo, String> {
@@ -277,6 +283,7 @@ fn spawn_script_directly(
     let child = Command::new(&script_path)
         .env("OVIM_SESSION_ID", uuid::Uuid::new_v4().to_string())
         .env("OVIM_FILE", file_path)
+        .env("OVIM_WINDOW_TITLE", window_title)
         .env("OVIM_EDITOR", &editor_path)
         .env("OVIM_WIDTH", width.to_string())
         .env("OVIM_HEIGHT", height.to_string())
@@ -302,6 +309,6 @@ fn spawn_script_directly(
         terminal_type: TerminalType::Custom,
         process_id,
         child: Some(child),
-        window_title: None,
+        window_title: Some(window_title.to_string()),
     })
 }
@@ -4,9 +4,10 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
 
+use super::applescript_utils::pixels_to_cells;
 use super::process_utils::{find_editor_pid_for_file, resolve_command_path};
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
 
 pub struct GhosttySpawner;
 
@@ -19,18 +20,16 @@ impl TerminalSpawner for GhosttySpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
-        // Generate a unique window title so we can find it
-        let unique_title = format!("ovim-edit-{}", std::process::id());
-
         // Get editor path and args from settings (insert mode if text is empty)
         let editor_path = settings.editor_path();
-        let editor_args = settings.editor_args(text_is_empty);
+        let editor_args = settings.editor_args(start);
         let process_name = settings.editor_process_name();
 
         // Build socket args for nvim RPC if socket_path provided and using nvim
@@ -75,39 +74,12 @@ impl TerminalSpawner for GhosttySpawner {
             c
         };
 
-        // Prevent Ghostty from creating its default initial window
-        // (we only want the window created by the -e command)
-        cmd.arg("--initial-window=false");
-
-        // Add window title
-        cmd.args([&format!("--title={}", unique_title)]);
-
-        // Add geometry if provided
-        if let Some(ref geo) = geometry {
-            // Ghostty window-width/height are in terminal grid cells, not pixels
-            let cols = (geo.width / 8).max(10);
-            let rows = (geo.height / 16).max(4);
-            cmd.args([
-                &format!("--window-width={}", cols),
-                &format!("--window-height={}", rows),
-                &format!("--window-position-x={}", geo.x),
-                &format!("--window-position-y={}", geo.y),
-            ]);
-        }
-
-        // Execute editor using -e flag
-        cmd.arg("-e");
-        cmd.arg(&resolved_editor);
-        for arg in &socket_args {
-            cmd.arg(arg);
-        }
-        for arg in &filetype_args {
-            cmd.arg(arg);
-        }
-        for arg in &editor_args {
-            cmd.arg(arg);
-        }
-        cmd.arg(file_path);
+        let mut editor_cmd = vec![resolved_editor];
+        editor_cmd.extend(socket_args);
+        editor_cmd.extend(filetype_args);
+        editor_cmd.extend(editor_args);
+        editor_cmd.push(file_path.to_string());
+        cmd.args(ghostty_args(window_title, geometry.as_ref(), editor_cmd));
 
         // Apply custom environment variables
         if let Some(env) = custom_env {
@@ -125,7 +97,38 @@ impl TerminalSpawner for GhosttySpawner {
             terminal_type: TerminalType::Ghostty,
             process_id: pid,
             child: None, // open command returns immediately
-            window_title: Some(unique_title),
+            window_title: Some(window_title.to_string()),
         })
     }
 }
+
+/// Ghostty arguments: only the window title, geometry and editor command.
+/// Everything else (theme, font, decorations) comes from the user's Ghostty config.
+pub(super) fn ghostty_args(
+    title: &str,
+    geometry: Option<&WindowGeometry>,
+    editor_cmd: Vec<String>,
+) -> Vec<String> {
+    // Prevent Ghostty from creating its default initial window
+    // (we only want the window created by the -e command)
+    let mut args = vec![
+        "--initial-window=false".to_string(),
+        format!("--title={}", title),
+    ];
+
+    if let Some(geo) = geometry {
+        // Ghostty window-width/height are in terminal grid cells, not pixels
+        let (cols, rows) = pixels_to_cells(geo.width, geo.height);
+        args.extend([
+            format!("--window-width={}", cols),
+            format!("--window-height={}", rows),
+            format!("--window-position-x={}", geo.x),
+            format!("--window-position-y={}", geo.y),
+        ]);
+    }
+
+    // Everything after -e is the editor command
+    args.push("-e".to_string());
+    args.extend(editor_cmd);
+    args
+}
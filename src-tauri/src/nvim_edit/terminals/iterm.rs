@@ -2,11 +2,11 @@
 
 use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
 
-use super::process_utils::find_editor_pid_for_file;
+use super::process_utils::{find_editor_pid_for_file, find_process_on_tty};
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
+use crate::osascript;
 
 /// Escape a string for use in shell (single-quote escaping)
 fn shell_escape(s: &str) -> String {
@@ -24,15 +24,16 @@ impl TerminalSpawner for ITermSpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
         // Get editor path and args from settings (insert mode if text is empty)
         let editor_path = settings.editor_path();
-        let editor_args = settings.editor_args(text_is_empty);
+        let editor_args = settings.editor_args(start);
         let process_name = settings.editor_process_name();
 
         // Build socket args for nvim RPC if socket_path provided and using nvim
@@ -61,7 +62,7 @@ impl TerminalSpawner for ITermSpawner {
         // Each argument must be shell-escaped to preserve integrity through AppleScript
         let mut all_args: Vec<String> = socket_args;
         all_args.extend(filetype_args);
-        all_args.extend(editor_args.iter().map(|s| s.to_string()));
+        all_args.extend(editor_args);
         let args_str = if all_args.is_empty() {
             String::new()
         } else {
@@ -84,58 +85,69 @@ impl TerminalSpawner for ITermSpawner {
             format!("{}; ", env_exports)
         };
 
-        // Use AppleScript to open iTerm and run editor with position/size
-        let script = if let Some(geo) = geometry {
+        let command = format!("{}{}{} '{}'; exit", env_prefix, editor_path, args_str, file_path);
+        let script = iterm_script(window_title, geometry.as_ref(), &command);
+
+        let output = osascript::run(&script, osascript::SPAWN_TIMEOUT)
+            .map_err(|e| format!("Failed to run iTerm AppleScript: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "iTerm AppleScript failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        // The script returns the new session's TTY; the editor is the process on it.
+        // Fall back to the file lookup if the TTY is unknown.
+        let tty = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        log::info!("iTerm session TTY: {:?}", tty);
+        let pid = match tty.as_str() {
+            "" => None,
+            tty => find_process_on_tty(tty, process_name),
+        }
+        .or_else(|| find_editor_pid_for_file(file_path, process_name));
+        log::info!("Found editor PID: {:?} for file: {}", pid, file_path);
+
+        Ok(SpawnInfo {
+            terminal_type: TerminalType::ITerm,
+            process_id: pid,
+            child: None,
+            window_title: Some(window_title.to_string()),
+        })
+    }
+}
+
+/// AppleScript that opens an iTerm window with the default profile, runs `command`
+/// in it and returns the session's TTY. Only the bounds and session name are set;
+/// colors and fonts come from the user's profile.
+pub(super) fn iterm_script(title: &str, geometry: Option<&WindowGeometry>, command: &str) -> String {
+    let bounds = geometry
+        .map(|geo| {
             format!(
-                r#"
-            tell application "iTerm"
-                activate
-                set newWindow to (create window with default profile)
-                set bounds of newWindow to {{{}, {}, {}, {}}}
-                tell current session of newWindow
-                    write text "{}{}{} '{}'; exit"
-                end tell
-            end tell
-            "#,
+                "\n                set bounds of newWindow to {{{}, {}, {}, {}}}",
                 geo.x,
                 geo.y,
                 geo.x + geo.width as i32,
                 geo.y + geo.height as i32,
-                env_prefix,
-                editor_path,
-                args_str,
-                file_path
             )
-        } else {
-            format!(
-                r#"
+        })
+        .unwrap_or_default();
+
+    // `activate` is asynchronous when iTerm isn't frontmost, so the new window
+    // is selected explicitly to make sure it ends up on top
+    format!(
+        r#"
             tell application "iTerm"
                 activate
-                set newWindow to (create window with default profile)
+                set newWindow to (create window with default profile){}
+                select newWindow
                 tell current session of newWindow
-                    write text "{}{}{} '{}'; exit"
+                    set name to "{}"
+                    write text "{}"
+                    return tty
                 end tell
             end tell
             "#,
-                env_prefix, editor_path, args_str, file_path
-            )
-        };
-
-        Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .output()
-            .map_err(|e| format!("Failed to run iTerm AppleScript: {}", e))?;
-
-        // Try to find the editor process ID by the file it's editing
-        let pid = find_editor_pid_for_file(file_path, process_name);
-        log::info!("Found editor PID: {:?} for file: {}", pid, file_path);
-
-        Ok(SpawnInfo {
-            terminal_type: TerminalType::ITerm,
-            process_id: pid,
-            child: None,
-            window_title: None,
-        })
-    }
+        bounds, title, command
+    )
 }
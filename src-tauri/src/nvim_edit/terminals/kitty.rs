@@ -4,9 +4,10 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
 
+use super::applescript_utils::pixels_to_cells;
 use super::process_utils::{find_editor_pid_for_file, resolve_command_path, resolve_terminal_path};
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
 
 pub struct KittySpawner;
 
@@ -19,18 +20,16 @@ impl TerminalSpawner for KittySpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
-        // Generate a unique window title
-        let unique_title = format!("ovim-edit-{}", std::process::id());
-
         // Get editor path and args from settings (insert mode if text is empty)
         let editor_path = settings.editor_path();
-        let editor_args = settings.editor_args(text_is_empty);
+        let editor_args = settings.editor_args(start);
         let process_name = settings.editor_process_name();
 
         // Build socket args for nvim RPC if socket_path provided and using nvim
@@ -64,39 +63,14 @@ impl TerminalSpawner for KittySpawner {
         let resolved_terminal = resolve_terminal_path(&terminal_cmd);
         log::info!("Resolved terminal path: {} -> {}", terminal_cmd, resolved_terminal);
 
-        let mut cmd = Command::new(&resolved_terminal);
-
-        // Use single instance to avoid multiple dock icons, close window when editor exits
-        cmd.args(["--single-instance", "--wait-for-single-instance-window-close"]);
-        cmd.args(["--title", &unique_title]);
-        cmd.args(["-o", "close_on_child_death=yes"]);
-
-        // Add window position/size if provided
-        if let Some(ref geo) = geometry {
-            cmd.args([
-                "--position",
-                &format!("{}x{}", geo.x, geo.y),
-                "-o",
-                &format!("initial_window_width={}c", geo.width / 8),
-                "-o",
-                &format!("initial_window_height={}c", geo.height / 16),
-                "-o",
-                "remember_window_size=no",
-            ]);
-        }
+        let mut editor_cmd = vec![resolved_editor];
+        editor_cmd.extend(socket_args);
+        editor_cmd.extend(filetype_args);
+        editor_cmd.extend(editor_args);
+        editor_cmd.push(file_path.to_string());
 
-        // Kitty runs the command directly (no -e flag needed)
-        cmd.arg(&resolved_editor);
-        for arg in &socket_args {
-            cmd.arg(arg);
-        }
-        for arg in &filetype_args {
-            cmd.arg(arg);
-        }
-        for arg in &editor_args {
-            cmd.arg(arg);
-        }
-        cmd.arg(file_path);
+        let mut cmd = Command::new(&resolved_terminal);
+        cmd.args(kitty_args(window_title, geometry.as_ref(), editor_cmd));
 
         // Apply custom environment variables
         if let Some(env) = custom_env {
@@ -115,7 +89,48 @@ impl TerminalSpawner for KittySpawner {
             terminal_type: TerminalType::Kitty,
             process_id: pid,
             child: Some(child),
-            window_title: Some(unique_title),
+            window_title: Some(window_title.to_string()),
         })
     }
 }
+
+/// Kitty arguments: window targeting, geometry and the editor command.
+/// Fonts, padding and decorations are left to the user's kitty.conf.
+pub(super) fn kitty_args(
+    title: &str,
+    geometry: Option<&WindowGeometry>,
+    editor_cmd: Vec<String>,
+) -> Vec<String> {
+    // Use single instance to avoid multiple dock icons, close window when editor exits
+    let mut args: Vec<String> = [
+        "--single-instance",
+        "--wait-for-single-instance-window-close",
+        "--title",
+        title,
+        "-o",
+        "close_on_child_death=yes",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+
+    // Add window position/size if provided
+    if let Some(geo) = geometry {
+        let (cols, rows) = pixels_to_cells(geo.width, geo.height);
+        args.extend([
+            "--position".to_string(),
+            format!("{}x{}", geo.x, geo.y),
+            "-o".to_string(),
+            format!("initial_window_width={}c", cols),
+            "-o".to_string(),
+            format!("initial_window_height={}c", rows),
+            // Otherwise kitty ignores the initial size in favor of the last window's
+            "-o".to_string(),
+            "remember_window_size=no".to_string(),
+        ]);
+    }
+
+    // Kitty runs the command directly (no -e flag needed)
+    args.extend(editor_cmd);
+    args
+}
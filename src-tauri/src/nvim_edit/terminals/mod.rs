@@ -21,14 +21,15 @@ pub use kitty::KittySpawner;
 pub use terminal_app::TerminalAppSpawner;
 pub use wezterm::WezTermSpawner;
 
-use crate::config::{NvimEditSettings, Settings};
+use crate::config::{EditorStart, NvimEditSettings, Settings};
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Child;
 use tauri::Manager;
+use uuid::Uuid;
 
 /// Window position and size for popup mode
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
 pub struct WindowGeometry {
     pub x: i32,
     pub y: i32,
@@ -61,7 +62,19 @@ impl TerminalType {
         }
     }
 
-    #[allow(dead_code)]
+    /// Process names of the terminal app, used to find its windows
+    pub fn process_names(&self) -> &'static [&'static str] {
+        match self {
+            TerminalType::Alacritty => &["alacritty", "Alacritty"],
+            TerminalType::Ghostty => &["ghostty", "Ghostty"],
+            TerminalType::Kitty => &["kitty"],
+            TerminalType::WezTerm => &["wezterm-gui", "WezTerm"],
+            TerminalType::ITerm => &["iTerm2"],
+            TerminalType::Custom => &[],
+            TerminalType::Default => &["Terminal"],
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TerminalType::Alacritty => "alacritty",
@@ -84,6 +97,28 @@ pub struct SpawnInfo {
     pub window_title: Option<String>,
 }
 
+/// Unique window title for the popup of edit session `session_id`, so ovim can find its window
+pub fn popup_window_title(session_id: &Uuid) -> String {
+    format!("ovim-edit-{}", &session_id.simple().to_string()[..8])
+}
+
+/// Bring the popup window forward: by title through accessibility when the spawner
+/// set one, otherwise (or if that fails) the terminal's first window via AppleScript
+pub fn focus_popup_window(terminal_type: &TerminalType, window_title: Option<&str>) {
+    let process_names = terminal_type.process_names();
+    if process_names.is_empty() {
+        return;
+    }
+    if let Some(title) = window_title {
+        if super::accessibility::raise_window_with_title(process_names, title) {
+            return;
+        }
+        log::info!("Window '{}' not raised via accessibility, using AppleScript", title);
+    }
+    // An empty title matches the terminal's first window
+    applescript_utils::focus_window_by_title(process_names, window_title.unwrap_or(""));
+}
+
 /// Trait for terminal spawners
 pub trait TerminalSpawner {
     /// The terminal type this spawner handles
@@ -92,29 +127,34 @@ pub trait TerminalSpawner {
 
     /// Spawn a terminal with the configured editor editing the given file
     ///
+    /// The window gets `window_title` where the terminal supports setting one.
+    ///
     /// If `socket_path` is provided, the editor will be started with RPC enabled
     /// (e.g., nvim --listen <socket_path>) for live buffer sync.
     ///
     /// If `custom_env` is provided, these environment variables will be applied
     /// to the spawned process (from the launcher script).
     ///
-    /// If `text_is_empty` is true, the editor should start in insert mode.
+    /// `start` is where the editor puts the cursor (insert mode for empty text).
     ///
     /// If `filetype` is provided, the editor will set the filetype on startup
     /// (e.g., nvim -c "set ft=markdown").
+    #[allow(clippy::too_many_arguments)]
     fn spawn(
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String>;
 }
 
 /// Spawn a terminal with the configured editor editing the given file
+/// in a window titled `window_title` (see `popup_window_title`)
 ///
 /// If `socket_path` is provided, the editor will be started with RPC enabled
 /// for live buffer sync.
@@ -126,15 +166,16 @@ pub trait TerminalSpawner {
 ///
 /// The terminal selection is passed via OVIM_TERMINAL env var for the script to use if needed.
 ///
-/// If `text_is_empty` is true, the editor should start in insert mode.
+/// `start` is where the editor puts the cursor (insert mode for empty text).
 ///
 /// If `filetype` is provided, the editor will set the filetype on startup.
 pub fn spawn_terminal(
     settings: &NvimEditSettings,
     temp_file: &Path,
+    window_title: &str,
     geometry: Option<WindowGeometry>,
     socket_path: Option<&Path>,
-    text_is_empty: bool,
+    start: EditorStart,
     filetype: Option<&str>,
 ) -> Result<SpawnInfo, String> {
     let terminal_type = TerminalType::from_string(&settings.terminal);
@@ -142,7 +183,7 @@ pub fn spawn_terminal(
 
     // If custom script is enabled, run it first
     if settings.use_custom_script {
-        match run_launcher_script(settings, &file_path, geometry.as_ref(), socket_path) {
+        match run_launcher_script(settings, &file_path, window_title, geometry.as_ref(), socket_path) {
             LauncherResult::Handled(info) => return Ok(info),
             LauncherResult::Fallthrough => {
                 log::info!("Launcher script returned fallthrough, continuing with normal terminal spawn");
@@ -153,13 +194,13 @@ pub fn spawn_terminal(
     }
 
     match terminal_type {
-        TerminalType::Alacritty => AlacrittySpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::Ghostty => GhosttySpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::Kitty => KittySpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::WezTerm => WezTermSpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::ITerm => ITermSpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::Custom => CustomSpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
-        TerminalType::Default => TerminalAppSpawner.spawn(settings, &file_path, geometry, socket_path, None, text_is_empty, filetype),
+        TerminalType::Alacritty => AlacrittySpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::Ghostty => GhosttySpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::Kitty => KittySpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::WezTerm => WezTermSpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::ITerm => ITermSpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::Custom => CustomSpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
+        TerminalType::Default => TerminalAppSpawner.spawn(settings, &file_path, window_title, geometry, socket_path, None, start, filetype),
     }
 }
 
@@ -295,3 +336,72 @@ pub fn get_ovim_cli_path() -> Option<std::path::PathBuf> {
         .map(|p| p.join("ovim").join("ovim"))
         .filter(|p| p.exists())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_popup_window_title_uses_session_id() {
+        let id = Uuid::parse_str("1a2b3c4d-5e6f-7a8b-9c0d-112233445566").unwrap();
+        assert_eq!(popup_window_title(&id), "ovim-edit-1a2b3c4d");
+    }
+
+    #[test]
+    fn test_spawn_args_contain_no_cosmetic_overrides() {
+        // Appearance comes from the user's own terminal config, never from ovim
+        let forbidden = [
+            "font", "color", "colour", "background", "opacity", "transparen", "blur",
+            "decoration", "padding", "theme", "titlebar", "margin", "shadow",
+        ];
+
+        let settings = NvimEditSettings::default();
+        let mut editor_cmd = vec![settings.editor_path()];
+        editor_cmd.extend(settings.editor_args(EditorStart::At { line: 2, column: 4 }));
+        editor_cmd.push("/tmp/edit.txt".to_string());
+        let title = "ovim-edit-1a2b3c4d";
+        let geometry = WindowGeometry {
+            x: 100,
+            y: 50,
+            width: 800,
+            height: 480,
+        };
+        let iterm_command = editor_cmd
+            .iter()
+            .map(|arg| format!("'{}'", arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let alacritty_config =
+            alacritty::SpawnConfig::with_command(title, editor_cmd.clone(), "alacritty".to_string())
+                .with_geometry(Some(&geometry));
+        let terminals: Vec<(&str, Vec<String>)> = vec![
+            ("Alacritty", alacritty::AlacrittySpawner.process_args(&alacritty_config)),
+            ("Ghostty", ghostty::ghostty_args(title, Some(&geometry), editor_cmd.clone())),
+            ("kitty", kitty::kitty_args(title, Some(&geometry), editor_cmd.clone())),
+            ("WezTerm", wezterm::wezterm_args(title, Some(&geometry), editor_cmd.clone())),
+            (
+                "iTerm",
+                iterm::iterm_script(title, Some(&geometry), &format!("{}; exit", iterm_command))
+                    .lines()
+                    .map(|line| line.trim().to_string())
+                    .collect(),
+            ),
+        ];
+
+        for (terminal, args) in terminals {
+            for arg in &args {
+                let lower = arg.to_lowercase();
+                if let Some(option) = forbidden.iter().find(|option| lower.contains(*option)) {
+                    panic!("{} sets a cosmetic option ({}): {}", terminal, option, arg);
+                }
+            }
+            assert!(args.iter().any(|arg| arg.contains(title)), "{} does not set the title", terminal);
+            assert!(
+                args.iter().any(|arg| arg.contains("/tmp/edit.txt")),
+                "{} does not run the editor",
+                terminal
+            );
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::process::Command;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Find the editor process editing a specific file (with initial delay)
 pub fn find_editor_pid_for_file(file_path: &str, process_name: &str) -> Option<u32> {
@@ -46,6 +46,48 @@ fn find_process_by_name(name: &str) -> Option<u32> {
     }
 }
 
+/// How long to wait for the editor to show up on a terminal's TTY
+const TTY_PROCESS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Find the editor process running on a terminal TTY (e.g., "/dev/ttys003").
+/// Polls until the shell has started the editor or the timeout passes.
+pub fn find_process_on_tty(tty: &str, process_name: &str) -> Option<u32> {
+    if process_name.is_empty() {
+        return None;
+    }
+    let tty = tty.trim().trim_start_matches("/dev/");
+    let deadline = Instant::now() + TTY_PROCESS_TIMEOUT;
+    loop {
+        let output = Command::new("ps").args(["-t", tty, "-o", "pid=,comm="]).output();
+        if let Ok(output) = output {
+            let listing = String::from_utf8_lossy(&output.stdout);
+            if let Some(pid) = parse_tty_processes(&listing, process_name) {
+                return Some(pid);
+            }
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Pick the newest `process_name` entry from `ps -o pid=,comm=` output
+fn parse_tty_processes(listing: &str, process_name: &str) -> Option<u32> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (pid, comm) = line.trim().split_once(char::is_whitespace)?;
+            // comm is the full executable path on macOS
+            let name = comm.trim().rsplit('/').next()?;
+            if name != process_name {
+                return None;
+            }
+            pid.parse().ok()
+        })
+        .max()
+}
+
 /// Common installation paths to check for binaries on macOS
 /// These are checked when the app is launched from GUI and has limited PATH
 const COMMON_BIN_PATHS: &[&str] = &[
@@ -134,3 +176,16 @@ pub fn resolve_terminal_path(terminal_name: &str) -> String {
     // Fall back to general command resolution
     resolve_command_path(terminal_name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tty_processes_matches_executable_name() {
+        let listing = "  812 -zsh\n  840 /opt/homebrew/bin/nvim\n  845 nvim-helper\n";
+        assert_eq!(parse_tty_processes(listing, "nvim"), Some(840));
+        assert_eq!(parse_tty_processes(listing, "vim"), None);
+        assert_eq!(parse_tty_processes("", "nvim"), None);
+    }
+}
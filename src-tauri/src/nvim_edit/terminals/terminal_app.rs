@@ -2,11 +2,11 @@
 
 use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
 
 use super::process_utils::find_editor_pid_for_file;
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
+use crate::osascript;
 
 /// Escape a string for use in shell (single-quote escaping)
 fn shell_escape(s: &str) -> String {
@@ -24,15 +24,16 @@ impl TerminalSpawner for TerminalAppSpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
         // Get editor path and args from settings (insert mode if text is empty)
         let editor_path = settings.editor_path();
-        let editor_args = settings.editor_args(text_is_empty);
+        let editor_args = settings.editor_args(start);
         let process_name = settings.editor_process_name();
 
         // Build socket args for nvim RPC if socket_path provided and using nvim
@@ -61,7 +62,7 @@ impl TerminalSpawner for TerminalAppSpawner {
         // Each argument must be shell-escaped to preserve integrity through AppleScript
         let mut all_args: Vec<String> = socket_args;
         all_args.extend(filetype_args);
-        all_args.extend(editor_args.iter().map(|s| s.to_string()));
+        all_args.extend(editor_args);
         let args_str = if all_args.is_empty() {
             String::new()
         } else {
@@ -89,6 +90,7 @@ impl TerminalSpawner for TerminalAppSpawner {
                 r#"
             tell application "Terminal"
                 do script "{}{}{} '{}'"
+                set custom title of selected tab of front window to "{}"
                 set bounds of front window to {{{}, {}, {}, {}}}
                 activate
             end tell
@@ -97,6 +99,7 @@ impl TerminalSpawner for TerminalAppSpawner {
                 editor_path,
                 args_str,
                 file_path,
+                window_title,
                 geo.x,
                 geo.y,
                 geo.x + geo.width as i32,
@@ -107,17 +110,15 @@ impl TerminalSpawner for TerminalAppSpawner {
                 r#"
             tell application "Terminal"
                 do script "{}{}{} '{}'"
+                set custom title of selected tab of front window to "{}"
                 activate
             end tell
             "#,
-                env_prefix, editor_path, args_str, file_path
+                env_prefix, editor_path, args_str, file_path, window_title
             )
         };
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .output()
+        osascript::run(&script, osascript::SPAWN_TIMEOUT)
             .map_err(|e| format!("Failed to run Terminal AppleScript: {}", e))?;
 
         // Try to find the editor process ID by the file it's editing
@@ -128,7 +129,7 @@ impl TerminalSpawner for TerminalAppSpawner {
             terminal_type: TerminalType::Default,
             process_id: pid,
             child: None,
-            window_title: None,
+            window_title: Some(window_title.to_string()),
         })
     }
 }
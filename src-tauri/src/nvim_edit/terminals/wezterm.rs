@@ -7,7 +7,7 @@ use std::process::Command;
 use super::applescript_utils::set_window_size;
 use super::process_utils::{resolve_command_path, resolve_terminal_path};
 use super::{SpawnInfo, TerminalSpawner, TerminalType, WindowGeometry};
-use crate::config::NvimEditSettings;
+use crate::config::{EditorStart, NvimEditSettings};
 
 pub struct WezTermSpawner;
 
@@ -20,15 +20,16 @@ impl TerminalSpawner for WezTermSpawner {
         &self,
         settings: &NvimEditSettings,
         file_path: &str,
+        window_title: &str,
         geometry: Option<WindowGeometry>,
         socket_path: Option<&Path>,
         custom_env: Option<&HashMap<String, String>>,
-        text_is_empty: bool,
+        start: EditorStart,
         filetype: Option<&str>,
     ) -> Result<SpawnInfo, String> {
         // Get editor path and args from settings (insert mode if text is empty)
         let editor_path = settings.editor_path();
-        let editor_args = settings.editor_args(text_is_empty);
+        let editor_args = settings.editor_args(start);
 
         // Build socket args for nvim RPC if socket_path provided and using nvim
         let socket_args: Vec<String> = if let Some(socket) = socket_path {
@@ -61,33 +62,14 @@ impl TerminalSpawner for WezTermSpawner {
         let resolved_terminal = resolve_terminal_path(&terminal_cmd);
         log::info!("Resolved terminal path: {} -> {}", terminal_cmd, resolved_terminal);
 
-        let mut cmd = Command::new(&resolved_terminal);
-
-        // Use --always-new-process so wezterm blocks until the command exits.
-        // WezTerm only supports --position for window placement (no --width/--height)
-        if let Some(ref geo) = geometry {
-            cmd.args([
-                "start",
-                "--always-new-process",
-                "--position",
-                &format!("screen:{},{}", geo.x, geo.y),
-                "--",
-            ]);
-        } else {
-            cmd.args(["start", "--always-new-process", "--"]);
-        }
+        let mut editor_cmd = vec![resolved_editor];
+        editor_cmd.extend(socket_args);
+        editor_cmd.extend(filetype_args);
+        editor_cmd.extend(editor_args);
+        editor_cmd.push(file_path.to_string());
 
-        cmd.arg(&resolved_editor);
-        for arg in &socket_args {
-            cmd.arg(arg);
-        }
-        for arg in &filetype_args {
-            cmd.arg(arg);
-        }
-        for arg in &editor_args {
-            cmd.arg(arg);
-        }
-        cmd.arg(file_path);
+        let mut cmd = Command::new(&resolved_terminal);
+        cmd.args(wezterm_args(window_title, geometry.as_ref(), editor_cmd));
 
         // Apply custom environment variables
         if let Some(env) = custom_env {
@@ -117,7 +99,34 @@ impl TerminalSpawner for WezTermSpawner {
             terminal_type: TerminalType::WezTerm,
             process_id: Some(wezterm_pid),
             child: Some(child),
-            window_title: None,
+            window_title: Some(window_title.to_string()),
         })
     }
 }
+
+/// Shell snippet that sets the window title to `$0` and then runs the editor
+const SET_TITLE_SCRIPT: &str = r#"printf '\033]2;%s\007' "$0"; exec "$@""#;
+
+/// WezTerm arguments: position and the editor command.
+/// WezTerm has no title option, so the command first sets `title` with an escape
+/// sequence. Fonts, colors and decorations are left to the user's wezterm.lua.
+pub(super) fn wezterm_args(title: &str, geometry: Option<&WindowGeometry>, editor_cmd: Vec<String>) -> Vec<String> {
+    // Use --always-new-process so wezterm blocks until the command exits.
+    // WezTerm only supports --position for window placement (no --width/--height)
+    let mut args = vec!["start".to_string(), "--always-new-process".to_string()];
+    if let Some(geo) = geometry {
+        args.extend([
+            "--position".to_string(),
+            format!("screen:{},{}", geo.x, geo.y),
+        ]);
+    }
+    args.extend([
+        "--".to_string(),
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        SET_TITLE_SCRIPT.to_string(),
+        title.to_string(),
+    ]);
+    args.extend(editor_cmd);
+    args
+}
@@ -1,5 +1,7 @@
 //! Text capture from focused elements
 
+use std::borrow::Cow;
+
 use super::accessibility::{self, ElementFrame};
 use super::browser_scripting::{self, BrowserType, CursorPosition};
 use super::clipboard::capture_text_via_clipboard;
@@ -12,14 +14,76 @@ pub struct CaptureResult {
     pub cursor_position: Option<CursorPosition>,
     /// Browser type if this is a browser
     pub browser_type: Option<BrowserType>,
+    /// The field's line endings; `text` has been normalized to `\n`
+    pub line_ending: LineEnding,
+}
+
+/// Line ending convention of a captured field.
+/// nvim edits the text with `\n`; the field gets its own convention back on restore.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, or a mix of conventions (left as captured)
+    #[default]
+    Lf,
+    /// `\r\n` (Windows-style, some AX text views)
+    CrLf,
+    /// `\r` (classic Mac)
+    Cr,
 }
 
-/// Capture text and element frame from the focused element
+impl LineEnding {
+    /// Detect the convention used by every line break in `text`
+    pub fn detect(text: &str) -> Self {
+        let lf = text.matches('\n').count();
+        let cr = text.matches('\r').count();
+        match (lf, cr) {
+            (_, 0) => Self::Lf,
+            (0, _) => Self::Cr,
+            _ if text.matches("\r\n").count() == lf && lf == cr => Self::CrLf,
+            // Mixed line endings can't be restored faithfully, so they aren't touched
+            _ => Self::Lf,
+        }
+    }
+
+    /// Convert `text` from this convention to `\n`
+    pub fn normalize(self, text: String) -> String {
+        match self {
+            Self::Lf => text,
+            Self::CrLf => text.replace("\r\n", "\n"),
+            Self::Cr => text.replace('\r', "\n"),
+        }
+    }
+
+    /// Convert `\n` line breaks in `text` back to this convention
+    pub fn restore(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Self::Lf => Cow::Borrowed(text),
+            Self::CrLf => Cow::Owned(text.replace('\n', "\r\n")),
+            Self::Cr => Cow::Owned(text.replace('\n', "\r")),
+        }
+    }
+}
+
+/// Capture text and element frame from the focused element, with line endings normalized to `\n`
 /// If clipboard_mode is true, always use clipboard-based capture (Cmd+A, Cmd+C)
 pub fn capture_text_and_frame(
     app_bundle_id: &str,
     initial_element_frame: Option<ElementFrame>,
     clipboard_mode: bool,
+) -> CaptureResult {
+    let mut result = capture(app_bundle_id, initial_element_frame, clipboard_mode);
+    result.line_ending = LineEnding::detect(&result.text);
+    if result.line_ending != LineEnding::Lf {
+        log::info!("Text capture: normalizing {:?} line endings", result.line_ending);
+        result.text = result.line_ending.normalize(std::mem::take(&mut result.text));
+    }
+    result
+}
+
+fn capture(
+    app_bundle_id: &str,
+    initial_element_frame: Option<ElementFrame>,
+    clipboard_mode: bool,
 ) -> CaptureResult {
     let browser_type = browser_scripting::detect_browser_type(app_bundle_id);
 
@@ -34,6 +98,21 @@ pub fn capture_text_and_frame(
             element_frame: initial_element_frame,
             cursor_position: None, // No cursor tracking in clipboard mode
             browser_type: None,    // Disable browser-specific features
+            line_ending: LineEnding::Lf,
+        };
+    }
+
+    // The address bar is browser UI, not page content, so JS can't reach it.
+    // Use the accessibility path for both capture and restore.
+    if is_browser_address_bar(app_bundle_id) {
+        log::info!("Browser address bar focused, using accessibility capture (skipping JS)");
+        let (text, _used_clipboard) = capture_text_content_with_source();
+        return CaptureResult {
+            text,
+            element_frame: initial_element_frame,
+            cursor_position: None,
+            browser_type: None, // AX live sync and restore, never JS
+            line_ending: LineEnding::Lf,
         };
     }
 
@@ -60,6 +139,7 @@ pub fn capture_text_and_frame(
                     element_frame,
                     cursor_position: result.cursor,
                     browser_type: Some(bt),
+                    line_ending: LineEnding::Lf,
                 };
             }
             log::info!("Text capture: JS returned empty text, falling back to clipboard");
@@ -88,53 +168,80 @@ pub fn capture_text_and_frame(
     };
 
     // Get text from the focused element, tracking whether we used clipboard
-    let (text, _used_clipboard, is_address_bar) = capture_text_content_with_source();
+    // browser_type = None since JS-based features won't work
+    let (text, _used_clipboard) = capture_text_content_with_source();
 
-    // If we're in a browser's address bar, disable browser live sync
-    // to avoid updating web page elements when editing the URL
-    let effective_browser_type = if browser_type.is_some() && is_address_bar {
-        log::info!("Browser address bar detected - disabling live sync to avoid updating web content");
-        None
-    } else {
-        // Return with browser_type = None since JS-based features won't work
-        None
-    };
+    CaptureResult {
+        text,
+        element_frame,
+        cursor_position: None,
+        browser_type: None,
+        line_ending: LineEnding::Lf,
+    }
+}
+
+/// AXIdentifier values browsers use for their address bar
+const ADDRESS_BAR_IDENTIFIERS: &[&str] = &[
+    "WEB_BROWSER_ADDRESS_AND_SEARCH_FIELD", // Safari
+];
+
+/// Accessibility attributes of a focused element, used to recognise the address bar
+#[derive(Debug, Default)]
+struct AddressBarProbe<'a> {
+    role: Option<&'a str>,
+    subrole: Option<&'a str>,
+    identifier: Option<&'a str>,
+    /// The element has an AXWebArea ancestor (it is page content)
+    in_web_area: bool,
+}
+
+/// Decide whether a focused browser element is the address bar
+fn looks_like_address_bar(probe: &AddressBarProbe) -> bool {
+    if probe
+        .identifier
+        .is_some_and(|id| ADDRESS_BAR_IDENTIFIERS.contains(&id))
+    {
+        return true;
+    }
+
+    // Page inputs live under the AXWebArea; the address bar is part of the browser chrome
+    if probe.in_web_area {
+        return false;
+    }
 
-    CaptureResult { text, element_frame, cursor_position: None, browser_type: effective_browser_type }
+    // Chrome/Brave/Arc address bar: AXTextField (or AXComboBox) with no subrole
+    matches!(probe.role, Some("AXTextField") | Some("AXComboBox")) && probe.subrole.is_none()
 }
 
-/// Check if the focused element is the browser's address bar (URL field)
-/// Returns true if it's the address bar, false otherwise
-fn is_browser_address_bar() -> bool {
+/// Check if the focused element is the address bar (URL field) of a supported browser
+pub(super) fn is_browser_address_bar(app_bundle_id: &str) -> bool {
+    if browser_scripting::detect_browser_type(app_bundle_id).is_none() {
+        return false;
+    }
+
     let role = accessibility::get_focused_element_role();
     let subrole = accessibility::get_focused_element_subrole();
-    log::info!("Focused element role: {:?}, subrole: {:?}", role, subrole);
-
-    // Browser address bar: AXTextField with no subrole
-    // Web content text fields: AXTextArea (multiline) or AXTextField with specific subroles
-    //
-    // Reddit comment field: AXTextArea
-    // Chrome/Brave address bar: AXTextField (no subrole)
-    if let Some(r) = &role {
-        if r == "AXTextField" {
-            // AXTextField could be address bar or a web form input
-            // Address bar has no subrole, web inputs often have subroles
-            // For safety, treat AXTextField without subrole as address bar in browsers
-            if subrole.is_none() {
-                return true;
-            }
-        }
-    }
+    let identifier = accessibility::get_focused_element_identifier();
+    let in_web_area = accessibility::is_focused_element_in_web_area();
+    log::info!(
+        "Focused element role: {:?}, subrole: {:?}, identifier: {:?}, in_web_area: {}",
+        role,
+        subrole,
+        identifier,
+        in_web_area
+    );
 
-    false
+    looks_like_address_bar(&AddressBarProbe {
+        role: role.as_deref(),
+        subrole: subrole.as_deref(),
+        identifier: identifier.as_deref(),
+        in_web_area,
+    })
 }
 
 /// Capture text content from the focused element
-/// Returns (text, used_clipboard, is_address_bar)
-fn capture_text_content_with_source() -> (String, bool, bool) {
-    // Check if we're in the browser address bar before capturing
-    let is_address_bar = is_browser_address_bar();
-
+/// Returns (text, used_clipboard)
+fn capture_text_content_with_source() -> (String, bool) {
     // First try accessibility API (doesn't cause beeps)
     let text = accessibility::get_focused_element_text().unwrap_or_default();
 
@@ -146,9 +253,98 @@ fn capture_text_content_with_source() -> (String, bool, bool) {
         log::info!("Accessibility text capture returned empty, trying clipboard-based capture");
         if let Some(captured) = capture_text_via_clipboard() {
             log::info!("Captured {} chars via clipboard", captured.len());
-            return (captured, true, is_address_bar);
+            return (captured, true);
+        }
+    }
+
+    (text, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TrailingNewline;
+
+    /// Capture `field`, save `edit(captured)` from nvim (which adds a missing final newline) and restore it
+    fn round_trip(field: &str, edit: impl Fn(&str) -> String) -> String {
+        let line_ending = LineEnding::detect(field);
+        let captured = line_ending.normalize(field.to_string());
+        assert!(!captured.contains('\r') || line_ending == LineEnding::Lf);
+        let mut saved = edit(&captured);
+        if !saved.is_empty() && !saved.ends_with('\n') {
+            saved.push('\n');
+        }
+        let edited = TrailingNewline::StripOne.apply(&saved, &captured);
+        line_ending.restore(edited).into_owned()
+    }
+
+    #[test]
+    fn test_detect_line_endings() {
+        assert_eq!(LineEnding::detect("one line"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("a\nb\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("a\r\nb\r\n"), LineEnding::CrLf);
+        assert_eq!(LineEnding::detect("a\rb"), LineEnding::Cr);
+        assert_eq!(LineEnding::detect("a\r\nb\nc"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("a\r\nb\rc"), LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_crlf_field_round_trip() {
+        let field = "Dear team,\r\n\r\nThe build is green.\r\nThanks\r\n";
+
+        // Unedited text comes back byte for byte
+        assert_eq!(round_trip(field, str::to_string), field);
+
+        // Edits keep the field's CRLF line breaks, including newly added lines
+        let edited = round_trip(field, |text| {
+            text.replace("green.", "green.\nRelease is tomorrow.")
+        });
+        assert_eq!(
+            edited,
+            "Dear team,\r\n\r\nThe build is green.\r\nRelease is tomorrow.\r\nThanks\r\n"
+        );
+    }
+
+    #[test]
+    fn test_other_line_endings_round_trip() {
+        for field in ["a\nb\n", "a\rb\r", "mixed\r\nline\nendings", ""] {
+            assert_eq!(round_trip(field, str::to_string), field);
         }
     }
 
-    (text, false, is_address_bar)
+    #[test]
+    fn test_safari_address_bar_by_identifier() {
+        let probe = AddressBarProbe {
+            role: Some("AXTextField"),
+            subrole: Some("AXSearchField"),
+            identifier: Some("WEB_BROWSER_ADDRESS_AND_SEARCH_FIELD"),
+            in_web_area: false,
+        };
+        assert!(looks_like_address_bar(&probe));
+    }
+
+    #[test]
+    fn test_chrome_address_bar_outside_web_area() {
+        let probe = AddressBarProbe {
+            role: Some("AXTextField"),
+            ..Default::default()
+        };
+        assert!(looks_like_address_bar(&probe));
+    }
+
+    #[test]
+    fn test_page_inputs_are_not_address_bar() {
+        let text_field = AddressBarProbe {
+            role: Some("AXTextField"),
+            in_web_area: true,
+            ..Default::default()
+        };
+        assert!(!looks_like_address_bar(&text_field));
+
+        let text_area = AddressBarProbe {
+            role: Some("AXTextArea"),
+            ..Default::default()
+        };
+        assert!(!looks_like_address_bar(&text_area));
+    }
 }
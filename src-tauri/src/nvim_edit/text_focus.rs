@@ -0,0 +1,213 @@
+//! Cached "is a text field focused" state, kept current by an AX observer
+//!
+//! The keyboard callback asks on every scroll/list key, and a live query walks
+//! the AX tree of the frontmost app on the event tap thread. Instead, an
+//! AXObserver on the frontmost app listens for `AXFocusedUIElementChanged` and
+//! re-checks the newly focused element off the main thread. Until the observer
+//! has reported (startup, or right after an app switch) the callback falls back
+//! to a live query and caches its result.
+
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+use core_foundation::runloop::{
+    kCFRunLoopDefaultMode, CFRunLoopAddSource, CFRunLoopGetMain, CFRunLoopRemoveSource,
+    CFRunLoopSourceRef,
+};
+use core_foundation::string::{CFString, CFStringRef};
+
+use super::accessibility::{self, AXElementHandle};
+
+type AXObserverRef = CFTypeRef;
+type AXObserverCallback =
+    unsafe extern "C" fn(AXObserverRef, CFTypeRef, CFStringRef, *mut std::ffi::c_void);
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXObserverCreate(
+        application: i32,
+        callback: AXObserverCallback,
+        out_observer: *mut AXObserverRef,
+    ) -> i32;
+    fn AXObserverAddNotification(
+        observer: AXObserverRef,
+        element: CFTypeRef,
+        notification: CFStringRef,
+        refcon: *mut std::ffi::c_void,
+    ) -> i32;
+    fn AXObserverGetRunLoopSource(observer: AXObserverRef) -> CFRunLoopSourceRef;
+    fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+}
+
+/// No observer registered: every check is a live query
+const UNOBSERVED: u8 = 0;
+/// Observing, but the focused element hasn't been checked yet
+const UNKNOWN: u8 = 1;
+const NOT_FOCUSED: u8 = 2;
+const FOCUSED: u8 = 3;
+
+static STATE: AtomicU8 = AtomicU8::new(UNOBSERVED);
+
+/// Bumped on every focus change, so a slow check can't overwrite a newer one
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+static OBSERVER: Mutex<Option<FocusObserver>> = Mutex::new(None);
+
+/// Whether a text field is focused, from the cache when the observer has reported
+pub fn is_text_field_focused() -> bool {
+    if let Some(focused) = cached(STATE.load(Ordering::Acquire)) {
+        return focused;
+    }
+    let generation = GENERATION.load(Ordering::Acquire);
+    let focused = accessibility::is_text_field_focused();
+    store_if_current(generation, focused);
+    focused
+}
+
+fn cached(state: u8) -> Option<bool> {
+    match state {
+        FOCUSED => Some(true),
+        NOT_FOCUSED => Some(false),
+        _ => None,
+    }
+}
+
+/// Cache `focused` unless focus moved (or the observer went away) since `generation`
+fn store_if_current(generation: u64, focused: bool) {
+    if STATE.load(Ordering::Acquire) == UNOBSERVED
+        || GENERATION.load(Ordering::Acquire) != generation
+    {
+        return;
+    }
+    let state = if focused { FOCUSED } else { NOT_FOCUSED };
+    STATE.store(state, Ordering::Release);
+}
+
+/// Forget the cached value; the next check queries live until the observer reports
+fn invalidate() -> u64 {
+    let generation = GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+    let _ = STATE.fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+        (state != UNOBSERVED).then_some(UNKNOWN)
+    });
+    generation
+}
+
+unsafe extern "C" fn focused_element_changed(
+    _observer: AXObserverRef,
+    element: CFTypeRef,
+    _notification: CFStringRef,
+    _refcon: *mut std::ffi::c_void,
+) {
+    let generation = invalidate();
+    let Some(element) = AXElementHandle::new(element) else {
+        return;
+    };
+    // AX queries can block on a busy app; keep them off the main run loop
+    std::thread::spawn(move || {
+        store_if_current(generation, element.is_text_field());
+    });
+}
+
+/// AXObserver registered on the main run loop, removed on drop
+struct FocusObserver {
+    observer: AXObserverRef,
+    source: CFRunLoopSourceRef,
+}
+
+// Safety: the observer is only created and dropped behind the OBSERVER mutex
+unsafe impl Send for FocusObserver {}
+
+impl FocusObserver {
+    fn new(pid: i32) -> Result<Self, i32> {
+        unsafe {
+            let mut observer: AXObserverRef = std::ptr::null();
+            let err = AXObserverCreate(pid, focused_element_changed, &mut observer);
+            if err != 0 || observer.is_null() {
+                return Err(err);
+            }
+
+            let app = AXUIElementCreateApplication(pid);
+            if app.is_null() {
+                CFRelease(observer);
+                return Err(-1);
+            }
+            let notification = CFString::new("AXFocusedUIElementChanged");
+            let err = AXObserverAddNotification(
+                observer,
+                app,
+                notification.as_concrete_TypeRef(),
+                std::ptr::null_mut(),
+            );
+            CFRelease(app);
+            if err != 0 {
+                CFRelease(observer);
+                return Err(err);
+            }
+
+            let source = AXObserverGetRunLoopSource(observer);
+            CFRunLoopAddSource(CFRunLoopGetMain(), source, kCFRunLoopDefaultMode);
+            Ok(Self { observer, source })
+        }
+    }
+}
+
+impl Drop for FocusObserver {
+    fn drop(&mut self) {
+        unsafe {
+            CFRunLoopRemoveSource(CFRunLoopGetMain(), self.source, kCFRunLoopDefaultMode);
+            CFRelease(self.observer);
+        }
+    }
+}
+
+/// Watch focus changes in the app with `pid` (call when the frontmost app changes)
+pub fn observe_app(pid: i32) {
+    let Ok(mut current) = OBSERVER.lock() else {
+        return;
+    };
+    current.take();
+    STATE.store(UNOBSERVED, Ordering::Release);
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+
+    match FocusObserver::new(pid) {
+        Ok(observer) => {
+            *current = Some(observer);
+            STATE.store(UNKNOWN, Ordering::Release);
+        }
+        Err(err) => {
+            log::debug!("Could not observe focus in pid {} (AX error {})", pid, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_checks_are_not_cached() {
+        assert_eq!(cached(UNOBSERVED), None);
+        assert_eq!(cached(UNKNOWN), None);
+
+        // Without an observer nothing is cached
+        let generation = GENERATION.load(Ordering::Acquire);
+        store_if_current(generation, true);
+        assert_eq!(cached(STATE.load(Ordering::Acquire)), None);
+
+        STATE.store(UNKNOWN, Ordering::Release);
+        let generation = GENERATION.load(Ordering::Acquire);
+        store_if_current(generation, true);
+        assert_eq!(cached(STATE.load(Ordering::Acquire)), Some(true));
+
+        // A check that started before the focus moved is dropped
+        let newer = invalidate();
+        assert_eq!(cached(STATE.load(Ordering::Acquire)), None);
+        store_if_current(generation, false);
+        assert_eq!(cached(STATE.load(Ordering::Acquire)), None);
+        store_if_current(newer, false);
+        assert_eq!(cached(STATE.load(Ordering::Acquire)), Some(false));
+
+        STATE.store(UNOBSERVED, Ordering::Release);
+    }
+}
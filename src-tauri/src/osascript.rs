@@ -0,0 +1,192 @@
+//! Run AppleScript through `osascript` with a timeout
+//!
+//! A hung browser or an Automation permission dialog can keep `osascript` from
+//! ever returning. Every script runs with a deadline instead; on expiry the
+//! process is killed and the caller gets a timeout error, which it handles like
+//! any other scripting failure (AX or clipboard fallback).
+//!
+//! `run_in_process_timeout` skips the process spawn (~240ms) for click mode's
+//! browser queries. It uses `NSAppleScript`, which is documented as main-thread
+//! only and can't be killed, so it is kept to those one-off queries: the script
+//! runs on a thread of its own and the caller stops waiting at the deadline.
+//! Anything that runs repeatedly, such as live sync, spawns `osascript` with `run`.
+
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Timeout for short queries and commands (browser JS, System Events)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for scripts that open terminal windows
+pub const SPAWN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often to check whether the script has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Run `script` with `osascript -e`, killing it after `timeout`.
+/// A non-zero exit is not an error here; callers check `status` as with `Command::output`.
+pub fn run(script: &str, timeout: Duration) -> Result<Output, String> {
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    run_command(command, timeout)
+}
+
+/// Like `Command::output`, but gives up after `timeout`
+fn run_command(mut command: Command, timeout: Duration) -> Result<Output, String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+
+    // Drain the pipes while waiting, so a large result can't fill them and block the script
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader.and_then(|r| r.join().ok()).unwrap_or_default()
+    };
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::warn!("osascript timed out after {:?}", timeout);
+                return Err(format!("osascript timed out after {:?}", timeout));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for osascript: {}", e)),
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Execute AppleScript in-process using NSAppleScript (avoids ~240ms osascript subprocess overhead)
+fn run_in_process(source: &str) -> Result<String, String> {
+    unsafe {
+        use objc::{class, msg_send, sel, sel_impl};
+
+        let ns_string_class = class!(NSString);
+        let c_source = std::ffi::CString::new(source).map_err(|e| format!("Invalid script: {}", e))?;
+        let source_nsstring: *mut objc::runtime::Object = msg_send![
+            ns_string_class,
+            stringWithUTF8String: c_source.as_ptr()
+        ];
+
+        if source_nsstring.is_null() {
+            return Err("Failed to create NSString".to_string());
+        }
+
+        let script_class = class!(NSAppleScript);
+        let script: *mut objc::runtime::Object = msg_send![script_class, alloc];
+        let script: *mut objc::runtime::Object = msg_send![script, initWithSource: source_nsstring];
+
+        if script.is_null() {
+            return Err("Failed to create NSAppleScript".to_string());
+        }
+
+        let mut error: *mut objc::runtime::Object = std::ptr::null_mut();
+        let result: *mut objc::runtime::Object = msg_send![script, executeAndReturnError: &mut error as *mut _];
+
+        if result.is_null() || !error.is_null() {
+            let err_msg = if !error.is_null() {
+                let desc: *mut objc::runtime::Object = msg_send![error, description];
+                if !desc.is_null() {
+                    let utf8: *const std::os::raw::c_char = msg_send![desc, UTF8String];
+                    if !utf8.is_null() {
+                        std::ffi::CStr::from_ptr(utf8).to_string_lossy().to_string()
+                    } else {
+                        "Unknown error".to_string()
+                    }
+                } else {
+                    "Unknown error".to_string()
+                }
+            } else {
+                "Script returned nil".to_string()
+            };
+            let _: () = msg_send![script, release];
+            return Err(format!("AppleScript error: {}", err_msg));
+        }
+
+        let string_value: *mut objc::runtime::Object = msg_send![result, stringValue];
+        let output = if !string_value.is_null() {
+            let utf8: *const std::os::raw::c_char = msg_send![string_value, UTF8String];
+            if !utf8.is_null() {
+                std::ffi::CStr::from_ptr(utf8).to_string_lossy().trim().to_string()
+            } else {
+                String::new()
+            }
+        } else {
+            String::new()
+        };
+
+        let _: () = msg_send![script, release];
+        Ok(output)
+    }
+}
+
+/// `run_in_process` on a separate thread, giving up after `timeout`. A script that
+/// times out can't be cancelled; it finishes (or hangs) on its thread unobserved.
+pub fn run_in_process_timeout(source: &str, timeout: Duration) -> Result<String, String> {
+    let (tx, rx) = mpsc::channel();
+    let source = source.to_string();
+    thread::spawn(move || {
+        let _ = tx.send(run_in_process(&source));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            log::warn!("In-process AppleScript timed out after {:?}", timeout);
+            Err(format!("AppleScript timed out after {:?}", timeout))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err("AppleScript thread panicked".to_string()),
+    }
+}
+
+fn read_to_end(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_output() {
+        let output = run(r#"return "ok""#, DEFAULT_TIMEOUT).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+    }
+
+    #[test]
+    fn test_slow_script_is_killed() {
+        let started = Instant::now();
+        let err = run("delay 10", Duration::from_millis(300)).unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_large_output_does_not_block() {
+        let mut command = Command::new("/bin/sh");
+        command.arg("-c").arg("head -c 300000 /dev/zero; echo done >&2");
+        let output = run_command(command, Duration::from_secs(5)).unwrap();
+        assert_eq!(output.stdout.len(), 300_000);
+        assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "done");
+    }
+}
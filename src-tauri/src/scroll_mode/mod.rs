@@ -3,15 +3,25 @@
 //! This module provides keyboard-driven scrolling similar to Vimium browser extension.
 //! Unlike vim mode, scroll mode is always active when enabled (no toggle needed).
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
-use crate::keyboard::{self, KeyCode};
+use crate::keyboard::{KeyCode, KeyInjector, SharedInjector};
+
+/// Time between wheel events while continuously scrolling
+const CONTINUOUS_SCROLL_TICK: Duration = Duration::from_millis(16);
+/// Continuous scrolling moves scroll_step pixels every this many ticks
+const CONTINUOUS_SCROLL_TICKS_PER_STEP: u32 = 8;
 
 /// State for scroll mode processing
 #[derive(Debug, Default)]
 pub struct ScrollModeState {
     /// Pending g key for gg command (scroll to top)
     pending_g: bool,
+    /// Held j/k key driving a continuous scroll, and the flag that stops its ticker
+    continuous: Option<(KeyCode, Arc<AtomicBool>)>,
 }
 
 /// Result of processing a scroll mode key
@@ -34,12 +44,79 @@ impl ScrollModeState {
         self.pending_g = false;
     }
 
+    /// Check if a g is waiting for a second g
+    pub fn has_pending_g(&self) -> bool {
+        self.pending_g
+    }
+
+    /// Scroll smoothly in the direction of `keycode` (j or k) until stopped
+    pub fn start_continuous_scroll(
+        &mut self,
+        injector: SharedInjector,
+        keycode: KeyCode,
+        scroll_step: u32,
+    ) {
+        let down = match keycode {
+            KeyCode::J => true,
+            KeyCode::K => false,
+            _ => return,
+        };
+        self.stop_continuous_scroll();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        self.continuous = Some((keycode, Arc::clone(&stop)));
+        let amount = (scroll_step / CONTINUOUS_SCROLL_TICKS_PER_STEP).max(1);
+
+        thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                let result = if down {
+                    injector.scroll_down(amount)
+                } else {
+                    injector.scroll_up(amount)
+                };
+                if let Err(e) = result {
+                    log::error!("Failed to scroll continuously: {}", e);
+                    break;
+                }
+                thread::sleep(CONTINUOUS_SCROLL_TICK);
+            }
+        });
+    }
+
+    /// Check if a continuous scroll is running for this key
+    pub fn is_continuous_scrolling(&self, keycode: KeyCode) -> bool {
+        self.continuous.as_ref().is_some_and(|(key, _)| *key == keycode)
+    }
+
+    /// Stop the continuous scroll when its key is released.
+    /// Releases of other keys are ignored. Returns true if the scroll was stopped.
+    pub fn release_key(&mut self, keycode: KeyCode) -> bool {
+        if self.is_continuous_scrolling(keycode) {
+            self.stop_continuous_scroll()
+        } else {
+            false
+        }
+    }
+
+    /// Stop any continuous scroll. Returns true if one was running.
+    pub fn stop_continuous_scroll(&mut self) -> bool {
+        match self.continuous.take() {
+            Some((_, stop)) => {
+                stop.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Process a key press in scroll mode
     ///
     /// Returns whether the key was handled or should pass through.
     /// Keys with modifiers (except Shift for G and R) are passed through.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_key(
         &mut self,
+        injector: &dyn KeyInjector,
         keycode: KeyCode,
         shift: bool,
         control: bool,
@@ -60,7 +137,7 @@ impl ScrollModeState {
             self.pending_g = false;
             if keycode == KeyCode::G && !shift {
                 // gg - scroll to top
-                if let Err(e) = keyboard::scroll_to_top() {
+                if let Err(e) = injector.scroll_to_top() {
                     log::error!("Failed to scroll to top: {}", e);
                 }
                 return ScrollResult::Handled;
@@ -75,7 +152,7 @@ impl ScrollModeState {
             // h - scroll left
             KeyCode::H if !shift => {
                 if is_disabled("hjkl") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::scroll_left(scroll_step) {
+                if let Err(e) = injector.scroll_left(scroll_step) {
                     log::error!("Failed to scroll left: {}", e);
                 }
                 ScrollResult::Handled
@@ -84,7 +161,7 @@ impl ScrollModeState {
             // j - scroll down
             KeyCode::J if !shift => {
                 if is_disabled("hjkl") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::scroll_down(scroll_step) {
+                if let Err(e) = injector.scroll_down(scroll_step) {
                     log::error!("Failed to scroll down: {}", e);
                 }
                 ScrollResult::Handled
@@ -93,7 +170,7 @@ impl ScrollModeState {
             // k - scroll up
             KeyCode::K if !shift => {
                 if is_disabled("hjkl") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::scroll_up(scroll_step) {
+                if let Err(e) = injector.scroll_up(scroll_step) {
                     log::error!("Failed to scroll up: {}", e);
                 }
                 ScrollResult::Handled
@@ -102,7 +179,7 @@ impl ScrollModeState {
             // l - scroll right
             KeyCode::L if !shift => {
                 if is_disabled("hjkl") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::scroll_right(scroll_step) {
+                if let Err(e) = injector.scroll_right(scroll_step) {
                     log::error!("Failed to scroll right: {}", e);
                 }
                 ScrollResult::Handled
@@ -111,7 +188,7 @@ impl ScrollModeState {
             // G (shift+g) - scroll to bottom
             KeyCode::G if shift => {
                 if is_disabled("G") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::scroll_to_bottom() {
+                if let Err(e) = injector.scroll_to_bottom() {
                     log::error!("Failed to scroll to bottom: {}", e);
                 }
                 ScrollResult::Handled
@@ -127,7 +204,7 @@ impl ScrollModeState {
             // d - half page down
             KeyCode::D if !shift => {
                 if is_disabled("du") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::half_page_scroll_down() {
+                if let Err(e) = injector.half_page_scroll_down() {
                     log::error!("Failed to half page down: {}", e);
                 }
                 ScrollResult::Handled
@@ -136,7 +213,7 @@ impl ScrollModeState {
             // u - half page up
             KeyCode::U if !shift => {
                 if is_disabled("du") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::half_page_scroll_up() {
+                if let Err(e) = injector.half_page_scroll_up() {
                     log::error!("Failed to half page up: {}", e);
                 }
                 ScrollResult::Handled
@@ -145,7 +222,7 @@ impl ScrollModeState {
             // / - open find (Cmd+F)
             KeyCode::Slash if !shift => {
                 if is_disabled("slash") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::open_find() {
+                if let Err(e) = injector.open_find() {
                     log::error!("Failed to open find: {}", e);
                 }
                 ScrollResult::Handled
@@ -154,7 +231,7 @@ impl ScrollModeState {
             // H (shift+h) - history back
             KeyCode::H if shift => {
                 if is_disabled("HL") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::history_back() {
+                if let Err(e) = injector.history_back() {
                     log::error!("Failed to go back in history: {}", e);
                 }
                 ScrollResult::Handled
@@ -163,7 +240,7 @@ impl ScrollModeState {
             // L (shift+l) - history forward
             KeyCode::L if shift => {
                 if is_disabled("HL") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::history_forward() {
+                if let Err(e) = injector.history_forward() {
                     log::error!("Failed to go forward in history: {}", e);
                 }
                 ScrollResult::Handled
@@ -172,7 +249,7 @@ impl ScrollModeState {
             // r - reload
             KeyCode::R if !shift => {
                 if is_disabled("rR") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::reload_page(false) {
+                if let Err(e) = injector.reload_page(false) {
                     log::error!("Failed to reload: {}", e);
                 }
                 ScrollResult::Handled
@@ -181,7 +258,7 @@ impl ScrollModeState {
             // R (shift+r) - hard reload
             KeyCode::R if shift => {
                 if is_disabled("rR") { return ScrollResult::PassThrough; }
-                if let Err(e) = keyboard::reload_page(true) {
+                if let Err(e) = injector.reload_page(true) {
                     log::error!("Failed to hard reload: {}", e);
                 }
                 ScrollResult::Handled
@@ -200,3 +277,71 @@ pub type SharedScrollModeState = Arc<Mutex<ScrollModeState>>;
 pub fn create_scroll_state() -> SharedScrollModeState {
     Arc::new(Mutex::new(ScrollModeState::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::RecordingInjector;
+
+    /// Press plain keys (no modifiers) and return what was injected
+    fn press(
+        state: &mut ScrollModeState,
+        keys: &[(KeyCode, bool)],
+        disabled: &[String],
+    ) -> Vec<String> {
+        let injector = RecordingInjector::default();
+        for &(keycode, shift) in keys {
+            state.process_key(&injector, keycode, shift, false, false, false, 40, disabled);
+        }
+        injector.take()
+    }
+
+    #[test]
+    fn test_scroll_keys_inject_expected_events() {
+        let mut state = ScrollModeState::new();
+        let keys = [
+            (KeyCode::J, false),
+            (KeyCode::L, false),
+            (KeyCode::G, false),
+            (KeyCode::G, false),
+            (KeyCode::G, true),
+            (KeyCode::R, true),
+        ];
+        assert_eq!(
+            press(&mut state, &keys, &[]),
+            ["scroll 0,-40", "scroll -40,0", "home", "end", "shift+cmd+r"]
+        );
+    }
+
+    #[test]
+    fn test_modified_and_disabled_keys_pass_through() {
+        let mut state = ScrollModeState::new();
+        let injector = RecordingInjector::default();
+        let result = state.process_key(&injector, KeyCode::J, false, false, false, true, 40, &[]);
+        assert_eq!(result, ScrollResult::PassThrough);
+
+        let disabled = vec!["hjkl".to_string()];
+        let result =
+            state.process_key(&injector, KeyCode::J, false, false, false, false, 40, &disabled);
+        assert_eq!(result, ScrollResult::PassThrough);
+        assert!(injector.take().is_empty());
+    }
+
+    #[test]
+    fn test_continuous_scroll_ignores_other_key_release() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut state = ScrollModeState {
+            continuous: Some((KeyCode::J, Arc::clone(&stop))),
+            ..Default::default()
+        };
+
+        assert!(!state.release_key(KeyCode::K));
+        assert!(state.is_continuous_scrolling(KeyCode::J));
+        assert!(!stop.load(Ordering::SeqCst));
+
+        assert!(state.release_key(KeyCode::J));
+        assert!(!state.is_continuous_scrolling(KeyCode::J));
+        assert!(stop.load(Ordering::SeqCst));
+        assert!(!state.stop_continuous_scroll());
+    }
+}
@@ -0,0 +1,110 @@
+//! Unix socket path helpers shared by the app and the `ovim` CLI
+//!
+//! The CLI includes this file with `#[path]`, so both resolve the IPC socket the
+//! same way; it must stay self-contained.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Longest socket path macOS accepts (sun_path is 104 bytes including the NUL)
+pub const MAX_SOCKET_PATH_LEN: usize = 103;
+
+/// Whether a socket path fits in sun_path
+pub fn fits_socket_limit(path: &Path) -> bool {
+    path.as_os_str().len() <= MAX_SOCKET_PATH_LEN
+}
+
+/// Expand a leading `~/` to the home folder
+pub fn expand_home(dir: &str) -> PathBuf {
+    match (dir.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(dir),
+    }
+}
+
+/// Numbers write probes so concurrent checks never share a file
+static PROBE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Create the directory if needed and check that files can be created in it
+pub fn ensure_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(
+        ".ovim-write-test-{}-{}",
+        std::process::id(),
+        PROBE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?;
+    std::fs::remove_file(&probe)
+}
+
+/// Get the default socket path for IPC
+pub fn default_ipc_socket_path() -> PathBuf {
+    let runtime_dir = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .unwrap_or_else(|| PathBuf::from("/tmp"));
+    runtime_dir.join("ovim.sock")
+}
+
+/// Get the socket path for IPC: the configured `ipc_socket_path`, or the default
+/// with a warning when the configured path is too long or its directory isn't writable
+pub fn ipc_socket_path(configured: Option<&Path>) -> PathBuf {
+    let Some(configured) = configured.filter(|p| !p.as_os_str().is_empty()) else {
+        return default_ipc_socket_path();
+    };
+    let path = expand_home(&configured.to_string_lossy());
+
+    if !fits_socket_limit(&path) {
+        log::warn!("IPC socket path {:?} is too long, using the default", path);
+        return default_ipc_socket_path();
+    }
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    if let Err(e) = ensure_writable(dir) {
+        log::warn!("IPC socket directory {:?} is not writable ({}), using the default", dir, e);
+        return default_ipc_socket_path();
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_socket_path_uses_default() {
+        assert_eq!(ipc_socket_path(None), default_ipc_socket_path());
+        assert_eq!(ipc_socket_path(Some(Path::new(""))), default_ipc_socket_path());
+    }
+
+    #[test]
+    fn test_configured_socket_path_in_writable_dir() {
+        let path = std::env::temp_dir().join("ovim-ipc-test.sock");
+        assert_eq!(ipc_socket_path(Some(&path)), path);
+    }
+
+    #[test]
+    fn test_unusable_socket_path_falls_back_to_default() {
+        // A file can't be a directory
+        assert_eq!(ipc_socket_path(Some(Path::new("/dev/null/ovim.sock"))), default_ipc_socket_path());
+        let long = PathBuf::from(format!("/tmp/{}.sock", "x".repeat(120)));
+        assert_eq!(ipc_socket_path(Some(&long)), default_ipc_socket_path());
+    }
+
+    #[test]
+    fn test_concurrent_write_checks_use_separate_probes() {
+        let dir = std::env::temp_dir().join(format!("ovim-probe-test-{}", std::process::id()));
+        let checks: Vec<_> = (0..8)
+            .map(|_| {
+                let dir = dir.clone();
+                std::thread::spawn(move || ensure_writable(&dir))
+            })
+            .collect();
+        for check in checks {
+            assert!(check.join().unwrap().is_ok());
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+}
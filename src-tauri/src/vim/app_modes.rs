@@ -0,0 +1,85 @@
+//! Per-app mode memory (`remember_mode_per_app` setting)
+
+use std::collections::HashMap;
+
+use super::VimMode;
+
+/// Modes apps were left in, keyed by bundle ID
+#[derive(Debug, Default)]
+pub struct AppModes {
+    modes: HashMap<String, VimMode>,
+    /// The app the current mode belongs to
+    current_app: Option<String>,
+}
+
+impl AppModes {
+    pub fn new(current_app: Option<String>) -> Self {
+        Self {
+            modes: HashMap::new(),
+            current_app,
+        }
+    }
+
+    /// Remember `current_mode` for the app that lost focus and return the mode for
+    /// `app`: the one it was left in, or `default_mode` (when set) the first time it
+    /// is focused
+    pub fn switch_to(
+        &mut self,
+        app: &str,
+        current_mode: VimMode,
+        default_mode: Option<VimMode>,
+    ) -> VimMode {
+        if self.current_app.as_deref() == Some(app) {
+            return current_mode;
+        }
+        if let Some(previous) = self.current_app.replace(app.to_string()) {
+            self.modes.insert(previous, current_mode);
+        }
+        self.modes
+            .get(app)
+            .copied()
+            .or(default_mode)
+            .unwrap_or(current_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modes_are_restored_per_app() {
+        let mut modes = AppModes::new(Some("com.apple.Safari".to_string()));
+        let insert = Some(VimMode::Insert);
+        // Safari was left in normal mode; Notes starts in the default mode
+        let mode = modes.switch_to("com.apple.Notes", VimMode::Normal, insert);
+        assert_eq!(mode, VimMode::Insert);
+        let mode = modes.switch_to("com.apple.Safari", VimMode::Visual, insert);
+        assert_eq!(mode, VimMode::Normal);
+        let mode = modes.switch_to("com.apple.Notes", VimMode::Insert, insert);
+        assert_eq!(mode, VimMode::Visual);
+    }
+
+    #[test]
+    fn test_new_apps_without_default_keep_the_mode() {
+        let mut modes = AppModes::new(Some("com.apple.Safari".to_string()));
+        let mode = modes.switch_to("com.apple.Notes", VimMode::Normal, None);
+        assert_eq!(mode, VimMode::Normal);
+        let mode = modes.switch_to("com.apple.Safari", VimMode::Insert, None);
+        assert_eq!(mode, VimMode::Normal);
+    }
+
+    #[test]
+    fn test_refocusing_the_same_app_keeps_the_mode() {
+        let mut modes = AppModes::default();
+        let normal = Some(VimMode::Normal);
+        assert_eq!(
+            modes.switch_to("com.apple.Notes", VimMode::Insert, normal),
+            VimMode::Normal
+        );
+        assert_eq!(
+            modes.switch_to("com.apple.Notes", VimMode::Visual, normal),
+            VimMode::Visual
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::keyboard;
+use crate::keyboard::KeyInjector;
 
 /// Vim commands that can be executed
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -71,115 +71,136 @@ pub enum VimCommand {
 }
 
 impl VimCommand {
+    /// Motions that keep moving while their key is held
+    pub fn is_repeatable_motion(&self) -> bool {
+        matches!(
+            self,
+            Self::MoveLeft
+                | Self::MoveRight
+                | Self::MoveUp
+                | Self::MoveDown
+                | Self::WordForward
+                | Self::WordEnd
+                | Self::WordBackward
+                | Self::WordEndBackward
+                | Self::ParagraphUp
+                | Self::ParagraphDown
+                | Self::PageUp
+                | Self::PageDown
+                | Self::HalfPageUp
+                | Self::HalfPageDown
+        )
+    }
+
     /// Execute the command, optionally with visual selection
-    pub fn execute(&self, count: u32, select: bool) -> Result<(), String> {
+    pub fn execute(&self, injector: &dyn KeyInjector, count: u32, select: bool) -> Result<(), String> {
         match self {
             // Basic motions
-            Self::MoveLeft => keyboard::cursor_left(count, select),
-            Self::MoveRight => keyboard::cursor_right(count, select),
-            Self::MoveUp => keyboard::cursor_up(count, select),
-            Self::MoveDown => keyboard::cursor_down(count, select),
+            Self::MoveLeft => injector.cursor_left(count, select),
+            Self::MoveRight => injector.cursor_right(count, select),
+            Self::MoveUp => injector.cursor_up(count, select),
+            Self::MoveDown => injector.cursor_down(count, select),
 
             // Word motions
-            Self::WordForward | Self::WordEnd => keyboard::word_forward(count, select),
-            Self::WordBackward | Self::WordEndBackward => keyboard::word_backward(count, select),
+            Self::WordForward | Self::WordEnd => injector.word_forward(count, select),
+            Self::WordBackward | Self::WordEndBackward => injector.word_backward(count, select),
 
             // Line motions
-            Self::LineStart => keyboard::line_start(select),
-            Self::LineEnd => keyboard::line_end(select),
+            Self::LineStart => injector.line_start(select),
+            Self::LineEnd => injector.line_end(select),
 
             // Paragraph motions
-            Self::ParagraphUp => keyboard::paragraph_up(count, select),
-            Self::ParagraphDown => keyboard::paragraph_down(count, select),
+            Self::ParagraphUp => injector.paragraph_up(count, select),
+            Self::ParagraphDown => injector.paragraph_down(count, select),
 
             // Document motions
-            Self::DocumentStart => keyboard::document_start(select),
-            Self::DocumentEnd => keyboard::document_end(select),
+            Self::DocumentStart => injector.document_start(select),
+            Self::DocumentEnd => injector.document_end(select),
 
             // Page motions
-            Self::PageUp | Self::HalfPageUp => keyboard::page_up(select),
-            Self::PageDown | Self::HalfPageDown => keyboard::page_down(select),
+            Self::PageUp | Self::HalfPageUp => injector.page_up(select),
+            Self::PageDown | Self::HalfPageDown => injector.page_down(select),
 
             // Insert mode transitions
-            Self::InsertAtLineStart => keyboard::line_start(false),
-            Self::AppendAfterCursor => keyboard::cursor_right(1, false),
-            Self::AppendAtLineEnd => keyboard::line_end(false),
-            Self::OpenLineBelow => keyboard::new_line_below(),
-            Self::OpenLineAbove => keyboard::new_line_above(),
-            Self::SubstituteChar => keyboard::delete_char(),
+            Self::InsertAtLineStart => injector.line_start(false),
+            Self::AppendAfterCursor => injector.cursor_right(1, false),
+            Self::AppendAtLineEnd => injector.line_end(false),
+            Self::OpenLineBelow => injector.new_line_below(),
+            Self::OpenLineAbove => injector.new_line_above(),
+            Self::SubstituteChar => injector.delete_char(),
             Self::SubstituteLine => {
-                keyboard::line_start(false)?;
-                keyboard::line_end(true)?;
-                keyboard::cut()
+                injector.line_start(false)?;
+                injector.line_end(true)?;
+                injector.cut()
             }
 
             // Operations
             Self::DeleteChar => {
                 for _ in 0..count {
-                    keyboard::delete_char()?;
+                    injector.delete_char()?;
                 }
                 Ok(())
             }
             Self::DeleteCharBefore => {
                 for _ in 0..count {
-                    keyboard::backspace()?;
+                    injector.backspace()?;
                 }
                 Ok(())
             }
             Self::DeleteLine => {
-                keyboard::line_start(false)?;
-                keyboard::line_end(true)?;
-                keyboard::cut()
+                injector.line_start(false)?;
+                injector.line_end(true)?;
+                injector.cut()
             }
             Self::DeleteToLineEnd => {
-                keyboard::line_end(true)?;
-                keyboard::cut()
+                injector.line_end(true)?;
+                injector.cut()
             }
             Self::YankLine => {
-                keyboard::line_start(false)?;
-                keyboard::line_end(true)?;
-                keyboard::copy()
+                injector.line_start(false)?;
+                injector.line_end(true)?;
+                injector.copy()
             }
             Self::ChangeLine => {
-                keyboard::line_start(false)?;
-                keyboard::line_end(true)?;
-                keyboard::cut()
+                injector.line_start(false)?;
+                injector.line_end(true)?;
+                injector.cut()
             }
             Self::ChangeToLineEnd => {
-                keyboard::line_end(true)?;
-                keyboard::cut()
+                injector.line_end(true)?;
+                injector.cut()
             }
             Self::JoinLines => {
                 for _ in 0..count {
-                    keyboard::join_lines()?;
+                    injector.join_lines()?;
                 }
                 Ok(())
             }
 
             // Text objects
-            Self::InnerWord => keyboard::select_inner_word(),
-            Self::AroundWord => keyboard::select_around_word(),
+            Self::InnerWord => injector.select_inner_word(),
+            Self::AroundWord => injector.select_around_word(),
 
             // Indent
             Self::IndentLine => {
                 for _ in 0..count {
-                    keyboard::indent_line()?;
+                    injector.indent_line()?;
                 }
                 Ok(())
             }
             Self::OutdentLine => {
                 for _ in 0..count {
-                    keyboard::outdent_line()?;
+                    injector.outdent_line()?;
                 }
                 Ok(())
             }
 
             // Clipboard
-            Self::Paste | Self::PasteBefore => keyboard::paste(),
+            Self::Paste | Self::PasteBefore => injector.paste(),
 
             // Undo/Redo
-            Self::Undo => keyboard::undo(),
-            Self::Redo => keyboard::redo(),
+            Self::Undo => injector.undo(),
+            Self::Redo => injector.redo(),
         }
     }
 }
@@ -194,26 +215,52 @@ pub enum Operator {
 
 impl Operator {
     /// Execute operator with the given motion
-    pub fn execute_with_motion(&self, motion: VimCommand, count: u32) -> Result<bool, String> {
+    pub fn execute_with_motion(
+        &self,
+        injector: &dyn KeyInjector,
+        motion: VimCommand,
+        count: u32,
+    ) -> Result<bool, String> {
         // First, select the text
-        motion.execute(count, true)?;
+        motion.execute(injector, count, true)?;
 
         // Then apply the operator
         match self {
             Self::Delete => {
-                keyboard::cut()?;
+                injector.cut()?;
                 Ok(false) // Stay in normal mode
             }
             Self::Yank => {
-                keyboard::copy()?;
+                injector.copy()?;
                 // Move cursor back (yank doesn't delete)
-                keyboard::cursor_left(1, false)?;
+                injector.cursor_left(1, false)?;
                 Ok(false) // Stay in normal mode
             }
             Self::Change => {
-                keyboard::cut()?;
+                injector.cut()?;
                 Ok(true) // Enter insert mode
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::RecordingInjector;
+
+    #[test]
+    fn test_operator_selects_motion_then_applies() {
+        let injector = RecordingInjector::default();
+        let insert = Operator::Yank.execute_with_motion(&injector, VimCommand::WordForward, 2);
+        assert!(!insert.unwrap());
+        assert_eq!(
+            injector.take(),
+            ["shift+opt+right", "shift+opt+right", "cmd+c", "left"]
+        );
+
+        let insert = Operator::Change.execute_with_motion(&injector, VimCommand::LineEnd, 1);
+        assert!(insert.unwrap());
+        assert_eq!(injector.take(), ["shift+cmd+right", "cmd+x"]);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod state;
 pub mod modes;
 pub mod commands;
+pub mod app_modes;
 
 pub use state::{VimState, ProcessResult, VimAction};
 pub use modes::VimMode;
+pub use app_modes::AppModes;
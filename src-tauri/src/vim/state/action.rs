@@ -1,4 +1,4 @@
-use crate::keyboard::{self, KeyCode};
+use crate::keyboard::{KeyCode, KeyInjector};
 use super::super::commands::{Operator, VimCommand};
 
 /// Action to execute after suppressing the key event
@@ -20,33 +20,33 @@ pub enum VimAction {
 
 impl VimAction {
     /// Execute the action
-    pub fn execute(&self) -> Result<bool, String> {
+    pub fn execute(&self, injector: &dyn KeyInjector) -> Result<bool, String> {
         match self {
             VimAction::Command { command, count, select } => {
-                command.execute(*count, *select)?;
+                command.execute(injector, *count, *select)?;
                 Ok(false)
             }
             VimAction::OperatorMotion { operator, motion, count } => {
-                operator.execute_with_motion(*motion, *count)
+                operator.execute_with_motion(injector, *motion, *count)
             }
             VimAction::TextObject { operator, text_object, count } => {
                 // Execute the text object selection
                 for _ in 0..*count {
-                    text_object.execute(1, false)?;
+                    text_object.execute(injector, 1, false)?;
                 }
                 // Apply the operator
                 match operator {
                     Operator::Delete => {
-                        keyboard::cut()?;
+                        injector.cut()?;
                         Ok(false)
                     }
                     Operator::Yank => {
-                        keyboard::copy()?;
-                        keyboard::cursor_left(1, false)?;
+                        injector.copy()?;
+                        injector.cursor_left(1, false)?;
                         Ok(false)
                     }
                     Operator::Change => {
-                        keyboard::cut()?;
+                        injector.cut()?;
                         Ok(true) // Enter insert mode
                     }
                 }
@@ -54,19 +54,24 @@ impl VimAction {
             VimAction::ReplaceChar { keycode, shift, count } => {
                 // Delete char(s) and type replacement
                 for _ in 0..*count {
-                    keyboard::delete_char()?;
-                    keyboard::type_char(*keycode, *shift)?;
+                    injector.delete_char()?;
+                    injector.type_char(*keycode, *shift)?;
                 }
                 Ok(false)
             }
             VimAction::Cut => {
-                keyboard::cut()?;
+                injector.cut()?;
                 Ok(false)
             }
             VimAction::Copy => {
-                keyboard::copy()?;
+                injector.copy()?;
                 Ok(false)
             }
         }
     }
+
+    /// Check if holding the key that produced this action should repeat it
+    pub fn is_repeatable_motion(&self) -> bool {
+        matches!(self, VimAction::Command { command, .. } if command.is_repeatable_motion())
+    }
 }
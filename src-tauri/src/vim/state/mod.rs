@@ -57,11 +57,12 @@ pub struct VimState {
 }
 
 impl VimState {
-    pub fn new() -> (Self, broadcast::Receiver<VimMode>) {
+    /// Create the state in `initial_mode` (the `initial_mode` setting)
+    pub fn new(initial_mode: VimMode) -> (Self, broadcast::Receiver<VimMode>) {
         let (mode_tx, mode_rx) = broadcast::channel(16);
         (
             Self {
-                mode: VimMode::Insert,
+                mode: initial_mode,
                 pending_count: None,
                 pending_operator: None,
                 pending_g: false,
@@ -218,6 +219,164 @@ impl VimState {
 
 impl Default for VimState {
     fn default() -> Self {
-        Self::new().0
+        Self::new(VimMode::default()).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::Modifiers;
+    use crate::vim::commands::VimCommand;
+
+    fn press(state: &mut VimState, keycode: KeyCode, modifiers: Modifiers) -> ProcessResult {
+        state.process_key(KeyEvent {
+            code: keycode.as_raw(),
+            modifiers,
+            is_key_down: true,
+        })
+    }
+
+    fn shift() -> Modifiers {
+        Modifiers {
+            shift: true,
+            ..Default::default()
+        }
+    }
+
+    fn visual_state() -> VimState {
+        let mut state = VimState::default();
+        state.set_mode(VimMode::Normal);
+        press(&mut state, KeyCode::V, Modifiers::default());
+        state
+    }
+
+    fn selects(result: ProcessResult, expected: VimCommand) -> bool {
+        matches!(
+            result,
+            ProcessResult::SuppressWithAction(VimAction::Command { command, select: true, .. })
+                if command == expected
+        )
+    }
+
+    #[test]
+    fn test_v_toggles_visual_mode() {
+        let mut state = visual_state();
+        assert_eq!(state.mode(), VimMode::Visual);
+
+        let result = press(&mut state, KeyCode::V, Modifiers::default());
+        assert!(matches!(result, ProcessResult::ModeChanged(VimMode::Normal, None)));
+        assert_eq!(state.mode(), VimMode::Normal);
+    }
+
+    #[test]
+    fn test_visual_yank_and_delete_return_to_normal() {
+        let mut state = visual_state();
+        let result = press(&mut state, KeyCode::Y, Modifiers::default());
+        assert!(matches!(result, ProcessResult::ModeChanged(VimMode::Normal, Some(VimAction::Copy))));
+
+        let mut state = visual_state();
+        let result = press(&mut state, KeyCode::D, Modifiers::default());
+        assert!(matches!(result, ProcessResult::ModeChanged(VimMode::Normal, Some(VimAction::Cut))));
+        assert_eq!(state.mode(), VimMode::Normal);
+    }
+
+    #[test]
+    fn test_visual_motions_extend_selection() {
+        let mut state = visual_state();
+        let plain = Modifiers::default();
+
+        assert!(selects(press(&mut state, KeyCode::H, plain), VimCommand::MoveLeft));
+        assert!(selects(press(&mut state, KeyCode::J, plain), VimCommand::MoveDown));
+        assert!(selects(press(&mut state, KeyCode::K, plain), VimCommand::MoveUp));
+        assert!(selects(press(&mut state, KeyCode::L, plain), VimCommand::MoveRight));
+        assert!(selects(press(&mut state, KeyCode::W, plain), VimCommand::WordForward));
+        assert!(selects(press(&mut state, KeyCode::B, plain), VimCommand::WordBackward));
+        assert!(selects(press(&mut state, KeyCode::Num0, plain), VimCommand::LineStart));
+        assert!(selects(press(&mut state, KeyCode::Num4, shift()), VimCommand::LineEnd));
+        assert!(selects(press(&mut state, KeyCode::G, shift()), VimCommand::DocumentEnd));
+
+        assert!(matches!(press(&mut state, KeyCode::G, plain), ProcessResult::Suppress));
+        assert!(selects(press(&mut state, KeyCode::G, plain), VimCommand::DocumentStart));
+        assert_eq!(state.mode(), VimMode::Visual);
+    }
+
+    #[test]
+    fn test_zero_is_a_motion_unless_building_a_count() {
+        let mut state = VimState::default();
+        state.set_mode(VimMode::Normal);
+        let plain = Modifiers::default();
+
+        let result = press(&mut state, KeyCode::Num0, plain);
+        assert!(matches!(
+            result,
+            ProcessResult::SuppressWithAction(VimAction::Command { command: VimCommand::LineStart, .. })
+        ));
+
+        assert!(matches!(press(&mut state, KeyCode::Num1, plain), ProcessResult::Suppress));
+        assert!(matches!(press(&mut state, KeyCode::Num0, plain), ProcessResult::Suppress));
+        assert_eq!(state.get_pending_keys(), "10");
+        assert!(matches!(
+            press(&mut state, KeyCode::J, plain),
+            ProcessResult::SuppressWithAction(VimAction::Command { command: VimCommand::MoveDown, count: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn test_line_end_and_first_non_blank() {
+        let mut state = VimState::default();
+        state.set_mode(VimMode::Normal);
+
+        assert!(matches!(
+            press(&mut state, KeyCode::Num4, shift()),
+            ProcessResult::SuppressWithAction(VimAction::Command { command: VimCommand::LineEnd, select: false, .. })
+        ));
+        assert!(matches!(
+            press(&mut state, KeyCode::Num6, shift()),
+            ProcessResult::SuppressWithAction(VimAction::Command { command: VimCommand::LineStart, select: false, .. })
+        ));
+        // Shift+digit is a symbol, never part of a count
+        assert_eq!(state.get_pending_keys(), "");
+    }
+
+    #[test]
+    fn test_insert_entry_commands() {
+        let cases = [
+            (KeyCode::I, Modifiers::default(), None),
+            (KeyCode::A, Modifiers::default(), Some(VimCommand::AppendAfterCursor)),
+            (KeyCode::A, shift(), Some(VimCommand::AppendAtLineEnd)),
+            (KeyCode::I, shift(), Some(VimCommand::InsertAtLineStart)),
+            (KeyCode::O, Modifiers::default(), Some(VimCommand::OpenLineBelow)),
+            (KeyCode::O, shift(), Some(VimCommand::OpenLineAbove)),
+        ];
+
+        for (keycode, modifiers, expected) in cases {
+            let mut state = VimState::default();
+            state.set_mode(VimMode::Normal);
+
+            let action = match press(&mut state, keycode, modifiers) {
+                ProcessResult::ModeChanged(VimMode::Insert, action) => action,
+                other => panic!("{:?} did not enter insert mode: {:?}", keycode, other),
+            };
+            let command = action.map(|action| match action {
+                VimAction::Command { command, count: 1, select: false } => command,
+                other => panic!("unexpected action {:?}", other),
+            });
+            assert_eq!(command, expected, "{:?} shift={}", keycode, modifiers.shift);
+            assert_eq!(state.mode(), VimMode::Insert);
+        }
+    }
+
+    #[test]
+    fn test_visual_control_combos_do_not_cut() {
+        let mut state = visual_state();
+        let control = Modifiers {
+            control: true,
+            ..Default::default()
+        };
+
+        assert!(selects(press(&mut state, KeyCode::D, control), VimCommand::HalfPageDown));
+        assert!(matches!(press(&mut state, KeyCode::A, control), ProcessResult::PassThrough));
+        assert_eq!(state.mode(), VimMode::Visual);
     }
 }
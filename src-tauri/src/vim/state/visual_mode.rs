@@ -22,7 +22,7 @@ impl VimState {
         // Handle pending g
         if self.pending_g {
             self.pending_g = false;
-            return self.handle_visual_g_combo(keycode);
+            return self.handle_visual_g_combo(keycode, modifiers);
         }
 
         // Handle pending text object modifier
@@ -43,6 +43,11 @@ impl VimState {
             }
         }
 
+        // Page motions extend the selection too; other Ctrl combos aren't visual commands
+        if modifiers.control {
+            return self.handle_visual_control_combo(keycode);
+        }
+
         let count = self.get_count();
         self.pending_count = None;
 
@@ -136,7 +141,24 @@ impl VimState {
         }
     }
 
-    fn handle_visual_g_combo(&mut self, keycode: KeyCode) -> ProcessResult {
+    fn handle_visual_control_combo(&mut self, keycode: KeyCode) -> ProcessResult {
+        let count = self.get_count();
+        self.pending_count = None;
+
+        let command = match keycode {
+            KeyCode::F => VimCommand::PageDown,
+            KeyCode::B => VimCommand::PageUp,
+            KeyCode::D => VimCommand::HalfPageDown,
+            KeyCode::U => VimCommand::HalfPageUp,
+            _ => return ProcessResult::PassThrough,
+        };
+
+        ProcessResult::SuppressWithAction(VimAction::Command {
+            command, count, select: true
+        })
+    }
+
+    fn handle_visual_g_combo(&mut self, keycode: KeyCode, modifiers: &Modifiers) -> ProcessResult {
         let count = self.get_count();
         self.pending_count = None;
 
@@ -153,6 +175,18 @@ impl VimState {
                     command: VimCommand::WordEndBackward, count, select: true
                 })
             }
+            KeyCode::J => ProcessResult::SuppressWithAction(VimAction::Command {
+                command: VimCommand::MoveDown, count, select: true
+            }),
+            KeyCode::K => ProcessResult::SuppressWithAction(VimAction::Command {
+                command: VimCommand::MoveUp, count, select: true
+            }),
+            KeyCode::Num0 => ProcessResult::SuppressWithAction(VimAction::Command {
+                command: VimCommand::LineStart, count: 1, select: true
+            }),
+            KeyCode::Num4 if modifiers.shift => ProcessResult::SuppressWithAction(VimAction::Command {
+                command: VimCommand::LineEnd, count: 1, select: true
+            }),
             _ => ProcessResult::PassThrough,
         }
     }
@@ -87,6 +87,27 @@ pub fn setup_click_overlay_window(window: &WebviewWindow) -> Result<(), String>
     Ok(())
 }
 
+/// Bring a window to the front without making it key, so the focused app keeps focus
+pub fn show_window_without_focus(window: &WebviewWindow) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    #[allow(deprecated)] // cocoa crate is deprecated, but objc2-app-kit migration is future work
+    {
+        use cocoa::base::id;
+
+        let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
+
+        unsafe {
+            use objc::*;
+            let _: () = msg_send![ns_window, orderFrontRegardless];
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    window.show().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 /// Position the click overlay to cover all screens
 /// Returns the window offset (min_x, min_y) in screen coordinates
 pub fn position_click_overlay_fullscreen(window: &WebviewWindow) -> Result<(f64, f64), String> {
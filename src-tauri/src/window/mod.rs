@@ -1,6 +1,8 @@
 mod indicator;
+mod settings;
 
 pub use indicator::{
     position_click_overlay_fullscreen, set_indicator_ignores_mouse, setup_click_overlay_window,
-    setup_indicator_window,
+    setup_indicator_window, show_window_without_focus,
 };
+pub use settings::show_settings_window;
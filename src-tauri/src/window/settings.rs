@@ -0,0 +1,12 @@
+//! Settings window helpers
+
+use tauri::{AppHandle, Manager};
+
+/// Show and focus the settings window (tray "Settings..." item and indicator click)
+pub fn show_settings_window(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("settings")
+        .ok_or("Settings window not found")?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
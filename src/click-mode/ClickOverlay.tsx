@@ -1,5 +1,6 @@
 import { CSSProperties } from "react"
 import { HintLabel } from "./HintLabel"
+import { EditPopupDebugOverlay } from "./EditPopupDebugOverlay"
 import { useClickModeEvents, useClickModeKeyboard } from "./hooks"
 import "./click-overlay.css"
 
@@ -22,7 +23,7 @@ export function ClickOverlay() {
   })
 
   if (!isActive) {
-    return null
+    return <EditPopupDebugOverlay />
   }
 
   const overlayStyle: CSSProperties = {
@@ -0,0 +1,91 @@
+import { CSSProperties, useEffect, useState } from "react"
+import { listen } from "@tauri-apps/api/event"
+
+interface Rect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+interface EditPopupDebugPayload {
+  element_frame: Rect | null
+  window_frame: Rect | null
+  popup_geometry: Rect | null
+  window_offset: [number, number]
+}
+
+const RECT_COLORS = {
+  window: "rgba(52, 199, 89, 0.9)",
+  element: "rgba(255, 204, 0, 0.9)",
+  popup: "rgba(0, 122, 255, 0.9)",
+}
+
+/** Translucent rectangles showing the frames used to place the Edit Popup (edit_popup_debug) */
+export function EditPopupDebugOverlay() {
+  const [payload, setPayload] = useState<EditPopupDebugPayload | null>(null)
+
+  useEffect(() => {
+    const unlistenShow = listen<EditPopupDebugPayload>("edit-popup-debug", (event) => {
+      setPayload(event.payload)
+    })
+    const unlistenHide = listen("edit-popup-debug-hide", () => {
+      setPayload(null)
+    })
+
+    return () => {
+      unlistenShow.then((fn) => fn())
+      unlistenHide.then((fn) => fn())
+    }
+  }, [])
+
+  if (!payload) {
+    return null
+  }
+
+  const [offsetX, offsetY] = payload.window_offset
+
+  const renderRect = (label: string, rect: Rect | null, color: string) => {
+    if (!rect) {
+      return null
+    }
+    const style: CSSProperties = {
+      position: "fixed",
+      left: rect.x - offsetX,
+      top: rect.y - offsetY,
+      width: rect.width,
+      height: rect.height,
+      border: `2px solid ${color}`,
+      backgroundColor: color.replace("0.9)", "0.15)"),
+      pointerEvents: "none",
+      boxSizing: "border-box",
+    }
+    const labelStyle: CSSProperties = {
+      position: "absolute",
+      top: 0,
+      left: 0,
+      backgroundColor: color,
+      color: "#000",
+      fontFamily: "SF Mono, Monaco, Menlo, monospace",
+      fontSize: "11px",
+      padding: "1px 4px",
+      whiteSpace: "nowrap",
+    }
+    return (
+      <div style={style}>
+        <span style={labelStyle}>
+          {label} {Math.round(rect.x)},{Math.round(rect.y)} {Math.round(rect.width)}x
+          {Math.round(rect.height)}
+        </span>
+      </div>
+    )
+  }
+
+  return (
+    <>
+      {renderRect("window", payload.window_frame, RECT_COLORS.window)}
+      {renderRect("element", payload.element_frame, RECT_COLORS.element)}
+      {renderRect("popup", payload.popup_geometry, RECT_COLORS.popup)}
+    </>
+  )
+}
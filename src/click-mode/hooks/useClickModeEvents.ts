@@ -104,10 +104,18 @@ export function useClickModeEvents(): UseClickModeEventsResult {
     })
 
     // Listen for filtered elements update
-    const unlistenFiltered = listen<ClickableElement[]>(
+    // Payload is either the elements or an (elements, input) pair
+    const unlistenFiltered = listen<ClickableElement[] | [ClickableElement[], string]>(
       "click-mode-filtered",
       (event) => {
-        setElements(event.payload)
+        const payload = event.payload
+        if (payload.length === 2 && Array.isArray(payload[0])) {
+          const [filtered, input] = payload as [ClickableElement[], string]
+          setElements(filtered)
+          setInputBuffer(input)
+        } else {
+          setElements(payload as ClickableElement[])
+        }
       }
     )
 
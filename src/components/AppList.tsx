@@ -1,15 +1,65 @@
-import { useState } from "react";
+import { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { PickedApp } from "./SettingsApp";
 
 interface Props {
   items: string[];
   onAdd: () => void;
   onAddManual: (bundleId: string) => void;
   onRemove: (item: string) => void;
+  /** Settings list name; enables adding the app that was active before the settings window */
+  listName?: string;
+  /** Localized app names keyed by bundle ID */
+  displayNames?: Record<string, string>;
 }
 
-export function AppList({ items, onAdd, onAddManual, onRemove }: Props) {
+export function AppList({ items, onAdd, onAddManual, onRemove, listName, displayNames }: Props) {
   const [showManualInput, setShowManualInput] = useState(false);
   const [manualBundleId, setManualBundleId] = useState("");
+  // Keyboard selection; arrow keys come from the user or from ovim's list mode (j/k)
+  const [selected, setSelected] = useState<number | null>(null);
+  const listRef = useRef<HTMLUListElement>(null);
+
+  useEffect(() => {
+    if (selected !== null && selected >= items.length) {
+      setSelected(items.length > 0 ? items.length - 1 : null);
+    }
+  }, [items.length, selected]);
+
+  useEffect(() => {
+    if (selected !== null) {
+      listRef.current?.children[selected]?.scrollIntoView({ block: "nearest" });
+    }
+  }, [selected]);
+
+  const handleListKeyDown = (e: React.KeyboardEvent) => {
+    if (items.length === 0) return;
+    const last = items.length - 1;
+    const current = selected ?? -1;
+    let next: number | null = null;
+    switch (e.key) {
+      case "ArrowDown":
+        next = Math.min(current + 1, last);
+        break;
+      case "ArrowUp":
+        next = current < 0 ? last : Math.max(current - 1, 0);
+        break;
+      case "Home":
+        next = 0;
+        break;
+      case "End":
+        next = last;
+        break;
+      case "Delete":
+      case "Backspace":
+        if (selected !== null) onRemove(items[selected]);
+        break;
+      default:
+        return;
+    }
+    e.preventDefault();
+    if (next !== null) setSelected(next);
+  };
 
   const handleManualSubmit = () => {
     const trimmed = manualBundleId.trim();
@@ -20,6 +70,13 @@ export function AppList({ items, onAdd, onAddManual, onRemove }: Props) {
     }
   };
 
+  const handleAddCurrent = () => {
+    // The backend persists the change and emits settings-changed
+    invoke<PickedApp>("add_frontmost_app_to_list", { listName }).catch((e) =>
+      console.error("Failed to add current app:", e),
+    );
+  };
+
   const handleKeyDown = (e: React.KeyboardEvent) => {
     if (e.key === "Enter") {
       handleManualSubmit();
@@ -31,14 +88,35 @@ export function AppList({ items, onAdd, onAddManual, onRemove }: Props) {
 
   return (
     <div className="app-list">
-      <ul className="app-list-items">
-        {items.map((item) => (
-          <li key={item} className="app-list-item">
-            <span className="app-bundle-id">{item}</span>
+      <ul
+        className="app-list-items"
+        ref={listRef}
+        role="listbox"
+        aria-label={listName ?? "Apps"}
+        tabIndex={0}
+        onKeyDown={handleListKeyDown}
+        onBlur={() => setSelected(null)}
+      >
+        {items.map((item, index) => (
+          <li
+            key={item}
+            className={`app-list-item${index === selected ? " selected" : ""}`}
+            role="option"
+            aria-selected={index === selected}
+            onClick={() => setSelected(index)}
+          >
+            {displayNames?.[item] ? (
+              <span className="app-bundle-id" title={item}>
+                {displayNames[item]}
+              </span>
+            ) : (
+              <span className="app-bundle-id">{item}</span>
+            )}
             <button
               className="remove-button"
               onClick={() => onRemove(item)}
               title="Remove"
+              tabIndex={-1}
             >
               {"\u2715"}
             </button>
@@ -80,6 +158,11 @@ export function AppList({ items, onAdd, onAddManual, onRemove }: Props) {
           <button className="add-button" onClick={onAdd}>
             + Browse Application
           </button>
+          {listName && (
+            <button className="add-button" onClick={handleAddCurrent}>
+              + Current App
+            </button>
+          )}
           <button
             className="add-button add-manual"
             onClick={() => setShowManualInput(true)}
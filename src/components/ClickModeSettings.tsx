@@ -1,6 +1,8 @@
-import { useCallback } from "react"
-import type { Settings, ClickModeSettings, DoubleTapModifier } from "./SettingsApp"
+import { useCallback, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import type { PickedApp, Settings, ClickModeSettings, DoubleTapModifier, HintOrder } from "./SettingsApp"
 import { useKeyRecording } from "../hooks/useKeyRecording"
+import { AppList } from "./AppList"
 import { Slider, ColorPicker } from "./common"
 
 interface Props {
@@ -105,6 +107,7 @@ export function ClickModeSettingsComponent({ settings, onUpdate }: Props) {
                     <option value="option">Opt+Opt</option>
                     <option value="control">Ctrl+Ctrl</option>
                     <option value="shift">Shift+Shift</option>
+                    <option value="function">Fn+Fn</option>
                     <option value="escape">Esc+Esc</option>
                   </select>
                   <button
@@ -129,6 +132,7 @@ export function ClickModeSettingsComponent({ settings, onUpdate }: Props) {
                   <option value="option">Opt+Opt</option>
                   <option value="control">Ctrl+Ctrl</option>
                   <option value="shift">Shift+Shift</option>
+                  <option value="function">Fn+Fn</option>
                   <option value="escape">Esc+Esc</option>
                 </select>
               )}
@@ -170,6 +174,115 @@ export function ClickModeSettingsComponent({ settings, onUpdate }: Props) {
         <span className="hint">Display current input at top of screen</span>
       </div>
 
+      {/* Element Labels Toggle */}
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={clickMode.show_element_labels ?? false}
+            onChange={(e) => updateClickMode({ show_element_labels: e.target.checked })}
+            disabled={!clickMode.enabled}
+          />
+          Show element labels
+        </label>
+        <span className="hint">Show a shortened element title next to each hint</span>
+      </div>
+
+      {/* Deactivation Triggers */}
+      <div className="form-group">
+        <label>Deactivate on</label>
+        {(
+          [
+            ["deactivate_on_left_click", "Left click"],
+            ["deactivate_on_right_click", "Right click"],
+            ["deactivate_on_scroll", "Scroll"],
+          ] as const
+        ).map(([field, label]) => (
+          <label key={field} className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={clickMode[field] ?? true}
+              onChange={(e) => updateClickMode({ [field]: e.target.checked })}
+              disabled={!clickMode.enabled}
+            />
+            {label}
+          </label>
+        ))}
+        <span className="hint">Real mouse input that hides hints. Clicks made by click mode itself never do</span>
+      </div>
+
+      {/* Full-Page Scan Toggle */}
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={clickMode.full_page_scan ?? false}
+            onChange={(e) => updateClickMode({ full_page_scan: e.target.checked })}
+            disabled={!clickMode.enabled}
+          />
+          Scan the whole page in browsers
+        </label>
+        <span className="hint">Scrolls through long pages to hint every link, then scrolls back. Slower</span>
+      </div>
+
+      {/* Click Undo Toggle */}
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={clickMode.click_undo_enabled ?? false}
+            onChange={(e) => updateClickMode({ click_undo_enabled: e.target.checked })}
+            disabled={!clickMode.enabled}
+          />
+          Allow undoing the last click
+        </label>
+        <span className="hint">Ctrl+Option+Z sends Cmd+Z up to 5 seconds after a click. Best-effort: only works if the app can undo that action</span>
+      </div>
+
+      {/* Hint Ordering */}
+      <div className="form-group">
+        <label htmlFor="hint-order">Hint order</label>
+        <select
+          id="hint-order"
+          value={clickMode.hint_order ?? "discovery_order"}
+          onChange={(e) => updateClickMode({ hint_order: e.target.value as HintOrder })}
+          disabled={!clickMode.enabled}
+        >
+          <option value="discovery_order">Discovery order (default)</option>
+          <option value="visual_order">Top-to-bottom, left-to-right</option>
+          <option value="distance_from_cursor">Closest to mouse cursor</option>
+        </select>
+        <span className="hint">Which elements get the shortest hints</span>
+      </div>
+
+      {/* Search Mode Keys */}
+      <SearchKeysSection clickMode={clickMode} updateClickMode={updateClickMode} />
+
+      <ExtraShortcutSection
+        clickMode={clickMode}
+        updateClickMode={updateClickMode}
+        label="Window Controls Shortcut"
+        keyField="window_controls_shortcut_key"
+        modifiersField="window_controls_shortcut_modifiers"
+        hint="Show hints only for close/minimize/zoom buttons, toolbars and tabs"
+      />
+
+      <ExtraShortcutSection
+        clickMode={clickMode}
+        updateClickMode={updateClickMode}
+        label="Menu Bar Shortcut"
+        keyField="menu_bar_shortcut_key"
+        modifiersField="menu_bar_shortcut_modifiers"
+        hint="Show hints for the menu bar titles; choosing one opens its menu and hints its items"
+      />
+
+      {/* Per-app Lists */}
+      <AppListsSection
+        clickMode={clickMode}
+        updateClickMode={updateClickMode}
+        displayNames={settings.app_display_names}
+      />
+
       {/* Advanced Settings Section */}
       <AdvancedSettingsSection
         clickMode={clickMode}
@@ -262,6 +375,201 @@ function HintPreview({ clickMode }: { clickMode: ClickModeSettings }) {
   )
 }
 
+const NO_MODIFIERS = { shift: false, control: false, option: false, command: false }
+
+function SearchKeysSection({ clickMode, updateClickMode }: SectionProps) {
+  const enterKey = useKeyRecording({
+    key: clickMode.search_key,
+    modifiers: NO_MODIFIERS,
+    onKeyRecorded: (key) => updateClickMode({ search_key: key }),
+  })
+  const exitKey = useKeyRecording({
+    key: clickMode.exit_search_key,
+    modifiers: NO_MODIFIERS,
+    onKeyRecorded: (key) => updateClickMode({ exit_search_key: key }),
+  })
+  const toggleKey = useKeyRecording({
+    key: clickMode.toggle_search_key,
+    modifiers: NO_MODIFIERS,
+    onKeyRecorded: (key) => updateClickMode({ toggle_search_key: key }),
+  })
+
+  const keyButton = (recording: typeof enterKey, key: string) => (
+    <button
+      type="button"
+      className={`current-key clickable${recording.isRecording ? " recording" : ""}`}
+      onClick={recording.isRecording ? recording.handleCancelRecord : recording.handleRecordKey}
+      disabled={!clickMode.enabled && !recording.isRecording}
+    >
+      {recording.isRecording ? "Press any key..." : recording.displayName || key}
+    </button>
+  )
+
+  return (
+    <div className="form-group">
+      <label>Search Mode</label>
+      <div className="activation-row">
+        <div className="activation-item">
+          <span className="activation-label">Enter search</span>
+          <div className="activation-input-group">
+            {keyButton(enterKey, clickMode.search_key)}
+          </div>
+        </div>
+        <div className="activation-item">
+          <span className="activation-label">Back to hints</span>
+          <div className="activation-input-group">
+            {keyButton(exitKey, clickMode.exit_search_key)}
+          </div>
+        </div>
+        <div className="activation-item">
+          <span className="activation-label">Toggle hints/search</span>
+          <div className="activation-input-group">
+            {keyButton(toggleKey, clickMode.toggle_search_key || "Not set")}
+            {clickMode.toggle_search_key && (
+              <button
+                type="button"
+                className="activation-clear-btn"
+                onClick={() => updateClickMode({ toggle_search_key: "" })}
+                disabled={!clickMode.enabled}
+                title="Disable toggle key"
+              >
+                x
+              </button>
+            )}
+          </div>
+        </div>
+      </div>
+      <span className="hint">Type to filter elements by text, Enter clicks the best match</span>
+    </div>
+  )
+}
+
+interface ExtraShortcutProps extends SectionProps {
+  label: string
+  keyField: "window_controls_shortcut_key" | "menu_bar_shortcut_key"
+  modifiersField: "window_controls_shortcut_modifiers" | "menu_bar_shortcut_modifiers"
+  hint: string
+}
+
+function ExtraShortcutSection({
+  clickMode,
+  updateClickMode,
+  label,
+  keyField,
+  modifiersField,
+  hint,
+}: ExtraShortcutProps) {
+  const shortcutKey = clickMode[keyField]
+  const recording = useKeyRecording({
+    key: shortcutKey,
+    modifiers: clickMode[modifiersField],
+    onKeyRecorded: (key, modifiers) =>
+      updateClickMode({ [keyField]: key, [modifiersField]: modifiers } as Partial<ClickModeSettings>),
+  })
+
+  return (
+    <div className="form-group">
+      <label>{label}</label>
+      <div className="activation-input-group">
+        <button
+          type="button"
+          className={`current-key clickable${shortcutKey ? "" : " placeholder"}${recording.isRecording ? " recording" : ""}`}
+          onClick={recording.isRecording ? recording.handleCancelRecord : recording.handleRecordKey}
+          disabled={!clickMode.enabled && !recording.isRecording}
+        >
+          {recording.isRecording ? "Press any key..." : recording.displayName || shortcutKey || "Set shortcut..."}
+        </button>
+        {shortcutKey && (
+          <button
+            type="button"
+            className="activation-clear-btn"
+            onClick={() =>
+              updateClickMode({ [keyField]: "", [modifiersField]: NO_MODIFIERS } as Partial<ClickModeSettings>)
+            }
+            disabled={!clickMode.enabled}
+            title="Disable shortcut"
+          >
+            x
+          </button>
+        )}
+      </div>
+      <span className="hint">{hint}</span>
+    </div>
+  )
+}
+
+type AppListKey = "enabled_apps" | "disabled_apps" | "auto_activate_apps"
+
+function AppListsSection({
+  clickMode,
+  updateClickMode,
+  displayNames,
+}: SectionProps & { displayNames: Record<string, string> }) {
+  const addApp = (list: AppListKey, bundleId: string | null | undefined) => {
+    if (bundleId && !clickMode[list].includes(bundleId)) {
+      updateClickMode({ [list]: [...clickMode[list], bundleId] } as Partial<ClickModeSettings>)
+    }
+  }
+
+  const pickApp = (list: AppListKey) => async () => {
+    try {
+      addApp(list, (await invoke<PickedApp | null>("pick_app"))?.bundle_id)
+    } catch (e) {
+      console.error("Failed to pick app:", e)
+    }
+  }
+
+  const removeApp = (list: AppListKey) => (bundleId: string) => {
+    updateClickMode({ [list]: clickMode[list].filter((id) => id !== bundleId) } as Partial<ClickModeSettings>)
+  }
+
+  return (
+    <div className="color-settings">
+      <h3>Apps</h3>
+      <p className="help-text">
+        Click mode only activates in the enabled apps (all apps when empty). Disabled apps always win.
+      </p>
+
+      <div className="form-group">
+        <label>Enabled apps</label>
+        <AppList
+          items={clickMode.enabled_apps}
+          onAdd={pickApp("enabled_apps")}
+          onAddManual={(bundleId) => addApp("enabled_apps", bundleId)}
+          listName="click_mode.enabled_apps"
+          displayNames={displayNames}
+          onRemove={removeApp("enabled_apps")}
+        />
+      </div>
+
+      <div className="form-group">
+        <label>Disabled apps</label>
+        <AppList
+          items={clickMode.disabled_apps}
+          onAdd={pickApp("disabled_apps")}
+          onAddManual={(bundleId) => addApp("disabled_apps", bundleId)}
+          listName="click_mode.disabled_apps"
+          displayNames={displayNames}
+          onRemove={removeApp("disabled_apps")}
+        />
+      </div>
+
+      <div className="form-group">
+        <label>Auto-activate apps</label>
+        <AppList
+          items={clickMode.auto_activate_apps ?? []}
+          onAdd={pickApp("auto_activate_apps")}
+          onAddManual={(bundleId) => addApp("auto_activate_apps", bundleId)}
+          listName="click_mode.auto_activate_apps"
+          displayNames={displayNames}
+          onRemove={removeApp("auto_activate_apps")}
+        />
+        <span className="hint">Click mode activates whenever one of these apps becomes frontmost</span>
+      </div>
+    </div>
+  )
+}
+
 function AdvancedSettingsSection({ clickMode, updateClickMode }: SectionProps) {
   return (
     <div className="color-settings">
@@ -321,6 +629,33 @@ function AdvancedSettingsSection({ clickMode, updateClickMode }: SectionProps) {
           onChange={(v) => updateClickMode({ cache_ttl_ms: v })}
         />
       </div>
+
+      <AxTreeDump />
+    </div>
+  )
+}
+
+function AxTreeDump() {
+  const [status, setStatus] = useState<string | null>(null)
+
+  const handleDump = async () => {
+    setStatus("Dumping...")
+    try {
+      const path = await invoke<string>("dump_ax_tree")
+      setStatus(`Saved to ${path}`)
+    } catch (e) {
+      setStatus(`Failed: ${e}`)
+    }
+  }
+
+  return (
+    <div className="form-group">
+      <button type="button" className="btn-secondary" onClick={handleDump}>
+        Dump accessibility tree
+      </button>
+      <span className="hint">
+        {status ?? "Saves every element of the last used app, including ones click mode skipped, as JSON"}
+      </span>
     </div>
   )
 }
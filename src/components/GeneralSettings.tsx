@@ -1,18 +1,22 @@
 import { useState, useEffect } from "react"
 import { invoke } from "@tauri-apps/api/core"
 import type { Settings } from "./SettingsApp"
+import type { VimMode } from "../indicator/types"
 
 interface Props {
   settings: Settings
   onUpdate: (updates: Partial<Settings>) => void
+  onClearLearnedData: () => void
 }
 
 interface PermissionStatus {
   accessibility: boolean
+  input_monitoring: boolean
   capture_running: boolean
+  tap_live: boolean
 }
 
-export function GeneralSettings({ settings, onUpdate }: Props) {
+export function GeneralSettings({ settings, onUpdate, onClearLearnedData }: Props) {
   const [permissionStatus, setPermissionStatus] = useState<PermissionStatus | null>(null)
   const [version, setVersion] = useState<string>("")
 
@@ -47,7 +51,9 @@ export function GeneralSettings({ settings, onUpdate }: Props) {
     setPermissionStatus(status)
   }
 
-  const permissionsOk = permissionStatus?.accessibility && permissionStatus?.capture_running
+  // A live event tap is what matters; the Input Monitoring check can report false
+  // even when key events arrive
+  const permissionsOk = permissionStatus?.accessibility && permissionStatus?.tap_live
 
   return (
     <div className="settings-section">
@@ -74,7 +80,7 @@ export function GeneralSettings({ settings, onUpdate }: Props) {
                 </button>
               </div>
             )}
-            {!permissionStatus.capture_running && (
+            {!permissionStatus.tap_live && (
               <div className="permission-item">
                 <span className="permission-status missing">Input Monitoring</span>
                 <button type="button" className="permission-btn" onClick={handleOpenInputMonitoring}>
@@ -111,6 +117,68 @@ export function GeneralSettings({ settings, onUpdate }: Props) {
         </label>
       </div>
 
+      <div className="form-group">
+        <label htmlFor="initial-mode">Mode on startup</label>
+        <select
+          id="initial-mode"
+          value={settings.initial_mode}
+          onChange={(e) => onUpdate({ initial_mode: e.target.value as VimMode })}
+        >
+          <option value="insert">Insert</option>
+          <option value="normal">Normal</option>
+          <option value="visual">Visual</option>
+        </select>
+        <span className="hint">Takes effect the next time ovim starts</span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={settings.remember_mode_per_app}
+            onChange={(e) => onUpdate({ remember_mode_per_app: e.target.checked })}
+          />
+          Remember mode per app
+        </label>
+        <span className="hint">
+          Each app returns to the mode it was left in when you switch back to it
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="default-mode-on-new-app">Mode when switching apps</label>
+        <select
+          id="default-mode-on-new-app"
+          value={settings.default_mode_on_new_app ?? ""}
+          onChange={(e) =>
+            onUpdate({ default_mode_on_new_app: (e.target.value || null) as VimMode | null })
+          }
+        >
+          <option value="">Keep current mode</option>
+          <option value="insert">Insert</option>
+          <option value="normal">Normal</option>
+          <option value="visual">Visual</option>
+        </select>
+        <span className="hint">
+          {settings.remember_mode_per_app
+            ? "Used for apps without a remembered mode"
+            : "Applied on every app switch"}
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label>Learned data</label>
+        <div className="path-input-row">
+          <button type="button" className="edit-script-btn" onClick={onClearLearnedData}>
+            Clear learned data
+          </button>
+        </div>
+        <span className="hint">
+          Forget the filetypes learned per domain/app and the recorded app names. Filetypes you
+          set yourself, shortcuts, app lists and other settings are kept.
+        </span>
+      </div>
+
     </div>
   )
 }
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings } from "./SettingsApp";
+import type { PickedApp, Settings } from "./SettingsApp";
 import { AppList } from "./AppList";
 
 interface Props {
@@ -10,7 +10,7 @@ interface Props {
 export function IgnoredAppsSettings({ settings, onUpdate }: Props) {
   const handleAddApp = async () => {
     try {
-      const bundleId = await invoke<string | null>("pick_app");
+      const bundleId = (await invoke<PickedApp | null>("pick_app"))?.bundle_id;
       if (bundleId && !settings.ignored_apps.includes(bundleId)) {
         onUpdate({ ignored_apps: [...settings.ignored_apps, bundleId] });
       }
@@ -43,6 +43,8 @@ export function IgnoredAppsSettings({ settings, onUpdate }: Props) {
         onAdd={handleAddApp}
         onAddManual={handleAddManualApp}
         onRemove={handleRemoveApp}
+        listName="ignored_apps"
+        displayNames={settings.app_display_names}
       />
     </div>
   );
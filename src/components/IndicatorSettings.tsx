@@ -1,6 +1,7 @@
-import type { Settings, ModeColors } from "./SettingsApp";
+import type { Settings, ModeColors, CapsLockRemap, KeyRepeatSettings } from "./SettingsApp";
 import { hasAnyModifier } from "./keyRecording";
 import { useKeyRecording } from "../hooks/useKeyRecording";
+import { PassthroughShortcuts } from "./PassthroughShortcuts";
 import { PRESET_KEYS, POSITION_OPTIONS, FONT_OPTIONS } from "./indicator/constants";
 import { rgbToHex, hexToRgb } from "./colorUtils";
 
@@ -38,6 +39,10 @@ export function IndicatorSettings({ settings, onUpdate }: Props) {
     onUpdate({ mode_colors: newColors });
   };
 
+  const updateKeyRepeat = (updates: Partial<KeyRepeatSettings>) => {
+    onUpdate({ key_repeat: { ...settings.key_repeat, ...updates } });
+  };
+
   return (
     <div className="settings-section">
       <h2>Indicator</h2>
@@ -71,6 +76,21 @@ export function IndicatorSettings({ settings, onUpdate }: Props) {
           </p>
         </div>
 
+        <div className="form-group checkbox-group">
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.indicator_click_opens_settings}
+              onChange={(e) => onUpdate({ indicator_click_opens_settings: e.target.checked })}
+            />
+            <span>Click indicator to open settings</span>
+          </label>
+          <p className="setting-description">
+            Opens settings without the menu bar icon. Clicks on the indicator no longer reach the
+            window behind it. When off, hold Cmd and click instead.
+          </p>
+        </div>
+
         <div className="form-group checkbox-group">
           <label className="checkbox-label">
             <input
@@ -115,6 +135,74 @@ export function IndicatorSettings({ settings, onUpdate }: Props) {
           </div>
         </div>
 
+        <div className="form-group">
+          <label htmlFor="caps-lock-remap">Caps Lock remapping</label>
+          <select
+            id="caps-lock-remap"
+            value={settings.caps_lock_remap}
+            onChange={(e) => onUpdate({ caps_lock_remap: e.target.value as CapsLockRemap })}
+          >
+            <option value="none">None</option>
+            <option value="escape">Escape</option>
+            <option value="control">Control (hold)</option>
+            <option value="escape_or_control">Escape (tap) / Control (hold)</option>
+          </select>
+          <p className="setting-description">
+            Takes over the Caps Lock key, so don't combine it with Caps Lock as the vim mode key.
+          </p>
+        </div>
+
+        <div className="form-group">
+          <label htmlFor="key-repeat-mode">Held motion keys</label>
+          <select
+            id="key-repeat-mode"
+            value={settings.key_repeat.mode}
+            onChange={(e) => updateKeyRepeat({ mode: e.target.value as KeyRepeatSettings["mode"] })}
+          >
+            <option value="system">Use macOS key repeat</option>
+            <option value="managed">Repeat at a custom rate</option>
+          </select>
+          <p className="setting-description">
+            Applies to motions in Normal/Visual mode and to scrolling in scroll mode.
+          </p>
+        </div>
+
+        {settings.key_repeat.mode === "managed" && (
+          <div className="form-row">
+            <div className="form-group">
+              <label htmlFor="key-repeat-delay">Initial delay (ms)</label>
+              <input
+                type="number"
+                id="key-repeat-delay"
+                value={settings.key_repeat.initial_delay_ms}
+                onChange={(e) => updateKeyRepeat({ initial_delay_ms: parseInt(e.target.value) || 0 })}
+                min={0}
+              />
+            </div>
+            <div className="form-group">
+              <label htmlFor="key-repeat-interval">Repeat every (ms)</label>
+              <input
+                type="number"
+                id="key-repeat-interval"
+                value={settings.key_repeat.interval_ms}
+                onChange={(e) => updateKeyRepeat({ interval_ms: parseInt(e.target.value) || 35 })}
+                min={5}
+              />
+            </div>
+          </div>
+        )}
+
+        <div className="form-group">
+          <label>Passthrough shortcuts</label>
+          <p className="setting-description">
+            These key combinations always reach the app, even in Normal mode.
+          </p>
+          <PassthroughShortcuts
+            items={settings.passthrough_shortcuts}
+            onChange={(passthrough_shortcuts) => onUpdate({ passthrough_shortcuts })}
+          />
+        </div>
+
         <div className="slider-group">
           <label>
             Alpha: {Math.round(settings.indicator_opacity * 100)}%
@@ -0,0 +1,67 @@
+import { useState } from "react";
+import type { KeyBinding } from "./SettingsApp";
+import { cancelRecordKey, formatKeyWithModifiers, recordKey } from "./keyRecording";
+
+interface Props {
+  items: KeyBinding[];
+  onChange: (items: KeyBinding[]) => void;
+}
+
+const sameBinding = (a: KeyBinding, b: KeyBinding) =>
+  a.key === b.key &&
+  a.modifiers.shift === b.modifiers.shift &&
+  a.modifiers.control === b.modifiers.control &&
+  a.modifiers.option === b.modifiers.option &&
+  a.modifiers.command === b.modifiers.command &&
+  !!a.modifiers.function === !!b.modifiers.function;
+
+export function PassthroughShortcuts({ items, onChange }: Props) {
+  const [isRecording, setIsRecording] = useState(false);
+
+  const handleRecord = async () => {
+    setIsRecording(true);
+    try {
+      const recorded = await recordKey();
+      const binding = { key: recorded.name, modifiers: recorded.modifiers };
+      if (!items.some((item) => sameBinding(item, binding))) {
+        onChange([...items, binding]);
+      }
+    } catch (e) {
+      console.error("Failed to record shortcut:", e);
+    } finally {
+      setIsRecording(false);
+    }
+  };
+
+  const handleCancel = () => {
+    cancelRecordKey().catch(() => {});
+    setIsRecording(false);
+  };
+
+  return (
+    <div className="app-list">
+      <ul className="app-list-items">
+        {items.map((item) => (
+          <li key={formatKeyWithModifiers(item.key, item.modifiers)} className="app-list-item">
+            <span className="app-bundle-id">
+              {formatKeyWithModifiers(item.key.toUpperCase(), item.modifiers)}
+            </span>
+            <button
+              className="remove-button"
+              onClick={() => onChange(items.filter((other) => !sameBinding(other, item)))}
+              title="Remove"
+            >
+              {"\u2715"}
+            </button>
+          </li>
+        ))}
+        {items.length === 0 && <li className="app-list-empty">No shortcuts configured</li>}
+      </ul>
+      <div className="add-buttons">
+        <button className="add-button" onClick={isRecording ? handleCancel : handleRecord}>
+          {isRecording ? "Press a shortcut..." : "+ Record Shortcut"}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,6 @@
-import { useCallback } from "react"
+import { useCallback, useEffect, useState } from "react"
 import { invoke } from "@tauri-apps/api/core"
-import type { Settings, ScrollModeSettings } from "./SettingsApp"
+import type { PickedApp, Settings, ScrollHoldModifier, ScrollModeSettings } from "./SettingsApp"
 import { AppList } from "./AppList"
 import { Slider } from "./common"
 
@@ -9,6 +9,27 @@ interface Props {
   onUpdate: (updates: Partial<Settings>) => void
 }
 
+/** One "bundle.id: title" line per allowlisted overlay window title */
+function formatTitleAllowlist(allowlist: Record<string, string[]>): string {
+  return Object.entries(allowlist)
+    .flatMap(([bundleId, titles]) => titles.map((title) => `${bundleId}: ${title}`))
+    .join("\n")
+}
+
+function parseTitleAllowlist(text: string): Record<string, string[]> {
+  const allowlist: Record<string, string[]> = {}
+  for (const line of text.split("\n")) {
+    const separator = line.indexOf(":")
+    if (separator < 0) continue
+    const bundleId = line.slice(0, separator).trim()
+    const title = line.slice(separator + 1).trim()
+    if (bundleId && title) {
+      allowlist[bundleId] = [...(allowlist[bundleId] ?? []), title]
+    }
+  }
+  return allowlist
+}
+
 export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
   const scrollMode = settings.scroll_mode
 
@@ -21,9 +42,17 @@ export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
     [scrollMode, onUpdate],
   )
 
+  const [titleAllowlistText, setTitleAllowlistText] = useState(() =>
+    formatTitleAllowlist(scrollMode.overlay_title_allowlist),
+  )
+
+  useEffect(() => {
+    setTitleAllowlistText(formatTitleAllowlist(scrollMode.overlay_title_allowlist))
+  }, [scrollMode.overlay_title_allowlist])
+
   const handleAddEnabledApp = useCallback(async () => {
     try {
-      const bundleId = await invoke<string | null>("pick_app")
+      const bundleId = (await invoke<PickedApp | null>("pick_app"))?.bundle_id
       if (bundleId && !scrollMode.enabled_apps.includes(bundleId)) {
         updateScrollMode({
           enabled_apps: [...scrollMode.enabled_apps, bundleId],
@@ -56,7 +85,7 @@ export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
 
   const handleAddBlocklistApp = useCallback(async () => {
     try {
-      const bundleId = await invoke<string | null>("pick_app")
+      const bundleId = (await invoke<PickedApp | null>("pick_app"))?.bundle_id
       if (bundleId && !scrollMode.overlay_blocklist.includes(bundleId)) {
         updateScrollMode({
           overlay_blocklist: [...scrollMode.overlay_blocklist, bundleId],
@@ -126,6 +155,90 @@ export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
           formatMax="300px"
           onChange={(v) => updateScrollMode({ scroll_step: v })}
         />
+        <Slider
+          label="Focus Grace Period"
+          value={scrollMode.focus_grace_ms}
+          min={0}
+          max={500}
+          step={10}
+          disabled={!scrollMode.enabled}
+          formatValue={(v) => (v === 0 ? "Off" : `${v}ms`)}
+          formatMin="Off"
+          formatMax="500ms"
+          onChange={(v) => updateScrollMode({ focus_grace_ms: v })}
+        />
+        <span className="hint">
+          After switching apps or clicking, keys pass through this long while focus settles
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={scrollMode.continuous_scroll}
+            onChange={(e) => updateScrollMode({ continuous_scroll: e.target.checked })}
+            disabled={!scrollMode.enabled}
+          />
+          Continuous scrolling
+        </label>
+        <span className="hint">
+          Holding j/k scrolls smoothly until the key is released
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="scroll-hold-modifier">Hold to scroll</label>
+        <select
+          id="scroll-hold-modifier"
+          value={scrollMode.scroll_hold_modifier ?? ""}
+          onChange={(e) =>
+            updateScrollMode({
+              scroll_hold_modifier: (e.target.value || null) as ScrollHoldModifier | null,
+            })
+          }
+          disabled={!scrollMode.enabled}
+        >
+          <option value="">Always on (default)</option>
+          <option value="option">Option</option>
+          <option value="control">Control</option>
+          <option value="command">Command</option>
+          <option value="function">Fn</option>
+        </select>
+        <span className="hint">
+          Scroll shortcuts only work while this modifier is held, e.g. Option+j
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={scrollMode.list_navigation}
+            onChange={(e) => updateScrollMode({ list_navigation: e.target.checked })}
+            disabled={!scrollMode.enabled}
+          />
+          List navigation
+        </label>
+        <span className="hint">
+          In Finder, System Settings and other list apps, h/j/k/l send arrow keys
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={scrollMode.list_navigation_grid}
+            onChange={(e) => updateScrollMode({ list_navigation_grid: e.target.checked })}
+            disabled={!scrollMode.enabled || !scrollMode.list_navigation}
+          />
+          Grid layout
+        </label>
+        <span className="hint">
+          For icon grids: H/L extend the selection by column instead of going back/forward.
+          Grid navigation differs between apps, so this is best-effort
+        </span>
       </div>
 
       {/* Keyboard Shortcuts Reference */}
@@ -179,6 +292,8 @@ export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
           onAdd={handleAddEnabledApp}
           onAddManual={handleAddManualApp}
           onRemove={handleRemoveEnabledApp}
+          listName="scroll_mode.enabled_apps"
+          displayNames={settings.app_display_names}
         />
       </div>
 
@@ -186,14 +301,36 @@ export function ScrollModeSettingsComponent({ settings, onUpdate }: Props) {
       <div className="color-settings">
         <h3>Overlay Blocklist</h3>
         <p className="help-text">
-          Scroll mode is disabled when these apps have visible windows (e.g., Keyboard Maestro palettes).
+          Scroll mode is disabled while a window of these apps is in front and has keyboard
+          focus (e.g., Keyboard Maestro palettes).
         </p>
         <AppList
           items={scrollMode.overlay_blocklist}
           onAdd={handleAddBlocklistApp}
           onAddManual={handleAddManualBlocklistApp}
           onRemove={handleRemoveBlocklistApp}
+          listName="scroll_mode.overlay_blocklist"
+          displayNames={settings.app_display_names}
         />
+        <div className="form-group" style={{ marginTop: 12 }}>
+          <label>Allowed overlay windows</label>
+          <textarea
+            value={titleAllowlistText}
+            onChange={(e) => setTitleAllowlistText(e.target.value)}
+            onBlur={() =>
+              updateScrollMode({
+                overlay_title_allowlist: parseTitleAllowlist(titleAllowlistText),
+              })
+            }
+            placeholder="com.raycast.macos: Find Results"
+            rows={3}
+            spellCheck={false}
+          />
+          <span className="hint">
+            One "bundle ID: title" per line. Windows whose title contains the text keep
+            scroll mode working (e.g., scrollable popovers).
+          </span>
+        </div>
       </div>
     </div>
   )
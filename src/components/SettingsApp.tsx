@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { GeneralSettings } from "./GeneralSettings";
 import { IndicatorSettings } from "./IndicatorSettings";
 import { WidgetSettings } from "./WidgetSettings";
@@ -7,15 +8,37 @@ import { IgnoredAppsSettings } from "./IgnoredAppsSettings";
 import { NvimEditSettings } from "./NvimEditSettings";
 import { ClickModeSettingsComponent } from "./ClickModeSettings";
 import { ScrollModeSettingsComponent } from "./ScrollModeSettings";
+import type { VimMode } from "../indicator/types";
 
 export interface VimKeyModifiers {
   shift: boolean;
   control: boolean;
   option: boolean;
   command: boolean;
+  function?: boolean;
 }
 
-export type DoubleTapModifier = "none" | "command" | "option" | "control" | "shift" | "escape";
+export interface KeyBinding {
+  key: string;
+  modifiers: VimKeyModifiers;
+}
+
+export type CapsLockRemap = "none" | "escape" | "control" | "escape_or_control";
+
+export type KeyRepeatMode = "system" | "managed";
+
+export interface KeyRepeatSettings {
+  mode: KeyRepeatMode;
+  initial_delay_ms: number;
+  interval_ms: number;
+}
+
+export type DoubleTapModifier = "none" | "command" | "option" | "control" | "shift" | "function" | "escape";
+
+export type TrailingNewline = "strip_one" | "strip_all" | "preserve";
+export type CancelBehavior = "keep_live_synced" | "revert_to_original";
+
+export type HintOrder = "discovery_order" | "visual_order" | "distance_from_cursor";
 
 export interface NvimEditSettings {
   enabled: boolean;
@@ -28,24 +51,69 @@ export interface NvimEditSettings {
   popup_mode: boolean;
   popup_width: number;
   popup_height: number;
+  popup_width_pct: number;
+  popup_height_pct: number;
+  popup_margin: number;
   live_sync_enabled: boolean;
+  socket_dir: string;
   use_custom_script: boolean;
   clipboard_mode: boolean;
+  rich_text_mode: boolean;
+  sanitize_control_chars: boolean;
+  reactivate_original_immediately: boolean;
   double_tap_modifier: DoubleTapModifier;
+  trailing_newline: TrailingNewline;
+  cancel_behavior: CancelBehavior;
+  format_command: string | null;
+  open_at_cursor: boolean;
+  clipboard_chunk_threshold: number;
+  edit_popup_debug: boolean;
+  learn_filetype: boolean;
   domain_filetypes: Record<string, string>;
 }
 
+export interface ClickableRoleOverrides {
+  add: string[];
+  remove: string[];
+}
+
 export interface ClickModeSettings {
   enabled: boolean;
   shortcut_key: string;
   shortcut_modifiers: VimKeyModifiers;
+  window_controls_shortcut_key: string;
+  window_controls_shortcut_modifiers: VimKeyModifiers;
+  menu_bar_shortcut_key: string;
+  menu_bar_shortcut_modifiers: VimKeyModifiers;
   double_tap_modifier: DoubleTapModifier;
   hint_chars: string;
   show_search_bar: boolean;
+  search_key: string;
+  exit_search_key: string;
+  toggle_search_key: string;
+  cycle_overlap_key: string;
+  pin_key: string;
+  batch_copy_key: string;
+  scroll_modifiers: VimKeyModifiers;
+  copy_modifiers: VimKeyModifiers;
+  show_element_labels: boolean;
+  hint_order: HintOrder;
+  click_undo_enabled: boolean;
+  click_undo_key: string;
+  click_undo_modifiers: VimKeyModifiers;
+  deactivate_on_left_click: boolean;
+  deactivate_on_right_click: boolean;
+  deactivate_on_scroll: boolean;
+  full_page_scan: boolean;
+  title_attributes: Record<string, string[]>;
   hint_opacity: number;
   hint_font_size: number;
   hint_bg_color: string;
   hint_text_color: string;
+  enabled_apps: string[];
+  disabled_apps: string[];
+  auto_activate_apps: string[];
+  app_clickable_roles: Record<string, ClickableRoleOverrides>;
   // Advanced timing settings
   ax_stabilization_delay_ms: number;
   cache_ttl_ms: number;
@@ -54,11 +122,19 @@ export interface ClickModeSettings {
   max_elements: number;
 }
 
+export type ScrollHoldModifier = "command" | "option" | "control" | "function";
+
 export interface ScrollModeSettings {
   enabled: boolean;
   scroll_step: number;
+  continuous_scroll: boolean;
+  scroll_hold_modifier: ScrollHoldModifier | null;
+  list_navigation: boolean;
+  list_navigation_grid: boolean;
   enabled_apps: string[];
   overlay_blocklist: string[];
+  overlay_title_allowlist: Record<string, string[]>;
+  focus_grace_ms: number;
   disabled_shortcuts: string[];
 }
 
@@ -107,10 +183,17 @@ export interface Settings {
   indicator_offset_x: number;
   indicator_offset_y: number;
   indicator_visible: boolean;
+  indicator_click_opens_settings: boolean;
   show_mode_in_menu_bar: boolean;
   mode_colors: ModeColors;
   indicator_font: string;
   ignored_apps: string[];
+  passthrough_shortcuts: KeyBinding[];
+  caps_lock_remap: CapsLockRemap;
+  key_repeat: KeyRepeatSettings;
+  initial_mode: VimMode;
+  remember_mode_per_app: boolean;
+  default_mode_on_new_app: VimMode | null;
   launch_at_login: boolean;
   show_in_menu_bar: boolean;
   indicator_rows: RowItem[];
@@ -120,6 +203,18 @@ export interface Settings {
   scroll_mode: ScrollModeSettings;
   auto_update_enabled: boolean;
   shell_widgets: ShellWidgetConfig[];
+  app_display_names: Record<string, string>;
+  ipc_socket_path: string | null;
+  ipc_token: string | null;
+  ipc_normal_keys: KeyBinding[];
+  ipc_bridge_enabled: boolean;
+  ipc_bridge_port: number;
+}
+
+/** An app chosen via pick_app / add_frontmost_app_to_list */
+export interface PickedApp {
+  bundle_id: string;
+  display_name: string | null;
 }
 
 type TabId = "general" | "indicator" | "widgets" | "ignored" | "nvim-config" | "nvim-window" | "click-mode" | "scroll-mode";
@@ -133,7 +228,7 @@ export function SettingsApp() {
     // (domain_filetypes are stored in a separate file)
     Promise.all([
       invoke<Settings>("get_settings"),
-      invoke<Record<string, string>>("get_domain_filetypes"),
+      invoke<Record<string, string>>("get_filetype_mappings"),
     ])
       .then(([loadedSettings, domainFiletypes]) => {
         // Merge domain_filetypes into nvim_edit settings
@@ -148,6 +243,40 @@ export function SettingsApp() {
       .catch((e) => console.error("Failed to load settings:", e));
   }, []);
 
+  // Keep in sync with changes made by the backend (tray menu, "add current app", ...)
+  useEffect(() => {
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      setSettings((prev) =>
+        prev
+          ? {
+              ...event.payload,
+              nvim_edit: {
+                ...event.payload.nvim_edit,
+                domain_filetypes: prev.nvim_edit.domain_filetypes,
+              },
+            }
+          : prev,
+      );
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Filetype mappings are saved by their own commands, not through set_settings
+  useEffect(() => {
+    const unlisten = listen<Record<string, string>>("filetype-mappings-changed", (event) => {
+      setSettings((prev) =>
+        prev ? { ...prev, nvim_edit: { ...prev.nvim_edit, domain_filetypes: event.payload } } : prev,
+      );
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const updateSettings = async (updates: Partial<Settings>) => {
     if (!settings) return;
 
@@ -161,6 +290,17 @@ export function SettingsApp() {
     }
   };
 
+  const clearLearnedData = async () => {
+    if (!confirm("Forget all learned filetypes and recorded app names?")) return;
+
+    try {
+      // The remaining (explicitly set) filetypes arrive as filetype-mappings-changed
+      await invoke("clear_learned_data");
+    } catch (e) {
+      console.error("Failed to clear learned data:", e);
+    }
+  };
+
   if (!settings) {
     return <div className="loading">Loading settings...</div>;
   }
@@ -262,7 +402,11 @@ export function SettingsApp() {
 
       <div className="tab-content">
         {activeTab === "general" && (
-          <GeneralSettings settings={settings} onUpdate={updateSettings} />
+          <GeneralSettings
+            settings={settings}
+            onUpdate={updateSettings}
+            onClearLearnedData={clearLearnedData}
+          />
         )}
         {activeTab === "indicator" && (
           <IndicatorSettings settings={settings} onUpdate={updateSettings} />
@@ -21,7 +21,7 @@ import {
   arrayMove,
 } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
-import type { Settings, ShellWidgetConfig, RowItem, WidgetType } from "./SettingsApp";
+import type { PickedApp, Settings, ShellWidgetConfig, RowItem, WidgetType } from "./SettingsApp";
 import { AppList } from "./AppList";
 
 interface Props {
@@ -394,7 +394,7 @@ export function WidgetSettings({ settings, onUpdate }: Props) {
   // Electron apps handlers
   const handleAddElectronApp = async () => {
     try {
-      const bundleId = await invoke<string | null>("pick_app");
+      const bundleId = (await invoke<PickedApp | null>("pick_app"))?.bundle_id;
       if (bundleId && !settings.electron_apps.includes(bundleId)) {
         onUpdate({ electron_apps: [...settings.electron_apps, bundleId] });
       }
@@ -667,6 +667,8 @@ export function WidgetSettings({ settings, onUpdate }: Props) {
           onAdd={handleAddElectronApp}
           onAddManual={handleAddManualElectronApp}
           onRemove={handleRemoveElectronApp}
+          listName="electron_apps"
+          displayNames={settings.app_display_names}
         />
         <p className="help-text">
           Observing selection in Electron apps requires more performance.
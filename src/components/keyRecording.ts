@@ -5,6 +5,7 @@ export interface VimKeyModifiers {
   control: boolean
   option: boolean
   command: boolean
+  function?: boolean
 }
 
 export interface RecordedKey {
@@ -22,12 +23,13 @@ export function formatKeyWithModifiers(
   if (modifiers.option) parts.push("Opt")
   if (modifiers.shift) parts.push("Shift")
   if (modifiers.command) parts.push("Cmd")
+  if (modifiers.function) parts.push("Fn")
   parts.push(displayName)
   return parts.join(" + ")
 }
 
 export function hasAnyModifier(modifiers: VimKeyModifiers): boolean {
-  return modifiers.shift || modifiers.control || modifiers.option || modifiers.command
+  return modifiers.shift || modifiers.control || modifiers.option || modifiers.command || !!modifiers.function
 }
 
 export async function recordKey(): Promise<RecordedKey> {
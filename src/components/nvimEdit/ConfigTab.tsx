@@ -1,8 +1,15 @@
-import { useState } from "react"
+import { useEffect, useState } from "react"
 import { open } from "@tauri-apps/plugin-dialog"
 import { invoke } from "@tauri-apps/api/core"
-import type { NvimEditSettings, DoubleTapModifier } from "../SettingsApp"
+import type {
+  NvimEditSettings,
+  DoubleTapModifier,
+  TrailingNewline,
+  CancelBehavior,
+} from "../SettingsApp"
 import {
+  type EditSessionInfo,
+  type EditorCapabilities,
   type PathValidation,
   TERMINAL_OPTIONS,
   DEFAULT_TERMINAL_PATHS,
@@ -25,12 +32,28 @@ function DomainFiletypesModal({
   filetypes,
   onClose,
   onRemove,
+  onSave,
 }: {
   filetypes: Record<string, string>
   onClose: () => void
   onRemove: (domain: string) => void
+  onSave: (domain: string, filetype: string) => Promise<void>
 }) {
   const entries = Object.entries(filetypes)
+  const [domain, setDomain] = useState("")
+  const [filetype, setFiletype] = useState("")
+  const [error, setError] = useState<string | null>(null)
+
+  const handleSave = async () => {
+    try {
+      await onSave(domain.trim(), filetype.trim())
+      setDomain("")
+      setFiletype("")
+      setError(null)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
 
   return (
     <div className="error-dialog-overlay" onClick={onClose}>
@@ -38,7 +61,8 @@ function DomainFiletypesModal({
         <h3>Saved Filetypes</h3>
         <p className="hint">
           Filetypes are automatically saved when you set them in nvim (e.g., <code>:set ft=markdown</code>).
-          They will be restored the next time you edit text on that domain.
+          They will be restored the next time you edit text on that domain. Click a filetype to
+          change it, or add one below.
         </p>
         {entries.length === 0 ? (
           <p className="empty-state">No saved filetypes yet.</p>
@@ -55,7 +79,16 @@ function DomainFiletypesModal({
               {entries.map(([domain, filetype]) => (
                 <tr key={domain}>
                   <td className="domain-cell" title={domain}>{domain}</td>
-                  <td className="filetype-cell">{filetype}</td>
+                  <td
+                    className="filetype-cell"
+                    onClick={() => {
+                      setDomain(domain)
+                      setFiletype(filetype)
+                    }}
+                    title="Edit"
+                  >
+                    {filetype}
+                  </td>
                   <td className="action-cell">
                     <button
                       type="button"
@@ -71,7 +104,96 @@ function DomainFiletypesModal({
             </tbody>
           </table>
         )}
+        <div className="path-input-row">
+          <input
+            type="text"
+            value={domain}
+            onChange={(e) => setDomain(e.target.value)}
+            placeholder="github.com or com.apple.Notes"
+          />
+          <input
+            type="text"
+            value={filetype}
+            onChange={(e) => setFiletype(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && handleSave()}
+            placeholder="markdown"
+          />
+          <button type="button" onClick={handleSave} disabled={!domain.trim() || !filetype.trim()}>
+            Save
+          </button>
+        </div>
+        {error && <div className="alert alert-warning">{error}</div>}
+        <div className="error-dialog-buttons">
+          <button onClick={onClose}>Close</button>
+        </div>
+      </div>
+    </div>
+  )
+}
+
+function ActiveSessionsModal({ onClose }: { onClose: () => void }) {
+  const [sessions, setSessions] = useState<EditSessionInfo[]>([])
+
+  const loadSessions = () => {
+    invoke<EditSessionInfo[]>("list_edit_sessions")
+      .then(setSessions)
+      .catch((e) => console.error("Failed to list edit sessions:", e))
+  }
+
+  useEffect(loadSessions, [])
+
+  const handleKill = async (id: string) => {
+    try {
+      await invoke("kill_edit_session", { id })
+    } catch (e) {
+      console.error("Failed to kill edit session:", e)
+    }
+    loadSessions()
+  }
+
+  return (
+    <div className="error-dialog-overlay" onClick={onClose}>
+      <div className="error-dialog domain-filetypes-modal" onClick={(e) => e.stopPropagation()}>
+        <h3>Active Sessions</h3>
+        <p className="hint">
+          Ending a stuck session closes its editor and returns focus to the original app. The edited
+          text is not restored.
+        </p>
+        {sessions.length === 0 ? (
+          <p className="empty-state">No active sessions.</p>
+        ) : (
+          <table className="domain-filetypes-table">
+            <thead>
+              <tr>
+                <th>App</th>
+                <th>Started</th>
+                <th></th>
+              </tr>
+            </thead>
+            <tbody>
+              {sessions.map((session) => (
+                <tr key={session.id}>
+                  <td className="domain-cell" title={`${session.terminal_type}, PID ${session.editor_pid ?? "?"}`}>
+                    {session.app}
+                  </td>
+                  <td className="filetype-cell">{new Date(session.started_at).toLocaleTimeString()}</td>
+                  <td className="action-cell">
+                    <button
+                      type="button"
+                      className="remove-btn"
+                      onClick={() => handleKill(session.id)}
+                      title="End session"
+                    >
+                      x
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
         <div className="error-dialog-buttons">
+          <button onClick={loadSessions}>Refresh</button>
           <button onClick={onClose}>Close</button>
         </div>
       </div>
@@ -90,19 +212,34 @@ export function ConfigTab({
   onShowErrorDialog,
 }: Props) {
   const [showFiletypesModal, setShowFiletypesModal] = useState(false)
+  const [showSessionsModal, setShowSessionsModal] = useState(false)
+  const [editors, setEditors] = useState<EditorCapabilities[]>([])
+
+  useEffect(() => {
+    invoke<EditorCapabilities[]>("get_supported_editors")
+      .then(setEditors)
+      .catch((e) => console.error("Failed to get supported editors:", e))
+  }, [])
+
+  const editorOptions = editors.length
+    ? editors.map((e) => ({ value: e.name, label: e.label }))
+    : EDITOR_OPTIONS
+  const selectedEditor = editors.find((e) => e.name === nvimEdit.editor)
 
+  // The settings window picks up the new mappings from filetype-mappings-changed
   const handleRemoveFiletype = async (domain: string) => {
     try {
-      await invoke("remove_domain_filetype", { domain })
-      // Update local state
-      const newFiletypes = { ...nvimEdit.domain_filetypes }
-      delete newFiletypes[domain]
-      onUpdate({ domain_filetypes: newFiletypes })
+      await invoke("remove_filetype_mapping", { domain })
     } catch (e) {
       console.error("Failed to remove filetype:", e)
     }
   }
 
+  // Errors are shown in the modal
+  const handleSaveFiletype = async (domain: string, filetype: string) => {
+    await invoke("set_filetype_mapping", { domain, filetype })
+  }
+
   const handleEditorChange = (newEditor: string) => {
     const currentPath = nvimEdit.nvim_path
     const isDefaultPath =
@@ -190,6 +327,7 @@ export function ConfigTab({
                     <option value="option">Opt+Opt</option>
                     <option value="control">Ctrl+Ctrl</option>
                     <option value="shift">Shift+Shift</option>
+                    <option value="function">Fn+Fn</option>
                     <option value="escape">Esc+Esc</option>
                   </select>
                   <button
@@ -214,6 +352,7 @@ export function ConfigTab({
                   <option value="option">Opt+Opt</option>
                   <option value="control">Ctrl+Ctrl</option>
                   <option value="shift">Shift+Shift</option>
+                  <option value="function">Fn+Fn</option>
                   <option value="escape">Esc+Esc</option>
                 </select>
               )}
@@ -246,7 +385,7 @@ export function ConfigTab({
               validation && !validation.editor_valid && nvimEdit.enabled ? "input-error" : ""
             }
           >
-            {EDITOR_OPTIONS.map((opt) => (
+            {editorOptions.map((opt) => (
               <option key={opt.value} value={opt.value}>
                 {opt.label}
               </option>
@@ -457,6 +596,45 @@ export function ConfigTab({
         <span className="hint">
           Sync changes to the original text field as you type in the editor. Only works with Neovim.
         </span>
+        {selectedEditor && !selectedEditor.supports_live_sync && (
+          <span className="hint">
+            {selectedEditor.label} doesn't support live sync. The text is restored when the editor
+            exits.
+          </span>
+        )}
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="cancel-behavior">When quitting without saving</label>
+        <select
+          id="cancel-behavior"
+          value={nvimEdit.cancel_behavior ?? "keep_live_synced"}
+          onChange={(e) => onUpdate({ cancel_behavior: e.target.value as CancelBehavior })}
+          disabled={!nvimEdit.enabled || !nvimEdit.live_sync_enabled}
+        >
+          <option value="keep_live_synced">Keep live-synced changes (default)</option>
+          <option value="revert_to_original">Revert to the original text</option>
+        </select>
+        <span className="hint">
+          Quitting with :q! leaves the text field as live sync last updated it, or puts the text it
+          had before editing back.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="socket-dir">Socket directory</label>
+        <input
+          type="text"
+          id="socket-dir"
+          value={nvimEdit.socket_dir ?? ""}
+          onChange={(e) => onUpdate({ socket_dir: e.target.value })}
+          placeholder="~/Library/Caches/ovim"
+          disabled={!nvimEdit.enabled || !nvimEdit.live_sync_enabled}
+        />
+        <span className="hint">
+          Where the live sync socket is created. Paths longer than the 104 byte socket limit fall
+          back to ~/.ovim/sockets.
+        </span>
       </div>
 
       <div className="form-group">
@@ -475,6 +653,147 @@ export function ConfigTab({
         </span>
       </div>
 
+      <div className="form-group">
+        <label htmlFor="clipboard-chunk-threshold">Paste in chunks above (bytes)</label>
+        <input
+          type="number"
+          id="clipboard-chunk-threshold"
+          value={nvimEdit.clipboard_chunk_threshold ?? 10000}
+          onChange={(e) =>
+            onUpdate({ clipboard_chunk_threshold: Math.max(0, parseInt(e.target.value) || 0) })
+          }
+          min={0}
+          step={1000}
+          disabled={!nvimEdit.enabled}
+        />
+        <span className="hint">
+          Larger texts are pasted back in pieces of this size, since some apps silently truncate
+          big pastes. 0 pastes everything at once.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.rich_text_mode ?? false}
+            onChange={(e) => onUpdate({ rich_text_mode: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Edit rich text as Markdown
+        </label>
+        <span className="hint">
+          Edit formatted browser fields as Markdown and convert back to HTML when the editor
+          closes. Only bold, italic, links and simple lists are kept; other formatting is lost.
+          Disables live sync for these fields.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.sanitize_control_chars ?? false}
+            onChange={(e) => onUpdate({ sanitize_control_chars: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Strip control characters
+        </label>
+        <span className="hint">
+          Remove terminal color codes and other invisible control characters from captured text
+          before editing. Tabs and line breaks are kept.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.reactivate_original_immediately ?? false}
+            onChange={(e) => onUpdate({ reactivate_original_immediately: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Keep original selection
+        </label>
+        <span className="hint">
+          Briefly switch back to the original app after the editor opens, then bring the editor
+          forward. Helps apps that clear their selection when they lose focus.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="trailing-newline">Trailing newlines</label>
+        <select
+          id="trailing-newline"
+          value={nvimEdit.trailing_newline ?? "strip_one"}
+          onChange={(e) => onUpdate({ trailing_newline: e.target.value as TrailingNewline })}
+          disabled={!nvimEdit.enabled}
+        >
+          <option value="strip_one">Strip one (default)</option>
+          <option value="strip_all">Strip all trailing blank lines</option>
+          <option value="preserve">Preserve exactly</option>
+        </select>
+        <span className="hint">
+          How the editor's trailing newlines are handled when the text is restored. "Strip one" removes
+          the newline the editor adds on save, unless the original text ended with one; "Strip all"
+          also drops trailing blank lines.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="format-command">Format on restore</label>
+        <input
+          type="text"
+          id="format-command"
+          value={nvimEdit.format_command ?? ""}
+          onChange={(e) => onUpdate({ format_command: e.target.value.trim() ? e.target.value : null })}
+          placeholder="prettier --write {file}"
+          disabled={!nvimEdit.enabled}
+        />
+        <span className="hint">
+          Shell command run on the edited file before the text is restored. {"{file}"} is replaced
+          with the file path. If it fails or takes over 10 seconds, the unformatted text is restored.
+        </span>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.open_at_cursor ?? true}
+            onChange={(e) => onUpdate({ open_at_cursor: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Open at the text field's cursor
+        </label>
+        <span className="hint">
+          Start the editor on the line where the cursor was in the browser text field, when it is
+          known. Otherwise the editor starts at the end of the text.
+        </span>
+        {selectedEditor && !selectedEditor.supports_start_position && (
+          <span className="hint">
+            {selectedEditor.label} can't be started at a given line. It starts at the end of the
+            text.
+          </span>
+        )}
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.learn_filetype ?? true}
+            onChange={(e) => onUpdate({ learn_filetype: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Remember filetypes
+        </label>
+        <span className="hint">
+          Save the filetype set in the editor (e.g., <code>:set ft=markdown</code>) for the
+          domain/app when the editor closes. Turn off to only use filetypes added by hand.
+        </span>
+      </div>
+
       <div className="form-group">
         <label>Saved Filetypes</label>
         <div className="path-input-row">
@@ -492,11 +811,30 @@ export function ConfigTab({
         </span>
       </div>
 
+      <div className="form-group">
+        <label>Active Sessions</label>
+        <div className="path-input-row">
+          <button
+            type="button"
+            className="edit-script-btn"
+            onClick={() => setShowSessionsModal(true)}
+          >
+            Manage Sessions
+          </button>
+        </div>
+        <span className="hint">
+          End an edit session whose editor is stuck without quitting ovim.
+        </span>
+      </div>
+
+      {showSessionsModal && <ActiveSessionsModal onClose={() => setShowSessionsModal(false)} />}
+
       {showFiletypesModal && (
         <DomainFiletypesModal
           filetypes={nvimEdit.domain_filetypes || {}}
           onClose={() => setShowFiletypesModal(false)}
           onRemove={handleRemoveFiletype}
+          onSave={handleSaveFiletype}
         />
       )}
     </>
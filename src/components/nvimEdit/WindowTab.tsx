@@ -1,11 +1,37 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
 import type { NvimEditSettings } from "../SettingsApp"
+import type { PopupGeometryPreview } from "./constants"
+
+// Time to focus a text field before the preview is taken
+const PREVIEW_DELAY_MS = 3000
 
 interface Props {
   nvimEdit: NvimEditSettings
   onUpdate: (updates: Partial<NvimEditSettings>) => void
 }
 
+function describePreview(preview: PopupGeometryPreview): string {
+  if (!preview.geometry) {
+    return `No popup geometry for ${preview.app_bundle_id} (popup mode off or no frame found)`
+  }
+  const { x, y, width, height } = preview.geometry
+  const anchor = preview.anchor === "element" ? "anchored to the text field" : "centered in the window"
+  return `${preview.app_bundle_id}: ${width}×${height} at (${x}, ${y}), ${anchor}`
+}
+
 export function WindowTab({ nvimEdit, onUpdate }: Props) {
+  const [previewStatus, setPreviewStatus] = useState<string | null>(null)
+
+  const handlePreview = () => {
+    setPreviewStatus("Focus a text field…")
+    setTimeout(() => {
+      invoke<PopupGeometryPreview>("preview_popup_geometry")
+        .then((preview) => setPreviewStatus(describePreview(preview)))
+        .catch((e) => setPreviewStatus(`Preview failed: ${e?.message ?? e}`))
+    }, PREVIEW_DELAY_MS)
+  }
+
   return (
     <>
       <div className="form-group">
@@ -24,33 +50,97 @@ export function WindowTab({ nvimEdit, onUpdate }: Props) {
       </div>
 
       {nvimEdit.popup_mode && (
-        <div className="form-row">
-          <div className="form-group">
-            <label htmlFor="popup-width">Popup width (px)</label>
-            <input
-              type="number"
-              id="popup-width"
-              value={nvimEdit.popup_width}
-              onChange={(e) => onUpdate({ popup_width: parseInt(e.target.value) || 0 })}
-              min={0}
-              disabled={!nvimEdit.enabled}
-            />
-            <span className="hint">0 = match text field width</span>
+        <>
+          <div className="form-row">
+            <div className="form-group">
+              <label htmlFor="popup-width">Popup width (px)</label>
+              <input
+                type="number"
+                id="popup-width"
+                value={nvimEdit.popup_width}
+                onChange={(e) => onUpdate({ popup_width: parseInt(e.target.value) || 0 })}
+                min={0}
+                disabled={!nvimEdit.enabled}
+              />
+              <span className="hint">0 = match text field width</span>
+            </div>
+            <div className="form-group">
+              <label htmlFor="popup-height">Popup height (px)</label>
+              <input
+                type="number"
+                id="popup-height"
+                value={nvimEdit.popup_height}
+                onChange={(e) => onUpdate({ popup_height: parseInt(e.target.value) || 300 })}
+                min={100}
+                disabled={!nvimEdit.enabled}
+              />
+            </div>
+          </div>
+          <div className="form-row">
+            <div className="form-group">
+              <label htmlFor="popup-width-pct">Width (% of screen)</label>
+              <input
+                type="number"
+                id="popup-width-pct"
+                value={nvimEdit.popup_width_pct ?? 0}
+                onChange={(e) => onUpdate({ popup_width_pct: parseInt(e.target.value) || 0 })}
+                min={0}
+                max={100}
+                disabled={!nvimEdit.enabled}
+              />
+              <span className="hint">0 = use pixel width; overrides it when set</span>
+            </div>
+            <div className="form-group">
+              <label htmlFor="popup-height-pct">Height (% of screen)</label>
+              <input
+                type="number"
+                id="popup-height-pct"
+                value={nvimEdit.popup_height_pct ?? 0}
+                onChange={(e) => onUpdate({ popup_height_pct: parseInt(e.target.value) || 0 })}
+                min={0}
+                max={100}
+                disabled={!nvimEdit.enabled}
+              />
+              <span className="hint">0 = use pixel height; overrides it when set</span>
+            </div>
+            <div className="form-group">
+              <label htmlFor="popup-margin">Screen margin (px)</label>
+              <input
+                type="number"
+                id="popup-margin"
+                value={nvimEdit.popup_margin ?? 8}
+                onChange={(e) => onUpdate({ popup_margin: parseInt(e.target.value) || 0 })}
+                min={0}
+                disabled={!nvimEdit.enabled}
+              />
+              <span className="hint">Space kept from screen edges and the menu bar</span>
+            </div>
           </div>
           <div className="form-group">
-            <label htmlFor="popup-height">Popup height (px)</label>
-            <input
-              type="number"
-              id="popup-height"
-              value={nvimEdit.popup_height}
-              onChange={(e) => onUpdate({ popup_height: parseInt(e.target.value) || 300 })}
-              min={100}
-              disabled={!nvimEdit.enabled}
-            />
+            <button onClick={handlePreview} disabled={!nvimEdit.enabled}>
+              Preview popup position
+            </button>
+            <span className="hint">
+              {previewStatus ?? "Shows where the popup would open for the text field focused 3 seconds later"}
+            </span>
           </div>
-        </div>
+        </>
       )}
 
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={nvimEdit.edit_popup_debug ?? false}
+            onChange={(e) => onUpdate({ edit_popup_debug: e.target.checked })}
+            disabled={!nvimEdit.enabled}
+          />
+          Show geometry debug overlay
+        </label>
+        <span className="hint">
+          Briefly outline the detected text field, window and popup frames when the editor opens
+        </span>
+      </div>
     </>
   )
 }
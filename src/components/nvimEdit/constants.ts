@@ -7,6 +7,38 @@ export interface PathValidation {
   editor_error: string | null
 }
 
+export interface EditorCapabilities {
+  name: string
+  label: string
+  supports_live_sync: boolean
+  needs_socket: boolean
+  supports_start_position: boolean
+  default_args: string[]
+}
+
+export interface ElementFrame {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+export interface PopupGeometryPreview {
+  app_bundle_id: string
+  element_frame: ElementFrame | null
+  window_frame: ElementFrame | null
+  anchor: "element" | "window" | null
+  geometry: { x: number; y: number; width: number; height: number } | null
+}
+
+export interface EditSessionInfo {
+  id: string
+  app: string
+  editor_pid: number | null
+  started_at: number
+  terminal_type: string
+}
+
 export const TERMINAL_OPTIONS = [
   { value: "alacritty", label: "Alacritty" },
   { value: "kitty", label: "Kitty" },
@@ -1,4 +1,4 @@
-import { useEffect, useState, useCallback } from "react"
+import { useEffect, useState, useCallback, useRef } from "react"
 import { listen } from "@tauri-apps/api/event"
 import { invoke } from "@tauri-apps/api/core"
 import { openUrl } from "@tauri-apps/plugin-opener"
@@ -10,7 +10,14 @@ interface PendingUpdate {
   version: string
 }
 
-type ClickAction = "Click" | "RightClick" | "CmdClick" | "DoubleClick"
+type ClickAction = "Click" | "RightClick" | "CmdClick" | "DoubleClick" | "Copy" | "BatchCopy"
+
+type EditSyncStatus = "live" | "clipboard" | "ended"
+
+interface EditSessionStatus {
+  session_id: string
+  status: EditSyncStatus
+}
 
 const defaultColors: ModeColors = {
   insert: { r: 74, g: 144, b: 217 },
@@ -26,6 +33,10 @@ export function Indicator() {
   const [pendingUpdate, setPendingUpdate] = useState<PendingUpdate | null>(null)
   const [clickModeActive, setClickModeActive] = useState(false)
   const [clickAction, setClickAction] = useState<ClickAction>("Click")
+  const [captureStalled, setCaptureStalled] = useState(false)
+  const [editSession, setEditSession] = useState<EditSessionStatus | null>(null)
+  const clickOpensSettings = settings?.indicator_click_opens_settings ?? false
+  const clickOpensSettingsRef = useRef(clickOpensSettings)
 
   useEffect(() => {
     invoke<Settings>("get_settings")
@@ -59,6 +70,14 @@ export function Indicator() {
     }
   }, [])
 
+  // Keep the window clickable while clicking the indicator opens settings
+  useEffect(() => {
+    clickOpensSettingsRef.current = clickOpensSettings
+    invoke("set_indicator_clickable", { clickable: clickOpensSettings }).catch((e) =>
+      console.error("Failed to update indicator clickability:", e)
+    )
+  }, [clickOpensSettings])
+
   // Poll for mouse position and Cmd key state
   useEffect(() => {
     let intervalId: ReturnType<typeof setInterval> | null = null
@@ -83,14 +102,16 @@ export function Indicator() {
           if (cmdPressed !== lastCmdState) {
             lastCmdState = cmdPressed
             setIsHoverable(cmdPressed)
-            await invoke("set_indicator_ignores_mouse", { ignore: !cmdPressed })
+            await invoke("set_indicator_ignores_mouse", {
+              ignore: !cmdPressed && !clickOpensSettingsRef.current,
+            })
           }
         } else {
           // Reset when not hovering
           if (lastCmdState) {
             lastCmdState = false
             setIsHoverable(false)
-            await invoke("set_indicator_ignores_mouse", { ignore: true })
+            await invoke("set_indicator_ignores_mouse", { ignore: !clickOpensSettingsRef.current })
           }
         }
       } catch (e) {
@@ -125,6 +146,33 @@ export function Indicator() {
     }
   }, [])
 
+  // Listen for event tap health changes
+  useEffect(() => {
+    const unlistenStalled = listen("capture-stalled", () => setCaptureStalled(true))
+    const unlistenRecovered = listen("capture-recovered", () => setCaptureStalled(false))
+
+    return () => {
+      unlistenStalled.then((fn) => fn())
+      unlistenRecovered.then((fn) => fn())
+    }
+  }, [])
+
+  // Listen for edit popup sync status (live sync vs paste on exit)
+  useEffect(() => {
+    const unlisten = listen<EditSessionStatus>("edit-session-status", (event) => {
+      const update = event.payload
+      setEditSession((current) => {
+        if (update.status !== "ended") return update
+        // Another session may have started since this one
+        return current?.session_id === update.session_id ? null : current
+      })
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
   // Listen for click mode events
   useEffect(() => {
     const unlistenActivated = listen("click-mode-activated", () => {
@@ -211,10 +259,10 @@ export function Indicator() {
         overflow: "hidden",
         padding: "4px 4px 5px 4px",
         opacity,
-        cursor: isHoverable ? "pointer" : "default",
+        cursor: isHoverable || clickOpensSettings ? "pointer" : "default",
         position: "relative",
       }}
-      onClick={isHoverable ? handleOpenSettings : undefined}
+      onClick={isHoverable || clickOpensSettings ? handleOpenSettings : undefined}
     >
       {/* Update badge overlay - covers entire indicator */}
       {pendingUpdate && (
@@ -245,6 +293,48 @@ export function Indicator() {
         </button>
       )}
 
+      {/* Capture stalled badge - keys are not being captured */}
+      {captureStalled && (
+        <div
+          title="Keyboard capture stalled - recreating event tap"
+          style={{
+            position: "absolute",
+            top: 2,
+            left: 2,
+            width: 8,
+            height: 8,
+            borderRadius: "50%",
+            background: "#ff453a",
+            zIndex: 12,
+          }}
+        />
+      )}
+
+      {/* Edit popup badge - whether edits sync live or are pasted on exit */}
+      {editSession && (
+        <div
+          title={
+            editSession.status === "live"
+              ? "Edit popup: changes sync into the text field as you type"
+              : "Edit popup: text is pasted when the editor exits"
+          }
+          style={{
+            position: "absolute",
+            top: 2,
+            right: 2,
+            padding: "0 3px",
+            borderRadius: "3px",
+            background: editSession.status === "live" ? "#30d158" : "rgba(0, 0, 0, 0.45)",
+            fontSize: "7px",
+            fontWeight: "bold",
+            lineHeight: "10px",
+            zIndex: 12,
+          }}
+        >
+          {editSession.status === "live" ? "live" : "paste"}
+        </div>
+      )}
+
       {/* Click mode overlay - shows action shortcuts */}
       {clickModeActive && (
         <div
@@ -268,6 +358,8 @@ export function Indicator() {
             {clickAction === "RightClick" && "right"}
             {clickAction === "CmdClick" && "cmd"}
             {clickAction === "DoubleClick" && "double"}
+            {clickAction === "Copy" && "copy"}
+            {clickAction === "BatchCopy" && "batch"}
           </div>
           {/* Action shortcuts - just the key to switch */}
           <div
@@ -381,7 +473,7 @@ export function Indicator() {
             <circle cx="12" cy="12" r="3" />
             <path d="M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z" />
           </svg>
-          {!isHoverable && (
+          {!isHoverable && !clickOpensSettings && (
             <span style={{ fontSize: "8px", opacity: 0.8 }}>hold Cmd</span>
           )}
         </div>
@@ -43,6 +43,7 @@ export interface Settings {
   indicator_offset_x: number
   indicator_offset_y: number
   indicator_visible: boolean
+  indicator_click_opens_settings: boolean
   show_mode_in_menu_bar: boolean
   mode_colors: ModeColors
   indicator_font: string
@@ -55,6 +56,14 @@ export interface SelectionInfo {
   line_count: number
 }
 
+/** Focused field contents, from the on-demand `get_selection_content` command */
+export interface SelectionContent {
+  value: string | null
+  selected_text: string | null
+  selection_start: number | null
+  selection_end: number | null
+}
+
 export interface BatteryInfo {
   percentage: number
   is_charging: boolean